@@ -1,6 +1,7 @@
 use criterion::*;
 use rand::prelude::*;
 use triple_accel::*;
+use triple_accel::bitap::*;
 use triple_accel::levenshtein::*;
 use triple_accel::hamming::*;
 
@@ -120,7 +121,120 @@ fn bench_rand_levenshtein_search(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(bench_rand, bench_rand_hamming, bench_rand_hamming_search, bench_rand_levenshtein, bench_rand_levenshtein_k, bench_rand_levenshtein_search);
+fn bench_rand_search_count(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut group = c.benchmark_group("bench_rand_search_count");
+    let config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    group.plot_config(config);
+
+    for num_repeats in [1000, 10000].iter() {
+        // the haystack is made of repeated copies of the needle, so there are thousands of matches
+        let needle = black_box(rand_str(8, &mut rng));
+        let haystack = black_box(needle.repeat(*num_repeats));
+        let k = black_box(1u32);
+
+        assert!(hamming_search_count(&needle, &haystack, k) == hamming_search_simd_with_opts(&needle, &haystack, k, SearchType::All).collect::<Vec<Match>>().len());
+        assert!(levenshtein_search_count(&needle, &haystack, k) == levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>().len());
+
+        group.bench_function(BenchmarkId::new("hamming_search_collect", *num_repeats), |b| b.iter(|| hamming_search_simd_with_opts(&needle, &haystack, k, SearchType::All).collect::<Vec<Match>>().len()));
+        group.bench_function(BenchmarkId::new("hamming_search_count", *num_repeats), |b| b.iter(|| hamming_search_count(&needle, &haystack, k)));
+        group.bench_function(BenchmarkId::new("levenshtein_search_collect", *num_repeats), |b| b.iter(|| levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>().len()));
+        group.bench_function(BenchmarkId::new("levenshtein_search_count", *num_repeats), |b| b.iter(|| levenshtein_search_count(&needle, &haystack, k)));
+    }
+
+    group.finish();
+}
+
+fn bench_rand_search_contains(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut group = c.benchmark_group("bench_rand_search_contains");
+    let config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    group.plot_config(config);
+
+    for str_len in [1000, 10000].iter() {
+        let needle_len = black_box(*str_len / 100);
+        let num_needles = black_box(*str_len / 200);
+        let k = black_box(((*str_len) as u32) / 1000);
+        let (needle, haystack) = black_box(rand_levenshtein_needle_haystack(needle_len, *str_len, num_needles, k, &mut rng));
+
+        assert!(levenshtein_contains(&needle, &haystack, k) == !levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>().is_empty());
+
+        group.bench_function(BenchmarkId::new("levenshtein_search_collect", *str_len), |b| b.iter(|| !levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>().is_empty()));
+        group.bench_function(BenchmarkId::new("levenshtein_contains", *str_len), |b| b.iter(|| levenshtein_contains(&needle, &haystack, k)));
+    }
+
+    group.finish();
+}
+
+fn bench_rand_scratch(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut group = c.benchmark_group("bench_rand_scratch");
+    // each iteration does a million comparisons
+    group.sample_size(10);
+
+    for str_len in [10, 30].iter() {
+        let k = black_box(((*str_len) as u32) / 5);
+        let pairs: Vec<(Vec<u8>, Vec<u8>)> = black_box((0..1000).map(|_| rand_levenshtein_pair(*str_len, k, &mut rng)).collect());
+        let mut scratch = Scratch::new();
+
+        for (a_str, b_str) in pairs.iter() {
+            assert!(levenshtein(a_str, b_str) == levenshtein_with_scratch(a_str, b_str, &mut scratch));
+        }
+
+        group.bench_function(BenchmarkId::new("levenshtein", *str_len), |b| b.iter(|| (0..1000).map(|_| pairs.iter().map(|(a_str, b_str)| levenshtein(a_str, b_str)).sum::<u32>()).sum::<u32>()));
+        group.bench_function(BenchmarkId::new("levenshtein_with_scratch", *str_len), |b| b.iter(|| (0..1000).map(|_| pairs.iter().map(|(a_str, b_str)| levenshtein_with_scratch(a_str, b_str, &mut scratch)).sum::<u32>()).sum::<u32>()));
+    }
+
+    group.finish();
+}
+
+fn bench_rand_compiled_needle(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut group = c.benchmark_group("bench_rand_compiled_needle");
+    group.sample_size(10);
+
+    for str_len in [100, 1000].iter() {
+        // one needle is searched through 10,000 haystacks, and only some of them contain matches
+        let needle_len = black_box(*str_len / 10);
+        let k = black_box(((*str_len) as u32) / 100);
+        let needle = black_box(rand_str(needle_len, &mut rng));
+        let haystacks: Vec<Vec<u8>> = black_box((0..10000).map(|i| if i % 10 == 0 { let mut h = rand_levenshtein_mutate(&needle, k, &mut rng); h.extend(rand_str(*str_len - h.len(), &mut rng)); h } else { rand_str(*str_len, &mut rng) }).collect());
+        let compiled = CompiledNeedle::new(&needle);
+
+        for haystack in haystacks.iter() {
+            assert!(compiled.search(haystack, k) == levenshtein_search_simd_with_opts(&needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>());
+        }
+
+        group.bench_function(BenchmarkId::new("levenshtein_search_simd", *str_len), |b| b.iter(|| haystacks.iter().map(|haystack| levenshtein_search_simd_with_opts(&needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).count()).sum::<usize>()));
+        group.bench_function(BenchmarkId::new("compiled_needle_search", *str_len), |b| b.iter(|| haystacks.iter().map(|haystack| compiled.search(haystack, k).len()).sum::<usize>()));
+    }
+
+    group.finish();
+}
+
+fn bench_rand_bitap_search(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1234);
+    let mut group = c.benchmark_group("bench_rand_bitap_search");
+    let config = PlotConfiguration::default().summary_scale(AxisScale::Logarithmic);
+    group.plot_config(config);
+
+    for needle_len in [8, 32, 64].iter() {
+        let str_len = black_box(10000);
+        let num_needles = black_box(str_len / 200);
+        let k = black_box((*needle_len as u32) / 8);
+        let (needle, haystack) = black_box(rand_levenshtein_needle_haystack(*needle_len, str_len, num_needles, k, &mut rng));
+
+        let res: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == bitap_search(&needle, &haystack, k).collect::<Vec<Match>>());
+
+        group.bench_function(BenchmarkId::new("levenshtein_search_simd_k", *needle_len), |b| b.iter(|| levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).last()));
+        group.bench_function(BenchmarkId::new("bitap_search", *needle_len), |b| b.iter(|| bitap_search(&needle, &haystack, k).last()));
+    }
+
+    group.finish();
+}
+
+criterion_group!(bench_rand, bench_rand_hamming, bench_rand_hamming_search, bench_rand_levenshtein, bench_rand_levenshtein_k, bench_rand_levenshtein_search, bench_rand_search_count, bench_rand_search_contains, bench_rand_scratch, bench_rand_compiled_needle, bench_rand_bitap_search);
 criterion_main!(bench_rand);
 
 fn rand_hamming_needle_haystack<R: Rng>(needle_len: usize, haystack_len: usize, num_match: usize, k: u32, rng: &mut R) -> (Vec<u8>, Vec<u8>) {
@@ -0,0 +1,44 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use triple_accel::hamming::*;
+use triple_accel::levenshtein::*;
+use triple_accel::*;
+
+// differential fuzzing of the SIMD routines against the naive scalar routines
+//
+// the first byte is the number of edits allowed, the second byte is where the input is split
+// into the two strings, and the rest of the input is the strings
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+
+    let k = (data[0] & 15) as u32;
+    let split = data[1] as usize;
+    let data = &data[2..];
+    let (a, b) = data.split_at(split * data.len() / 256);
+
+    assert_eq!(levenshtein(a, b), levenshtein_naive(a, b));
+    assert_eq!(levenshtein_simd_k(a, b, k), levenshtein_naive_k(a, b, k));
+
+    let len = a.len().min(b.len());
+    assert_eq!(hamming(&a[..len], &b[..len]), hamming_naive(&a[..len], &b[..len]));
+    assert_eq!(byte_mismatches(&a[..len], &b[..len]), hamming_naive(&a[..len], &b[..len]));
+
+    // the search routines do not support null bytes
+    let needle: Vec<u8> = a.iter().map(|&c| c.max(1)).collect();
+    let haystack: Vec<u8> = b.iter().map(|&c| c.max(1)).collect();
+
+    for &search_type in &[SearchType::All, SearchType::Best] {
+        let simd: Vec<Match> = hamming_search_simd_with_opts(&needle, &haystack, k, search_type).collect();
+        let naive: Vec<Match> = hamming_search_naive_with_opts(&needle, &haystack, k, search_type).collect();
+        assert_eq!(simd, naive);
+
+        for &anchored in &[false, true] {
+            let simd: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+            let naive: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+            assert_eq!(simd, naive);
+        }
+    }
+});
@@ -0,0 +1,267 @@
+//! This module provides bit-parallel search routines for short needles.
+//!
+//! The Bitap (Shift-Or) algorithm packs the search state for a needle of up to 64 bytes into a
+//! single `u64` word for each number of edits, so every byte of the haystack is handled with a few
+//! branchless bitwise operations, instead of a column of the dynamic programming matrix.
+//!
+//! These search functions use the bit-parallel algorithm:
+//! * `bitap_search`
+
+use super::*;
+use core::borrow::Borrow;
+
+/// The length of the longest needle that is supported by `bitap_search`.
+pub const BITAP_MAX_NEEDLE_LEN: usize = 64;
+
+/// Returns an iterator over all `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using the bit-parallel Bitap (Shift-Or) algorithm.
+///
+/// Every match with a Levenshtein distance less than or equal to `k` is returned, in the order of
+/// their end positions. This returns the same matches as `levenshtein_search_simd_with_opts` with
+/// `SearchType::All`, `LEVENSHTEIN_COSTS`, and `anchored` set to false.
+/// A mask of the positions of each byte in `needle` is built first. Then, the haystack is scanned
+/// with the shift-or recurrence of Wu and Manber, which keeps one `u64` word of state for each
+/// number of edits from 0 to `k`, so the time spent on each byte only depends on `k`, and not on
+/// the length of `needle`.
+/// The recurrence only finds where matches end. The start of each match is found afterwards by
+/// aligning `needle` backwards from the end of the match, and if multiple matches end at the same
+/// position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Null bytes/characters are supported.
+/// `levenshtein_search` automatically uses this for short needles and a small `k`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Panics
+/// * If `needle` is longer than `BITAP_MAX_NEEDLE_LEN` bytes.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::bitap::*;
+/// let matches: Vec<Match> = bitap_search(b"abcd", b"  abxd  abcd", 1).collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 6, k: 1},
+///                         Match{start: 8, end: 11, k: 1},
+///                         Match{start: 8, end: 12, k: 0}]);
+/// ```
+pub fn bitap_search<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let needle_len = needle.len();
+
+    assert!(
+        needle_len <= BITAP_MAX_NEEDLE_LEN,
+        "needle has {} bytes, but at most {} bytes are supported",
+        needle_len,
+        BITAP_MAX_NEEDLE_LEN
+    );
+
+    if needle_len == 0 {
+        return Box::new(iter::empty());
+    }
+
+    bitap_search_core(BitapMasks::new(needle), haystack, k)
+}
+
+/// The masks of the positions of each byte in a needle, which only have to be built once for
+/// searching through many haystacks.
+#[derive(Clone)]
+pub(crate) struct BitapMasks {
+    needle_len: usize,
+    // bit j of the mask for a byte is zero if the byte is at index j of the needle
+    masks: [u64; 256],
+    // bit i of the reversed mask for a byte is set if the byte is at index i from the end of the
+    // needle, for finding the start of each match
+    rev_masks: [u64; 256],
+}
+
+impl BitapMasks {
+    /// The length of `needle` must be at most `BITAP_MAX_NEEDLE_LEN`.
+    pub(crate) fn new(needle: &[u8]) -> Self {
+        let needle_len = needle.len();
+        let mut masks = [!0u64; 256];
+        let mut rev_masks = [0u64; 256];
+
+        for (j, &c) in needle.iter().enumerate() {
+            masks[c as usize] &= !(1u64 << j);
+            rev_masks[c as usize] |= 1u64 << (needle_len - 1 - j);
+        }
+
+        Self {
+            needle_len,
+            masks,
+            rev_masks,
+        }
+    }
+}
+
+fn bitap_search_core<'a, M: Borrow<BitapMasks> + 'a>(
+    masks: M,
+    haystack: &'a [u8],
+    k: u32,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let needle_len = masks.borrow().needle_len;
+
+    if needle_len == 0 {
+        return Box::new(iter::empty());
+    }
+
+    // deleting the whole needle costs its length, so more edits do not lead to more matches
+    let k = cmp::min(k as usize, needle_len);
+    let last = 1u64 << (needle_len - 1);
+
+    // a zero bit j in the word for d edits means that the first j + 1 bytes of the needle match
+    // the haystack right before the current position with at most d edits; initially, the
+    // first d bytes can be deleted
+    let mut states: Vec<u64> = (0..=k)
+        .map(|d| (!0u64).checked_shl(d as u32).unwrap_or(0))
+        .collect();
+    let mut first = true;
+    let mut i = 0;
+
+    let res = iter::from_fn(move || {
+        if first {
+            first = false;
+
+            // the whole needle can only be deleted if k is at least the length of the needle
+            if states[k] & last == 0 {
+                return Some(Match {
+                    start: 0,
+                    end: 0,
+                    k: needle_len as u32,
+                });
+            }
+        }
+
+        let BitapMasks {
+            masks, rev_masks, ..
+        } = masks.borrow();
+
+        while i < haystack.len() {
+            let mask = masks[haystack[i] as usize];
+            // the word for d - 1 edits, before the current byte
+            let mut prev = states[0];
+            states[0] = (states[0] << 1) | mask;
+
+            for d in 1..=k {
+                let curr = states[d];
+                // match, substitution, deletion from the needle, and insertion into the needle
+                states[d] = ((curr << 1) | mask) & (prev << 1) & (states[d - 1] << 1) & prev;
+                prev = curr;
+            }
+
+            i += 1;
+
+            // the words are nested, so the first word that matches has the lowest number of edits
+            if let Some(d) = states.iter().position(|&s| s & last == 0) {
+                return Some(Match {
+                    start: bitap_match_start(rev_masks, needle_len, haystack, i, d as u32),
+                    end: i,
+                    k: d as u32,
+                });
+            }
+        }
+
+        None
+    });
+
+    Box::new(res)
+}
+
+/// Returns `Match`s like `bitap_search`, but if `search_type` is `SearchType::Best`, then only the
+/// matches with the lowest number of edits that do not fully overlap are kept, like
+/// `levenshtein_search_simd_with_opts`.
+/// If `masks` were already built for `needle`, then they are used instead of building new ones.
+pub(crate) fn bitap_search_with_type<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    masks: Option<&'a BitapMasks>,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let res = match masks {
+        Some(masks) => bitap_search_core(masks, haystack, k),
+        None => bitap_search(needle, haystack, k),
+    };
+
+    if search_type == SearchType::All {
+        return res;
+    }
+
+    let mut res_vec: Vec<Match> = Vec::new();
+    let mut curr_k = k;
+
+    for m in res {
+        if m.k < curr_k {
+            res_vec.clear();
+            curr_k = m.k;
+        }
+
+        if m.k > curr_k {
+            continue;
+        }
+
+        match res_vec.last_mut() {
+            // replace previous if fully overlapping
+            Some(last) if m.start <= last.start => *last = m,
+            _ => res_vec.push(m),
+        }
+    }
+
+    Box::new(res_vec.into_iter())
+}
+
+/// Returns the start of the longest match of `needle` that ends at `end` in `haystack` with
+/// exactly `k` edits, where `k` is the lowest number of edits for any match that ends there.
+///
+/// This uses Myers' bit-vector algorithm to align the reversed needle against the haystack,
+/// going backwards from `end`, so each byte only costs a few bitwise operations.
+fn bitap_match_start(
+    rev_masks: &[u64; 256],
+    needle_len: usize,
+    haystack: &[u8],
+    end: usize,
+    k: u32,
+) -> usize {
+    // a match with more than k insertions cannot have k edits
+    let max_len = cmp::min(end, needle_len + k as usize);
+    let last = 1u64 << (needle_len - 1);
+    // bit i is set if the distance increases or decreases going down the current column
+    let mut pos_v = !0u64;
+    let mut neg_v = 0u64;
+    // distance between the whole needle and the last j bytes of the haystack before end
+    let mut dist = needle_len as u32;
+    let mut best_len = 0;
+
+    for j in 1..=max_len {
+        let eq = rev_masks[haystack[end - j] as usize];
+        let x_v = eq | neg_v;
+        let x_h = ((eq & pos_v).wrapping_add(pos_v) ^ pos_v) | eq;
+        let mut pos_h = neg_v | !(x_h | pos_v);
+        let mut neg_h = pos_v & x_h;
+
+        if pos_h & last != 0 {
+            dist += 1;
+        } else if neg_h & last != 0 {
+            dist -= 1;
+        }
+
+        // the first row of the matrix increases by one for every byte of the haystack
+        pos_h = (pos_h << 1) | 1;
+        neg_h <<= 1;
+        pos_v = neg_h | !(x_v | pos_h);
+        neg_v = pos_h & x_v;
+
+        if dist == k {
+            best_len = j;
+        }
+    }
+
+    end - best_len
+}
@@ -0,0 +1,214 @@
+//! This module provides a memoizing wrapper around the distance functions.
+//!
+//! `DistanceCache` remembers the results of recent comparisons in a least recently used (LRU)
+//! cache that is keyed by the hashes of both strings, so comparing the same pair of strings again
+//! does not need to recompute the distance. This is only available with the `cache` feature.
+//!
+//! Hashing and storing the strings is not free, so the cache is only beneficial when the same
+//! pairs of strings are compared many times, or when the strings are long. For workloads with
+//! few repeated pairs, calling the distance functions directly is faster.
+
+use super::*;
+use alloc::collections::BTreeMap;
+
+// which distance function an entry was computed with, so different functions can share a cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum DistanceKind {
+    Hamming,
+    Levenshtein,
+    RDamerau,
+}
+
+type CacheKey = (DistanceKind, u64, u64);
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    a: Vec<u8>,
+    b: Vec<u8>,
+    dist: u32,
+    last_used: u64,
+}
+
+/// A least recently used cache of distances between pairs of strings.
+///
+/// Each entry is keyed by the 64-bit FNV-1a hashes of both strings. The strings themselves are
+/// also stored, and they are compared on every lookup, so a hash collision can never return the
+/// distance of a different pair of strings. If two pairs collide, then the newer pair replaces the
+/// older one.
+/// Once the cache holds `capacity` entries, the least recently used entry is evicted to make room
+/// for a new one.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::cache::*;
+/// let mut cache = DistanceCache::new(2);
+///
+/// assert!(cache.levenshtein(b"abc", b"abd") == 1);
+/// assert!(cache.levenshtein(b"abc", b"abd") == 1);
+/// assert!(cache.hits() == 1);
+/// assert!(cache.misses() == 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DistanceCache {
+    capacity: usize,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+    entries: BTreeMap<CacheKey, CacheEntry>,
+    recency: BTreeMap<u64, CacheKey>,
+}
+
+impl DistanceCache {
+    /// Create a new, empty `DistanceCache` that holds at most `capacity` distances.
+    ///
+    /// # Arguments
+    /// * `capacity` - maximum number of cached distances
+    ///
+    /// # Panics
+    /// * If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            tick: 0,
+            hits: 0,
+            misses: 0,
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the Levenshtein distance between two strings, like `levenshtein`, using a cached
+    /// result if the same pair of strings was compared recently.
+    ///
+    /// # Arguments
+    /// * `a` - first string (slice)
+    /// * `b` - second string (slice)
+    #[must_use]
+    pub fn levenshtein(&mut self, a: &[u8], b: &[u8]) -> u32 {
+        self.get_or_insert_with(DistanceKind::Levenshtein, a, b, levenshtein)
+    }
+
+    /// Returns the restricted Damerau-Levenshtein distance between two strings, like `rdamerau`,
+    /// using a cached result if the same pair of strings was compared recently.
+    ///
+    /// # Arguments
+    /// * `a` - first string (slice)
+    /// * `b` - second string (slice)
+    #[must_use]
+    pub fn rdamerau(&mut self, a: &[u8], b: &[u8]) -> u32 {
+        self.get_or_insert_with(DistanceKind::RDamerau, a, b, rdamerau)
+    }
+
+    /// Returns the Hamming distance between two strings, like `hamming`, using a cached result
+    /// if the same pair of strings was compared recently.
+    ///
+    /// # Arguments
+    /// * `a` - first string (slice)
+    /// * `b` - second string (slice)
+    ///
+    /// # Panics
+    /// * If the length of `a` does not equal the length of `b`.
+    #[must_use]
+    pub fn hamming(&mut self, a: &[u8], b: &[u8]) -> u32 {
+        self.get_or_insert_with(DistanceKind::Hamming, a, b, hamming)
+    }
+
+    /// Returns the number of lookups that were answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of lookups that had to compute the distance.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the number of cached distances.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of cached distances.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Remove all cached distances and reset the hit and miss counters.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    fn get_or_insert_with(
+        &mut self,
+        kind: DistanceKind,
+        a: &[u8],
+        b: &[u8],
+        f: fn(&[u8], &[u8]) -> u32,
+    ) -> u32 {
+        let key = (kind, fnv1a(a), fnv1a(b));
+        self.tick += 1;
+        let tick = self.tick;
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            self.recency.remove(&entry.last_used);
+            self.recency.insert(tick, key);
+            entry.last_used = tick;
+
+            if entry.a == a && entry.b == b {
+                self.hits += 1;
+                return entry.dist;
+            }
+
+            // hash collision, so replace the older pair
+            self.misses += 1;
+            entry.dist = f(a, b);
+            entry.a = a.to_vec();
+            entry.b = b.to_vec();
+            return entry.dist;
+        }
+
+        self.misses += 1;
+        let dist = f(a, b);
+
+        if self.entries.len() >= self.capacity {
+            let oldest = *self.recency.keys().next().unwrap();
+            let oldest_key = self.recency.remove(&oldest).unwrap();
+            self.entries.remove(&oldest_key);
+        }
+
+        self.recency.insert(tick, key);
+        self.entries.insert(
+            key,
+            CacheEntry {
+                a: a.to_vec(),
+                b: b.to_vec(),
+                dist,
+                last_used: tick,
+            },
+        );
+        dist
+    }
+}
+
+// 64-bit FNV-1a, which is fast for short strings
+fn fnv1a(s: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+
+    for &c in s {
+        hash ^= c as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
@@ -9,6 +9,7 @@
 //! * `hamming_search_simd`
 //! * `hamming_search_simd_with_opts`
 
+#[cfg(not(feature = "pure-scalar"))]
 use super::jewel::*;
 use super::*;
 
@@ -31,6 +32,7 @@ use super::*;
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming_naive(a: &[u8], b: &[u8]) -> u32 {
     let len = a.len();
     assert!(len == b.len());
@@ -52,6 +54,7 @@ pub fn hamming_naive(a: &[u8], b: &[u8]) -> u32 {
 /// Each returned `Match` requires at least half or more bytes of the `needle` to match
 /// somewhere in the `haystack`.
 /// The length of `needle` must be less than or equal to the length of `haystack`.
+/// If `needle` is empty, then no `Match`es are returned.
 ///
 /// # Arguments
 /// * `needle` - pattern string (slice)
@@ -83,6 +86,7 @@ pub fn hamming_search_naive<'a>(
 /// Only matches with less than `k` mismatches are returned.
 /// This is done by naively counting mismatches at every position in `haystack`.
 /// The length of `needle` must be less than or equal to the length of `haystack`.
+/// If `needle` is empty, then no `Match`es are returned.
 ///
 /// # Arguments
 /// * `needle` - pattern string (slice)
@@ -104,24 +108,48 @@ pub fn hamming_search_naive_with_opts<'a>(
     haystack: &'a [u8],
     k: u32,
     search_type: SearchType,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search_naive_core(needle, haystack, k, search_type, false, false, None)
+}
+
+fn hamming_search_naive_core<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    case_insensitive: bool,
+    reverse: bool,
+    wildcard: Option<u8>,
 ) -> Box<dyn Iterator<Item = Match> + 'a> {
     let needle_len = needle.len();
     let haystack_len = haystack.len();
 
-    if needle_len > haystack_len {
+    if needle_len == 0 || needle_len > haystack_len {
         return Box::new(iter::empty());
     }
 
     let len = haystack_len + 1 - needle_len;
+    let fold = move |c: u8| {
+        if case_insensitive {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    };
     let mut curr_k = k;
     let mut i = 0;
 
     let res = iter::from_fn(move || {
         'outer: while i < len {
+            // when scanning in reverse, positions are visited right to left, but the
+            // resulting matches are still in forward coordinates
+            let start = if reverse { len - 1 - i } else { i };
             let mut final_res = 0u32;
 
             for j in 0..needle_len {
-                final_res += (needle[j] != haystack[i + j]) as u32;
+                // wildcard bytes in the needle match anything
+                final_res += (fold(needle[j]) != fold(haystack[start + j])
+                    && Some(needle[j]) != wildcard) as u32;
 
                 // early stop
                 if final_res > curr_k {
@@ -139,8 +167,8 @@ pub fn hamming_search_naive_with_opts<'a>(
 
             return Some((
                 Match {
-                    start: i - 1,
-                    end: i + needle_len - 1,
+                    start,
+                    end: start + needle_len,
                     k: final_res,
                 },
                 curr_k,
@@ -151,8 +179,14 @@ pub fn hamming_search_naive_with_opts<'a>(
     });
 
     if search_type == SearchType::Best {
-        let mut res_vec = Vec::with_capacity(haystack_len / needle_len);
+        // only matches with the lowest k so far are kept, so the memory used does not grow
+        // with the length of the haystack
+        let mut res_vec: Vec<Match> = Vec::new();
         res.for_each(|m| {
+            if m.1 < curr_k {
+                res_vec.clear();
+            }
+
             res_vec.push(m.0);
             curr_k = m.1;
         });
@@ -191,9 +225,17 @@ pub fn hamming_search_naive_with_opts<'a>(
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming_words_64(a: &[u8], b: &[u8]) -> u32 {
     assert!(a.len() == b.len());
 
+    // without unsafe code, the strings cannot be read as words
+    #[cfg(feature = "pure-scalar")]
+    {
+        hamming_naive(a, b)
+    }
+
+    #[cfg(not(feature = "pure-scalar"))]
     unsafe {
         let mut res = 0u32;
         // the pointer address better be aligned for u64
@@ -264,9 +306,17 @@ pub fn hamming_words_64(a: &[u8], b: &[u8]) -> u32 {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming_words_128(a: &[u8], b: &[u8]) -> u32 {
     assert!(a.len() == b.len());
 
+    // without unsafe code, the strings cannot be read as words
+    #[cfg(feature = "pure-scalar")]
+    {
+        hamming_naive(a, b)
+    }
+
+    #[cfg(not(feature = "pure-scalar"))]
     unsafe {
         let mut res = 0u32;
         // the pointer address better be aligned for u128
@@ -332,6 +382,7 @@ pub fn hamming_words_128(a: &[u8], b: &[u8]) -> u32 {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming_simd_parallel(a: &[u8], b: &[u8]) -> u32 {
     assert!(a.len() == b.len());
 
@@ -360,6 +411,7 @@ pub fn hamming_simd_parallel(a: &[u8], b: &[u8]) -> u32 {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming_simd_movemask(a: &[u8], b: &[u8]) -> u32 {
     assert!(a.len() == b.len());
 
@@ -372,6 +424,7 @@ pub fn hamming_simd_movemask(a: &[u8], b: &[u8]) -> u32 {
 /// This will automatically fall back to a scalar alternative if AVX2 and
 /// SSE4.1 are not supported.
 /// Internally, this calls `hamming_simd_parallel`.
+/// Two empty strings have a distance of zero.
 ///
 /// # Arguments
 /// * `a` - first string (slice)
@@ -387,243 +440,1800 @@ pub fn hamming_simd_movemask(a: &[u8], b: &[u8]) -> u32 {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn hamming(a: &[u8], b: &[u8]) -> u32 {
     hamming_simd_parallel(a, b)
 }
 
-/// Returns an iterator over best `Match`s by searching through the text `haystack`
-/// for the pattern `needle` using SIMD.
+/// Returns the hamming distance between two strings using the scalar implementation.
 ///
-/// This is done by counting mismatches at every position in `haystack`.
-/// This will automatically fall back to `hamming_search_naive_with_opts` if AVX2 and SSE4.1
-/// are not supported.
-/// Null bytes/characters are not supported.
-/// The length of `needle` must be less than or equal to the length of `haystack`.
-/// Each returned `Match` requires at least half or more bytes of the `needle` to match
-/// somwhere in the `haystack`.
-/// Only the matches with the lowest Hamming distance are returned.
-/// This should be faster than `hamming_search_naive`.
+/// This has the same signature and output as `hamming`, but it never uses SIMD, so it is always
+/// available regardless of the target features or crate feature flags that are enabled.
+/// This is useful for reproducible benchmarks or for checking SIMD results against.
+/// Internally, this calls `hamming_naive`.
 ///
 /// # Arguments
-/// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
 ///
 /// # Panics
-/// * When there are zero/null bytes in the `haystack` string.
+/// * If the length of `a` does not equal the length of `b`.
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
-/// # use triple_accel::hamming::*;
-/// let matches: Vec<Match> = hamming_search_simd(b"abc", b"  abd").collect();
+/// let dist = hamming_scalar(b"abc", b"abd");
 ///
-/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// assert!(dist == 1);
 /// ```
-pub fn hamming_search_simd<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    hamming_search_simd_with_opts(
-        needle,
-        haystack,
-        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
-        SearchType::Best,
-    )
+#[must_use]
+pub fn hamming_scalar(a: &[u8], b: &[u8]) -> u32 {
+    hamming_naive(a, b)
 }
 
-/// Returns an iterator over `Match`s by searching through the text `haystack` for the
-/// pattern `needle` using SIMD, with extra options.
+/// Returns the hamming distance between two strings using the best method, or an error if
+/// their lengths are not the same.
 ///
-/// This is done by using SIMD to count mismatches at every position in `haystack`.
-/// This will automatically fall back to `hamming_search_naive_with_opts` if AVX2 and SSE4.1
-/// are not supported.
-/// Null bytes/characters are not supported.
-/// The length of `needle` must be less than or equal to the length of `haystack`.
-/// This should be faster than `hamming_search_naive_with_opts`.
+/// This is the same as `hamming`, except that unequal lengths return a `LengthMismatch` with
+/// both lengths instead of panicking. Use `hamming` if the lengths are already known to match.
 ///
 /// # Arguments
-/// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
-/// * `k` - number of mismatches allowed
-/// * `search_type` - whether to only return the "best" matches with the lowest Hamming distance, or
-/// all matches
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// assert!(hamming_checked(b"abc", b"abd") == Ok(1));
+/// assert!(hamming_checked(b"abc", b"ab") == Err(LengthMismatch{a_len: 3, b_len: 2}));
+/// ```
+pub fn hamming_checked(a: &[u8], b: &[u8]) -> Result<u32, LengthMismatch> {
+    if a.len() != b.len() {
+        return Err(LengthMismatch {
+            a_len: a.len(),
+            b_len: b.len(),
+        });
+    }
+
+    Ok(hamming(a, b))
+}
+
+/// Returns the number of differing bits between two strings by XORing them and counting the
+/// set bits.
+///
+/// This is different from the other Hamming distance routines, which count differing bytes.
+/// This is useful for comparing fixed-width binary fingerprints.
+/// The length of `a` and `b` must be the same.
+/// There are no constraints on how `a` and `b` are aligned and padded.
+/// If AVX2 is supported, then the bits are counted in 256-bit chunks using a shuffle-based SIMD
+/// popcount. Otherwise, this will automatically fall back to counting the bits in 64-bit words.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
 ///
 /// # Panics
-/// * When there are zero/null bytes in the `haystack` string.
+/// * If the length of `a` does not equal the length of `b`.
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
 /// # use triple_accel::hamming::*;
-/// let matches: Vec<Match> = hamming_search_simd_with_opts(b"abc", b"  abd", 1, SearchType::All).collect();
+/// let dist = hamming_bits(&[0b1010u8, 0xff], &[0b0110u8, 0x0f]);
 ///
-/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// assert!(dist == 6);
 /// ```
-pub fn hamming_search_simd_with_opts<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-    k: u32,
-    search_type: SearchType,
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    if needle.len() > haystack.len() {
-        return Box::new(iter::empty());
+#[must_use]
+pub fn hamming_bits(a: &[u8], b: &[u8]) -> u32 {
+    assert!(a.len() == b.len());
+
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { hamming_bits_avx(a, b) };
+        }
     }
 
-    if needle.len() == 0 {
-        return Box::new(iter::empty());
+    hamming_bits_words(a, b)
+}
+
+fn hamming_bits_words(a: &[u8], b: &[u8]) -> u32 {
+    use core::convert::TryInto;
+
+    let mut res = 0u32;
+    let a_words = a.chunks_exact(8);
+    let b_words = b.chunks_exact(8);
+    let a_rem = a_words.remainder();
+    let b_rem = b_words.remainder();
+
+    for (x, y) in a_words.zip(b_words) {
+        let x = u64::from_ne_bytes(x.try_into().unwrap());
+        let y = u64::from_ne_bytes(y.try_into().unwrap());
+        res += (x ^ y).count_ones();
     }
 
-    check_no_null_bytes(haystack);
+    for (x, y) in a_rem.iter().zip(b_rem) {
+        res += (x ^ y).count_ones();
+    }
 
-    hamming_search_naive_with_opts(needle, haystack, k, search_type)
+    res
 }
 
-macro_rules! create_hamming_search_simd_core {
-    ($name:ident, $jewel:ty, $target:literal) => {
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        #[target_feature(enable = $target)]
-        unsafe fn $name<'a>(
-            needle: &'a [u8],
-            haystack: &'a [u8],
-            k: u32,
-            search_type: SearchType,
-        ) -> Box<dyn Iterator<Item = Match> + 'a> {
-            #[cfg(feature = "debug")]
-            {
-                println!(
-                    "Debug: Hamming search Jewel vector type {} for target {}.",
-                    stringify!($jewel),
-                    stringify!($target)
-                );
-            }
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn hamming_bits_avx(a: &[u8], b: &[u8]) -> u32 {
+    use core::arch::x86_64::*;
 
-            let needle_len = needle.len();
-            let haystack_len = haystack.len();
-            let needle_vector = <$jewel>::loadu(needle.as_ptr(), needle_len);
-            // calculate len using the unused bytes in the needle Jewel vector, for speed
-            // there may be leftover positions in haystack that need to be calculated using a
-            // scalar search afterwards
-            // there should be no null bytes in the strings
-            let len = if needle_vector.upper_bound() > haystack_len {
-                0
-            } else {
-                haystack_len + 1 - needle_vector.upper_bound()
-            };
-            let real_len = haystack_len + 1 - needle_len;
-            let haystack_ptr = haystack.as_ptr();
-            let mut curr_k = k;
-            let mut i = 0;
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Hamming bits for target \"avx2\".");
+    }
 
-            let res = iter::from_fn(move || {
-                while i < len {
-                    let final_res = <$jewel>::vector_count_mismatches(
-                        &needle_vector,
-                        haystack_ptr.offset(i as isize),
-                        needle_len,
-                    );
-                    i += 1;
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    // number of set bits for each 4-bit value
+    let lookup = _mm256_setr_epi8(
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3,
+        3, 4,
+    );
+    let low_mask = _mm256_set1_epi8(0x0f);
+    let zeros = _mm256_setzero_si256();
+    let mut sums = _mm256_setzero_si256();
+    let mut i = 0;
 
-                    if final_res <= curr_k {
-                        match search_type {
-                            SearchType::Best => curr_k = final_res,
-                            _ => (),
-                        }
+    while i + 32 <= len {
+        let x = _mm256_xor_si256(
+            _mm256_loadu_si256(a_ptr.add(i) as *const __m256i),
+            _mm256_loadu_si256(b_ptr.add(i) as *const __m256i),
+        );
+        let lo = _mm256_shuffle_epi8(lookup, _mm256_and_si256(x, low_mask));
+        let hi = _mm256_shuffle_epi8(lookup, _mm256_and_si256(_mm256_srli_epi16(x, 4), low_mask));
+        // horizontally add the byte counts into four 64-bit sums
+        sums = _mm256_add_epi64(sums, _mm256_sad_epu8(_mm256_add_epi8(lo, hi), zeros));
+        i += 32;
+    }
 
-                        return Some((
-                            Match {
-                                start: i - 1,
-                                end: i + needle_len - 1,
-                                k: final_res,
-                            },
-                            curr_k,
-                        ));
-                    }
-                }
+    let res = _mm256_extract_epi64(sums, 0)
+        + _mm256_extract_epi64(sums, 1)
+        + _mm256_extract_epi64(sums, 2)
+        + _mm256_extract_epi64(sums, 3);
 
-                // scalar search
-                'outer: while i < real_len {
-                    let mut final_res = 0u32;
+    (res as u32) + hamming_bits_words(&a[i..], &b[i..])
+}
 
-                    for j in 0..needle_len {
-                        final_res +=
-                            (*needle.get_unchecked(j) != *haystack.get_unchecked(i + j)) as u32;
+/// Returns the indexes of the bytes that differ between two strings, in ascending order.
+///
+/// The number of returned indexes is the Hamming distance between `a` and `b`.
+/// The length of `a` and `b` must be the same.
+/// There are no constraints on how `a` and `b` are aligned and padded.
+/// If AVX2 is supported, then 32 bytes are compared at a time, and the indexes are extracted
+/// from the set bits of the resulting mask. Otherwise, this will automatically fall back to
+/// comparing each byte.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let idxs = hamming_mismatches(b"abcdef", b"abxyeg");
+///
+/// assert!(idxs == vec![2, 3, 5]);
+/// ```
+pub fn hamming_mismatches(a: &[u8], b: &[u8]) -> Vec<usize> {
+    assert!(a.len() == b.len());
 
-                        if final_res > curr_k {
-                            i += 1;
-                            continue 'outer;
-                        }
-                    }
+    let mut res = Vec::new();
 
-                    match search_type {
-                        SearchType::Best => curr_k = final_res,
-                        _ => (),
-                    }
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            unsafe { hamming_mismatches_avx(a, b, &mut res) };
+            return res;
+        }
+    }
 
-                    i += 1;
+    hamming_mismatches_scalar(a, b, 0, &mut res);
+    res
+}
 
-                    return Some((
-                        Match {
-                            start: i - 1,
-                            end: i + needle_len - 1,
-                            k: final_res,
-                        },
-                        curr_k,
-                    ));
-                }
+fn hamming_mismatches_scalar(a: &[u8], b: &[u8], offset: usize, res: &mut Vec<usize>) {
+    for (i, (x, y)) in a.iter().zip(b).enumerate() {
+        if x != y {
+            res.push(offset + i);
+        }
+    }
+}
 
-                None
-            });
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn hamming_mismatches_avx(a: &[u8], b: &[u8], res: &mut Vec<usize>) {
+    use core::arch::x86_64::*;
 
-            if search_type == SearchType::Best {
-                let mut res_vec = Vec::with_capacity(haystack_len / needle_len);
-                res.for_each(|m| {
-                    res_vec.push(m.0);
-                    curr_k = m.1;
-                });
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Hamming mismatches for target \"avx2\".");
+    }
 
-                return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
-            }
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    let mut i = 0;
 
-            Box::new(res.map(|m| m.0))
+    while i + 32 <= len {
+        let eq = _mm256_cmpeq_epi8(
+            _mm256_loadu_si256(a_ptr.add(i) as *const __m256i),
+            _mm256_loadu_si256(b_ptr.add(i) as *const __m256i),
+        );
+        // one set bit for each mismatched byte
+        let mut mask = !(_mm256_movemask_epi8(eq) as u32);
+
+        while mask != 0 {
+            res.push(i + (mask.trailing_zeros() as usize));
+            // clear the lowest set bit
+            mask &= mask - 1;
         }
-    };
+
+        i += 32;
+    }
+
+    hamming_mismatches_scalar(&a[i..], &b[i..], i, res);
 }
 
-// generate different versions for different intrinsics
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_hamming_search_simd_core!(hamming_search_simd_core_avx, Avx, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_hamming_search_simd_core!(hamming_search_simd_core_sse, Sse, "sse4.1");
+/// Returns whether two strings are equal, like `a == b`.
+///
+/// Strings with different lengths are never equal.
+/// There are no constraints on how `a` and `b` are aligned and padded.
+/// If AVX2 is supported, then 32 bytes are compared at a time, and this returns early at the
+/// first block that contains a mismatch. Otherwise, this will automatically fall back to the
+/// standard slice comparison.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// assert!(bytes_eq(b"abcdef", b"abcdef"));
+/// assert!(!bytes_eq(b"abcdef", b"abcdeg"));
+/// assert!(!bytes_eq(b"abc", b"abcd"));
+/// ```
+pub fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
 
-/// Returns an iterator over best `Match`s by searching through the text `haystack`
-/// for the pattern `needle` using SIMD.
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { bytes_eq_avx(a, b) };
+        }
+    }
+
+    a == b
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn bytes_eq_avx(a: &[u8], b: &[u8]) -> bool {
+    use core::arch::x86_64::*;
+
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Bytes equal for target \"avx2\".");
+    }
+
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    let mut i = 0;
+
+    while i + 32 <= len {
+        let eq = _mm256_cmpeq_epi8(
+            _mm256_loadu_si256(a_ptr.add(i) as *const __m256i),
+            _mm256_loadu_si256(b_ptr.add(i) as *const __m256i),
+        );
+
+        // all bits are set only if every byte is equal
+        if _mm256_movemask_epi8(eq) != -1 {
+            return false;
+        }
+
+        i += 32;
+    }
+
+    a[i..] == b[i..]
+}
+
+/// Returns the number of mismatched bases between two DNA sequences that were packed with
+/// `pack_dna`.
 ///
-/// This will automatically fall back to a scalar alternative if AVX2 and SSE4.1
-/// are not supported.
-/// Null bytes/characters are not supported.
-/// The length of `needle` must be less than or equal to the length of `haystack`.
-/// Each returned `Match` requires at least half or more bytes of the `needle` to match
-/// somewhere in the `haystack`.
-/// Only the matches with the lowest Hamming distance are returned.
-/// Internally, this calls `hamming_search_simd`.
+/// Each byte holds four bases with two bits per base, so this compares four times as many bases
+/// per instruction as the byte-based Hamming distance routines.
+/// Each packed byte is XORed, then the two bits of each base are folded together with OR, so
+/// that one bit is set for each mismatched base before the bits are counted.
+/// Both sequences must have been packed from sequences of the same length, so that the unused
+/// bases in the last byte are zero in both.
+/// There are no constraints on how `a` and `b` are aligned and padded.
+/// If AVX2 is supported, then the bases are counted in 256-bit chunks. Otherwise, this will
+/// automatically fall back to counting the bases in 64-bit words.
 ///
 /// # Arguments
-/// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
+/// * `a` - first packed sequence (slice)
+/// * `b` - second packed sequence (slice)
 ///
 /// # Panics
-/// * When there are zero/null bytes in the `haystack` string.
+/// * If the length of `a` does not equal the length of `b`.
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
-/// let matches: Vec<Match> = hamming_search(b"abc", b"  abd").collect();
+/// # use triple_accel::hamming::*;
+/// let a = pack_dna(b"ACGTACGTA");
+/// let b = pack_dna(b"ACGAACCTA");
 ///
-/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// assert!(hamming_dna(&a, &b) == 2);
+/// ```
+#[must_use]
+pub fn hamming_dna(a: &[u8], b: &[u8]) -> u32 {
+    assert!(a.len() == b.len());
+
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { hamming_dna_avx(a, b) };
+        }
+    }
+
+    hamming_dna_words(a, b)
+}
+
+fn hamming_dna_words(a: &[u8], b: &[u8]) -> u32 {
+    use core::convert::TryInto;
+
+    let mut res = 0u32;
+    let a_words = a.chunks_exact(8);
+    let b_words = b.chunks_exact(8);
+    let a_rem = a_words.remainder();
+    let b_rem = b_words.remainder();
+
+    for (x, y) in a_words.zip(b_words) {
+        let x = u64::from_ne_bytes(x.try_into().unwrap());
+        let y = u64::from_ne_bytes(y.try_into().unwrap());
+        let r = x ^ y;
+        // fold the high bit of each base onto the low bit
+        // ...01010101
+        res += ((r | (r >> 1)) & 0x5555555555555555u64).count_ones();
+    }
+
+    for (x, y) in a_rem.iter().zip(b_rem) {
+        let r = x ^ y;
+        res += ((r | (r >> 1)) & 0x55u8).count_ones();
+    }
+
+    res
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn hamming_dna_avx(a: &[u8], b: &[u8]) -> u32 {
+    use core::arch::x86_64::*;
+
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Hamming DNA for target \"avx2\".");
+    }
+
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    // number of set bits for each 4-bit value
+    let lookup = _mm256_setr_epi8(
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3,
+        3, 4,
+    );
+    let low_mask = _mm256_set1_epi8(0x0f);
+    let base_mask = _mm256_set1_epi8(0x55);
+    let zeros = _mm256_setzero_si256();
+    let mut sums = _mm256_setzero_si256();
+    let mut i = 0;
+
+    while i + 32 <= len {
+        let x = _mm256_xor_si256(
+            _mm256_loadu_si256(a_ptr.add(i) as *const __m256i),
+            _mm256_loadu_si256(b_ptr.add(i) as *const __m256i),
+        );
+        // one bit for each mismatched base; bits shifted across bytes are masked out
+        let x = _mm256_and_si256(_mm256_or_si256(x, _mm256_srli_epi16(x, 1)), base_mask);
+        let lo = _mm256_shuffle_epi8(lookup, _mm256_and_si256(x, low_mask));
+        let hi = _mm256_shuffle_epi8(lookup, _mm256_and_si256(_mm256_srli_epi16(x, 4), low_mask));
+        // horizontally add the byte counts into four 64-bit sums
+        sums = _mm256_add_epi64(sums, _mm256_sad_epu8(_mm256_add_epi8(lo, hi), zeros));
+        i += 32;
+    }
+
+    let res = _mm256_extract_epi64(sums, 0)
+        + _mm256_extract_epi64(sums, 1)
+        + _mm256_extract_epi64(sums, 2)
+        + _mm256_extract_epi64(sums, 3);
+
+    (res as u32) + hamming_dna_words(&a[i..], &b[i..])
+}
+
+/// Packs a DNA sequence into two bits per base, for use with `hamming_dna`.
+///
+/// The bases `A`, `C`, `G`, and `T` (or their lowercase counterparts) are encoded as 0, 1, 2,
+/// and 3. Four bases are packed into each byte, starting from the lowest bits, and the unused
+/// bases in the last byte are set to zero. The length of the sequence is not stored, so it must
+/// be kept separately to call `unpack_dna`.
+///
+/// # Arguments
+/// * `seq` - DNA sequence (slice)
+///
+/// # Panics
+/// * When there are bytes other than `A`, `C`, `G`, or `T` (ignoring case) in `seq`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let packed = pack_dna(b"ACGTG");
+///
+/// assert!(packed == vec![0b11100100, 0b10]);
+/// ```
+pub fn pack_dna(seq: &[u8]) -> Vec<u8> {
+    let mut res = alloc::vec![0u8; (seq.len() + 3) >> 2];
+
+    for (i, &c) in seq.iter().enumerate() {
+        let bits = match c {
+            b'A' | b'a' => 0u8,
+            b'C' | b'c' => 1u8,
+            b'G' | b'g' => 2u8,
+            b'T' | b't' => 3u8,
+            _ => panic!("Invalid DNA base: {}", c),
+        };
+        res[i >> 2] |= bits << ((i & 3) << 1);
+    }
+
+    res
+}
+
+/// Unpacks the first `len` bases of a DNA sequence that was packed with `pack_dna`.
+///
+/// The bases are returned as uppercase `A`, `C`, `G`, and `T`.
+///
+/// # Arguments
+/// * `packed` - packed DNA sequence (slice)
+/// * `len` - number of bases in the original sequence
+///
+/// # Panics
+/// * If `len` is greater than the number of bases that fit in `packed`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let seq = unpack_dna(&pack_dna(b"acgtG"), 5);
+///
+/// assert!(seq == b"ACGTG");
+/// ```
+pub fn unpack_dna(packed: &[u8], len: usize) -> Vec<u8> {
+    assert!(len <= packed.len() << 2);
+
+    (0..len)
+        .map(|i| b"ACGT"[((packed[i >> 2] >> ((i & 3) << 1)) & 3) as usize])
+        .collect()
+}
+
+/// Returns the hamming distance between a DNA sequence `a` and the reverse complement of another
+/// DNA sequence `b`.
+///
+/// The complement swaps `A` with `T` and `C` with `G`, keeping the case of each base. All other
+/// bytes (like `N`) are their own complement. The bytes are compared exactly, so the case matters.
+/// The length of `a` and `b` must be the same.
+/// The reverse complement of `b` is never stored: `b` is read backwards and each base is
+/// complemented during the comparison.
+/// If AVX2 is supported, then 32 bytes of `b` are loaded at a time and reversed and
+/// complemented with byte shuffles. Otherwise, this will automatically fall back to comparing each
+/// byte with a lookup table.
+///
+/// # Arguments
+/// * `a` - first sequence (slice)
+/// * `b` - second sequence (slice), which is reverse complemented
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// // the reverse complement of AACGT is ACGTT
+/// assert!(hamming_revcomp(b"ACGTT", b"AACGT") == 0);
+/// assert!(hamming_revcomp(b"ACGTA", b"AACGT") == 1);
+/// ```
+#[must_use]
+pub fn hamming_revcomp(a: &[u8], b: &[u8]) -> u32 {
+    assert!(a.len() == b.len());
+
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { hamming_revcomp_avx(a, b) };
+        }
+    }
+
+    hamming_revcomp_scalar(a, b)
+}
+
+const DNA_COMPLEMENT: [u8; 256] = dna_complement_table();
+
+const fn dna_complement_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let c = i as u8;
+        table[i] = match c {
+            b'A' => b'T',
+            b'T' => b'A',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'a' => b't',
+            b't' => b'a',
+            b'c' => b'g',
+            b'g' => b'c',
+            _ => c,
+        };
+        i += 1;
+    }
+
+    table
+}
+
+fn hamming_revcomp_scalar(a: &[u8], b: &[u8]) -> u32 {
+    let mut res = 0u32;
+
+    for (&x, &y) in a.iter().zip(b.iter().rev()) {
+        res += (x != DNA_COMPLEMENT[y as usize]) as u32;
+    }
+
+    res
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn hamming_revcomp_avx(a: &[u8], b: &[u8]) -> u32 {
+    use core::arch::x86_64::*;
+
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Hamming reverse complement for target \"avx2\".");
+    }
+
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    // reverses the bytes within each 128-bit lane
+    let reverse = _mm256_setr_epi8(
+        15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8, 7, 6,
+        5, 4, 3, 2, 1, 0,
+    );
+    // indexed by the low 4 bits of each byte: A/a = 1, C/c = 3, T/t = 4, G/g = 7
+    let bases = _mm256_setr_epi8(
+        0, b'a' as i8, 0, b'c' as i8, b't' as i8, 0, 0, b'g' as i8, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        b'a' as i8, 0, b'c' as i8, b't' as i8, 0, 0, b'g' as i8, 0, 0, 0, 0, 0, 0, 0, 0,
+    );
+    // A ^ T = 0x15 and C ^ G = 0x04, for both cases
+    let flips = _mm256_setr_epi8(
+        0, 0x15, 0, 0x04, 0x15, 0, 0, 0x04, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x15, 0, 0x04, 0x15, 0, 0,
+        0x04, 0, 0, 0, 0, 0, 0, 0, 0,
+    );
+    let low_mask = _mm256_set1_epi8(0x0f);
+    let case_bit = _mm256_set1_epi8(0x20);
+    let mut res = 0u32;
+    let mut i = 0;
+
+    while i + 32 <= len {
+        let y = _mm256_loadu_si256(b_ptr.add(len - i - 32) as *const __m256i);
+        // reverse the bytes in each lane, then swap the lanes
+        let y = _mm256_permute4x64_epi64(_mm256_shuffle_epi8(y, reverse), 0b01001110i32);
+        let low = _mm256_and_si256(y, low_mask);
+        // only flip the bits of bytes that are bases
+        let is_base = _mm256_cmpeq_epi8(
+            _mm256_or_si256(y, case_bit),
+            _mm256_shuffle_epi8(bases, low),
+        );
+        let y = _mm256_xor_si256(
+            y,
+            _mm256_and_si256(_mm256_shuffle_epi8(flips, low), is_base),
+        );
+        let eq = _mm256_cmpeq_epi8(_mm256_loadu_si256(a_ptr.add(i) as *const __m256i), y);
+        res += 32 - (_mm256_movemask_epi8(eq) as u32).count_ones();
+        i += 32;
+    }
+
+    res + hamming_revcomp_scalar(&a[i..], &b[..len - i])
+}
+
+/// Returns the hamming distance between two DNA or RNA strings with IUPAC nucleotide codes,
+/// using SIMD acceleration.
+///
+/// The length of `a` and `b` must be the same.
+/// Each IUPAC code stands for a set of bases, and two codes match if their sets share a base.
+/// For example, `N` matches any base or code, `R` (A or G) matches `A`, `G`, `R`, `D`, and so on,
+/// while `R` and `Y` (C or T) do not match. The codes are `A`, `C`, `G`, `T`, `U`, `R`, `Y`, `S`,
+/// `W`, `K`, `M`, `B`, `D`, `H`, `V`, and `N`, in both uppercase and lowercase, and `U` is treated
+/// like `T`. All other bytes, like gaps, only match themselves.
+/// Each code is mapped to a 4-bit set of bases, and a 16 by 16 table of which sets are compatible
+/// is consulted for each pair of characters. With AVX2, 32 characters are mapped at a time by
+/// looking up the low 4 bits of each byte in a shuffle, and the sets of both strings are checked
+/// for overlapping bases, which gives the same result as the table.
+/// If AVX2 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// assert!(hamming_iupac(b"ACGT", b"ACGN") == 0);
+/// assert!(hamming_iupac(b"ACGT", b"RCGY") == 0);
+/// assert!(hamming_iupac(b"ACGT", b"YCGR") == 2);
+/// ```
+#[must_use]
+pub fn hamming_iupac(a: &[u8], b: &[u8]) -> u32 {
+    assert!(a.len() == b.len());
+
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { hamming_iupac_avx(a, b) };
+        }
+    }
+
+    hamming_iupac_scalar(a, b)
+}
+
+/// Sets of bases for each IUPAC code, with A = 1, C = 2, G = 4, and T/U = 8.
+const IUPAC_BASES: [u8; 256] = iupac_bases_table();
+
+const fn iupac_bases_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        // fold lowercase letters to uppercase
+        let c = if i >= b'a' as usize && i <= b'z' as usize {
+            (i as u8) - 0x20
+        } else {
+            i as u8
+        };
+        table[i] = match c {
+            b'A' => 0b0001,
+            b'C' => 0b0010,
+            b'G' => 0b0100,
+            b'T' | b'U' => 0b1000,
+            b'R' => 0b0101,
+            b'Y' => 0b1010,
+            b'S' => 0b0110,
+            b'W' => 0b1001,
+            b'K' => 0b1100,
+            b'M' => 0b0011,
+            b'B' => 0b1110,
+            b'D' => 0b1101,
+            b'H' => 0b1011,
+            b'V' => 0b0111,
+            b'N' => 0b1111,
+            _ => 0,
+        };
+        i += 1;
+    }
+
+    table
+}
+
+/// Whether two sets of bases share a base; the empty set is not compatible with anything.
+const IUPAC_COMPATIBLE: [[bool; 16]; 16] = iupac_compatible_table();
+
+const fn iupac_compatible_table() -> [[bool; 16]; 16] {
+    let mut table = [[false; 16]; 16];
+    let mut i = 0;
+
+    while i < 16 {
+        let mut j = 0;
+
+        while j < 16 {
+            table[i][j] = (i & j) != 0;
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+fn hamming_iupac_scalar(a: &[u8], b: &[u8]) -> u32 {
+    let mut res = 0u32;
+
+    for (&x, &y) in a.iter().zip(b) {
+        let compatible =
+            IUPAC_COMPATIBLE[IUPAC_BASES[x as usize] as usize][IUPAC_BASES[y as usize] as usize];
+        res += (x != y && !compatible) as u32;
+    }
+
+    res
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn hamming_iupac_avx(a: &[u8], b: &[u8]) -> u32 {
+    use core::arch::x86_64::*;
+
+    #[cfg(feature = "debug")]
+    {
+        std::println!("Debug: Hamming IUPAC for target \"avx2\".");
+    }
+
+    let len = a.len();
+    let a_ptr = a.as_ptr();
+    let b_ptr = b.as_ptr();
+    // sets of bases for the lowercase letters, indexed by the low 4 bits of each byte;
+    // 0x60 to 0x6f is `abcdefghijklmno and 0x70 to 0x7f is pqrstuvwxyz{|}~
+    let mut low_table = [0i8; 32];
+    let mut high_table = [0i8; 32];
+
+    for i in 0..16 {
+        low_table[i] = IUPAC_BASES[0x60 + i] as i8;
+        low_table[i + 16] = low_table[i];
+        high_table[i] = IUPAC_BASES[0x70 + i] as i8;
+        high_table[i + 16] = high_table[i];
+    }
+
+    let low_table = _mm256_loadu_si256(low_table.as_ptr() as *const __m256i);
+    let high_table = _mm256_loadu_si256(high_table.as_ptr() as *const __m256i);
+    let zeros = _mm256_setzero_si256();
+    let mut res = 0u32;
+    let mut i = 0;
+
+    while i + 32 <= len {
+        let x = _mm256_loadu_si256(a_ptr.add(i) as *const __m256i);
+        let y = _mm256_loadu_si256(b_ptr.add(i) as *const __m256i);
+        let x_bases = iupac_bases_avx(x, low_table, high_table);
+        let y_bases = iupac_bases_avx(y, low_table, high_table);
+        let disjoint = _mm256_cmpeq_epi8(_mm256_and_si256(x_bases, y_bases), zeros);
+        let mismatch = _mm256_andnot_si256(_mm256_cmpeq_epi8(x, y), disjoint);
+        res += (_mm256_movemask_epi8(mismatch) as u32).count_ones();
+        i += 32;
+    }
+
+    res + hamming_iupac_scalar(&a[i..], &b[i..])
+}
+
+/// Look up the set of bases of each byte, which is zero if the byte is not an IUPAC code.
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn iupac_bases_avx(
+    v: core::arch::x86_64::__m256i,
+    low_table: core::arch::x86_64::__m256i,
+    high_table: core::arch::x86_64::__m256i,
+) -> core::arch::x86_64::__m256i {
+    use core::arch::x86_64::*;
+
+    let v = _mm256_or_si256(v, _mm256_set1_epi8(0x20));
+    let low = _mm256_and_si256(v, _mm256_set1_epi8(0x0f));
+    let high = _mm256_and_si256(v, _mm256_set1_epi8(0xf0u8 as i8));
+    let low_bases = _mm256_and_si256(
+        _mm256_shuffle_epi8(low_table, low),
+        _mm256_cmpeq_epi8(high, _mm256_set1_epi8(0x60)),
+    );
+    let high_bases = _mm256_and_si256(
+        _mm256_shuffle_epi8(high_table, low),
+        _mm256_cmpeq_epi8(high, _mm256_set1_epi8(0x70)),
+    );
+    _mm256_or_si256(low_bases, high_bases)
+}
+
+/// Returns the hamming distance between two strings, ignoring ASCII case.
+///
+/// The length of `a` and `b` must be the same.
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// The strings are not copied: the case of each byte is folded during the comparison.
+/// With AVX2 or SSE4.1, the bytes are folded as they are loaded into vectors for counting
+/// mismatches. Otherwise, this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let dist = hamming_ci(b"ABC", b"abd");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn hamming_ci(a: &[u8], b: &[u8]) -> u32 {
+    let len = a.len();
+    assert!(len == b.len());
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { Avx::count_mismatches(a.as_ptr(), b.as_ptr(), len, true) };
+        } else if cfg!(feature = "jewel-sse") {
+            return unsafe { Sse::count_mismatches(a.as_ptr(), b.as_ptr(), len, true) };
+        }
+    }
+
+    let mut res = 0u32;
+
+    for i in 0..len {
+        res += !a[i].eq_ignore_ascii_case(&b[i]) as u32;
+    }
+
+    res
+}
+
+/// Returns the hamming distance between two strings, divided by the length of the strings.
+///
+/// The length of `a` and `b` must be the same.
+/// The result is in the range [0, 1], where 0 indicates that the strings are equal.
+/// If both `a` and `b` are empty, then 0 is returned.
+/// Internally, this calls `hamming`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let dist = hamming_normalized(b"abcd", b"abdd");
+///
+/// assert!(dist == 0.25);
+/// ```
+#[must_use]
+pub fn hamming_normalized(a: &[u8], b: &[u8]) -> f64 {
+    let dist = hamming(a, b);
+
+    if a.is_empty() {
+        return 0.0;
+    }
+
+    (dist as f64) / (a.len() as f64)
+}
+
+/// Returns the fraction of positions where two strings have the same character, which is the
+/// sequence identity that is usually reported by bioinformatics tools.
+///
+/// The length of `a` and `b` must be the same.
+/// The result is in the range [0, 1], where 1 indicates that the strings are equal. This is the
+/// complement of `hamming_normalized`, but it is calculated from the number of matching
+/// characters, so for example, one mismatch in 100 characters gives exactly 0.99.
+/// If both `a` and `b` are empty, then 1 is returned.
+/// Internally, this calls `hamming`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let identity = hamming_identity(b"abcd", b"abdd");
+///
+/// assert!(identity == 0.75);
+/// ```
+#[must_use]
+pub fn hamming_identity(a: &[u8], b: &[u8]) -> f64 {
+    let dist = hamming(a, b);
+
+    if a.is_empty() {
+        return 1.0;
+    }
+
+    ((a.len() - dist as usize) as f64) / (a.len() as f64)
+}
+
+/// Returns the hamming distances between the string `a` and each of the strings in `candidates`.
+///
+/// The length of each candidate must be the same as the length of `a`.
+/// The returned distances are in the same order as `candidates`.
+/// Internally, this calls `hamming` for each candidate.
+/// `hamming` does not allocate any Jewel buffers, so unlike `levenshtein_batch`, no per-thread
+/// `Scratch` is needed.
+/// If the `rayon` feature is enabled, then the candidates are split across multiple threads.
+/// Otherwise, the candidates are processed one by one on the current thread.
+///
+/// # Arguments
+/// * `a` - query string (slice)
+/// * `candidates` - strings to compare against `a` (slice of slices)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of any candidate.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let dists = hamming_batch(b"abc", &[b"abd", b"abc", b"xyz"]);
+///
+/// assert!(dists == vec![1, 0, 3]);
+/// ```
+pub fn hamming_batch(a: &[u8], candidates: &[&[u8]]) -> Vec<u32> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        candidates.par_iter().map(|b| hamming(a, b)).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut res = Vec::with_capacity(candidates.len());
+
+        for b in candidates {
+            res.push(hamming(a, b));
+        }
+
+        res
+    }
+}
+
+/// A struct for computing the hamming distance between two strings that arrive in chunks.
+///
+/// The chunks of the two strings do not have to be the same length. Bytes that do not have a
+/// counterpart in the other string yet are buffered until the next call to `update`, so
+/// the strings are never concatenated.
+/// Internally, this calls `hamming` on each range of paired bytes.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let mut stream = HammingStream::new();
+/// stream.update(b"ab", b"abc");
+/// stream.update(b"dd", b"d");
+///
+/// assert!(stream.finish() == 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct HammingStream {
+    dist: u32,
+    pending: Vec<u8>,
+    pending_a: bool,
+}
+
+impl HammingStream {
+    /// Create a new `HammingStream` with a distance of zero.
+    pub fn new() -> Self {
+        Self {
+            dist: 0,
+            pending: Vec::new(),
+            pending_a: false,
+        }
+    }
+
+    /// Add the next chunks of the two strings.
+    ///
+    /// # Arguments
+    /// * `a_chunk` - next chunk of the first string (slice)
+    /// * `b_chunk` - next chunk of the second string (slice)
+    pub fn update(&mut self, a_chunk: &[u8], b_chunk: &[u8]) {
+        let mut a = a_chunk;
+        let mut b = b_chunk;
+
+        // pair the buffered bytes with the new bytes from the other string first
+        if !self.pending.is_empty() {
+            let (same, other) = if self.pending_a {
+                (&mut a, &mut b)
+            } else {
+                (&mut b, &mut a)
+            };
+            let len = cmp::min(self.pending.len(), other.len());
+            self.dist += hamming(&self.pending[..len], &other[..len]);
+            self.pending.drain(..len);
+            *other = &other[len..];
+
+            if !self.pending.is_empty() {
+                self.pending.extend_from_slice(same);
+                return;
+            }
+        }
+
+        let len = cmp::min(a.len(), b.len());
+        self.dist += hamming(&a[..len], &b[..len]);
+
+        if a.len() > len {
+            self.pending.extend_from_slice(&a[len..]);
+            self.pending_a = true;
+        } else if b.len() > len {
+            self.pending.extend_from_slice(&b[len..]);
+            self.pending_a = false;
+        }
+    }
+
+    /// Returns the hamming distance between the two strings.
+    ///
+    /// # Panics
+    /// * If the total length of the first string does not equal the total length of the second
+    ///   string.
+    pub fn finish(self) -> u32 {
+        assert!(self.pending.is_empty());
+
+        self.dist
+    }
+}
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack`
+/// for the pattern `needle` using SIMD.
+///
+/// This is done by counting mismatches at every position in `haystack`.
+/// This will automatically fall back to `hamming_search_naive_with_opts` if AVX2 and SSE4.1
+/// are not supported.
+/// Null bytes/characters are not supported.
+/// The length of `needle` must be less than or equal to the length of `haystack`.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somwhere in the `haystack`.
+/// Only the matches with the lowest Hamming distance are returned.
+/// This should be faster than `hamming_search_naive`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_simd(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn hamming_search_simd<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search_simd_with_opts(
+        needle,
+        haystack,
+        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD, with extra options.
+///
+/// This is done by using SIMD to count mismatches at every position in `haystack`.
+/// This will automatically fall back to `hamming_search_naive_with_opts` if AVX2 and SSE4.1
+/// are not supported.
+/// Null bytes/characters are not supported.
+/// The length of `needle` must be less than or equal to the length of `haystack`.
+/// This should be faster than `hamming_search_naive_with_opts`.
+/// The `haystack` is only read in place and never copied, so it can be a memory-mapped file.
+/// Apart from the returned matches, the extra memory used is O(1). With `SearchType::All`, the
+/// matches are lazily generated. With `SearchType::Best`, the haystack is searched before the
+/// first match is returned, and only the matches with the lowest Hamming distance so far are kept.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+/// * `search_type` - whether to only return the "best" matches with the lowest Hamming distance, or
+/// all matches
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_simd_with_opts(b"abc", b"  abd", 1, SearchType::All).collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn hamming_search_simd_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    if needle.len() > haystack.len() {
+        return Box::new(iter::empty());
+    }
+
+    if needle.len() == 0 {
+        return Box::new(iter::empty());
+    }
+
+    check_no_null_bytes(haystack);
+
+    hamming_search_naive_core(needle, haystack, k, search_type, false, false, None)
+}
+
+macro_rules! create_hamming_search_simd_core {
+    ($name:ident, $jewel:ty, $target:literal) => {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        #[cfg(not(feature = "pure-scalar"))]
+        #[target_feature(enable = $target)]
+        unsafe fn $name<'a>(
+            needle: &'a [u8],
+            haystack: &'a [u8],
+            k: u32,
+            search_type: SearchType,
+            case_insensitive: bool,
+            reverse: bool,
+            wildcard: Option<u8>,
+        ) -> Box<dyn Iterator<Item = Match> + 'a> {
+            #[cfg(feature = "debug")]
+            {
+                std::println!(
+                    "Debug: Hamming search Jewel vector type {} for target {}.",
+                    stringify!($jewel),
+                    stringify!($target)
+                );
+            }
+
+            let needle_len = needle.len();
+            let haystack_len = haystack.len();
+            let mut needle_vector = <$jewel>::loadu(needle.as_ptr(), needle_len);
+
+            if case_insensitive {
+                needle_vector.ascii_fold_mut();
+            }
+
+            // calculate len using the unused bytes in the needle Jewel vector, for speed
+            // there may be leftover positions at the end of haystack that need to be calculated
+            // using a scalar search
+            // there should be no null bytes in the strings
+            let len = if needle_vector.upper_bound() > haystack_len {
+                0
+            } else {
+                haystack_len + 1 - needle_vector.upper_bound()
+            };
+            let real_len = haystack_len + 1 - needle_len;
+            let haystack_ptr = haystack.as_ptr();
+            let mut curr_k = k;
+            let mut i = 0;
+
+            let res = iter::from_fn(move || {
+                'outer: while i < real_len {
+                    // when scanning in reverse, positions are visited right to left, but the
+                    // resulting matches are still in forward coordinates
+                    let start = if reverse { real_len - 1 - i } else { i };
+                    i += 1;
+
+                    let final_res = if start < len {
+                        <$jewel>::vector_count_mismatches(
+                            &needle_vector,
+                            haystack_ptr.offset(start as isize),
+                            needle_len,
+                            case_insensitive,
+                            wildcard,
+                        )
+                    } else {
+                        // scalar search
+                        let mut final_res = 0u32;
+
+                        for j in 0..needle_len {
+                            let n = *needle.get_unchecked(j);
+                            let h = *haystack.get_unchecked(start + j);
+                            // wildcard bytes in the needle match anything
+                            final_res += (if case_insensitive {
+                                !n.eq_ignore_ascii_case(&h)
+                            } else {
+                                n != h
+                            } && Some(n) != wildcard) as u32;
+
+                            if final_res > curr_k {
+                                continue 'outer;
+                            }
+                        }
+
+                        final_res
+                    };
+
+                    if final_res > curr_k {
+                        continue;
+                    }
+
+                    match search_type {
+                        SearchType::Best => curr_k = final_res,
+                        _ => (),
+                    }
+
+                    return Some((
+                        Match {
+                            start,
+                            end: start + needle_len,
+                            k: final_res,
+                        },
+                        curr_k,
+                    ));
+                }
+
+                None
+            });
+
+            if search_type == SearchType::Best {
+                // only matches with the lowest k so far are kept, so the memory used does not grow
+                // with the length of the haystack
+                let mut res_vec: Vec<Match> = Vec::new();
+                res.for_each(|m| {
+                    if m.1 < curr_k {
+                        res_vec.clear();
+                    }
+
+                    res_vec.push(m.0);
+                    curr_k = m.1;
+                });
+
+                return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
+            }
+
+            Box::new(res.map(|m| m.0))
+        }
+    };
+}
+
+// generate different versions for different intrinsics
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_hamming_search_simd_core!(hamming_search_simd_core_avx, Avx, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_hamming_search_simd_core!(hamming_search_simd_core_sse, Sse, "sse4.1");
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack`
+/// for the pattern `needle` using SIMD.
+///
+/// This will automatically fall back to a scalar alternative if AVX2 and SSE4.1
+/// are not supported.
+/// Null bytes/characters are not supported.
+/// The length of `needle` must be less than or equal to the length of `haystack`.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+/// Only the matches with the lowest Hamming distance are returned.
+/// Internally, this calls `hamming_search_simd`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches: Vec<Match> = hamming_search(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
 /// ```
 pub fn hamming_search<'a>(
     needle: &'a [u8],
     haystack: &'a [u8],
 ) -> Box<dyn Iterator<Item = Match> + 'a> {
-    hamming_search_simd(needle, haystack)
+    hamming_search_simd(needle, haystack)
+}
+
+/// Returns an iterator over best `Match`s by searching through the bytes of the `&str`
+/// `haystack` for the bytes of the `&str` pattern `needle`.
+///
+/// This is the same as calling `hamming_search` with `needle.as_bytes()` and
+/// `haystack.as_bytes()`. The `start` and `end` of each returned `Match` are byte offsets into
+/// `haystack`, not char offsets, so they can be used to slice `haystack` directly only when they
+/// fall on char boundaries. For ASCII strings, byte offsets and char offsets are the same.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (&str)
+/// * `haystack` - text string (&str)
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches: Vec<Match> = hamming_search_str("abc", "  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+///
+/// // 'é' is two bytes, so the match starts at byte 3, not char 2
+/// let matches: Vec<Match> = hamming_search_str("abc", "éxabc").collect();
+///
+/// assert!(matches == vec![Match{start: 3, end: 6, k: 0}]);
+/// ```
+pub fn hamming_search_str<'a>(
+    needle: &'a str,
+    haystack: &'a str,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search(needle.as_bytes(), haystack.as_bytes())
+}
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack`
+/// for the pattern `needle` using the scalar implementation.
+///
+/// This has the same signature and output as `hamming_search`, but it never uses SIMD, so it is
+/// always available regardless of the target features or crate feature flags that are enabled.
+/// The length of `needle` must be less than or equal to the length of `haystack`.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+/// Only the matches with the lowest Hamming distance are returned.
+/// Internally, this calls `hamming_search_naive`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches: Vec<Match> = hamming_search_scalar(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn hamming_search_scalar<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search_naive(needle, haystack)
+}
+
+/// Returns the number of `Match`s found by searching through the text `haystack` for the
+/// pattern `needle` using SIMD.
+///
+/// Every position with a Hamming distance less than or equal to `k` is counted, without
+/// allocating a vector of `Match`s.
+/// If `needle` is empty or longer than `haystack`, then zero is returned.
+/// Internally, this will call `hamming_search_simd_with_opts`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let count = hamming_search_count(b"abc", b"  abd  abc  xyz", 1);
+///
+/// assert!(count == 2);
+/// ```
+pub fn hamming_search_count(needle: &[u8], haystack: &[u8], k: u32) -> usize {
+    hamming_search_simd_with_opts(needle, haystack, k, SearchType::All).count()
+}
+
+/// Returns the Hamming distance between `needle` and the window of `haystack` at every offset.
+///
+/// The distance at index `i` is between `needle` and `haystack[i..i + needle.len()]`, so there
+/// are `haystack.len() - needle.len() + 1` distances. Unlike the search routines, every offset is
+/// reported, regardless of how many mismatches it has.
+/// If `needle` is longer than `haystack`, then an empty vector is returned.
+/// Internally, this will call `hamming` for each window.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let profile = hamming_search_profile(b"abc", b"abdabc");
+///
+/// assert!(profile == vec![1, 3, 3, 0]);
+/// ```
+pub fn hamming_search_profile(needle: &[u8], haystack: &[u8]) -> Vec<u32> {
+    if needle.len() > haystack.len() {
+        return alloc::vec![];
+    }
+
+    (0..=haystack.len() - needle.len())
+        .map(|i| hamming(needle, &haystack[i..i + needle.len()]))
+        .collect()
+}
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack`
+/// for the pattern `needle`, ignoring ASCII case.
+///
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// Other than the case insensitivity, this behaves exactly like `hamming_search`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_ci(b"ABC", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn hamming_search_ci<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search_ci_with_opts(
+        needle,
+        haystack,
+        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle`, ignoring ASCII case, with extra options.
+///
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// Other than the case insensitivity, this behaves exactly like `hamming_search_simd_with_opts`.
+/// With AVX2 or SSE4.1, the needle is folded once and each window of the haystack is folded as it
+/// is loaded into vectors for counting mismatches. Otherwise, this will automatically fall back
+/// to a scalar alternative.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+/// * `search_type` - whether to only return the "best" matches with the lowest Hamming distance, or
+///   all matches
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_ci_with_opts(b"HELLO", b"say hello", 0, SearchType::All).collect();
+///
+/// assert!(matches == vec![Match{start: 4, end: 9, k: 0}]);
+/// ```
+pub fn hamming_search_ci_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    if needle.len() > haystack.len() {
+        return Box::new(iter::empty());
+    }
+
+    if needle.is_empty() {
+        return Box::new(iter::empty());
+    }
+
+    check_no_null_bytes(haystack);
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe {
+                hamming_search_simd_core_avx(needle, haystack, k, search_type, true, false, None)
+            };
+        } else if cfg!(feature = "jewel-sse") {
+            return unsafe {
+                hamming_search_simd_core_sse(needle, haystack, k, search_type, true, false, None)
+            };
+        }
+    }
+
+    hamming_search_naive_core(needle, haystack, k, search_type, true, false, None)
+}
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack`
+/// for the pattern `needle` from right to left.
+///
+/// Matches closer to the end of `haystack` are returned first, so this is useful for finding
+/// matches that are anchored near the end of `haystack`. The `start` and `end` indexes of each
+/// returned `Match` are still in forward coordinates.
+/// Other than the scanning direction, this behaves exactly like `hamming_search`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_rev(b"abc", b"abd  abc").collect();
+///
+/// assert!(matches == vec![Match{start: 5, end: 8, k: 0}]);
+/// ```
+pub fn hamming_search_rev<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    hamming_search_rev_with_opts(
+        needle,
+        haystack,
+        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` from right to left, with extra options.
+///
+/// Matches closer to the end of `haystack` are returned first. The `start` and `end` indexes of
+/// each returned `Match` are still in forward coordinates.
+/// Other than the scanning direction, this behaves exactly like `hamming_search_simd_with_opts`.
+/// This will automatically fall back to a scalar alternative if AVX2 and SSE4.1 are not supported.
+/// The number of mismatches in a window does not depend on the direction it is read in, so
+/// each window is compared with the same forward vector loads as a left to right search, and
+/// only the order of the windows is reversed. Loading reversed vectors with
+/// `Jewel::slow_loadu` would require a slow byte by byte load for every window.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+/// * `search_type` - whether to only return the "best" matches with the lowest Hamming distance, or
+///   all matches
+///
+/// # Panics
+/// * When there are zero/null bytes in the `haystack` string.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_rev_with_opts(b"abc", b"abd  abc", 1, SearchType::All).collect();
+///
+/// assert!(matches == vec![Match{start: 5, end: 8, k: 0}, Match{start: 0, end: 3, k: 1}]);
+/// ```
+pub fn hamming_search_rev_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    if needle.len() > haystack.len() {
+        return Box::new(iter::empty());
+    }
+
+    if needle.is_empty() {
+        return Box::new(iter::empty());
+    }
+
+    check_no_null_bytes(haystack);
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        if cfg!(feature = "jewel-avx") {
+            return unsafe {
+                hamming_search_simd_core_avx(needle, haystack, k, search_type, false, true, None)
+            };
+        } else if cfg!(feature = "jewel-sse") {
+            return unsafe {
+                hamming_search_simd_core_sse(needle, haystack, k, search_type, false, true, None)
+            };
+        }
+    }
+
+    hamming_search_naive_core(needle, haystack, k, search_type, false, true, None)
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle`, where `wildcard` bytes in `needle` match any byte.
+///
+/// Positions in `needle` that are equal to `wildcard` never contribute a mismatch, which is
+/// useful for masked fingerprint search. Wildcards in `haystack` are not special.
+/// Every match with a Hamming distance less than or equal to `k` is returned.
+/// The wildcard may be a zero/null byte, since only the `haystack` is restricted from having
+/// null bytes in the SIMD search. If there are null bytes in `haystack`, then this falls back to
+/// the naive scalar algorithm instead of panicking.
+/// This will also automatically fall back to the naive scalar algorithm if AVX2 and SSE4.1 are
+/// not supported.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+/// * `wildcard` - byte in `needle` that matches any byte
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches: Vec<Match> = hamming_search_wildcard(b"a\0c", b"axc abd", 0, 0u8).collect();
+///
+/// assert!(matches == vec![Match{start: 0, end: 3, k: 0}]);
+/// ```
+pub fn hamming_search_wildcard<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    wildcard: u8,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    if needle.len() > haystack.len() {
+        return Box::new(iter::empty());
+    }
+
+    if needle.is_empty() {
+        return Box::new(iter::empty());
+    }
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        if !haystack.contains(&0u8) {
+            if cfg!(feature = "jewel-avx") {
+                return unsafe {
+                    hamming_search_simd_core_avx(
+                        needle,
+                        haystack,
+                        k,
+                        SearchType::All,
+                        false,
+                        false,
+                        Some(wildcard),
+                    )
+                };
+            } else if cfg!(feature = "jewel-sse") {
+                return unsafe {
+                    hamming_search_simd_core_sse(
+                        needle,
+                        haystack,
+                        k,
+                        SearchType::All,
+                        false,
+                        false,
+                        Some(wildcard),
+                    )
+                };
+            }
+        }
+    }
+
+    hamming_search_naive_core(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        false,
+        false,
+        Some(wildcard),
+    )
+}
+
+/// Returns all `Match`s of several equal-length patterns by searching through the text
+/// `haystack` once, along with the index of the pattern in `needles` that matched.
+///
+/// Every match with a Hamming distance less than or equal to `k` is returned.
+/// The haystack is scanned a single time: at each position, every needle is compared against
+/// the same window of `haystack`, stopping early for a needle once it has more than `k`
+/// mismatches.
+/// The results are ordered by the `start` of the `Match`, and then by the index of the needle,
+/// so if multiple needles match at the same position, then the needle that comes first in
+/// `needles` is returned first.
+/// If `needles` is empty or the needles are empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needles` - pattern strings (slices), which must all have the same length
+/// * `haystack` - text string (slice)
+/// * `k` - number of mismatches allowed
+///
+/// # Panics
+/// * If the needles do not all have the same length.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::hamming::*;
+/// let matches = hamming_search_multi(&[b"abc", b"xyz"], b"xyz abc", 0);
+///
+/// assert!(matches == vec![(1, Match{start: 0, end: 3, k: 0}), (0, Match{start: 4, end: 7, k: 0})]);
+/// ```
+pub fn hamming_search_multi(needles: &[&[u8]], haystack: &[u8], k: u32) -> Vec<(usize, Match)> {
+    if needles.is_empty() {
+        return alloc::vec![];
+    }
+
+    let needle_len = needles[0].len();
+    assert!(needles.iter().all(|needle| needle.len() == needle_len));
+
+    if needle_len == 0 || needle_len > haystack.len() {
+        return alloc::vec![];
+    }
+
+    let mut res = Vec::new();
+
+    for start in 0..(haystack.len() + 1 - needle_len) {
+        // the window is shared by all of the needles
+        let window = &haystack[start..start + needle_len];
+
+        'needles: for (idx, needle) in needles.iter().enumerate() {
+            let mut final_res = 0u32;
+
+            for j in 0..needle_len {
+                final_res += (needle[j] != window[j]) as u32;
+
+                // early stop
+                if final_res > k {
+                    continue 'needles;
+                }
+            }
+
+            res.push((
+                idx,
+                Match {
+                    start,
+                    end: start + needle_len,
+                    k: final_res,
+                },
+            ));
+        }
+    }
+
+    res
 }
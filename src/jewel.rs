@@ -3,8 +3,11 @@
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
 
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+use core::arch::wasm32::*;
+
 use alloc::vec::Vec;
-use core::fmt;
+use core::{cmp, fmt, mem};
 
 /// Jewel provides a uniform interface for SIMD operations.
 ///
@@ -13,17 +16,64 @@ use core::fmt;
 /// Additionally, the functions should be inlined to the caller for
 /// maximum efficiency.
 pub trait Jewel: fmt::Display {
+    /// The width of each lane in bytes.
+    const LANE_BYTES: usize;
+
     /// Functions for allocating memory and creating a new Jewel vector.
+    /// At least one vector is always allocated, even if `len` is zero.
+    ///
+    /// `repeating_max` fills every lane with all ones (`u8::MAX`, `u16::MAX`, or `u32::MAX`),
+    /// which is used as the "infinity" sentinel in the dynamic programming. Lanes are unsigned and
+    /// costs are added with `adds`, which saturates, so adding any cost to the sentinel leaves it
+    /// at the sentinel instead of wrapping around. The sentinel is also what an overflowing
+    /// distance saturates to, so callers only pick a lane width if the largest distance that they
+    /// need is at most the sentinel minus one.
     unsafe fn repeating(val: u32, len: usize) -> Self;
     unsafe fn repeating_max(len: usize) -> Self;
 
+    /// Overwrite an existing Jewel vector with the same contents as `repeating` or
+    /// `repeating_max`, reusing its memory if possible.
+    unsafe fn reset(&mut self, val: u32, len: usize)
+    where
+        Self: Sized,
+    {
+        *self = Self::repeating(val, len);
+    }
+    unsafe fn reset_max(&mut self, len: usize)
+    where
+        Self: Sized,
+    {
+        *self = Self::repeating_max(len);
+    }
+
     /// Figure out the length of the created vector, which may
     /// be longer than the length given by the caller.
     fn upper_bound(&self) -> usize;
     /// Figure out the length if it is static.
     fn static_upper_bound() -> usize;
 
+    /// Clamp a value to the largest value that fits in a lane, instead of truncating it.
+    ///
+    /// Lanes are unsigned and saturating, so this is used by every operation that puts a `u32`
+    /// into a lane, so that a large value saturates instead of wrapping around to a small one.
+    #[inline]
+    fn clamp_lane(val: u32) -> u32
+    where
+        Self: Sized,
+    {
+        if Self::LANE_BYTES >= 4 {
+            val
+        } else {
+            cmp::min(val, (1u32 << (Self::LANE_BYTES * 8)) - 1)
+        }
+    }
+
     /// These operations do not have to be very efficient.
+    ///
+    /// `slow_loadu` loads `len` bytes from `ptr` into the vector, starting at `idx`. If `reverse`
+    /// is true, then the bytes are stored going backwards from `idx`, so `len` must be at most
+    /// `idx + 1`. Otherwise, `idx + len` must be at most `upper_bound()`. These preconditions are
+    /// checked with debug assertions.
     unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool);
     unsafe fn slow_extract(&self, i: usize) -> u32;
     unsafe fn slow_insert(&mut self, i: usize, val: u32);
@@ -39,19 +89,32 @@ pub trait Jewel: fmt::Display {
 
     unsafe fn add_mut(&mut self, b: &Self);
     unsafe fn adds_mut(&mut self, b: &Self);
+    unsafe fn subs_mut(&mut self, b: &Self);
     unsafe fn and_mut(&mut self, b: &Self);
     unsafe fn andnot_mut(&mut self, b: &Self);
     unsafe fn cmpeq_mut(&mut self, b: &Self);
     unsafe fn min_mut(&mut self, b: &Self);
     unsafe fn max_mut(&mut self, b: &Self);
     unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self);
+    /// Shifts move elements across vector boundaries and shift in zeros at the ends. With a
+    /// single vector, it is both the first and the last vector, so only zeros are shifted in.
     unsafe fn shift_left_1_mut(&mut self);
     unsafe fn shift_left_2_mut(&mut self);
     unsafe fn shift_right_1_mut(&mut self);
+    /// Convert ASCII uppercase letters to lowercase, leaving every other value untouched.
+    unsafe fn ascii_fold_mut(&mut self);
+    /// Convert ASCII and Latin-1 (À-Þ, except ×) uppercase letters to lowercase, leaving every
+    /// other value untouched.
+    unsafe fn latin1_fold_mut(&mut self);
+    /// Minimum and maximum values across every element, including the padding past the length
+    /// that the vector was created with.
+    unsafe fn horizontal_min(&self) -> u32;
+    unsafe fn horizontal_max(&self) -> u32;
 
     /// These operations overwrite a res vector to reduce memory allocations.
     unsafe fn add(a: &Self, b: &Self, res: &mut Self);
     unsafe fn adds(a: &Self, b: &Self, res: &mut Self);
+    unsafe fn subs(a: &Self, b: &Self, res: &mut Self);
     unsafe fn andnot(a: &Self, b: &Self, res: &mut Self);
     unsafe fn cmpeq(a: &Self, b: &Self, res: &mut Self);
     unsafe fn min(a: &Self, b: &Self, res: &mut Self);
@@ -61,6 +124,7 @@ pub trait Jewel: fmt::Display {
 
     /// `triple_argmin` will allocate memory and create a new Jewel vector.
     unsafe fn triple_argmin(sub: &Self, a_gap: &Self, b_gap: &Self, res_min: &mut Self) -> Self;
+    /// `triple_min_length` maximizes the length if the edit costs are equal.
     unsafe fn triple_min_length(
         sub: &Self,
         a_gap: &Self,
@@ -71,6 +135,17 @@ pub trait Jewel: fmt::Display {
         res_min: &mut Self,
         res_length: &mut Self,
     );
+    /// `triple_min_length_short` minimizes the length if the edit costs are equal.
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    );
     unsafe fn double_min_length(
         new_gap: &Self,
         res_cont_gap: &mut Self,
@@ -79,6 +154,36 @@ pub trait Jewel: fmt::Display {
     );
 }
 
+/// Check the preconditions of `Jewel::slow_loadu` in debug builds.
+#[inline]
+fn debug_check_loadu(idx: usize, len: usize, upper_bound: usize, reverse: bool) {
+    if reverse {
+        // idx - i must not wrap around for any i < len
+        debug_assert!(len <= idx + 1, "reverse slow_loadu goes past index 0");
+        debug_assert!(
+            idx < upper_bound,
+            "slow_loadu goes past the end of the vector"
+        );
+    } else {
+        debug_assert!(
+            idx + len <= upper_bound,
+            "slow_loadu goes past the end of the vector"
+        );
+    }
+}
+
+/// Returns the number of vectors with `1 << shift` lanes that are needed to hold `len` lanes.
+///
+/// At least one vector is always allocated, even if `len` is zero, so the shifts and inserts,
+/// which access the last vector, never index into an empty `Vec`.
+#[inline]
+fn num_vectors(len: usize, shift: usize) -> usize {
+    cmp::max(
+        (len >> shift) + ((len & ((1 << shift) - 1)) > 0) as usize,
+        1,
+    )
+}
+
 // macros to help generate implementations for some of the Jewel vector functions
 macro_rules! operation_param2 {
     ($target:literal, $fn_name:ident, $intrinsic:ident) => {
@@ -93,6 +198,27 @@ macro_rules! operation_param2 {
     };
 }
 
+macro_rules! operation_reset {
+    ($target:literal, $shift:literal, $val:ident => $splat:expr, $max:expr) => {
+        #[target_feature(enable = $target)]
+        #[inline]
+        unsafe fn reset(&mut self, $val: u32, len: usize) {
+            let num = num_vectors(len, $shift);
+            // keep the allocation around
+            self.v.clear();
+            self.v.resize(num, $splat);
+        }
+
+        #[target_feature(enable = $target)]
+        #[inline]
+        unsafe fn reset_max(&mut self, len: usize) {
+            let num = num_vectors(len, $shift);
+            self.v.clear();
+            self.v.resize(num, $max);
+        }
+    };
+}
+
 macro_rules! operation_mut_param2 {
     ($target:literal, $fn_name:ident, $intrinsic:ident) => {
         #[target_feature(enable = $target)]
@@ -106,6 +232,95 @@ macro_rules! operation_mut_param2 {
     };
 }
 
+macro_rules! operation_fold_mut {
+    ($target:literal, $set1:ident, $cmpgt:ident, $and:ident, $or:ident) => {
+        #[target_feature(enable = $target)]
+        #[inline]
+        unsafe fn ascii_fold_mut(&mut self) {
+            let lo = $set1((b'A' - 1) as _);
+            let hi = $set1((b'Z' + 1) as _);
+            let bit = $set1(0x20);
+
+            for i in 0..self.v.len() {
+                let curr = *self.v.get_unchecked(i);
+                // mask of values in A-Z, which get the lowercase bit set
+                let mask = $and($cmpgt(curr, lo), $cmpgt(hi, curr));
+                *self.v.get_unchecked_mut(i) = $or(curr, $and(mask, bit));
+            }
+        }
+
+        #[target_feature(enable = $target)]
+        #[inline]
+        unsafe fn latin1_fold_mut(&mut self) {
+            // 8-bit lanes are compared as signed values, but the bounds are cast the same way,
+            // so every range stays contiguous
+            let ascii_lo = $set1((b'A' - 1) as _);
+            let ascii_hi = $set1((b'Z' + 1) as _);
+            let latin1_lo = $set1(0xBFu8 as _);
+            let times = $set1(0xD7u8 as _);
+            let latin1_hi = $set1(0xDFu8 as _);
+            let bit = $set1(0x20);
+
+            for i in 0..self.v.len() {
+                let curr = *self.v.get_unchecked(i);
+                // mask of values in A-Z, À-Ö, and Ø-Þ, which get the lowercase bit set
+                let mask = $or(
+                    $and($cmpgt(curr, ascii_lo), $cmpgt(ascii_hi, curr)),
+                    $or(
+                        $and($cmpgt(curr, latin1_lo), $cmpgt(times, curr)),
+                        $and($cmpgt(curr, times), $cmpgt(latin1_hi, curr)),
+                    ),
+                );
+                *self.v.get_unchecked_mut(i) = $or(curr, $and(mask, bit));
+            }
+        }
+    };
+}
+
+macro_rules! operation_horizontal_avx {
+    ($fn_name:ident, $intrinsic:ident, $intrinsic_128:ident, $elem:ty, $reduce:ident) => {
+        #[target_feature(enable = "avx2")]
+        #[inline]
+        unsafe fn $fn_name(&self) -> u32 {
+            // reduce all vectors into one vector, then reduce the two 128-bit halves
+            let mut curr = *self.v.get_unchecked(0);
+
+            for i in 1..self.v.len() {
+                curr = $intrinsic(curr, *self.v.get_unchecked(i));
+            }
+
+            let curr = $intrinsic_128(
+                _mm256_castsi256_si128(curr),
+                _mm256_extracti128_si256(curr, 1),
+            );
+
+            // scalar sweep over the leftover 128-bit lane
+            let mut arr = [0 as $elem; 16 / mem::size_of::<$elem>()];
+            _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, curr);
+            *arr.iter().$reduce().unwrap() as u32
+        }
+    };
+}
+
+macro_rules! operation_horizontal_sse {
+    ($fn_name:ident, $intrinsic:ident, $elem:ty, $reduce:ident) => {
+        #[target_feature(enable = "sse4.1")]
+        #[inline]
+        unsafe fn $fn_name(&self) -> u32 {
+            let mut curr = *self.v.get_unchecked(0);
+
+            for i in 1..self.v.len() {
+                curr = $intrinsic(curr, *self.v.get_unchecked(i));
+            }
+
+            // scalar sweep over the leftover 128-bit lane
+            let mut arr = [0 as $elem; 16 / mem::size_of::<$elem>()];
+            _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, curr);
+            *arr.iter().$reduce().unwrap() as u32
+        }
+    };
+}
+
 /// N x 32 x 8 vector backed with 256-bit AVX vectors.
 macro_rules! create_avx_nx32x8 {
     ($name:ident, $num:literal) => {
@@ -116,10 +331,12 @@ macro_rules! create_avx_nx32x8 {
 
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
         impl Jewel for $name {
+            const LANE_BYTES: usize = 1;
+
             #[target_feature(enable = "avx2")]
             #[inline]
             unsafe fn repeating(val: u32, _len: usize) -> Self {
-                let v = [_mm256_set1_epi8(val as i8); $num];
+                let v = [_mm256_set1_epi8(Self::clamp_lane(val) as i8); $num];
 
                 Self { v: v }
             }
@@ -149,6 +366,8 @@ macro_rules! create_avx_nx32x8 {
                     return;
                 }
 
+                debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
                 let mut arr = [0u8; 32];
                 let arr_ptr = arr.as_mut_ptr() as *mut __m256i;
                 let store_idx = if reverse { 31 } else { 0 };
@@ -189,7 +408,7 @@ macro_rules! create_avx_nx32x8 {
                 let mut arr = [0u8; 32];
                 let arr_ptr = arr.as_mut_ptr() as *mut __m256i;
                 _mm256_storeu_si256(arr_ptr, *self.v.get_unchecked(idx));
-                *arr.get_unchecked_mut(j) = val as u8;
+                *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u8;
                 *self.v.get_unchecked_mut(idx) = _mm256_loadu_si256(arr_ptr);
             }
 
@@ -197,24 +416,33 @@ macro_rules! create_avx_nx32x8 {
             #[inline]
             unsafe fn insert_last_0(&mut self, val: u32) {
                 let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm256_insert_epi8(*self.v.get_unchecked(last), val as i8, 31i32);
+                *self.v.get_unchecked_mut(last) = _mm256_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i8,
+                    31i32,
+                );
             }
 
             #[target_feature(enable = "avx2")]
             #[inline]
             unsafe fn insert_last_1(&mut self, val: u32) {
                 let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm256_insert_epi8(*self.v.get_unchecked(last), val as i8, 30i32);
+                *self.v.get_unchecked_mut(last) = _mm256_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i8,
+                    30i32,
+                );
             }
 
             #[target_feature(enable = "avx2")]
             #[inline]
             unsafe fn insert_last_2(&mut self, val: u32) {
                 let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm256_insert_epi8(*self.v.get_unchecked(last), val as i8, 29i32);
+                *self.v.get_unchecked_mut(last) = _mm256_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i8,
+                    29i32,
+                );
             }
 
             #[target_feature(enable = "avx2")]
@@ -229,7 +457,7 @@ macro_rules! create_avx_nx32x8 {
             #[inline]
             unsafe fn insert_first(&mut self, val: u32) {
                 *self.v.get_unchecked_mut(0) =
-                    _mm256_insert_epi8(*self.v.get_unchecked(0), val as i8, 0i32);
+                    _mm256_insert_epi8(*self.v.get_unchecked(0), Self::clamp_lane(val) as i8, 0i32);
             }
 
             #[target_feature(enable = "avx2")]
@@ -241,11 +469,21 @@ macro_rules! create_avx_nx32x8 {
 
             operation_mut_param2!("avx2", add_mut, _mm256_add_epi8);
             operation_mut_param2!("avx2", adds_mut, _mm256_adds_epu8);
+            operation_mut_param2!("avx2", subs_mut, _mm256_subs_epu8);
             operation_mut_param2!("avx2", and_mut, _mm256_and_si256);
             operation_mut_param2!("avx2", andnot_mut, _mm256_andnot_si256);
             operation_mut_param2!("avx2", cmpeq_mut, _mm256_cmpeq_epi8);
             operation_mut_param2!("avx2", min_mut, _mm256_min_epu8);
             operation_mut_param2!("avx2", max_mut, _mm256_max_epu8);
+            operation_fold_mut!(
+                "avx2",
+                _mm256_set1_epi8,
+                _mm256_cmpgt_epi8,
+                _mm256_and_si256,
+                _mm256_or_si256
+            );
+            operation_horizontal_avx!(horizontal_min, _mm256_min_epu8, _mm_min_epu8, u8, min);
+            operation_horizontal_avx!(horizontal_max, _mm256_max_epu8, _mm_max_epu8, u8, max);
 
             #[target_feature(enable = "avx2")]
             #[inline]
@@ -344,6 +582,7 @@ macro_rules! create_avx_nx32x8 {
 
             operation_param2!("avx2", add, _mm256_add_epi8);
             operation_param2!("avx2", adds, _mm256_adds_epu8);
+            operation_param2!("avx2", subs, _mm256_subs_epu8);
             operation_param2!("avx2", andnot, _mm256_andnot_si256);
             operation_param2!("avx2", cmpeq, _mm256_cmpeq_epi8);
             operation_param2!("avx2", min, _mm256_min_epu8);
@@ -470,6 +709,48 @@ macro_rules! create_avx_nx32x8 {
                 }
             }
 
+            #[target_feature(enable = "avx2")]
+            #[inline]
+            unsafe fn triple_min_length_short(
+                sub: &Self,
+                a_gap: &Self,
+                b_gap: &Self,
+                sub_length: &Self,
+                a_gap_length: &Self,
+                b_gap_length: &Self,
+                res_min: &mut Self,
+                res_length: &mut Self,
+            ) {
+                // choose the length based on which edit is chosen during the min operation
+                // secondary objective of minimizing length if edit costs equal
+                for i in 0..sub.v.len() {
+                    let sub = *sub.v.get_unchecked(i);
+                    let a_gap = *a_gap.v.get_unchecked(i);
+                    let b_gap = *b_gap.v.get_unchecked(i);
+                    let sub_length = *sub_length.v.get_unchecked(i);
+                    let a_gap_length = *a_gap_length.v.get_unchecked(i);
+                    let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+                    let res_min1 = _mm256_min_epu8(a_gap, b_gap);
+                    let a_b_gt_mask = _mm256_cmpeq_epi8(a_gap, res_min1); // a gap: -1, b gap: 0
+                    let mut res_length1 =
+                        _mm256_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+                    let a_b_eq_mask = _mm256_cmpeq_epi8(a_gap, b_gap); // equal: -1
+                    let a_b_min_len = _mm256_min_epu8(a_gap_length, b_gap_length);
+                    res_length1 = _mm256_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+                    let res_min2 = _mm256_min_epu8(sub, res_min1);
+                    let sub_gt_mask = _mm256_cmpeq_epi8(sub, res_min2); // sub: -1, prev a or b gap: 0
+                    let mut res_length2 = _mm256_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+                    let sub_eq_mask = _mm256_cmpeq_epi8(sub, res_min1);
+                    let sub_min_len = _mm256_min_epu8(sub_length, res_length1);
+                    res_length2 = _mm256_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
+
+                    *res_min.v.get_unchecked_mut(i) = res_min2;
+                    *res_length.v.get_unchecked_mut(i) = res_length2;
+                }
+            }
+
             #[target_feature(enable = "avx2")]
             #[inline]
             unsafe fn double_min_length(
@@ -558,10 +839,12 @@ pub struct AvxNx16x16 {
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl Jewel for AvxNx16x16 {
+    const LANE_BYTES: usize = 2;
+
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn repeating(val: u32, len: usize) -> Self {
-        let v = alloc::vec![_mm256_set1_epi16(val as i16); (len >> 4) + if (len & 15) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm256_set1_epi16(Self::clamp_lane(val) as i16); num_vectors(len, 4)];
 
         Self { v }
     }
@@ -569,12 +852,13 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn repeating_max(len: usize) -> Self {
-        let v =
-            alloc::vec![_mm256_set1_epi16(-1i16); (len >> 4) + if (len & 15) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm256_set1_epi16(-1i16); num_vectors(len, 4)];
 
         Self { v }
     }
 
+    operation_reset!("avx2", 4, val => _mm256_set1_epi16(Self::clamp_lane(val) as i16), _mm256_set1_epi16(-1i16));
+
     #[inline]
     fn upper_bound(&self) -> usize {
         self.v.len() << 4
@@ -592,6 +876,8 @@ impl Jewel for AvxNx16x16 {
             return;
         }
 
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
         let mut arr = [0u16; 16];
         let arr_ptr = arr.as_mut_ptr() as *mut __m256i;
         let store_idx = if reverse { 15 } else { 0 };
@@ -631,7 +917,7 @@ impl Jewel for AvxNx16x16 {
         let mut arr = [0u16; 16];
         let arr_ptr = arr.as_mut_ptr() as *mut __m256i;
         _mm256_storeu_si256(arr_ptr, *self.v.get_unchecked(idx));
-        *arr.get_unchecked_mut(j) = val as u16;
+        *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u16;
         *self.v.get_unchecked_mut(idx) = _mm256_loadu_si256(arr_ptr);
     }
 
@@ -639,24 +925,33 @@ impl Jewel for AvxNx16x16 {
     #[inline]
     unsafe fn insert_last_0(&mut self, val: u32) {
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) =
-            _mm256_insert_epi16(*self.v.get_unchecked(last), val as i16, 15i32);
+        *self.v.get_unchecked_mut(last) = _mm256_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i16,
+            15i32,
+        );
     }
 
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn insert_last_1(&mut self, val: u32) {
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) =
-            _mm256_insert_epi16(*self.v.get_unchecked(last), val as i16, 14i32);
+        *self.v.get_unchecked_mut(last) = _mm256_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i16,
+            14i32,
+        );
     }
 
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn insert_last_2(&mut self, val: u32) {
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) =
-            _mm256_insert_epi16(*self.v.get_unchecked(last), val as i16, 13i32);
+        *self.v.get_unchecked_mut(last) = _mm256_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i16,
+            13i32,
+        );
     }
 
     #[target_feature(enable = "avx2")]
@@ -671,7 +966,7 @@ impl Jewel for AvxNx16x16 {
     #[inline]
     unsafe fn insert_first(&mut self, val: u32) {
         *self.v.get_unchecked_mut(0) =
-            _mm256_insert_epi16(*self.v.get_unchecked(0), val as i16, 0i32);
+            _mm256_insert_epi16(*self.v.get_unchecked(0), Self::clamp_lane(val) as i16, 0i32);
     }
 
     #[target_feature(enable = "avx2")]
@@ -682,11 +977,21 @@ impl Jewel for AvxNx16x16 {
 
     operation_mut_param2!("avx2", add_mut, _mm256_add_epi16);
     operation_mut_param2!("avx2", adds_mut, _mm256_adds_epu16);
+    operation_mut_param2!("avx2", subs_mut, _mm256_subs_epu16);
     operation_mut_param2!("avx2", and_mut, _mm256_and_si256);
     operation_mut_param2!("avx2", andnot_mut, _mm256_andnot_si256);
     operation_mut_param2!("avx2", cmpeq_mut, _mm256_cmpeq_epi16);
     operation_mut_param2!("avx2", min_mut, _mm256_min_epu16);
     operation_mut_param2!("avx2", max_mut, _mm256_max_epu16);
+    operation_fold_mut!(
+        "avx2",
+        _mm256_set1_epi16,
+        _mm256_cmpgt_epi16,
+        _mm256_and_si256,
+        _mm256_or_si256
+    );
+    operation_horizontal_avx!(horizontal_min, _mm256_min_epu16, _mm_min_epu16, u16, min);
+    operation_horizontal_avx!(horizontal_max, _mm256_max_epu16, _mm_max_epu16, u16, max);
 
     #[target_feature(enable = "avx2")]
     #[inline]
@@ -703,6 +1008,7 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -727,6 +1033,7 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -751,6 +1058,7 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in (1..self.v.len()).rev() {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the previous vector and the first half of the current vector
@@ -773,6 +1081,7 @@ impl Jewel for AvxNx16x16 {
 
     operation_param2!("avx2", add, _mm256_add_epi16);
     operation_param2!("avx2", adds, _mm256_adds_epu16);
+    operation_param2!("avx2", subs, _mm256_subs_epu16);
     operation_param2!("avx2", andnot, _mm256_andnot_si256);
     operation_param2!("avx2", cmpeq, _mm256_cmpeq_epi16);
     operation_param2!("avx2", min, _mm256_min_epu16);
@@ -781,6 +1090,7 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in 0..(a.v.len() - 1) {
             let curr = *a.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -805,6 +1115,7 @@ impl Jewel for AvxNx16x16 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in (1..a.v.len()).rev() {
             let curr = *a.v.get_unchecked(i);
             // permute concatenates the second half of the previous vector and the first half of the current vector
@@ -893,6 +1204,47 @@ impl Jewel for AvxNx16x16 {
         }
     }
 
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm256_min_epu16(a_gap, b_gap);
+            let a_b_gt_mask = _mm256_cmpeq_epi16(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = _mm256_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = _mm256_cmpeq_epi16(a_gap, b_gap); // equal: -1
+            let a_b_min_len = _mm256_min_epu16(a_gap_length, b_gap_length);
+            res_length1 = _mm256_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+            let res_min2 = _mm256_min_epu16(sub, res_min1);
+            let sub_gt_mask = _mm256_cmpeq_epi16(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = _mm256_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = _mm256_cmpeq_epi16(sub, res_min1);
+            let sub_min_len = _mm256_min_epu16(sub_length, res_length1);
+            res_length2 = _mm256_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn double_min_length(
@@ -971,6 +1323,15 @@ pub struct AvxNx8x32 {
     v: Vec<__m256i>,
 }
 
+/// Workaround for the lack of the _mm256_subs_epu32 intrinsic.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn _mm256_subs_epu32(a: __m256i, b: __m256i) -> __m256i {
+    // clamp a to at least b first, so the difference is zero instead of wrapping around
+    _mm256_sub_epi32(_mm256_max_epu32(a, b), b)
+}
+
 /// Workaround for the lack of the _mm256_adds_epu32 intrinsic.
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 #[target_feature(enable = "avx2")]
@@ -986,10 +1347,12 @@ unsafe fn _mm256_adds_epu32(a: __m256i, b: __m256i) -> __m256i {
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl Jewel for AvxNx8x32 {
+    const LANE_BYTES: usize = 4;
+
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn repeating(val: u32, len: usize) -> Self {
-        let v = alloc::vec![_mm256_set1_epi32(val as i32); (len >> 3) + if (len & 7) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm256_set1_epi32(val as i32); num_vectors(len, 3)];
 
         Self { v: v }
     }
@@ -997,12 +1360,13 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn repeating_max(len: usize) -> Self {
-        let v =
-            alloc::vec![_mm256_set1_epi32(-1i32); (len >> 3) + if (len & 7) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm256_set1_epi32(-1i32); num_vectors(len, 3)];
 
         Self { v: v }
     }
 
+    operation_reset!("avx2", 3, val => _mm256_set1_epi32(val as i32), _mm256_set1_epi32(-1i32));
+
     #[inline]
     fn upper_bound(&self) -> usize {
         self.v.len() << 3
@@ -1020,6 +1384,8 @@ impl Jewel for AvxNx8x32 {
             return;
         }
 
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
         let mut arr = [0u32; 8];
         let arr_ptr = arr.as_mut_ptr() as *mut __m256i;
         let store_idx = if reverse { 7 } else { 0 };
@@ -1110,11 +1476,21 @@ impl Jewel for AvxNx8x32 {
 
     operation_mut_param2!("avx2", add_mut, _mm256_add_epi32);
     operation_mut_param2!("avx2", adds_mut, _mm256_adds_epu32);
+    operation_mut_param2!("avx2", subs_mut, _mm256_subs_epu32);
     operation_mut_param2!("avx2", and_mut, _mm256_and_si256);
     operation_mut_param2!("avx2", andnot_mut, _mm256_andnot_si256);
     operation_mut_param2!("avx2", cmpeq_mut, _mm256_cmpeq_epi32);
     operation_mut_param2!("avx2", min_mut, _mm256_min_epu32);
     operation_mut_param2!("avx2", max_mut, _mm256_max_epu32);
+    operation_fold_mut!(
+        "avx2",
+        _mm256_set1_epi32,
+        _mm256_cmpgt_epi32,
+        _mm256_and_si256,
+        _mm256_or_si256
+    );
+    operation_horizontal_avx!(horizontal_min, _mm256_min_epu32, _mm_min_epu32, u32, min);
+    operation_horizontal_avx!(horizontal_max, _mm256_max_epu32, _mm_max_epu32, u32, max);
 
     #[target_feature(enable = "avx2")]
     #[inline]
@@ -1131,6 +1507,7 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -1155,6 +1532,7 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -1179,6 +1557,7 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in (1..self.v.len()).rev() {
             let curr = *self.v.get_unchecked(i);
             // permute concatenates the second half of the previous vector and the first half of the current vector
@@ -1201,6 +1580,7 @@ impl Jewel for AvxNx8x32 {
 
     operation_param2!("avx2", add, _mm256_add_epi32);
     operation_param2!("avx2", adds, _mm256_adds_epu32);
+    operation_param2!("avx2", subs, _mm256_subs_epu32);
     operation_param2!("avx2", andnot, _mm256_andnot_si256);
     operation_param2!("avx2", cmpeq, _mm256_cmpeq_epi32);
     operation_param2!("avx2", min, _mm256_min_epu32);
@@ -1209,6 +1589,7 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in 0..(a.v.len() - 1) {
             let curr = *a.v.get_unchecked(i);
             // permute concatenates the second half of the current vector and the first half of the next vector
@@ -1233,6 +1614,7 @@ impl Jewel for AvxNx8x32 {
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in (1..a.v.len()).rev() {
             let curr = *a.v.get_unchecked(i);
             // permute concatenates the second half of the previous vector and the first half of the current vector
@@ -1321,6 +1703,47 @@ impl Jewel for AvxNx8x32 {
         }
     }
 
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm256_min_epu32(a_gap, b_gap);
+            let a_b_gt_mask = _mm256_cmpeq_epi32(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = _mm256_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = _mm256_cmpeq_epi32(a_gap, b_gap); // equal: -1
+            let a_b_min_len = _mm256_min_epu32(a_gap_length, b_gap_length);
+            res_length1 = _mm256_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+            let res_min2 = _mm256_min_epu32(sub, res_min1);
+            let sub_gt_mask = _mm256_cmpeq_epi32(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = _mm256_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = _mm256_cmpeq_epi32(sub, res_min1);
+            let sub_min_len = _mm256_min_epu32(sub_length, res_length1);
+            res_length2 = _mm256_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn double_min_length(
@@ -1393,404 +1816,1524 @@ impl fmt::Display for AvxNx8x32 {
     }
 }
 
-/// N x 16 x 8 vector backed with 128-bit SSE vectors.
-macro_rules! create_sse_nx16x8 {
-    ($name:ident, $num:literal) => {
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        pub struct $name {
-            v: [__m128i; $num],
-        }
+/// N x 64 x 8 vector backed with 512-bit AVX-512 vectors.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub struct Avx512Nx64x8 {
+    v: Vec<__m512i>,
+}
 
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        impl Jewel for $name {
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn repeating(val: u32, _len: usize) -> Self {
-                let v = [_mm_set1_epi8(val as i8); $num];
+/// Returns whether the AVX-512 instructions that are used by `Avx512Nx64x8` are supported.
+///
+/// This is detected at runtime if the `std` feature is enabled. Otherwise, this depends on
+/// whether the target features were enabled at compile time.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+pub fn avx512_supported() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx512bw")
+    }
 
-                Self { v: v }
-            }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "avx512bw")
+    }
+}
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn repeating_max(_len: usize) -> Self {
-                let v = [_mm_set1_epi8(-1i8); $num];
+/// Returns whether the AVX2 instructions that are used by the `Avx*` vectors are supported.
+///
+/// This is detected at runtime if the `std` feature is enabled. Otherwise, this depends on
+/// whether the target features were enabled at compile time.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+pub fn avx2_supported() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
 
-                Self { v: v }
-            }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "avx2")
+    }
+}
 
-            #[inline]
-            fn upper_bound(&self) -> usize {
-                self.v.len() << 4
-            }
+/// Returns whether the SSE4.1 instructions that are used by the `Sse*` vectors are supported.
+///
+/// This is detected at runtime if the `std` feature is enabled. Otherwise, this depends on
+/// whether the target features were enabled at compile time.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+pub fn sse41_supported() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("sse4.1")
+    }
 
-            #[inline]
-            fn static_upper_bound() -> usize {
-                $num << 4
-            }
-
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool) {
-                if len == 0 {
-                    return;
-                }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "sse4.1")
+    }
+}
 
-                let mut arr = [0u8; 16];
-                let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
-                let store_idx = if reverse { 15 } else { 0 };
-                let load_idx = if reverse { 0 } else { 15 };
+/// Workaround for AVX-512 comparisons only returning mask registers.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_cmpeq_epi8(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_movm_epi8(_mm512_cmpeq_epi8_mask(a, b))
+}
 
-                for i in 0..len {
-                    let curr_idx = if reverse { idx - i } else { idx + i };
-                    let arr_idx = curr_idx & 15;
+/// Workaround for AVX-512 comparisons only returning mask registers.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_cmpgt_epi8(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_movm_epi8(_mm512_cmpgt_epi8_mask(a, b))
+}
 
-                    if arr_idx == store_idx || i == 0 {
-                        _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 4));
-                    }
+/// Workaround for AVX-512 blends only accepting mask registers.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_blendv_epi8(a: __m512i, b: __m512i, mask: __m512i) -> __m512i {
+    _mm512_mask_blend_epi8(_mm512_movepi8_mask(mask), a, b)
+}
 
-                    *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize);
+/// Concatenate `a` (high) and `b` (low) and shift right by 1 byte.
+///
+/// `_mm512_alignr_epi8` only shifts within each 128-bit lane, so the 128-bit lanes are first
+/// rotated across the two vectors to carry bytes across the whole 512-bit vector.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_alignr_1_epi8(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_alignr_epi8(_mm512_alignr_epi64(a, b, 2), b, 1)
+}
 
-                    if arr_idx == load_idx || i == len - 1 {
-                        *self.v.get_unchecked_mut(curr_idx >> 4) = _mm_loadu_si128(arr_ptr);
-                    }
-                }
-            }
+/// Concatenate `a` (high) and `b` (low) and shift right by 2 bytes.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_alignr_2_epi8(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_alignr_epi8(_mm512_alignr_epi64(a, b, 2), b, 2)
+}
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn slow_extract(&self, i: usize) -> u32 {
-                let idx = i >> 4;
-                let j = i & 15;
-                let mut arr = [0u8; 16];
-                _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, *self.v.get_unchecked(idx));
-                *arr.get_unchecked(j) as u32
-            }
+/// Concatenate `a` (high) and `b` (low) and shift right by 63 bytes.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512bw")]
+#[inline]
+unsafe fn _mm512_alignr_63_epi8(a: __m512i, b: __m512i) -> __m512i {
+    _mm512_alignr_epi8(a, _mm512_alignr_epi64(a, b, 6), 15)
+}
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn slow_insert(&mut self, i: usize, val: u32) {
-                let idx = i >> 4;
-                let j = i & 15;
-                let mut arr = [0u8; 16];
-                let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
-                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(idx));
-                *arr.get_unchecked_mut(j) = val as u8;
-                *self.v.get_unchecked_mut(idx) = _mm_loadu_si128(arr_ptr);
-            }
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl Jewel for Avx512Nx64x8 {
+    const LANE_BYTES: usize = 1;
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_last_0(&mut self, val: u32) {
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm_insert_epi8(*self.v.get_unchecked(last), val as i32, 15i32);
-            }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn repeating(val: u32, len: usize) -> Self {
+        let v = alloc::vec![_mm512_set1_epi8(Self::clamp_lane(val) as i8); num_vectors(len, 6)];
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_last_1(&mut self, val: u32) {
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm_insert_epi8(*self.v.get_unchecked(last), val as i32, 14i32);
-            }
+        Self { v }
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_last_2(&mut self, val: u32) {
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm_insert_epi8(*self.v.get_unchecked(last), val as i32, 13i32);
-            }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn repeating_max(len: usize) -> Self {
+        let v = alloc::vec![_mm512_set1_epi8(-1i8); num_vectors(len, 6)];
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_last_max(&mut self) {
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) =
-                    _mm_insert_epi8(*self.v.get_unchecked(last), u8::MAX as i32, 15i32);
-            }
+        Self { v }
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_first(&mut self, val: u32) {
-                *self.v.get_unchecked_mut(0) =
-                    _mm_insert_epi8(*self.v.get_unchecked(0), val as i32, 0i32);
-            }
+    operation_reset!("avx512bw", 6, val => _mm512_set1_epi8(Self::clamp_lane(val) as i8), _mm512_set1_epi8(-1i8));
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn insert_first_max(&mut self) {
-                *self.v.get_unchecked_mut(0) =
-                    _mm_insert_epi8(*self.v.get_unchecked(0), u8::MAX as i32, 0i32);
-            }
+    #[inline]
+    fn upper_bound(&self) -> usize {
+        self.v.len() << 6
+    }
 
-            operation_mut_param2!("sse4.1", add_mut, _mm_add_epi8);
-            operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu8);
-            operation_mut_param2!("sse4.1", and_mut, _mm_and_si128);
-            operation_mut_param2!("sse4.1", andnot_mut, _mm_andnot_si128);
-            operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi8);
-            operation_mut_param2!("sse4.1", min_mut, _mm_min_epu8);
-            operation_mut_param2!("sse4.1", max_mut, _mm_max_epu8);
+    #[inline]
+    fn static_upper_bound() -> usize {
+        unimplemented!()
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self) {
-                for i in 0..self.v.len() {
-                    *self.v.get_unchecked_mut(i) = _mm_blendv_epi8(
-                        *self.v.get_unchecked(i),
-                        *b.v.get_unchecked(i),
-                        *mask.v.get_unchecked(i),
-                    );
-                }
-            }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool) {
+        if len == 0 {
+            return;
+        }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn shift_left_1_mut(&mut self) {
-                for i in 0..(self.v.len() - 1) {
-                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
-                        *self.v.get_unchecked(i + 1),
-                        *self.v.get_unchecked(i),
-                        1i32,
-                    );
-                }
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
 
-                // last one gets to shift in zeros
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 1i32);
-            }
+        let mut arr = [0u8; 64];
+        let arr_ptr = arr.as_mut_ptr() as *mut __m512i;
+        let store_idx = if reverse { 63 } else { 0 };
+        let load_idx = if reverse { 0 } else { 63 };
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn shift_left_2_mut(&mut self) {
-                for i in 0..(self.v.len() - 1) {
-                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
-                        *self.v.get_unchecked(i + 1),
-                        *self.v.get_unchecked(i),
-                        2i32,
-                    );
-                }
+        for i in 0..len {
+            let curr_idx = if reverse { idx - i } else { idx + i };
+            let arr_idx = curr_idx & 63;
 
-                // last one gets to shift in zeros
-                let last = self.v.len() - 1;
-                *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 2i32);
+            if arr_idx == store_idx || i == 0 {
+                _mm512_storeu_si512(arr_ptr, *self.v.get_unchecked(curr_idx >> 6));
             }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn shift_right_1_mut(&mut self) {
-                for i in (1..self.v.len()).rev() {
-                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
-                        *self.v.get_unchecked(i),
-                        *self.v.get_unchecked(i - 1),
-                        15i32,
-                    );
-                }
+            *arr.get_unchecked_mut(arr_idx) = *ptr.add(i);
 
-                // first one gets to shift in zeros
-                *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 1i32);
+            if arr_idx == load_idx || i == len - 1 {
+                *self.v.get_unchecked_mut(curr_idx >> 6) = _mm512_loadu_si512(arr_ptr);
             }
+        }
+    }
 
-            operation_param2!("sse4.1", add, _mm_add_epi8);
-            operation_param2!("sse4.1", adds, _mm_adds_epu8);
-            operation_param2!("sse4.1", andnot, _mm_andnot_si128);
-            operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi8);
-            operation_param2!("sse4.1", min, _mm_min_epu8);
-            operation_param2!("sse4.1", max, _mm_max_epu8);
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn slow_extract(&self, i: usize) -> u32 {
+        let idx = i >> 6;
+        let j = i & 63;
+        let mut arr = [0u8; 64];
+        _mm512_storeu_si512(arr.as_mut_ptr() as *mut __m512i, *self.v.get_unchecked(idx));
+        *arr.get_unchecked(j) as u32
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn shift_left_1(a: &Self, res: &mut Self) {
-                for i in 0..(a.v.len() - 1) {
-                    *res.v.get_unchecked_mut(i) =
-                        _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 1i32);
-                }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn slow_insert(&mut self, i: usize, val: u32) {
+        let idx = i >> 6;
+        let j = i & 63;
+        let mut arr = [0u8; 64];
+        let arr_ptr = arr.as_mut_ptr() as *mut __m512i;
+        _mm512_storeu_si512(arr_ptr, *self.v.get_unchecked(idx));
+        *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u8;
+        *self.v.get_unchecked_mut(idx) = _mm512_loadu_si512(arr_ptr);
+    }
 
-                // last one gets to shift in zeros
-                let last = a.v.len() - 1;
-                *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 1i32);
-            }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_last_0(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm512_mask_set1_epi8(
+            *self.v.get_unchecked(last),
+            1u64 << 63,
+            Self::clamp_lane(val) as i8,
+        );
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn shift_right_1(a: &Self, res: &mut Self) {
-                for i in (1..a.v.len()).rev() {
-                    *res.v.get_unchecked_mut(i) =
-                        _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 15i32);
-                }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_last_1(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm512_mask_set1_epi8(
+            *self.v.get_unchecked(last),
+            1u64 << 62,
+            Self::clamp_lane(val) as i8,
+        );
+    }
 
-                // first one gets to shift in zeros
-                *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 1i32);
-            }
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_last_2(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm512_mask_set1_epi8(
+            *self.v.get_unchecked(last),
+            1u64 << 61,
+            Self::clamp_lane(val) as i8,
+        );
+    }
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn triple_argmin(
-                sub: &Self,
-                a_gap: &Self,
-                b_gap: &Self,
-                res_min: &mut Self,
-            ) -> Self {
-                // return the edit used in addition to doing a min operation
-                let mut v = [_mm_undefined_si128(); $num];
-                let twos = _mm_set1_epi8(2);
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_last_max(&mut self) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) =
+            _mm512_mask_set1_epi8(*self.v.get_unchecked(last), 1u64 << 63, -1i8);
+    }
 
-                for i in 0..sub.v.len() {
-                    let sub = *sub.v.get_unchecked(i);
-                    let a_gap = *a_gap.v.get_unchecked(i);
-                    let b_gap = *b_gap.v.get_unchecked(i);
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_first(&mut self, val: u32) {
+        *self.v.get_unchecked_mut(0) =
+            _mm512_mask_set1_epi8(*self.v.get_unchecked(0), 1u64, Self::clamp_lane(val) as i8);
+    }
 
-                    let res_min1 = _mm_min_epu8(a_gap, b_gap);
-                    // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
-                    let res_arg1 = _mm_add_epi8(twos, _mm_cmpeq_epi8(a_gap, res_min1));
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn insert_first_max(&mut self) {
+        *self.v.get_unchecked_mut(0) = _mm512_mask_set1_epi8(*self.v.get_unchecked(0), 1u64, -1i8);
+    }
 
-                    let res_min2 = _mm_min_epu8(sub, res_min1);
-                    // sub: 0
-                    let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi8(sub, res_min2), res_arg1);
+    operation_mut_param2!("avx512bw", add_mut, _mm512_add_epi8);
+    operation_mut_param2!("avx512bw", adds_mut, _mm512_adds_epu8);
+    operation_mut_param2!("avx512bw", subs_mut, _mm512_subs_epu8);
+    operation_mut_param2!("avx512bw", and_mut, _mm512_and_si512);
+    operation_mut_param2!("avx512bw", andnot_mut, _mm512_andnot_si512);
+    operation_mut_param2!("avx512bw", cmpeq_mut, _mm512_cmpeq_epi8);
+    operation_mut_param2!("avx512bw", min_mut, _mm512_min_epu8);
+    operation_mut_param2!("avx512bw", max_mut, _mm512_max_epu8);
+    operation_fold_mut!(
+        "avx512bw",
+        _mm512_set1_epi8,
+        _mm512_cmpgt_epi8,
+        _mm512_and_si512,
+        _mm512_or_si512
+    );
 
-                    *res_min.v.get_unchecked_mut(i) = res_min2;
-                    *v.get_unchecked_mut(i) = res_arg2;
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn horizontal_min(&self) -> u32 {
+        let mut curr = *self.v.get_unchecked(0);
+
+        for i in 1..self.v.len() {
+            curr = _mm512_min_epu8(curr, *self.v.get_unchecked(i));
+        }
+
+        // reduce the two 256-bit halves, then the two 128-bit halves
+        let curr = _mm256_min_epu8(
+            _mm512_castsi512_si256(curr),
+            _mm512_extracti64x4_epi64(curr, 1),
+        );
+        let curr = _mm_min_epu8(
+            _mm256_castsi256_si128(curr),
+            _mm256_extracti128_si256(curr, 1),
+        );
+
+        // scalar sweep over the leftover 128-bit lane
+        let mut arr = [0u8; 16];
+        _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, curr);
+        *arr.iter().min().unwrap() as u32
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn horizontal_max(&self) -> u32 {
+        let mut curr = *self.v.get_unchecked(0);
+
+        for i in 1..self.v.len() {
+            curr = _mm512_max_epu8(curr, *self.v.get_unchecked(i));
+        }
+
+        // reduce the two 256-bit halves, then the two 128-bit halves
+        let curr = _mm256_max_epu8(
+            _mm512_castsi512_si256(curr),
+            _mm512_extracti64x4_epi64(curr, 1),
+        );
+        let curr = _mm_max_epu8(
+            _mm256_castsi256_si128(curr),
+            _mm256_extracti128_si256(curr, 1),
+        );
+
+        // scalar sweep over the leftover 128-bit lane
+        let mut arr = [0u8; 16];
+        _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, curr);
+        *arr.iter().max().unwrap() as u32
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self) {
+        for i in 0..self.v.len() {
+            *self.v.get_unchecked_mut(i) = _mm512_blendv_epi8(
+                *self.v.get_unchecked(i),
+                *b.v.get_unchecked(i),
+                *mask.v.get_unchecked(i),
+            );
+        }
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in 0..(self.v.len() - 1) {
+            let curr = *self.v.get_unchecked(i);
+            *self.v.get_unchecked_mut(i) = _mm512_alignr_1_epi8(*self.v.get_unchecked(i + 1), curr);
+        }
+
+        // last one gets to shift in zeros
+        let last = self.v.len() - 1;
+        let curr = *self.v.get_unchecked(last);
+        *self.v.get_unchecked_mut(last) = _mm512_alignr_1_epi8(_mm512_setzero_si512(), curr);
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in 0..(self.v.len() - 1) {
+            let curr = *self.v.get_unchecked(i);
+            *self.v.get_unchecked_mut(i) = _mm512_alignr_2_epi8(*self.v.get_unchecked(i + 1), curr);
+        }
+
+        // last one gets to shift in zeros
+        let last = self.v.len() - 1;
+        let curr = *self.v.get_unchecked(last);
+        *self.v.get_unchecked_mut(last) = _mm512_alignr_2_epi8(_mm512_setzero_si512(), curr);
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in (1..self.v.len()).rev() {
+            let curr = *self.v.get_unchecked(i);
+            *self.v.get_unchecked_mut(i) =
+                _mm512_alignr_63_epi8(curr, *self.v.get_unchecked(i - 1));
+        }
+
+        // first one gets to shift in zeros
+        let curr = *self.v.get_unchecked(0);
+        *self.v.get_unchecked_mut(0) = _mm512_alignr_63_epi8(curr, _mm512_setzero_si512());
+    }
+
+    operation_param2!("avx512bw", add, _mm512_add_epi8);
+    operation_param2!("avx512bw", adds, _mm512_adds_epu8);
+    operation_param2!("avx512bw", subs, _mm512_subs_epu8);
+    operation_param2!("avx512bw", andnot, _mm512_andnot_si512);
+    operation_param2!("avx512bw", cmpeq, _mm512_cmpeq_epi8);
+    operation_param2!("avx512bw", min, _mm512_min_epu8);
+    operation_param2!("avx512bw", max, _mm512_max_epu8);
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
+        for i in 0..(a.v.len() - 1) {
+            *res.v.get_unchecked_mut(i) =
+                _mm512_alignr_1_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i));
+        }
+
+        // last one gets to shift in zeros
+        let last = a.v.len() - 1;
+        *res.v.get_unchecked_mut(last) =
+            _mm512_alignr_1_epi8(_mm512_setzero_si512(), *a.v.get_unchecked(last));
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
+        for i in (1..a.v.len()).rev() {
+            *res.v.get_unchecked_mut(i) =
+                _mm512_alignr_63_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1));
+        }
+
+        // first one gets to shift in zeros
+        *res.v.get_unchecked_mut(0) =
+            _mm512_alignr_63_epi8(*a.v.get_unchecked(0), _mm512_setzero_si512());
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn triple_argmin(sub: &Self, a_gap: &Self, b_gap: &Self, res_min: &mut Self) -> Self {
+        // return the edit used in addition to doing a min operation
+        let mut v = Vec::with_capacity(sub.v.len());
+        let twos = _mm512_set1_epi8(2);
+
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+
+            let res_min1 = _mm512_min_epu8(a_gap, b_gap);
+            // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
+            let res_arg1 = _mm512_add_epi8(twos, _mm512_cmpeq_epi8(a_gap, res_min1));
+
+            let res_min2 = _mm512_min_epu8(sub, res_min1);
+            // sub: 0
+            let res_arg2 = _mm512_andnot_si512(_mm512_cmpeq_epi8(sub, res_min2), res_arg1);
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            v.push(res_arg2);
+        }
+
+        Self { v }
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn triple_min_length(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of maximizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm512_min_epu8(a_gap, b_gap);
+            // a gap: 1, b gap: 0
+            let a_b_gt_mask = _mm512_cmpeq_epi8_mask(a_gap, res_min1);
+            // lengths based on edits
+            let mut res_length1 = _mm512_mask_blend_epi8(a_b_gt_mask, b_gap_length, a_gap_length);
+            // equal: 1
+            let a_b_eq_mask = _mm512_cmpeq_epi8_mask(a_gap, b_gap);
+            let a_b_max_len = _mm512_max_epu8(a_gap_length, b_gap_length);
+            // maximize length if edits equal
+            res_length1 = _mm512_mask_blend_epi8(a_b_eq_mask, res_length1, a_b_max_len);
+
+            let res_min2 = _mm512_min_epu8(sub, res_min1);
+            // sub: 1, prev a or b gap: 0
+            let sub_gt_mask = _mm512_cmpeq_epi8_mask(sub, res_min2);
+            // length based on edits
+            let mut res_length2 = _mm512_mask_blend_epi8(sub_gt_mask, res_length1, sub_length);
+            let sub_eq_mask = _mm512_cmpeq_epi8_mask(sub, res_min1);
+            let sub_max_len = _mm512_max_epu8(sub_length, res_length1);
+            // maximize length if edits equal
+            res_length2 = _mm512_mask_blend_epi8(sub_eq_mask, res_length2, sub_max_len);
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm512_min_epu8(a_gap, b_gap);
+            // a gap: 1, b gap: 0
+            let a_b_gt_mask = _mm512_cmpeq_epi8_mask(a_gap, res_min1);
+            // lengths based on edits
+            let mut res_length1 = _mm512_mask_blend_epi8(a_b_gt_mask, b_gap_length, a_gap_length);
+            // equal: 1
+            let a_b_eq_mask = _mm512_cmpeq_epi8_mask(a_gap, b_gap);
+            let a_b_min_len = _mm512_min_epu8(a_gap_length, b_gap_length);
+            // minimize length if edits equal
+            res_length1 = _mm512_mask_blend_epi8(a_b_eq_mask, res_length1, a_b_min_len);
+
+            let res_min2 = _mm512_min_epu8(sub, res_min1);
+            // sub: 1, prev a or b gap: 0
+            let sub_gt_mask = _mm512_cmpeq_epi8_mask(sub, res_min2);
+            // length based on edits
+            let mut res_length2 = _mm512_mask_blend_epi8(sub_gt_mask, res_length1, sub_length);
+            let sub_eq_mask = _mm512_cmpeq_epi8_mask(sub, res_min1);
+            let sub_min_len = _mm512_min_epu8(sub_length, res_length1);
+            // minimize length if edits equal
+            res_length2 = _mm512_mask_blend_epi8(sub_eq_mask, res_length2, sub_min_len);
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
+    #[target_feature(enable = "avx512bw")]
+    #[inline]
+    unsafe fn double_min_length(
+        new_gap: &Self,
+        res_cont_gap: &mut Self,
+        new_gap_length: &Self,
+        res_cont_gap_length: &mut Self,
+    ) {
+        // choose the length based on which gap type is chosen during the min operation
+        // secondary objective of maximizing length if edit costs equal
+        for i in 0..new_gap.v.len() {
+            let new_gap = *new_gap.v.get_unchecked(i);
+            let cont_gap = *res_cont_gap.v.get_unchecked(i);
+            let new_gap_length = *new_gap_length.v.get_unchecked(i);
+            let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
+
+            let res_min = _mm512_min_epu8(new_gap, cont_gap);
+            // new gap: 1, continue gap: 0
+            let new_cont_gt_mask = _mm512_cmpeq_epi8_mask(new_gap, res_min);
+            // lengths based on edits
+            let mut res_length =
+                _mm512_mask_blend_epi8(new_cont_gt_mask, cont_gap_length, new_gap_length);
+            // equal: 1
+            let new_cont_eq_mask = _mm512_cmpeq_epi8_mask(new_gap, cont_gap);
+            let new_cont_max_len = _mm512_max_epu8(new_gap_length, cont_gap_length);
+            // maximize length if edits equal
+            res_length = _mm512_mask_blend_epi8(new_cont_eq_mask, res_length, new_cont_max_len);
+
+            *res_cont_gap.v.get_unchecked_mut(i) = res_min;
+            *res_cont_gap_length.v.get_unchecked_mut(i) = res_length;
+        }
+    }
+}
+
+// this implementation will probably only be used for debugging
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl fmt::Display for Avx512Nx64x8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        #[target_feature(enable = "avx512bw")]
+        #[inline]
+        unsafe fn fmt_internal(s: &Avx512Nx64x8, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "[")?;
+
+            let mut arr = [0u8; 64];
+            let arr_ptr = arr.as_mut_ptr() as *mut __m512i;
+
+            for i in 0..s.v.len() {
+                _mm512_storeu_si512(arr_ptr, *s.v.get_unchecked(i));
+
+                for j in 0..64 {
+                    if i == s.v.len() - 1 && j == 63 {
+                        write!(f, "{:>3}", *arr.get_unchecked(j))?;
+                    } else {
+                        write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
+                    }
                 }
+            }
+
+            write!(f, "]")
+        }
+
+        unsafe { fmt_internal(self, f) }
+    }
+}
+
+/// N x 16 x 8 vector backed with 128-bit SSE vectors.
+macro_rules! create_sse_nx16x8 {
+    ($name:ident, $num:literal) => {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        pub struct $name {
+            v: [__m128i; $num],
+        }
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        impl Jewel for $name {
+            const LANE_BYTES: usize = 1;
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn repeating(val: u32, _len: usize) -> Self {
+                let v = [_mm_set1_epi8(Self::clamp_lane(val) as i8); $num];
 
                 Self { v: v }
             }
 
             #[target_feature(enable = "sse4.1")]
             #[inline]
-            unsafe fn triple_min_length(
-                sub: &Self,
-                a_gap: &Self,
-                b_gap: &Self,
-                sub_length: &Self,
-                a_gap_length: &Self,
-                b_gap_length: &Self,
-                res_min: &mut Self,
-                res_length: &mut Self,
-            ) {
-                // choose the length based on which edit is chosen during the min operation
-                // secondary objective of maximizing length if edit costs equal
-                for i in 0..sub.v.len() {
-                    let sub = *sub.v.get_unchecked(i);
-                    let a_gap = *a_gap.v.get_unchecked(i);
-                    let b_gap = *b_gap.v.get_unchecked(i);
-                    let sub_length = *sub_length.v.get_unchecked(i);
-                    let a_gap_length = *a_gap_length.v.get_unchecked(i);
-                    let b_gap_length = *b_gap_length.v.get_unchecked(i);
+            unsafe fn repeating_max(_len: usize) -> Self {
+                let v = [_mm_set1_epi8(-1i8); $num];
 
-                    let res_min1 = _mm_min_epu8(a_gap, b_gap);
-                    let a_b_gt_mask = _mm_cmpeq_epi8(a_gap, res_min1); // a gap: -1, b gap: 0
-                    let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
-                    let a_b_eq_mask = _mm_cmpeq_epi8(a_gap, b_gap); // equal: -1
-                    let a_b_max_len = _mm_max_epu8(a_gap_length, b_gap_length);
-                    res_length1 = _mm_blendv_epi8(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
+                Self { v: v }
+            }
+
+            #[inline]
+            fn upper_bound(&self) -> usize {
+                self.v.len() << 4
+            }
+
+            #[inline]
+            fn static_upper_bound() -> usize {
+                $num << 4
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool) {
+                if len == 0 {
+                    return;
+                }
+
+                debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
+                let mut arr = [0u8; 16];
+                let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+                let store_idx = if reverse { 15 } else { 0 };
+                let load_idx = if reverse { 0 } else { 15 };
+
+                for i in 0..len {
+                    let curr_idx = if reverse { idx - i } else { idx + i };
+                    let arr_idx = curr_idx & 15;
+
+                    if arr_idx == store_idx || i == 0 {
+                        _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 4));
+                    }
+
+                    *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize);
+
+                    if arr_idx == load_idx || i == len - 1 {
+                        *self.v.get_unchecked_mut(curr_idx >> 4) = _mm_loadu_si128(arr_ptr);
+                    }
+                }
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn slow_extract(&self, i: usize) -> u32 {
+                let idx = i >> 4;
+                let j = i & 15;
+                let mut arr = [0u8; 16];
+                _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, *self.v.get_unchecked(idx));
+                *arr.get_unchecked(j) as u32
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn slow_insert(&mut self, i: usize, val: u32) {
+                let idx = i >> 4;
+                let j = i & 15;
+                let mut arr = [0u8; 16];
+                let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(idx));
+                *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u8;
+                *self.v.get_unchecked_mut(idx) = _mm_loadu_si128(arr_ptr);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_last_0(&mut self, val: u32) {
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) = _mm_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i32,
+                    15i32,
+                );
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_last_1(&mut self, val: u32) {
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) = _mm_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i32,
+                    14i32,
+                );
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_last_2(&mut self, val: u32) {
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) = _mm_insert_epi8(
+                    *self.v.get_unchecked(last),
+                    Self::clamp_lane(val) as i32,
+                    13i32,
+                );
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_last_max(&mut self) {
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) =
+                    _mm_insert_epi8(*self.v.get_unchecked(last), u8::MAX as i32, 15i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_first(&mut self, val: u32) {
+                *self.v.get_unchecked_mut(0) =
+                    _mm_insert_epi8(*self.v.get_unchecked(0), Self::clamp_lane(val) as i32, 0i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn insert_first_max(&mut self) {
+                *self.v.get_unchecked_mut(0) =
+                    _mm_insert_epi8(*self.v.get_unchecked(0), u8::MAX as i32, 0i32);
+            }
+
+            operation_mut_param2!("sse4.1", add_mut, _mm_add_epi8);
+            operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu8);
+            operation_mut_param2!("sse4.1", subs_mut, _mm_subs_epu8);
+            operation_mut_param2!("sse4.1", and_mut, _mm_and_si128);
+            operation_mut_param2!("sse4.1", andnot_mut, _mm_andnot_si128);
+            operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi8);
+            operation_mut_param2!("sse4.1", min_mut, _mm_min_epu8);
+            operation_mut_param2!("sse4.1", max_mut, _mm_max_epu8);
+            operation_fold_mut!(
+                "sse4.1",
+                _mm_set1_epi8,
+                _mm_cmpgt_epi8,
+                _mm_and_si128,
+                _mm_or_si128
+            );
+            operation_horizontal_sse!(horizontal_min, _mm_min_epu8, u8, min);
+            operation_horizontal_sse!(horizontal_max, _mm_max_epu8, u8, max);
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self) {
+                for i in 0..self.v.len() {
+                    *self.v.get_unchecked_mut(i) = _mm_blendv_epi8(
+                        *self.v.get_unchecked(i),
+                        *b.v.get_unchecked(i),
+                        *mask.v.get_unchecked(i),
+                    );
+                }
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn shift_left_1_mut(&mut self) {
+                for i in 0..(self.v.len() - 1) {
+                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
+                        *self.v.get_unchecked(i + 1),
+                        *self.v.get_unchecked(i),
+                        1i32,
+                    );
+                }
+
+                // last one gets to shift in zeros
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 1i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn shift_left_2_mut(&mut self) {
+                for i in 0..(self.v.len() - 1) {
+                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
+                        *self.v.get_unchecked(i + 1),
+                        *self.v.get_unchecked(i),
+                        2i32,
+                    );
+                }
+
+                // last one gets to shift in zeros
+                let last = self.v.len() - 1;
+                *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 2i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn shift_right_1_mut(&mut self) {
+                for i in (1..self.v.len()).rev() {
+                    *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
+                        *self.v.get_unchecked(i),
+                        *self.v.get_unchecked(i - 1),
+                        15i32,
+                    );
+                }
+
+                // first one gets to shift in zeros
+                *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 1i32);
+            }
+
+            operation_param2!("sse4.1", add, _mm_add_epi8);
+            operation_param2!("sse4.1", adds, _mm_adds_epu8);
+            operation_param2!("sse4.1", subs, _mm_subs_epu8);
+            operation_param2!("sse4.1", andnot, _mm_andnot_si128);
+            operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi8);
+            operation_param2!("sse4.1", min, _mm_min_epu8);
+            operation_param2!("sse4.1", max, _mm_max_epu8);
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+                for i in 0..(a.v.len() - 1) {
+                    *res.v.get_unchecked_mut(i) =
+                        _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 1i32);
+                }
+
+                // last one gets to shift in zeros
+                let last = a.v.len() - 1;
+                *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 1i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+                for i in (1..a.v.len()).rev() {
+                    *res.v.get_unchecked_mut(i) =
+                        _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 15i32);
+                }
+
+                // first one gets to shift in zeros
+                *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 1i32);
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn triple_argmin(
+                sub: &Self,
+                a_gap: &Self,
+                b_gap: &Self,
+                res_min: &mut Self,
+            ) -> Self {
+                // return the edit used in addition to doing a min operation
+                let mut v = [_mm_undefined_si128(); $num];
+                let twos = _mm_set1_epi8(2);
+
+                for i in 0..sub.v.len() {
+                    let sub = *sub.v.get_unchecked(i);
+                    let a_gap = *a_gap.v.get_unchecked(i);
+                    let b_gap = *b_gap.v.get_unchecked(i);
+
+                    let res_min1 = _mm_min_epu8(a_gap, b_gap);
+                    // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
+                    let res_arg1 = _mm_add_epi8(twos, _mm_cmpeq_epi8(a_gap, res_min1));
+
+                    let res_min2 = _mm_min_epu8(sub, res_min1);
+                    // sub: 0
+                    let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi8(sub, res_min2), res_arg1);
+
+                    *res_min.v.get_unchecked_mut(i) = res_min2;
+                    *v.get_unchecked_mut(i) = res_arg2;
+                }
+
+                Self { v: v }
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn triple_min_length(
+                sub: &Self,
+                a_gap: &Self,
+                b_gap: &Self,
+                sub_length: &Self,
+                a_gap_length: &Self,
+                b_gap_length: &Self,
+                res_min: &mut Self,
+                res_length: &mut Self,
+            ) {
+                // choose the length based on which edit is chosen during the min operation
+                // secondary objective of maximizing length if edit costs equal
+                for i in 0..sub.v.len() {
+                    let sub = *sub.v.get_unchecked(i);
+                    let a_gap = *a_gap.v.get_unchecked(i);
+                    let b_gap = *b_gap.v.get_unchecked(i);
+                    let sub_length = *sub_length.v.get_unchecked(i);
+                    let a_gap_length = *a_gap_length.v.get_unchecked(i);
+                    let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+                    let res_min1 = _mm_min_epu8(a_gap, b_gap);
+                    let a_b_gt_mask = _mm_cmpeq_epi8(a_gap, res_min1); // a gap: -1, b gap: 0
+                    let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+                    let a_b_eq_mask = _mm_cmpeq_epi8(a_gap, b_gap); // equal: -1
+                    let a_b_max_len = _mm_max_epu8(a_gap_length, b_gap_length);
+                    res_length1 = _mm_blendv_epi8(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
+
+                    let res_min2 = _mm_min_epu8(sub, res_min1);
+                    let sub_gt_mask = _mm_cmpeq_epi8(sub, res_min2); // sub: -1, prev a or b gap: 0
+                    let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+                    let sub_eq_mask = _mm_cmpeq_epi8(sub, res_min1);
+                    let sub_max_len = _mm_max_epu8(sub_length, res_length1);
+                    res_length2 = _mm_blendv_epi8(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
+
+                    *res_min.v.get_unchecked_mut(i) = res_min2;
+                    *res_length.v.get_unchecked_mut(i) = res_length2;
+                }
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn triple_min_length_short(
+                sub: &Self,
+                a_gap: &Self,
+                b_gap: &Self,
+                sub_length: &Self,
+                a_gap_length: &Self,
+                b_gap_length: &Self,
+                res_min: &mut Self,
+                res_length: &mut Self,
+            ) {
+                // choose the length based on which edit is chosen during the min operation
+                // secondary objective of minimizing length if edit costs equal
+                for i in 0..sub.v.len() {
+                    let sub = *sub.v.get_unchecked(i);
+                    let a_gap = *a_gap.v.get_unchecked(i);
+                    let b_gap = *b_gap.v.get_unchecked(i);
+                    let sub_length = *sub_length.v.get_unchecked(i);
+                    let a_gap_length = *a_gap_length.v.get_unchecked(i);
+                    let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+                    let res_min1 = _mm_min_epu8(a_gap, b_gap);
+                    let a_b_gt_mask = _mm_cmpeq_epi8(a_gap, res_min1); // a gap: -1, b gap: 0
+                    let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+                    let a_b_eq_mask = _mm_cmpeq_epi8(a_gap, b_gap); // equal: -1
+                    let a_b_min_len = _mm_min_epu8(a_gap_length, b_gap_length);
+                    res_length1 = _mm_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+                    let res_min2 = _mm_min_epu8(sub, res_min1);
+                    let sub_gt_mask = _mm_cmpeq_epi8(sub, res_min2); // sub: -1, prev a or b gap: 0
+                    let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+                    let sub_eq_mask = _mm_cmpeq_epi8(sub, res_min1);
+                    let sub_min_len = _mm_min_epu8(sub_length, res_length1);
+                    res_length2 = _mm_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
+
+                    *res_min.v.get_unchecked_mut(i) = res_min2;
+                    *res_length.v.get_unchecked_mut(i) = res_length2;
+                }
+            }
+
+            #[target_feature(enable = "sse4.1")]
+            #[inline]
+            unsafe fn double_min_length(
+                new_gap: &Self,
+                res_cont_gap: &mut Self,
+                new_gap_length: &Self,
+                res_cont_gap_length: &mut Self,
+            ) {
+                // choose the length based on which gap type is chosen during the min operation
+                // secondary objective of maximizing length if edit costs equal
+                for i in 0..new_gap.v.len() {
+                    let new_gap = *new_gap.v.get_unchecked(i);
+                    let cont_gap = *res_cont_gap.v.get_unchecked(i);
+                    let new_gap_length = *new_gap_length.v.get_unchecked(i);
+                    let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
+
+                    let res_min = _mm_min_epu8(new_gap, cont_gap);
+                    let new_cont_gt_mask = _mm_cmpeq_epi8(new_gap, res_min); // new gap: -1, continue gap: 0
+                    let mut res_length =
+                        _mm_blendv_epi8(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
+                    let new_cont_eq_mask = _mm_cmpeq_epi8(new_gap, cont_gap); // equal: -1
+                    let new_cont_max_len = _mm_max_epu8(new_gap_length, cont_gap_length);
+                    res_length = _mm_blendv_epi8(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
+
+                    *res_cont_gap.v.get_unchecked_mut(i) = res_min;
+                    *res_cont_gap_length.v.get_unchecked_mut(i) = res_length;
+                }
+            }
+        }
+
+        // this implementation will probably only be used for debugging
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                #[target_feature(enable = "sse4.1")]
+                #[inline]
+                unsafe fn fmt_internal(s: &$name, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "[")?;
+
+                    let mut arr = [0u8; 16];
+                    let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+
+                    for i in 0..(s.v.len() - 1) {
+                        _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(i));
+
+                        for j in 0..16 {
+                            write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
+                        }
+                    }
+
+                    // leftover elements
+
+                    _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(s.v.len() - 1));
+
+                    let start = (s.v.len() - 1) << 4;
+
+                    for i in 0..(s.upper_bound() - start) {
+                        if i == s.upper_bound() - start - 1 {
+                            write!(f, "{:>3}", *arr.get_unchecked(i))?;
+                        } else {
+                            write!(f, "{:>3}, ", *arr.get_unchecked(i))?;
+                        }
+                    }
+
+                    write!(f, "]")
+                }
+
+                unsafe { fmt_internal(self, f) }
+            }
+        }
+    };
+}
+
+// constant array size, so the compiler should unroll the loops
+create_sse_nx16x8!(Sse1x16x8, 1);
+create_sse_nx16x8!(Sse2x16x8, 2);
+create_sse_nx16x8!(Sse4x16x8, 4);
+create_sse_nx16x8!(Sse8x16x8, 8);
+create_sse_nx16x8!(Sse16x16x8, 16);
+
+/// N x 8 x 16 vector backed with 128-bit SSE vectors.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub struct SseNx8x16 {
+    v: Vec<__m128i>,
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl Jewel for SseNx8x16 {
+    const LANE_BYTES: usize = 2;
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn repeating(val: u32, len: usize) -> Self {
+        let v = alloc::vec![_mm_set1_epi16(Self::clamp_lane(val) as i16); num_vectors(len, 3)];
+
+        Self { v: v }
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn repeating_max(len: usize) -> Self {
+        let v = alloc::vec![_mm_set1_epi16(-1i16); num_vectors(len, 3)];
+
+        Self { v: v }
+    }
+
+    operation_reset!("sse4.1", 3, val => _mm_set1_epi16(Self::clamp_lane(val) as i16), _mm_set1_epi16(-1i16));
+
+    #[inline]
+    fn upper_bound(&self) -> usize {
+        self.v.len() << 3
+    }
+
+    #[inline]
+    fn static_upper_bound() -> usize {
+        unimplemented!()
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool) {
+        if len == 0 {
+            return;
+        }
+
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
+        let mut arr = [0u16; 8];
+        let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+        let store_idx = if reverse { 7 } else { 0 };
+        let load_idx = if reverse { 0 } else { 7 };
+
+        for i in 0..len {
+            let curr_idx = if reverse { idx - i } else { idx + i };
+            let arr_idx = curr_idx & 7;
+
+            if arr_idx == store_idx || i == 0 {
+                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 3));
+            }
+
+            *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize) as u16;
+
+            if arr_idx == load_idx || i == len - 1 {
+                *self.v.get_unchecked_mut(curr_idx >> 3) = _mm_loadu_si128(arr_ptr);
+            }
+        }
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn slow_extract(&self, i: usize) -> u32 {
+        let idx = i >> 3;
+        let j = i & 7;
+        let mut arr = [0u16; 8];
+        _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, *self.v.get_unchecked(idx));
+        *arr.get_unchecked(j) as u32
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn slow_insert(&mut self, i: usize, val: u32) {
+        let idx = i >> 3;
+        let j = i & 7;
+        let mut arr = [0u16; 8];
+        let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+        _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(idx));
+        *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u16;
+        *self.v.get_unchecked_mut(idx) = _mm_loadu_si128(arr_ptr);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_last_0(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i32,
+            7i32,
+        );
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_last_1(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i32,
+            6i32,
+        );
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_last_2(&mut self, val: u32) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm_insert_epi16(
+            *self.v.get_unchecked(last),
+            Self::clamp_lane(val) as i32,
+            5i32,
+        );
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_last_max(&mut self) {
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) =
+            _mm_insert_epi16(*self.v.get_unchecked(last), u16::MAX as i32, 7i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_first(&mut self, val: u32) {
+        *self.v.get_unchecked_mut(0) =
+            _mm_insert_epi16(*self.v.get_unchecked(0), Self::clamp_lane(val) as i32, 0i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn insert_first_max(&mut self) {
+        *self.v.get_unchecked_mut(0) =
+            _mm_insert_epi16(*self.v.get_unchecked(0), u16::MAX as i32, 0i32);
+    }
+
+    operation_mut_param2!("sse4.1", add_mut, _mm_add_epi16);
+    operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu16);
+    operation_mut_param2!("sse4.1", subs_mut, _mm_subs_epu16);
+    operation_mut_param2!("sse4.1", and_mut, _mm_and_si128);
+    operation_mut_param2!("sse4.1", andnot_mut, _mm_andnot_si128);
+    operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi16);
+    operation_mut_param2!("sse4.1", min_mut, _mm_min_epu16);
+    operation_mut_param2!("sse4.1", max_mut, _mm_max_epu16);
+    operation_fold_mut!(
+        "sse4.1",
+        _mm_set1_epi16,
+        _mm_cmpgt_epi16,
+        _mm_and_si128,
+        _mm_or_si128
+    );
+    operation_horizontal_sse!(horizontal_min, _mm_min_epu16, u16, min);
+    operation_horizontal_sse!(horizontal_max, _mm_max_epu16, u16, max);
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self) {
+        for i in 0..self.v.len() {
+            *self.v.get_unchecked_mut(i) = _mm_blendv_epi8(
+                *self.v.get_unchecked(i),
+                *b.v.get_unchecked(i),
+                *mask.v.get_unchecked(i),
+            );
+        }
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in 0..(self.v.len() - 1) {
+            *self.v.get_unchecked_mut(i) =
+                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 2i32);
+        }
+
+        // last one gets to shift in zeros
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 2i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in 0..(self.v.len() - 1) {
+            *self.v.get_unchecked_mut(i) =
+                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 4i32);
+        }
+
+        // last one gets to shift in zeros
+        let last = self.v.len() - 1;
+        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 4i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
+        for i in (1..self.v.len()).rev() {
+            *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
+                *self.v.get_unchecked(i),
+                *self.v.get_unchecked(i - 1),
+                14i32,
+            );
+        }
+
+        // first one gets to shift in zeros
+        *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 2i32);
+    }
+
+    operation_param2!("sse4.1", add, _mm_add_epi16);
+    operation_param2!("sse4.1", adds, _mm_adds_epu16);
+    operation_param2!("sse4.1", subs, _mm_subs_epu16);
+    operation_param2!("sse4.1", andnot, _mm_andnot_si128);
+    operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi16);
+    operation_param2!("sse4.1", min, _mm_min_epu16);
+    operation_param2!("sse4.1", max, _mm_max_epu16);
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
+        for i in 0..(a.v.len() - 1) {
+            *res.v.get_unchecked_mut(i) =
+                _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 2i32);
+        }
+
+        // last one gets to shift in zeros
+        let last = a.v.len() - 1;
+        *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 2i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
+        for i in (1..a.v.len()).rev() {
+            *res.v.get_unchecked_mut(i) =
+                _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 14i32);
+        }
+
+        // first one gets to shift in zeros
+        *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 2i32);
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn triple_argmin(sub: &Self, a_gap: &Self, b_gap: &Self, res_min: &mut Self) -> Self {
+        // return the edit used in addition to doing a min operation
+        let mut v = Vec::with_capacity(sub.v.len());
+        let twos = _mm_set1_epi16(2);
+
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+
+            let res_min1 = _mm_min_epu16(a_gap, b_gap);
+            // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
+            let res_arg1 = _mm_add_epi16(twos, _mm_cmpeq_epi16(a_gap, res_min1));
+
+            let res_min2 = _mm_min_epu16(sub, res_min1);
+            // sub: 0
+            let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi16(sub, res_min2), res_arg1);
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            v.push(res_arg2);
+        }
+
+        Self { v: v }
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn triple_min_length(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of maximizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm_min_epu16(a_gap, b_gap);
+            let a_b_gt_mask = _mm_cmpeq_epi16(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = _mm_cmpeq_epi16(a_gap, b_gap); // equal: -1
+            let a_b_max_len = _mm_max_epu16(a_gap_length, b_gap_length);
+            res_length1 = _mm_blendv_epi8(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
+
+            let res_min2 = _mm_min_epu16(sub, res_min1);
+            let sub_gt_mask = _mm_cmpeq_epi16(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = _mm_cmpeq_epi16(sub, res_min1);
+            let sub_max_len = _mm_max_epu16(sub_length, res_length1);
+            res_length2 = _mm_blendv_epi8(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
 
-                    let res_min2 = _mm_min_epu8(sub, res_min1);
-                    let sub_gt_mask = _mm_cmpeq_epi8(sub, res_min2); // sub: -1, prev a or b gap: 0
-                    let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
-                    let sub_eq_mask = _mm_cmpeq_epi8(sub, res_min1);
-                    let sub_max_len = _mm_max_epu8(sub_length, res_length1);
-                    res_length2 = _mm_blendv_epi8(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
+            let res_min1 = _mm_min_epu16(a_gap, b_gap);
+            let a_b_gt_mask = _mm_cmpeq_epi16(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = _mm_cmpeq_epi16(a_gap, b_gap); // equal: -1
+            let a_b_min_len = _mm_min_epu16(a_gap_length, b_gap_length);
+            res_length1 = _mm_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
 
-                    *res_min.v.get_unchecked_mut(i) = res_min2;
-                    *res_length.v.get_unchecked_mut(i) = res_length2;
-                }
-            }
+            let res_min2 = _mm_min_epu16(sub, res_min1);
+            let sub_gt_mask = _mm_cmpeq_epi16(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = _mm_cmpeq_epi16(sub, res_min1);
+            let sub_min_len = _mm_min_epu16(sub_length, res_length1);
+            res_length2 = _mm_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
 
-            #[target_feature(enable = "sse4.1")]
-            #[inline]
-            unsafe fn double_min_length(
-                new_gap: &Self,
-                res_cont_gap: &mut Self,
-                new_gap_length: &Self,
-                res_cont_gap_length: &mut Self,
-            ) {
-                // choose the length based on which gap type is chosen during the min operation
-                // secondary objective of maximizing length if edit costs equal
-                for i in 0..new_gap.v.len() {
-                    let new_gap = *new_gap.v.get_unchecked(i);
-                    let cont_gap = *res_cont_gap.v.get_unchecked(i);
-                    let new_gap_length = *new_gap_length.v.get_unchecked(i);
-                    let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
 
-                    let res_min = _mm_min_epu8(new_gap, cont_gap);
-                    let new_cont_gt_mask = _mm_cmpeq_epi8(new_gap, res_min); // new gap: -1, continue gap: 0
-                    let mut res_length =
-                        _mm_blendv_epi8(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
-                    let new_cont_eq_mask = _mm_cmpeq_epi8(new_gap, cont_gap); // equal: -1
-                    let new_cont_max_len = _mm_max_epu8(new_gap_length, cont_gap_length);
-                    res_length = _mm_blendv_epi8(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn double_min_length(
+        new_gap: &Self,
+        res_cont_gap: &mut Self,
+        new_gap_length: &Self,
+        res_cont_gap_length: &mut Self,
+    ) {
+        // choose the length based on which gap type is chosen during the min operation
+        // secondary objective of maximizing length if edit costs equal
+        for i in 0..new_gap.v.len() {
+            let new_gap = *new_gap.v.get_unchecked(i);
+            let cont_gap = *res_cont_gap.v.get_unchecked(i);
+            let new_gap_length = *new_gap_length.v.get_unchecked(i);
+            let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
 
-                    *res_cont_gap.v.get_unchecked_mut(i) = res_min;
-                    *res_cont_gap_length.v.get_unchecked_mut(i) = res_length;
-                }
-            }
+            let res_min = _mm_min_epu16(new_gap, cont_gap);
+            let new_cont_gt_mask = _mm_cmpeq_epi16(new_gap, res_min); // new gap: -1, continue gap: 0
+            let mut res_length = _mm_blendv_epi8(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
+            let new_cont_eq_mask = _mm_cmpeq_epi16(new_gap, cont_gap); // equal: -1
+            let new_cont_max_len = _mm_max_epu16(new_gap_length, cont_gap_length);
+            res_length = _mm_blendv_epi8(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
+
+            *res_cont_gap.v.get_unchecked_mut(i) = res_min;
+            *res_cont_gap_length.v.get_unchecked_mut(i) = res_length;
         }
+    }
+}
 
-        // this implementation will probably only be used for debugging
+// this implementation will probably only be used for debugging
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl fmt::Display for SseNx8x16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        impl fmt::Display for $name {
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-                #[target_feature(enable = "sse4.1")]
-                #[inline]
-                unsafe fn fmt_internal(s: &$name, f: &mut fmt::Formatter) -> fmt::Result {
-                    write!(f, "[")?;
-
-                    let mut arr = [0u8; 16];
-                    let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+        #[target_feature(enable = "sse4.1")]
+        #[inline]
+        unsafe fn fmt_internal(s: &SseNx8x16, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "[")?;
 
-                    for i in 0..(s.v.len() - 1) {
-                        _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(i));
+            let mut arr = [0u16; 8];
+            let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
 
-                        for j in 0..16 {
-                            write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
-                        }
-                    }
+            for i in 0..(s.v.len() - 1) {
+                _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(i));
 
-                    // leftover elements
+                for j in 0..8 {
+                    write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
+                }
+            }
 
-                    _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(s.v.len() - 1));
+            // leftover elements
 
-                    let start = (s.v.len() - 1) << 4;
+            _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(s.v.len() - 1));
 
-                    for i in 0..(s.upper_bound() - start) {
-                        if i == s.upper_bound() - start - 1 {
-                            write!(f, "{:>3}", *arr.get_unchecked(i))?;
-                        } else {
-                            write!(f, "{:>3}, ", *arr.get_unchecked(i))?;
-                        }
-                    }
+            let start = (s.v.len() - 1) << 3;
 
-                    write!(f, "]")
+            for i in 0..(s.upper_bound() - start) {
+                if i == s.upper_bound() - start - 1 {
+                    write!(f, "{:>3}", *arr.get_unchecked(i))?;
+                } else {
+                    write!(f, "{:>3}, ", *arr.get_unchecked(i))?;
                 }
-
-                unsafe { fmt_internal(self, f) }
             }
+
+            write!(f, "]")
         }
-    };
-}
 
-// constant array size, so the compiler should unroll the loops
-create_sse_nx16x8!(Sse1x16x8, 1);
-create_sse_nx16x8!(Sse2x16x8, 2);
-create_sse_nx16x8!(Sse4x16x8, 4);
-create_sse_nx16x8!(Sse8x16x8, 8);
-create_sse_nx16x8!(Sse16x16x8, 16);
+        unsafe { fmt_internal(self, f) }
+    }
+}
 
-/// N x 8 x 16 vector backed with 128-bit SSE vectors.
+/// N x 4 x 32 vector backed with 128-bit SSE vectors.
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-pub struct SseNx8x16 {
+pub struct SseNx4x32 {
     v: Vec<__m128i>,
 }
 
+/// Workaround for the lack of the _mm_subs_epu32 intrinsic.
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-impl Jewel for SseNx8x16 {
+#[target_feature(enable = "sse4.1")]
+#[inline]
+unsafe fn _mm_subs_epu32(a: __m128i, b: __m128i) -> __m128i {
+    // clamp a to at least b first, so the difference is zero instead of wrapping around
+    _mm_sub_epi32(_mm_max_epu32(a, b), b)
+}
+
+/// Workaround for the lack of the _mm_adds_epu32 intrinsic.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse4.1")]
+#[inline]
+unsafe fn _mm_adds_epu32(a: __m128i, b: __m128i) -> __m128i {
+    let sum = _mm_add_epi32(a, b);
+    let min = _mm_min_epu32(a, sum);
+    let eq = _mm_cmpeq_epi32(a, min);
+    // if the sum is less than a, then saturate
+    // note: sum is either greater than both a and b or less than both
+    _mm_blendv_epi8(_mm_set1_epi32(-1i32), sum, eq)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl Jewel for SseNx4x32 {
+    const LANE_BYTES: usize = 4;
+
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn repeating(val: u32, len: usize) -> Self {
-        let v =
-            alloc::vec![_mm_set1_epi16(val as i16); (len >> 3) + if (len & 7) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm_set1_epi32(val as i32); num_vectors(len, 2)];
 
         Self { v: v }
     }
@@ -1798,14 +3341,16 @@ impl Jewel for SseNx8x16 {
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn repeating_max(len: usize) -> Self {
-        let v = alloc::vec![_mm_set1_epi16(-1i16); (len >> 3) + if (len & 7) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![_mm_set1_epi32(-1i32); num_vectors(len, 2)];
 
         Self { v: v }
     }
 
+    operation_reset!("sse4.1", 2, val => _mm_set1_epi32(val as i32), _mm_set1_epi32(-1i32));
+
     #[inline]
     fn upper_bound(&self) -> usize {
-        self.v.len() << 3
+        self.v.len() << 2
     }
 
     #[inline]
@@ -1820,23 +3365,25 @@ impl Jewel for SseNx8x16 {
             return;
         }
 
-        let mut arr = [0u16; 8];
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
+        let mut arr = [0u32; 4];
         let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
-        let store_idx = if reverse { 7 } else { 0 };
-        let load_idx = if reverse { 0 } else { 7 };
+        let store_idx = if reverse { 3 } else { 0 };
+        let load_idx = if reverse { 0 } else { 3 };
 
         for i in 0..len {
             let curr_idx = if reverse { idx - i } else { idx + i };
-            let arr_idx = curr_idx & 7;
+            let arr_idx = curr_idx & 3;
 
             if arr_idx == store_idx || i == 0 {
-                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 3));
+                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 2));
             }
 
-            *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize) as u16;
+            *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize) as u32;
 
             if arr_idx == load_idx || i == len - 1 {
-                *self.v.get_unchecked_mut(curr_idx >> 3) = _mm_loadu_si128(arr_ptr);
+                *self.v.get_unchecked_mut(curr_idx >> 2) = _mm_loadu_si128(arr_ptr);
             }
         }
     }
@@ -1844,22 +3391,22 @@ impl Jewel for SseNx8x16 {
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn slow_extract(&self, i: usize) -> u32 {
-        let idx = i >> 3;
-        let j = i & 7;
-        let mut arr = [0u16; 8];
+        let idx = i >> 2;
+        let j = i & 3;
+        let mut arr = [0u32; 4];
         _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, *self.v.get_unchecked(idx));
-        *arr.get_unchecked(j) as u32
+        *arr.get_unchecked(j)
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn slow_insert(&mut self, i: usize, val: u32) {
-        let idx = i >> 3;
-        let j = i & 7;
-        let mut arr = [0u16; 8];
+        let idx = i >> 2;
+        let j = i & 3;
+        let mut arr = [0u32; 4];
         let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
         _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(idx));
-        *arr.get_unchecked_mut(j) = val as u16;
+        *arr.get_unchecked_mut(j) = val;
         *self.v.get_unchecked_mut(idx) = _mm_loadu_si128(arr_ptr);
     }
 
@@ -1868,7 +3415,7 @@ impl Jewel for SseNx8x16 {
     unsafe fn insert_last_0(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi16(*self.v.get_unchecked(last), val as i32, 7i32);
+            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 3i32);
     }
 
     #[target_feature(enable = "sse4.1")]
@@ -1876,7 +3423,7 @@ impl Jewel for SseNx8x16 {
     unsafe fn insert_last_1(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi16(*self.v.get_unchecked(last), val as i32, 6i32);
+            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 2i32);
     }
 
     #[target_feature(enable = "sse4.1")]
@@ -1884,7 +3431,7 @@ impl Jewel for SseNx8x16 {
     unsafe fn insert_last_2(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi16(*self.v.get_unchecked(last), val as i32, 5i32);
+            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 1i32);
     }
 
     #[target_feature(enable = "sse4.1")]
@@ -1892,29 +3439,38 @@ impl Jewel for SseNx8x16 {
     unsafe fn insert_last_max(&mut self) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi16(*self.v.get_unchecked(last), u16::MAX as i32, 7i32);
+            _mm_insert_epi32(*self.v.get_unchecked(last), -1i32, 3i32);
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_first(&mut self, val: u32) {
-        *self.v.get_unchecked_mut(0) = _mm_insert_epi16(*self.v.get_unchecked(0), val as i32, 0i32);
+        *self.v.get_unchecked_mut(0) = _mm_insert_epi32(*self.v.get_unchecked(0), val as i32, 0i32);
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_first_max(&mut self) {
-        *self.v.get_unchecked_mut(0) =
-            _mm_insert_epi16(*self.v.get_unchecked(0), u16::MAX as i32, 0i32);
+        *self.v.get_unchecked_mut(0) = _mm_insert_epi32(*self.v.get_unchecked(0), -1i32, 0i32);
     }
 
-    operation_mut_param2!("sse4.1", add_mut, _mm_add_epi16);
-    operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu16);
+    operation_mut_param2!("sse4.1", add_mut, _mm_add_epi32);
+    operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu32);
+    operation_mut_param2!("sse4.1", subs_mut, _mm_subs_epu32);
     operation_mut_param2!("sse4.1", and_mut, _mm_and_si128);
     operation_mut_param2!("sse4.1", andnot_mut, _mm_andnot_si128);
-    operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi16);
-    operation_mut_param2!("sse4.1", min_mut, _mm_min_epu16);
-    operation_mut_param2!("sse4.1", max_mut, _mm_max_epu16);
+    operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi32);
+    operation_mut_param2!("sse4.1", min_mut, _mm_min_epu32);
+    operation_mut_param2!("sse4.1", max_mut, _mm_max_epu32);
+    operation_fold_mut!(
+        "sse4.1",
+        _mm_set1_epi32,
+        _mm_cmpgt_epi32,
+        _mm_and_si128,
+        _mm_or_si128
+    );
+    operation_horizontal_sse!(horizontal_min, _mm_min_epu32, u32, min);
+    operation_horizontal_sse!(horizontal_max, _mm_max_epu32, u32, max);
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
@@ -1931,74 +3487,80 @@ impl Jewel for SseNx8x16 {
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             *self.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 2i32);
+                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 4i32);
         }
 
         // last one gets to shift in zeros
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 2i32);
+        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 4i32);
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             *self.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 4i32);
+                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 8i32);
         }
 
         // last one gets to shift in zeros
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 4i32);
+        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 8i32);
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in (1..self.v.len()).rev() {
             *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
                 *self.v.get_unchecked(i),
                 *self.v.get_unchecked(i - 1),
-                14i32,
+                12i32,
             );
         }
 
         // first one gets to shift in zeros
-        *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 2i32);
+        *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 4i32);
     }
 
-    operation_param2!("sse4.1", add, _mm_add_epi16);
-    operation_param2!("sse4.1", adds, _mm_adds_epu16);
+    operation_param2!("sse4.1", add, _mm_add_epi32);
+    operation_param2!("sse4.1", adds, _mm_adds_epu32);
+    operation_param2!("sse4.1", subs, _mm_subs_epu32);
     operation_param2!("sse4.1", andnot, _mm_andnot_si128);
-    operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi16);
-    operation_param2!("sse4.1", min, _mm_min_epu16);
-    operation_param2!("sse4.1", max, _mm_max_epu16);
+    operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi32);
+    operation_param2!("sse4.1", min, _mm_min_epu32);
+    operation_param2!("sse4.1", max, _mm_max_epu32);
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in 0..(a.v.len() - 1) {
             *res.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 2i32);
+                _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 4i32);
         }
 
         // last one gets to shift in zeros
         let last = a.v.len() - 1;
-        *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 2i32);
+        *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 4i32);
     }
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in (1..a.v.len()).rev() {
             *res.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 14i32);
+                _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 12i32);
         }
 
         // first one gets to shift in zeros
-        *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 2i32);
+        *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 4i32);
     }
 
     #[target_feature(enable = "sse4.1")]
@@ -2006,20 +3568,20 @@ impl Jewel for SseNx8x16 {
     unsafe fn triple_argmin(sub: &Self, a_gap: &Self, b_gap: &Self, res_min: &mut Self) -> Self {
         // return the edit used in addition to doing a min operation
         let mut v = Vec::with_capacity(sub.v.len());
-        let twos = _mm_set1_epi16(2);
+        let twos = _mm_set1_epi32(2);
 
         for i in 0..sub.v.len() {
             let sub = *sub.v.get_unchecked(i);
             let a_gap = *a_gap.v.get_unchecked(i);
             let b_gap = *b_gap.v.get_unchecked(i);
 
-            let res_min1 = _mm_min_epu16(a_gap, b_gap);
+            let res_min1 = _mm_min_epu32(a_gap, b_gap);
             // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
-            let res_arg1 = _mm_add_epi16(twos, _mm_cmpeq_epi16(a_gap, res_min1));
+            let res_arg1 = _mm_add_epi32(twos, _mm_cmpeq_epi32(a_gap, res_min1));
 
-            let res_min2 = _mm_min_epu16(sub, res_min1);
+            let res_min2 = _mm_min_epu32(sub, res_min1);
             // sub: 0
-            let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi16(sub, res_min2), res_arg1);
+            let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi32(sub, res_min2), res_arg1);
 
             *res_min.v.get_unchecked_mut(i) = res_min2;
             v.push(res_arg2);
@@ -2050,18 +3612,18 @@ impl Jewel for SseNx8x16 {
             let a_gap_length = *a_gap_length.v.get_unchecked(i);
             let b_gap_length = *b_gap_length.v.get_unchecked(i);
 
-            let res_min1 = _mm_min_epu16(a_gap, b_gap);
-            let a_b_gt_mask = _mm_cmpeq_epi16(a_gap, res_min1); // a gap: -1, b gap: 0
+            let res_min1 = _mm_min_epu32(a_gap, b_gap);
+            let a_b_gt_mask = _mm_cmpeq_epi32(a_gap, res_min1); // a gap: -1, b gap: 0
             let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
-            let a_b_eq_mask = _mm_cmpeq_epi16(a_gap, b_gap); // equal: -1
-            let a_b_max_len = _mm_max_epu16(a_gap_length, b_gap_length);
+            let a_b_eq_mask = _mm_cmpeq_epi32(a_gap, b_gap); // equal: -1
+            let a_b_max_len = _mm_max_epu32(a_gap_length, b_gap_length);
             res_length1 = _mm_blendv_epi8(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
 
-            let res_min2 = _mm_min_epu16(sub, res_min1);
-            let sub_gt_mask = _mm_cmpeq_epi16(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let res_min2 = _mm_min_epu32(sub, res_min1);
+            let sub_gt_mask = _mm_cmpeq_epi32(sub, res_min2); // sub: -1, prev a or b gap: 0
             let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
-            let sub_eq_mask = _mm_cmpeq_epi16(sub, res_min1);
-            let sub_max_len = _mm_max_epu16(sub_length, res_length1);
+            let sub_eq_mask = _mm_cmpeq_epi32(sub, res_min1);
+            let sub_max_len = _mm_max_epu32(sub_length, res_length1);
             res_length2 = _mm_blendv_epi8(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
 
             *res_min.v.get_unchecked_mut(i) = res_min2;
@@ -2069,6 +3631,47 @@ impl Jewel for SseNx8x16 {
         }
     }
 
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = _mm_min_epu32(a_gap, b_gap);
+            let a_b_gt_mask = _mm_cmpeq_epi32(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = _mm_cmpeq_epi32(a_gap, b_gap); // equal: -1
+            let a_b_min_len = _mm_min_epu32(a_gap_length, b_gap_length);
+            res_length1 = _mm_blendv_epi8(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+            let res_min2 = _mm_min_epu32(sub, res_min1);
+            let sub_gt_mask = _mm_cmpeq_epi32(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = _mm_cmpeq_epi32(sub, res_min1);
+            let sub_min_len = _mm_min_epu32(sub_length, res_length1);
+            res_length2 = _mm_blendv_epi8(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn double_min_length(
@@ -2085,11 +3688,11 @@ impl Jewel for SseNx8x16 {
             let new_gap_length = *new_gap_length.v.get_unchecked(i);
             let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
 
-            let res_min = _mm_min_epu16(new_gap, cont_gap);
-            let new_cont_gt_mask = _mm_cmpeq_epi16(new_gap, res_min); // new gap: -1, continue gap: 0
+            let res_min = _mm_min_epu32(new_gap, cont_gap);
+            let new_cont_gt_mask = _mm_cmpeq_epi32(new_gap, res_min); // new gap: -1, continue gap: 0
             let mut res_length = _mm_blendv_epi8(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
-            let new_cont_eq_mask = _mm_cmpeq_epi16(new_gap, cont_gap); // equal: -1
-            let new_cont_max_len = _mm_max_epu16(new_gap_length, cont_gap_length);
+            let new_cont_eq_mask = _mm_cmpeq_epi32(new_gap, cont_gap); // equal: -1
+            let new_cont_max_len = _mm_max_epu32(new_gap_length, cont_gap_length);
             res_length = _mm_blendv_epi8(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
 
             *res_cont_gap.v.get_unchecked_mut(i) = res_min;
@@ -2100,21 +3703,21 @@ impl Jewel for SseNx8x16 {
 
 // this implementation will probably only be used for debugging
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-impl fmt::Display for SseNx8x16 {
+impl fmt::Display for SseNx4x32 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
         #[target_feature(enable = "sse4.1")]
         #[inline]
-        unsafe fn fmt_internal(s: &SseNx8x16, f: &mut fmt::Formatter) -> fmt::Result {
+        unsafe fn fmt_internal(s: &SseNx4x32, f: &mut fmt::Formatter) -> fmt::Result {
             write!(f, "[")?;
 
-            let mut arr = [0u16; 8];
+            let mut arr = [0u32; 4];
             let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
 
             for i in 0..(s.v.len() - 1) {
                 _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(i));
 
-                for j in 0..8 {
+                for j in 0..4 {
                     write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
                 }
             }
@@ -2123,7 +3726,7 @@ impl fmt::Display for SseNx8x16 {
 
             _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(s.v.len() - 1));
 
-            let start = (s.v.len() - 1) << 3;
+            let start = (s.v.len() - 1) << 2;
 
             for i in 0..(s.upper_bound() - start) {
                 if i == s.upper_bound() - start - 1 {
@@ -2140,47 +3743,70 @@ impl fmt::Display for SseNx8x16 {
     }
 }
 
-/// N x 4 x 32 vector backed with 128-bit SSE vectors.
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-pub struct SseNx4x32 {
-    v: Vec<__m128i>,
+/// N x 16 x 8 vector backed with 128-bit WASM SIMD vectors.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+pub struct WasmNx16x8 {
+    v: Vec<v128>,
 }
 
-/// Workaround for the lack of the _mm_adds_epu32 intrinsic.
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-#[target_feature(enable = "sse4.1")]
+/// Workaround for the reversed operand order of `v128_andnot` compared to SSE and AVX.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
 #[inline]
-unsafe fn _mm_adds_epu32(a: __m128i, b: __m128i) -> __m128i {
-    let sum = _mm_add_epi32(a, b);
-    let min = _mm_min_epu32(a, sum);
-    let eq = _mm_cmpeq_epi32(a, min);
-    // if the sum is less than a, then saturate
-    // note: sum is either greater than both a and b or less than both
-    _mm_blendv_epi8(_mm_set1_epi32(-1i32), sum, eq)
+unsafe fn wasm_andnot(a: v128, b: v128) -> v128 {
+    v128_andnot(b, a)
 }
 
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-impl Jewel for SseNx4x32 {
-    #[target_feature(enable = "sse4.1")]
+/// Workaround for the lack of a byte blend intrinsic.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[inline]
+unsafe fn wasm_blendv(a: v128, b: v128, mask: v128) -> v128 {
+    v128_bitselect(b, a, mask)
+}
+
+/// Concatenate `a` (low) and `b` (high) and extract 16 bytes starting at byte 1.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[inline]
+unsafe fn wasm_alignr_1(a: v128, b: v128) -> v128 {
+    i8x16_shuffle::<1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16>(a, b)
+}
+
+/// Concatenate `a` (low) and `b` (high) and extract 16 bytes starting at byte 2.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[inline]
+unsafe fn wasm_alignr_2(a: v128, b: v128) -> v128 {
+    i8x16_shuffle::<2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17>(a, b)
+}
+
+/// Concatenate `a` (low) and `b` (high) and extract 16 bytes starting at byte 15.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[inline]
+unsafe fn wasm_alignr_15(a: v128, b: v128) -> v128 {
+    i8x16_shuffle::<15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30>(a, b)
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+impl Jewel for WasmNx16x8 {
+    const LANE_BYTES: usize = 1;
+
     #[inline]
     unsafe fn repeating(val: u32, len: usize) -> Self {
-        let v =
-            alloc::vec![_mm_set1_epi32(val as i32); (len >> 2) + if (len & 3) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![u8x16_splat(Self::clamp_lane(val) as u8); num_vectors(len, 4)];
 
-        Self { v: v }
+        Self { v }
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn repeating_max(len: usize) -> Self {
-        let v = alloc::vec![_mm_set1_epi32(-1i32); (len >> 2) + if (len & 3) > 0 { 1 } else { 0 }];
+        let v = alloc::vec![u8x16_splat(u8::MAX); num_vectors(len, 4)];
 
-        Self { v: v }
+        Self { v }
     }
 
+    operation_reset!("simd128", 4, val => u8x16_splat(Self::clamp_lane(val) as u8), u8x16_splat(u8::MAX));
+
     #[inline]
     fn upper_bound(&self) -> usize {
-        self.v.len() << 2
+        self.v.len() << 4
     }
 
     #[inline]
@@ -2188,113 +3814,136 @@ impl Jewel for SseNx4x32 {
         unimplemented!()
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn slow_loadu(&mut self, idx: usize, ptr: *const u8, len: usize, reverse: bool) {
         if len == 0 {
             return;
         }
 
-        let mut arr = [0u32; 4];
-        let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
-        let store_idx = if reverse { 3 } else { 0 };
-        let load_idx = if reverse { 0 } else { 3 };
+        debug_check_loadu(idx, len, self.upper_bound(), reverse);
+
+        let mut arr = [0u8; 16];
+        let arr_ptr = arr.as_mut_ptr() as *mut v128;
+        let store_idx = if reverse { 15 } else { 0 };
+        let load_idx = if reverse { 0 } else { 15 };
 
         for i in 0..len {
             let curr_idx = if reverse { idx - i } else { idx + i };
-            let arr_idx = curr_idx & 3;
+            let arr_idx = curr_idx & 15;
 
             if arr_idx == store_idx || i == 0 {
-                _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(curr_idx >> 2));
+                v128_store(arr_ptr, *self.v.get_unchecked(curr_idx >> 4));
             }
 
-            *arr.get_unchecked_mut(arr_idx) = *ptr.offset(i as isize) as u32;
+            *arr.get_unchecked_mut(arr_idx) = *ptr.add(i);
 
             if arr_idx == load_idx || i == len - 1 {
-                *self.v.get_unchecked_mut(curr_idx >> 2) = _mm_loadu_si128(arr_ptr);
+                *self.v.get_unchecked_mut(curr_idx >> 4) = v128_load(arr_ptr);
             }
         }
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn slow_extract(&self, i: usize) -> u32 {
-        let idx = i >> 2;
-        let j = i & 3;
-        let mut arr = [0u32; 4];
-        _mm_storeu_si128(arr.as_mut_ptr() as *mut __m128i, *self.v.get_unchecked(idx));
-        *arr.get_unchecked(j)
+        let idx = i >> 4;
+        let j = i & 15;
+        let mut arr = [0u8; 16];
+        v128_store(arr.as_mut_ptr() as *mut v128, *self.v.get_unchecked(idx));
+        *arr.get_unchecked(j) as u32
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn slow_insert(&mut self, i: usize, val: u32) {
-        let idx = i >> 2;
-        let j = i & 3;
-        let mut arr = [0u32; 4];
-        let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
-        _mm_storeu_si128(arr_ptr, *self.v.get_unchecked(idx));
-        *arr.get_unchecked_mut(j) = val;
-        *self.v.get_unchecked_mut(idx) = _mm_loadu_si128(arr_ptr);
+        let idx = i >> 4;
+        let j = i & 15;
+        let mut arr = [0u8; 16];
+        let arr_ptr = arr.as_mut_ptr() as *mut v128;
+        v128_store(arr_ptr, *self.v.get_unchecked(idx));
+        *arr.get_unchecked_mut(j) = Self::clamp_lane(val) as u8;
+        *self.v.get_unchecked_mut(idx) = v128_load(arr_ptr);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_last_0(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 3i32);
+            u8x16_replace_lane::<15>(*self.v.get_unchecked(last), Self::clamp_lane(val) as u8);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_last_1(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 2i32);
+            u8x16_replace_lane::<14>(*self.v.get_unchecked(last), Self::clamp_lane(val) as u8);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_last_2(&mut self, val: u32) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi32(*self.v.get_unchecked(last), val as i32, 1i32);
+            u8x16_replace_lane::<13>(*self.v.get_unchecked(last), Self::clamp_lane(val) as u8);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_last_max(&mut self) {
         let last = self.v.len() - 1;
         *self.v.get_unchecked_mut(last) =
-            _mm_insert_epi32(*self.v.get_unchecked(last), -1i32, 3i32);
+            u8x16_replace_lane::<15>(*self.v.get_unchecked(last), u8::MAX);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_first(&mut self, val: u32) {
-        *self.v.get_unchecked_mut(0) = _mm_insert_epi32(*self.v.get_unchecked(0), val as i32, 0i32);
+        *self.v.get_unchecked_mut(0) =
+            u8x16_replace_lane::<0>(*self.v.get_unchecked(0), Self::clamp_lane(val) as u8);
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn insert_first_max(&mut self) {
-        *self.v.get_unchecked_mut(0) = _mm_insert_epi32(*self.v.get_unchecked(0), -1i32, 0i32);
+        *self.v.get_unchecked_mut(0) = u8x16_replace_lane::<0>(*self.v.get_unchecked(0), u8::MAX);
     }
 
-    operation_mut_param2!("sse4.1", add_mut, _mm_add_epi32);
-    operation_mut_param2!("sse4.1", adds_mut, _mm_adds_epu32);
-    operation_mut_param2!("sse4.1", and_mut, _mm_and_si128);
-    operation_mut_param2!("sse4.1", andnot_mut, _mm_andnot_si128);
-    operation_mut_param2!("sse4.1", cmpeq_mut, _mm_cmpeq_epi32);
-    operation_mut_param2!("sse4.1", min_mut, _mm_min_epu32);
-    operation_mut_param2!("sse4.1", max_mut, _mm_max_epu32);
+    operation_mut_param2!("simd128", add_mut, u8x16_add);
+    operation_mut_param2!("simd128", adds_mut, u8x16_add_sat);
+    operation_mut_param2!("simd128", subs_mut, u8x16_sub_sat);
+    operation_mut_param2!("simd128", and_mut, v128_and);
+    operation_mut_param2!("simd128", andnot_mut, wasm_andnot);
+    operation_mut_param2!("simd128", cmpeq_mut, u8x16_eq);
+    operation_mut_param2!("simd128", min_mut, u8x16_min);
+    operation_mut_param2!("simd128", max_mut, u8x16_max);
+    operation_fold_mut!("simd128", i8x16_splat, i8x16_gt, v128_and, v128_or);
+
+    #[inline]
+    unsafe fn horizontal_min(&self) -> u32 {
+        let mut curr = *self.v.get_unchecked(0);
+
+        for i in 1..self.v.len() {
+            curr = u8x16_min(curr, *self.v.get_unchecked(i));
+        }
+
+        // scalar sweep over the leftover 128-bit lane
+        let mut arr = [0u8; 16];
+        v128_store(arr.as_mut_ptr() as *mut v128, curr);
+        *arr.iter().min().unwrap() as u32
+    }
+
+    #[inline]
+    unsafe fn horizontal_max(&self) -> u32 {
+        let mut curr = *self.v.get_unchecked(0);
+
+        for i in 1..self.v.len() {
+            curr = u8x16_max(curr, *self.v.get_unchecked(i));
+        }
+
+        // scalar sweep over the leftover 128-bit lane
+        let mut arr = [0u8; 16];
+        v128_store(arr.as_mut_ptr() as *mut v128, curr);
+        *arr.iter().max().unwrap() as u32
+    }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn blendv_mut(&mut self, b: &Self, mask: &Self) {
         for i in 0..self.v.len() {
-            *self.v.get_unchecked_mut(i) = _mm_blendv_epi8(
+            *self.v.get_unchecked_mut(i) = wasm_blendv(
                 *self.v.get_unchecked(i),
                 *b.v.get_unchecked(i),
                 *mask.v.get_unchecked(i),
@@ -2302,107 +3951,105 @@ impl Jewel for SseNx4x32 {
         }
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             *self.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 4i32);
+                wasm_alignr_1(*self.v.get_unchecked(i), *self.v.get_unchecked(i + 1));
         }
 
         // last one gets to shift in zeros
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 4i32);
+        *self.v.get_unchecked_mut(last) =
+            wasm_alignr_1(*self.v.get_unchecked(last), u8x16_splat(0));
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_2_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in 0..(self.v.len() - 1) {
             *self.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*self.v.get_unchecked(i + 1), *self.v.get_unchecked(i), 8i32);
+                wasm_alignr_2(*self.v.get_unchecked(i), *self.v.get_unchecked(i + 1));
         }
 
         // last one gets to shift in zeros
         let last = self.v.len() - 1;
-        *self.v.get_unchecked_mut(last) = _mm_srli_si128(*self.v.get_unchecked(last), 8i32);
+        *self.v.get_unchecked_mut(last) =
+            wasm_alignr_2(*self.v.get_unchecked(last), u8x16_splat(0));
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_right_1_mut(&mut self) {
+        debug_assert!(!self.v.is_empty());
         for i in (1..self.v.len()).rev() {
-            *self.v.get_unchecked_mut(i) = _mm_alignr_epi8(
-                *self.v.get_unchecked(i),
-                *self.v.get_unchecked(i - 1),
-                12i32,
-            );
+            *self.v.get_unchecked_mut(i) =
+                wasm_alignr_15(*self.v.get_unchecked(i - 1), *self.v.get_unchecked(i));
         }
 
         // first one gets to shift in zeros
-        *self.v.get_unchecked_mut(0) = _mm_slli_si128(*self.v.get_unchecked(0), 4i32);
+        *self.v.get_unchecked_mut(0) = wasm_alignr_15(u8x16_splat(0), *self.v.get_unchecked(0));
     }
 
-    operation_param2!("sse4.1", add, _mm_add_epi32);
-    operation_param2!("sse4.1", adds, _mm_adds_epu32);
-    operation_param2!("sse4.1", andnot, _mm_andnot_si128);
-    operation_param2!("sse4.1", cmpeq, _mm_cmpeq_epi32);
-    operation_param2!("sse4.1", min, _mm_min_epu32);
-    operation_param2!("sse4.1", max, _mm_max_epu32);
+    operation_param2!("simd128", add, u8x16_add);
+    operation_param2!("simd128", adds, u8x16_add_sat);
+    operation_param2!("simd128", subs, u8x16_sub_sat);
+    operation_param2!("simd128", andnot, wasm_andnot);
+    operation_param2!("simd128", cmpeq, u8x16_eq);
+    operation_param2!("simd128", min, u8x16_min);
+    operation_param2!("simd128", max, u8x16_max);
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_left_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in 0..(a.v.len() - 1) {
             *res.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*a.v.get_unchecked(i + 1), *a.v.get_unchecked(i), 4i32);
+                wasm_alignr_1(*a.v.get_unchecked(i), *a.v.get_unchecked(i + 1));
         }
 
         // last one gets to shift in zeros
         let last = a.v.len() - 1;
-        *res.v.get_unchecked_mut(last) = _mm_srli_si128(*a.v.get_unchecked(last), 4i32);
+        *res.v.get_unchecked_mut(last) = wasm_alignr_1(*a.v.get_unchecked(last), u8x16_splat(0));
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn shift_right_1(a: &Self, res: &mut Self) {
+        debug_assert!(!a.v.is_empty());
         for i in (1..a.v.len()).rev() {
             *res.v.get_unchecked_mut(i) =
-                _mm_alignr_epi8(*a.v.get_unchecked(i), *a.v.get_unchecked(i - 1), 12i32);
+                wasm_alignr_15(*a.v.get_unchecked(i - 1), *a.v.get_unchecked(i));
         }
 
         // first one gets to shift in zeros
-        *res.v.get_unchecked_mut(0) = _mm_slli_si128(*a.v.get_unchecked(0), 4i32);
+        *res.v.get_unchecked_mut(0) = wasm_alignr_15(u8x16_splat(0), *a.v.get_unchecked(0));
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn triple_argmin(sub: &Self, a_gap: &Self, b_gap: &Self, res_min: &mut Self) -> Self {
         // return the edit used in addition to doing a min operation
         let mut v = Vec::with_capacity(sub.v.len());
-        let twos = _mm_set1_epi32(2);
+        let twos = u8x16_splat(2);
 
         for i in 0..sub.v.len() {
             let sub = *sub.v.get_unchecked(i);
             let a_gap = *a_gap.v.get_unchecked(i);
             let b_gap = *b_gap.v.get_unchecked(i);
 
-            let res_min1 = _mm_min_epu32(a_gap, b_gap);
+            let res_min1 = u8x16_min(a_gap, b_gap);
             // a gap: 2 + -1 = 1, b gap: 2 + 0 = 2
-            let res_arg1 = _mm_add_epi32(twos, _mm_cmpeq_epi32(a_gap, res_min1));
+            let res_arg1 = u8x16_add(twos, u8x16_eq(a_gap, res_min1));
 
-            let res_min2 = _mm_min_epu32(sub, res_min1);
+            let res_min2 = u8x16_min(sub, res_min1);
             // sub: 0
-            let res_arg2 = _mm_andnot_si128(_mm_cmpeq_epi32(sub, res_min2), res_arg1);
+            let res_arg2 = wasm_andnot(u8x16_eq(sub, res_min2), res_arg1);
 
             *res_min.v.get_unchecked_mut(i) = res_min2;
             v.push(res_arg2);
         }
 
-        Self { v: v }
+        Self { v }
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn triple_min_length(
         sub: &Self,
@@ -2424,26 +4071,65 @@ impl Jewel for SseNx4x32 {
             let a_gap_length = *a_gap_length.v.get_unchecked(i);
             let b_gap_length = *b_gap_length.v.get_unchecked(i);
 
-            let res_min1 = _mm_min_epu32(a_gap, b_gap);
-            let a_b_gt_mask = _mm_cmpeq_epi32(a_gap, res_min1); // a gap: -1, b gap: 0
-            let mut res_length1 = _mm_blendv_epi8(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
-            let a_b_eq_mask = _mm_cmpeq_epi32(a_gap, b_gap); // equal: -1
-            let a_b_max_len = _mm_max_epu32(a_gap_length, b_gap_length);
-            res_length1 = _mm_blendv_epi8(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
+            let res_min1 = u8x16_min(a_gap, b_gap);
+            let a_b_gt_mask = u8x16_eq(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = wasm_blendv(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = u8x16_eq(a_gap, b_gap); // equal: -1
+            let a_b_max_len = u8x16_max(a_gap_length, b_gap_length);
+            res_length1 = wasm_blendv(res_length1, a_b_max_len, a_b_eq_mask); // maximize length if edits equal
 
-            let res_min2 = _mm_min_epu32(sub, res_min1);
-            let sub_gt_mask = _mm_cmpeq_epi32(sub, res_min2); // sub: -1, prev a or b gap: 0
-            let mut res_length2 = _mm_blendv_epi8(res_length1, sub_length, sub_gt_mask); // length based on edits
-            let sub_eq_mask = _mm_cmpeq_epi32(sub, res_min1);
-            let sub_max_len = _mm_max_epu32(sub_length, res_length1);
-            res_length2 = _mm_blendv_epi8(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
+            let res_min2 = u8x16_min(sub, res_min1);
+            let sub_gt_mask = u8x16_eq(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = wasm_blendv(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = u8x16_eq(sub, res_min1);
+            let sub_max_len = u8x16_max(sub_length, res_length1);
+            res_length2 = wasm_blendv(res_length2, sub_max_len, sub_eq_mask); // maximize length if edits equal
+
+            *res_min.v.get_unchecked_mut(i) = res_min2;
+            *res_length.v.get_unchecked_mut(i) = res_length2;
+        }
+    }
+
+    #[inline]
+    unsafe fn triple_min_length_short(
+        sub: &Self,
+        a_gap: &Self,
+        b_gap: &Self,
+        sub_length: &Self,
+        a_gap_length: &Self,
+        b_gap_length: &Self,
+        res_min: &mut Self,
+        res_length: &mut Self,
+    ) {
+        // choose the length based on which edit is chosen during the min operation
+        // secondary objective of minimizing length if edit costs equal
+        for i in 0..sub.v.len() {
+            let sub = *sub.v.get_unchecked(i);
+            let a_gap = *a_gap.v.get_unchecked(i);
+            let b_gap = *b_gap.v.get_unchecked(i);
+            let sub_length = *sub_length.v.get_unchecked(i);
+            let a_gap_length = *a_gap_length.v.get_unchecked(i);
+            let b_gap_length = *b_gap_length.v.get_unchecked(i);
+
+            let res_min1 = u8x16_min(a_gap, b_gap);
+            let a_b_gt_mask = u8x16_eq(a_gap, res_min1); // a gap: -1, b gap: 0
+            let mut res_length1 = wasm_blendv(b_gap_length, a_gap_length, a_b_gt_mask); // lengths based on edits
+            let a_b_eq_mask = u8x16_eq(a_gap, b_gap); // equal: -1
+            let a_b_min_len = u8x16_min(a_gap_length, b_gap_length);
+            res_length1 = wasm_blendv(res_length1, a_b_min_len, a_b_eq_mask); // minimize length if edits equal
+
+            let res_min2 = u8x16_min(sub, res_min1);
+            let sub_gt_mask = u8x16_eq(sub, res_min2); // sub: -1, prev a or b gap: 0
+            let mut res_length2 = wasm_blendv(res_length1, sub_length, sub_gt_mask); // length based on edits
+            let sub_eq_mask = u8x16_eq(sub, res_min1);
+            let sub_min_len = u8x16_min(sub_length, res_length1);
+            res_length2 = wasm_blendv(res_length2, sub_min_len, sub_eq_mask); // minimize length if edits equal
 
             *res_min.v.get_unchecked_mut(i) = res_min2;
             *res_length.v.get_unchecked_mut(i) = res_length2;
         }
     }
 
-    #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn double_min_length(
         new_gap: &Self,
@@ -2459,12 +4145,12 @@ impl Jewel for SseNx4x32 {
             let new_gap_length = *new_gap_length.v.get_unchecked(i);
             let cont_gap_length = *res_cont_gap_length.v.get_unchecked(i);
 
-            let res_min = _mm_min_epu32(new_gap, cont_gap);
-            let new_cont_gt_mask = _mm_cmpeq_epi32(new_gap, res_min); // new gap: -1, continue gap: 0
-            let mut res_length = _mm_blendv_epi8(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
-            let new_cont_eq_mask = _mm_cmpeq_epi32(new_gap, cont_gap); // equal: -1
-            let new_cont_max_len = _mm_max_epu32(new_gap_length, cont_gap_length);
-            res_length = _mm_blendv_epi8(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
+            let res_min = u8x16_min(new_gap, cont_gap);
+            let new_cont_gt_mask = u8x16_eq(new_gap, res_min); // new gap: -1, continue gap: 0
+            let mut res_length = wasm_blendv(cont_gap_length, new_gap_length, new_cont_gt_mask); // lengths based on edits
+            let new_cont_eq_mask = u8x16_eq(new_gap, cont_gap); // equal: -1
+            let new_cont_max_len = u8x16_max(new_gap_length, cont_gap_length);
+            res_length = wasm_blendv(res_length, new_cont_max_len, new_cont_eq_mask); // maximize length if edits equal
 
             *res_cont_gap.v.get_unchecked_mut(i) = res_min;
             *res_cont_gap_length.v.get_unchecked_mut(i) = res_length;
@@ -2473,53 +4159,82 @@ impl Jewel for SseNx4x32 {
 }
 
 // this implementation will probably only be used for debugging
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-impl fmt::Display for SseNx4x32 {
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+impl fmt::Display for WasmNx16x8 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        #[target_feature(enable = "sse4.1")]
-        #[inline]
-        unsafe fn fmt_internal(s: &SseNx4x32, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "[")?;
-
-            let mut arr = [0u32; 4];
-            let arr_ptr = arr.as_mut_ptr() as *mut __m128i;
+        write!(f, "[")?;
 
-            for i in 0..(s.v.len() - 1) {
-                _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(i));
-
-                for j in 0..4 {
-                    write!(f, "{:>3}, ", *arr.get_unchecked(j))?;
-                }
-            }
-
-            // leftover elements
-
-            _mm_storeu_si128(arr_ptr, *s.v.get_unchecked(s.v.len() - 1));
-
-            let start = (s.v.len() - 1) << 2;
+        for i in 0..self.upper_bound() {
+            let val = unsafe { self.slow_extract(i) };
 
-            for i in 0..(s.upper_bound() - start) {
-                if i == s.upper_bound() - start - 1 {
-                    write!(f, "{:>3}", *arr.get_unchecked(i))?;
-                } else {
-                    write!(f, "{:>3}, ", *arr.get_unchecked(i))?;
-                }
+            if i == self.upper_bound() - 1 {
+                write!(f, "{:>3}", val)?;
+            } else {
+                write!(f, "{:>3}, ", val)?;
             }
-
-            write!(f, "]")
         }
 
-        unsafe { fmt_internal(self, f) }
+        write!(f, "]")
     }
 }
 
 pub trait HammingJewel {
     unsafe fn loadu(ptr: *const u8, len: usize) -> Self;
     fn upper_bound(&self) -> usize;
+    /// Sets the lowercase bit for bytes that are the ASCII letters A-Z.
+    unsafe fn ascii_fold_mut(&mut self);
     unsafe fn mm_count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32;
-    unsafe fn count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32;
-    unsafe fn vector_count_mismatches(a: &Self, b_ptr: *const u8, len: usize) -> u32;
+    /// If `case_insensitive` is true, then the bytes are folded with `ascii_fold_mut` as they
+    /// are loaded.
+    unsafe fn count_mismatches(
+        a_ptr: *const u8,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+    ) -> u32;
+    /// Only the bytes loaded from `b_ptr` are folded if `case_insensitive` is true, so `a`
+    /// should already be folded. Bytes in `a` that are equal to `wildcard` always match.
+    unsafe fn vector_count_mismatches(
+        a: &Self,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+        wildcard: Option<u8>,
+    ) -> u32;
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn scalar_eq(a: u8, b: u8, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        a.eq_ignore_ascii_case(&b)
+    } else {
+        a == b
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn ascii_fold_avx(v: __m256i) -> __m256i {
+    // mask of bytes in A-Z, which get the lowercase bit set
+    let mask = _mm256_and_si256(
+        _mm256_cmpgt_epi8(v, _mm256_set1_epi8((b'A' - 1) as i8)),
+        _mm256_cmpgt_epi8(_mm256_set1_epi8((b'Z' + 1) as i8), v),
+    );
+    _mm256_or_si256(v, _mm256_and_si256(mask, _mm256_set1_epi8(0x20)))
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse4.1")]
+#[inline]
+unsafe fn ascii_fold_sse(v: __m128i) -> __m128i {
+    // mask of bytes in A-Z, which get the lowercase bit set
+    let mask = _mm_and_si128(
+        _mm_cmpgt_epi8(v, _mm_set1_epi8((b'A' - 1) as i8)),
+        _mm_cmpgt_epi8(_mm_set1_epi8((b'Z' + 1) as i8), v),
+    );
+    _mm_or_si128(v, _mm_and_si128(mask, _mm_set1_epi8(0x20)))
 }
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -2560,6 +4275,14 @@ impl HammingJewel for Avx {
         self.v.len() << 5
     }
 
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    unsafe fn ascii_fold_mut(&mut self) {
+        for i in 0..self.v.len() {
+            *self.v.get_unchecked_mut(i) = ascii_fold_avx(*self.v.get_unchecked(i));
+        }
+    }
+
     #[target_feature(enable = "avx2")]
     #[inline]
     unsafe fn mm_count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32 {
@@ -2585,7 +4308,12 @@ impl HammingJewel for Avx {
 
     #[target_feature(enable = "avx2")]
     #[inline]
-    unsafe fn count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32 {
+    unsafe fn count_mismatches(
+        a_ptr: *const u8,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+    ) -> u32 {
         let refresh_len = (len / (255 * 32)) as isize;
         let zeros = _mm256_setzero_si256();
         let mut sad = zeros;
@@ -2596,8 +4324,14 @@ impl HammingJewel for Avx {
             let mut curr = zeros;
 
             for j in (i * 255)..((i + 1) * 255) {
-                let a = _mm256_loadu_si256(avx2_a_ptr.offset(j));
-                let b = _mm256_loadu_si256(avx2_b_ptr.offset(j));
+                let mut a = _mm256_loadu_si256(avx2_a_ptr.offset(j));
+                let mut b = _mm256_loadu_si256(avx2_b_ptr.offset(j));
+
+                if case_insensitive {
+                    a = ascii_fold_avx(a);
+                    b = ascii_fold_avx(b);
+                }
+
                 let eq = _mm256_cmpeq_epi8(a, b);
                 curr = _mm256_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
                                                   // counting matches instead of mismatches for speed
@@ -2613,8 +4347,14 @@ impl HammingJewel for Avx {
 
         // leftover blocks of 32 bytes
         for i in (refresh_len * 255)..word_len {
-            let a = _mm256_loadu_si256(avx2_a_ptr.offset(i));
-            let b = _mm256_loadu_si256(avx2_b_ptr.offset(i));
+            let mut a = _mm256_loadu_si256(avx2_a_ptr.offset(i));
+            let mut b = _mm256_loadu_si256(avx2_b_ptr.offset(i));
+
+            if case_insensitive {
+                a = ascii_fold_avx(a);
+                b = ascii_fold_avx(b);
+            }
+
             let eq = _mm256_cmpeq_epi8(a, b);
             curr = _mm256_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
         }
@@ -2628,7 +4368,7 @@ impl HammingJewel for Avx {
             + *sad_arr.get_unchecked(6);
 
         for i in (word_len << 5)..len as isize {
-            res += (*a_ptr.offset(i) == *b_ptr.offset(i)) as u32;
+            res += scalar_eq(*a_ptr.offset(i), *b_ptr.offset(i), case_insensitive) as u32;
         }
 
         len as u32 - res
@@ -2636,10 +4376,17 @@ impl HammingJewel for Avx {
 
     #[target_feature(enable = "avx2")]
     #[inline]
-    unsafe fn vector_count_mismatches(a: &Self, b_ptr: *const u8, len: usize) -> u32 {
+    unsafe fn vector_count_mismatches(
+        a: &Self,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+        wildcard: Option<u8>,
+    ) -> u32 {
         let refresh_len = (a.v.len() / 255) as isize;
         let zeros = _mm256_setzero_si256();
         let mut sad = zeros;
+        let wildcard_vector = wildcard.map(|w| _mm256_set1_epi8(w as i8));
         let avx2_b_ptr = b_ptr as *const __m256i;
 
         for i in 0..refresh_len {
@@ -2647,8 +4394,19 @@ impl HammingJewel for Avx {
 
             for j in (i * 255)..((i + 1) * 255) {
                 let a = *a.v.get_unchecked(j as usize);
-                let b = _mm256_loadu_si256(avx2_b_ptr.offset(j));
-                let eq = _mm256_cmpeq_epi8(a, b);
+                let mut b = _mm256_loadu_si256(avx2_b_ptr.offset(j));
+
+                if case_insensitive {
+                    b = ascii_fold_avx(b);
+                }
+
+                let mut eq = _mm256_cmpeq_epi8(a, b);
+
+                if let Some(wildcard_vector) = wildcard_vector {
+                    // wildcard bytes in a match anything
+                    eq = _mm256_or_si256(eq, _mm256_cmpeq_epi8(a, wildcard_vector));
+                }
+
                 curr = _mm256_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
                                                   // counting matches instead of mismatches for speed
             }
@@ -2663,19 +4421,36 @@ impl HammingJewel for Avx {
         // leftover blocks of 32 bytes
         for i in (refresh_len * 255)..a.v.len() as isize {
             let a = *a.v.get_unchecked(i as usize);
-            let b = _mm256_loadu_si256(avx2_b_ptr.offset(i));
-            let eq = _mm256_cmpeq_epi8(a, b);
+            let mut b = _mm256_loadu_si256(avx2_b_ptr.offset(i));
+
+            if case_insensitive {
+                b = ascii_fold_avx(b);
+            }
+
+            let mut eq = _mm256_cmpeq_epi8(a, b);
+
+            if let Some(wildcard_vector) = wildcard_vector {
+                // wildcard bytes in a match anything
+                eq = _mm256_or_si256(eq, _mm256_cmpeq_epi8(a, wildcard_vector));
+            }
+
             curr = _mm256_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
         }
 
         sad = _mm256_add_epi64(sad, _mm256_sad_epu8(curr, zeros));
         let mut sad_arr = [0u32; 8];
         _mm256_storeu_si256(sad_arr.as_mut_ptr() as *mut __m256i, sad);
-        let res = *sad_arr.get_unchecked(0)
+        let mut res = *sad_arr.get_unchecked(0)
             + *sad_arr.get_unchecked(2)
             + *sad_arr.get_unchecked(4)
             + *sad_arr.get_unchecked(6);
 
+        // the zero padding past len in the last vector must not be counted as matches, and a zero
+        // wildcard matches all of the padding
+        for i in len..a.upper_bound() {
+            res -= (*b_ptr.add(i) == 0u8 || wildcard == Some(0u8)) as u32;
+        }
+
         len as u32 - res
     }
 }
@@ -2718,6 +4493,14 @@ impl HammingJewel for Sse {
         self.v.len() << 4
     }
 
+    #[target_feature(enable = "sse4.1")]
+    #[inline]
+    unsafe fn ascii_fold_mut(&mut self) {
+        for i in 0..self.v.len() {
+            *self.v.get_unchecked_mut(i) = ascii_fold_sse(*self.v.get_unchecked(i));
+        }
+    }
+
     #[target_feature(enable = "sse4.1")]
     #[inline]
     unsafe fn mm_count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32 {
@@ -2743,7 +4526,12 @@ impl HammingJewel for Sse {
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
-    unsafe fn count_mismatches(a_ptr: *const u8, b_ptr: *const u8, len: usize) -> u32 {
+    unsafe fn count_mismatches(
+        a_ptr: *const u8,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+    ) -> u32 {
         let refresh_len = (len / (255 * 16)) as isize;
         let zeros = _mm_setzero_si128();
         let mut sad = zeros;
@@ -2754,8 +4542,14 @@ impl HammingJewel for Sse {
             let mut curr = zeros;
 
             for j in (i * 255)..((i + 1) * 255) {
-                let a = _mm_loadu_si128(sse_a_ptr.offset(j));
-                let b = _mm_loadu_si128(sse_b_ptr.offset(j));
+                let mut a = _mm_loadu_si128(sse_a_ptr.offset(j));
+                let mut b = _mm_loadu_si128(sse_b_ptr.offset(j));
+
+                if case_insensitive {
+                    a = ascii_fold_sse(a);
+                    b = ascii_fold_sse(b);
+                }
+
                 let eq = _mm_cmpeq_epi8(a, b);
                 curr = _mm_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
                                                // counting matches instead of mismatches for speed
@@ -2771,8 +4565,14 @@ impl HammingJewel for Sse {
 
         // leftover blocks of 16 bytes
         for i in (refresh_len * 255)..word_len {
-            let a = _mm_loadu_si128(sse_a_ptr.offset(i));
-            let b = _mm_loadu_si128(sse_b_ptr.offset(i));
+            let mut a = _mm_loadu_si128(sse_a_ptr.offset(i));
+            let mut b = _mm_loadu_si128(sse_b_ptr.offset(i));
+
+            if case_insensitive {
+                a = ascii_fold_sse(a);
+                b = ascii_fold_sse(b);
+            }
+
             let eq = _mm_cmpeq_epi8(a, b);
             curr = _mm_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
         }
@@ -2783,7 +4583,7 @@ impl HammingJewel for Sse {
         let mut res = *sad_arr.get_unchecked(0) + *sad_arr.get_unchecked(2);
 
         for i in (word_len << 4)..len as isize {
-            res += (*a_ptr.offset(i) == *b_ptr.offset(i)) as u32;
+            res += scalar_eq(*a_ptr.offset(i), *b_ptr.offset(i), case_insensitive) as u32;
         }
 
         len as u32 - res
@@ -2791,10 +4591,17 @@ impl HammingJewel for Sse {
 
     #[target_feature(enable = "sse4.1")]
     #[inline]
-    unsafe fn vector_count_mismatches(a: &Self, b_ptr: *const u8, len: usize) -> u32 {
+    unsafe fn vector_count_mismatches(
+        a: &Self,
+        b_ptr: *const u8,
+        len: usize,
+        case_insensitive: bool,
+        wildcard: Option<u8>,
+    ) -> u32 {
         let refresh_len = (a.v.len() / 255) as isize;
         let zeros = _mm_setzero_si128();
         let mut sad = zeros;
+        let wildcard_vector = wildcard.map(|w| _mm_set1_epi8(w as i8));
         let sse_b_ptr = b_ptr as *const __m128i;
 
         for i in 0..refresh_len {
@@ -2802,8 +4609,19 @@ impl HammingJewel for Sse {
 
             for j in (i * 255)..((i + 1) * 255) {
                 let a = *a.v.get_unchecked(j as usize);
-                let b = _mm_loadu_si128(sse_b_ptr.offset(j));
-                let eq = _mm_cmpeq_epi8(a, b);
+                let mut b = _mm_loadu_si128(sse_b_ptr.offset(j));
+
+                if case_insensitive {
+                    b = ascii_fold_sse(b);
+                }
+
+                let mut eq = _mm_cmpeq_epi8(a, b);
+
+                if let Some(wildcard_vector) = wildcard_vector {
+                    // wildcard bytes in a match anything
+                    eq = _mm_or_si128(eq, _mm_cmpeq_epi8(a, wildcard_vector));
+                }
+
                 curr = _mm_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
                                                // counting matches instead of mismatches for speed
             }
@@ -2818,16 +4636,539 @@ impl HammingJewel for Sse {
         // leftover blocks of 16 bytes
         for i in (refresh_len * 255)..a.v.len() as isize {
             let a = *a.v.get_unchecked(i as usize);
-            let b = _mm_loadu_si128(sse_b_ptr.offset(i));
-            let eq = _mm_cmpeq_epi8(a, b);
+            let mut b = _mm_loadu_si128(sse_b_ptr.offset(i));
+
+            if case_insensitive {
+                b = ascii_fold_sse(b);
+            }
+
+            let mut eq = _mm_cmpeq_epi8(a, b);
+
+            if let Some(wildcard_vector) = wildcard_vector {
+                // wildcard bytes in a match anything
+                eq = _mm_or_si128(eq, _mm_cmpeq_epi8(a, wildcard_vector));
+            }
+
             curr = _mm_sub_epi8(curr, eq); // subtract -1 = add 1 when matching
         }
 
         sad = _mm_add_epi64(sad, _mm_sad_epu8(curr, zeros));
         let mut sad_arr = [0u32; 4];
         _mm_storeu_si128(sad_arr.as_mut_ptr() as *mut __m128i, sad);
-        let res = *sad_arr.get_unchecked(0) + *sad_arr.get_unchecked(2);
+        let mut res = *sad_arr.get_unchecked(0) + *sad_arr.get_unchecked(2);
+
+        // the zero padding past len in the last vector must not be counted as matches, and a zero
+        // wildcard matches all of the padding
+        for i in len..a.upper_bound() {
+            res -= (*b_ptr.add(i) == 0u8 || wildcard == Some(0u8)) as u32;
+        }
 
         len as u32 - res
     }
 }
+
+#[cfg(all(test, target_arch = "x86_64", feature = "std"))]
+mod tests {
+    use super::*;
+
+    unsafe fn check_horizontal<T: Jewel>(len: usize) {
+        let v = T::repeating(100, len);
+        assert_eq!(v.horizontal_min(), 100);
+        assert_eq!(v.horizontal_max(), 100);
+
+        // includes positions in the padding past len
+        for i in 0..v.upper_bound() {
+            let mut v = T::repeating(100, len);
+            v.slow_insert(i, 3);
+            assert_eq!(v.horizontal_min(), 3);
+            assert_eq!(v.horizontal_max(), 100);
+
+            let mut v = T::repeating(100, len);
+            v.slow_insert(i, 200);
+            assert_eq!(v.horizontal_min(), 100);
+            assert_eq!(v.horizontal_max(), 200);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_min_max_avx() {
+        if !std::is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        unsafe {
+            check_horizontal::<Avx1x32x8>(20);
+            check_horizontal::<Avx2x32x8>(40);
+            check_horizontal::<Avx4x32x8>(100);
+            check_horizontal::<Avx8x32x8>(255);
+            check_horizontal::<AvxNx16x16>(20);
+            check_horizontal::<AvxNx8x32>(20);
+        }
+    }
+
+    #[test]
+    fn test_horizontal_min_max_sse() {
+        if !std::is_x86_feature_detected!("sse4.1") {
+            return;
+        }
+
+        unsafe {
+            check_horizontal::<Sse1x16x8>(10);
+            check_horizontal::<Sse2x16x8>(20);
+            check_horizontal::<Sse4x16x8>(50);
+            check_horizontal::<Sse8x16x8>(100);
+            check_horizontal::<Sse16x16x8>(255);
+            check_horizontal::<SseNx8x16>(20);
+            check_horizontal::<SseNx4x32>(10);
+        }
+    }
+
+    unsafe fn check_vector_count_mismatches<T: HammingJewel>() {
+        // 33 bytes, so the last vector is mostly padding
+        let a: Vec<u8> = (0..33u8).map(|i| b'a' + (i % 26)).collect();
+        // the bytes past the end of b are zero, like the padding in a
+        let mut b = a.clone();
+        b[3] = b'z';
+        b[32] = b'z';
+        b.resize(64, 0u8);
+
+        let v = T::loadu(a.as_ptr(), a.len());
+        assert_eq!(
+            T::vector_count_mismatches(&v, b.as_ptr(), a.len(), false, None),
+            2
+        );
+        assert_eq!(
+            T::vector_count_mismatches(&v, b.as_ptr(), a.len(), false, Some(b'd')),
+            1
+        );
+
+        // the zero padding in a matches a zero wildcard, even if the bytes past the end of b are
+        // not zero
+        let mut a = a;
+        a[32] = 0u8;
+        b.truncate(a.len());
+        b.resize(64, b'x');
+        let v = T::loadu(a.as_ptr(), a.len());
+        assert_eq!(
+            T::vector_count_mismatches(&v, b.as_ptr(), a.len(), false, Some(0u8)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_vector_count_mismatches_padding() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe { check_vector_count_mismatches::<Avx>() };
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe { check_vector_count_mismatches::<Sse>() };
+        }
+    }
+
+    unsafe fn load<T: Jewel>(vals: &[u8]) -> T {
+        let mut v = T::repeating(0, vals.len());
+        v.slow_loadu(0, vals.as_ptr(), vals.len(), false);
+        v
+    }
+
+    unsafe fn extract<T: Jewel>(v: &T) -> Vec<u32> {
+        (0..v.upper_bound()).map(|i| v.slow_extract(i)).collect()
+    }
+
+    unsafe fn check_parity<A: Jewel, B: Jewel>(len: usize) {
+        let x: Vec<u8> = (0..len).map(|i| ((i * 37 + 11) % 251) as u8).collect();
+        let y: Vec<u8> = (0..len).map(|i| ((i * 91 + 3) % 13 * 20) as u8).collect();
+        let z: Vec<u8> = (0..len).map(|i| ((i * 53 + 7) % 241) as u8).collect();
+
+        let (xa, ya, za) = (load::<A>(&x), load::<A>(&y), load::<A>(&z));
+        let (xb, yb, zb) = (load::<B>(&x), load::<B>(&y), load::<B>(&z));
+        assert_eq!(extract(&xa), extract(&xb));
+
+        let mut ra = A::repeating(0, len);
+        let mut rb = B::repeating(0, len);
+
+        A::shift_left_1(&xa, &mut ra);
+        B::shift_left_1(&xb, &mut rb);
+        assert_eq!(extract(&ra), extract(&rb));
+
+        A::shift_right_1(&xa, &mut ra);
+        B::shift_right_1(&xb, &mut rb);
+        assert_eq!(extract(&ra), extract(&rb));
+
+        let (mut ma, mut mb) = (load::<A>(&x), load::<B>(&x));
+        ma.shift_left_2_mut();
+        mb.shift_left_2_mut();
+        assert_eq!(extract(&ma), extract(&mb));
+        ma.shift_right_1_mut();
+        mb.shift_right_1_mut();
+        assert_eq!(extract(&ma), extract(&mb));
+        ma.insert_last_0(5);
+        mb.insert_last_0(5);
+        ma.insert_last_2(6);
+        mb.insert_last_2(6);
+        ma.insert_first(7);
+        mb.insert_first(7);
+        assert_eq!(extract(&ma), extract(&mb));
+
+        A::adds(&xa, &ya, &mut ra);
+        B::adds(&xb, &yb, &mut rb);
+        assert_eq!(extract(&ra), extract(&rb));
+
+        A::cmpeq(&ya, &za, &mut ra);
+        B::cmpeq(&yb, &zb, &mut rb);
+        assert_eq!(extract(&ra), extract(&rb));
+
+        let (mut ba, mut bb) = (load::<A>(&x), load::<B>(&x));
+        ba.blendv_mut(&za, &ra);
+        bb.blendv_mut(&zb, &rb);
+        assert_eq!(extract(&ba), extract(&bb));
+
+        let mut la = A::repeating(0, len);
+        let mut lb = B::repeating(0, len);
+        A::triple_min_length(&xa, &ya, &za, &za, &xa, &ya, &mut ra, &mut la);
+        B::triple_min_length(&xb, &yb, &zb, &zb, &xb, &yb, &mut rb, &mut lb);
+        assert_eq!(extract(&ra), extract(&rb));
+        assert_eq!(extract(&la), extract(&lb));
+
+        let (mut ga, mut gb) = (load::<A>(&y), load::<B>(&y));
+        let (mut gla, mut glb) = (load::<A>(&z), load::<B>(&z));
+        A::double_min_length(&xa, &mut ga, &za, &mut gla);
+        B::double_min_length(&xb, &mut gb, &zb, &mut glb);
+        assert_eq!(extract(&ga), extract(&gb));
+        assert_eq!(extract(&gla), extract(&glb));
+
+        let arg_a = A::triple_argmin(&xa, &ya, &za, &mut ra);
+        let arg_b = B::triple_argmin(&xb, &yb, &zb, &mut rb);
+        assert_eq!(extract(&arg_a), extract(&arg_b));
+        assert_eq!(extract(&ra), extract(&rb));
+
+        assert_eq!(xa.horizontal_min(), xb.horizontal_min());
+        assert_eq!(xa.horizontal_max(), xb.horizontal_max());
+    }
+
+    #[test]
+    fn test_avx512_parity() {
+        if !std::is_x86_feature_detected!("avx2") || !avx512_supported() {
+            return;
+        }
+
+        unsafe {
+            check_horizontal::<Avx512Nx64x8>(100);
+            check_parity::<Avx512Nx64x8, Avx2x32x8>(64);
+            check_parity::<Avx512Nx64x8, Avx4x32x8>(128);
+            check_parity::<Avx512Nx64x8, Avx8x32x8>(256);
+        }
+    }
+
+    unsafe fn check_shift_single_vector<T: Jewel>(len: usize) {
+        let vals: Vec<u8> = (1..=len as u8).collect();
+        let v = load::<T>(&vals);
+        let upper_bound = v.upper_bound();
+        let x = extract(&v);
+
+        let mut left_1 = alloc::vec![0u32; upper_bound];
+        left_1[..upper_bound - 1].copy_from_slice(&x[1..]);
+        let mut left_2 = alloc::vec![0u32; upper_bound];
+        left_2[..upper_bound - 2].copy_from_slice(&x[2..]);
+        let mut right_1 = alloc::vec![0u32; upper_bound];
+        right_1[1..].copy_from_slice(&x[..upper_bound - 1]);
+
+        let mut res = T::repeating(0, len);
+        T::shift_left_1(&v, &mut res);
+        assert_eq!(extract(&res), left_1);
+        T::shift_right_1(&v, &mut res);
+        assert_eq!(extract(&res), right_1);
+
+        let mut m = load::<T>(&vals);
+        m.shift_left_1_mut();
+        assert_eq!(extract(&m), left_1);
+        let mut m = load::<T>(&vals);
+        m.shift_left_2_mut();
+        assert_eq!(extract(&m), left_2);
+        let mut m = load::<T>(&vals);
+        m.shift_right_1_mut();
+        assert_eq!(extract(&m), right_1);
+    }
+
+    #[test]
+    fn test_shift_single_vector() {
+        // lengths that fit in one vector, including a full vector and a single element
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                for &len in &[1, 2, 31, 32] {
+                    check_shift_single_vector::<Avx1x32x8>(len);
+                }
+
+                for &len in &[1, 2, 15, 16] {
+                    check_shift_single_vector::<AvxNx16x16>(len);
+                }
+
+                for &len in &[1, 2, 7, 8] {
+                    check_shift_single_vector::<AvxNx8x32>(len);
+                }
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                for &len in &[1, 2, 15, 16] {
+                    check_shift_single_vector::<Sse1x16x8>(len);
+                }
+
+                for &len in &[1, 2, 7, 8] {
+                    check_shift_single_vector::<SseNx8x16>(len);
+                }
+
+                for &len in &[1, 2, 3, 4] {
+                    check_shift_single_vector::<SseNx4x32>(len);
+                }
+            }
+        }
+
+        if avx512_supported() {
+            unsafe {
+                for &len in &[1, 2, 63, 64] {
+                    check_shift_single_vector::<Avx512Nx64x8>(len);
+                }
+            }
+        }
+    }
+
+    unsafe fn check_zero_length<T: Jewel>() {
+        // at least one vector is allocated, so the shifts do not index into an empty vector
+        let v = T::repeating(7, 0);
+        assert!(v.upper_bound() > 0);
+        assert!(extract(&v).iter().all(|&x| x == 7));
+
+        let mut res = T::repeating_max(0);
+        assert!(res.upper_bound() > 0);
+        T::shift_left_1(&v, &mut res);
+        T::shift_right_1(&v, &mut res);
+        assert_eq!(extract(&res)[0], 0);
+
+        let mut m = T::repeating(7, 0);
+        m.shift_left_1_mut();
+        m.shift_left_2_mut();
+        m.shift_right_1_mut();
+
+        m.reset(3, 0);
+        assert!(m.upper_bound() > 0);
+        m.shift_right_1_mut();
+        m.reset_max(0);
+        assert!(m.upper_bound() > 0);
+        m.shift_left_1_mut();
+    }
+
+    #[test]
+    fn test_zero_length() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                check_zero_length::<Avx1x32x8>();
+                check_zero_length::<AvxNx16x16>();
+                check_zero_length::<AvxNx8x32>();
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                check_zero_length::<Sse1x16x8>();
+                check_zero_length::<SseNx8x16>();
+                check_zero_length::<SseNx4x32>();
+            }
+        }
+
+        if avx512_supported() {
+            unsafe {
+                check_zero_length::<Avx512Nx64x8>();
+            }
+        }
+    }
+
+    unsafe fn check_slow_loadu_reverse<T: Jewel>() {
+        let vals = [1u8, 2, 3];
+        let mut v = T::repeating(0, 8);
+
+        // stores 3 at index 2, 2 at index 1, and 1 at index 0
+        v.slow_loadu(2, vals.as_ptr(), vals.len(), true);
+        assert_eq!(&extract(&v)[..4], &[3, 2, 1, 0]);
+
+        let mut v = T::repeating(0, 8);
+        v.slow_loadu(0, vals.as_ptr(), 1, true);
+        assert_eq!(&extract(&v)[..2], &[1, 0]);
+    }
+
+    #[test]
+    fn test_slow_loadu_reverse_near_zero() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                check_slow_loadu_reverse::<Avx1x32x8>();
+                check_slow_loadu_reverse::<AvxNx16x16>();
+                check_slow_loadu_reverse::<AvxNx8x32>();
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                check_slow_loadu_reverse::<Sse1x16x8>();
+                check_slow_loadu_reverse::<SseNx8x16>();
+                check_slow_loadu_reverse::<SseNx4x32>();
+            }
+        }
+
+        if avx512_supported() {
+            unsafe { check_slow_loadu_reverse::<Avx512Nx64x8>() };
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reverse slow_loadu goes past index 0")]
+    fn test_slow_loadu_reverse_underflow() {
+        // one byte too many, which would wrap around to a huge index
+        debug_check_loadu(1, 3, 32, true);
+    }
+
+    unsafe fn check_insert_extract<T: Jewel>() {
+        let max = if T::LANE_BYTES >= 4 {
+            u32::MAX
+        } else {
+            (1u32 << (T::LANE_BYTES * 8)) - 1
+        };
+        let vals = [0u32, 200, 300, 40000, 70000, u32::MAX];
+        let mut v = T::repeating(0, 64);
+        let last = v.upper_bound() - 1;
+
+        for &val in vals.iter() {
+            let expected = cmp::min(val, max);
+
+            v.slow_insert(5, val);
+            assert_eq!(v.slow_extract(5), expected);
+            v.insert_first(val);
+            assert_eq!(v.slow_extract(0), expected);
+            v.insert_last_0(val);
+            v.insert_last_2(val);
+            assert_eq!(v.slow_extract(last), expected);
+            assert_eq!(v.slow_extract(last - 2), expected);
+            assert_eq!(extract(&T::repeating(val, 8))[..8], [expected; 8]);
+        }
+
+        // neighboring lanes are untouched
+        assert_eq!(v.slow_extract(4), 0);
+        assert_eq!(v.slow_extract(6), 0);
+    }
+
+    #[test]
+    fn test_insert_extract_wide_values() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                check_insert_extract::<Avx1x32x8>();
+                check_insert_extract::<AvxNx16x16>();
+                check_insert_extract::<AvxNx8x32>();
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                check_insert_extract::<Sse1x16x8>();
+                check_insert_extract::<SseNx8x16>();
+                check_insert_extract::<SseNx4x32>();
+            }
+        }
+
+        if avx512_supported() {
+            unsafe { check_insert_extract::<Avx512Nx64x8>() };
+        }
+    }
+
+    unsafe fn check_subs<T: Jewel>() {
+        let vals = [0u32, 1, 7, 200, 254, 255];
+        let mut a = T::repeating(0, 64);
+        let mut b = T::repeating(0, 64);
+
+        for (i, &x) in vals.iter().enumerate() {
+            for (j, &y) in vals.iter().enumerate() {
+                a.slow_insert(i * vals.len() + j, x);
+                b.slow_insert(i * vals.len() + j, y);
+            }
+        }
+
+        a.subs_mut(&b);
+
+        // saturates at zero instead of wrapping around
+        for (i, &x) in vals.iter().enumerate() {
+            for (j, &y) in vals.iter().enumerate() {
+                assert_eq!(a.slow_extract(i * vals.len() + j), x.saturating_sub(y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_subs() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                check_subs::<Avx2x32x8>();
+                check_subs::<AvxNx16x16>();
+                check_subs::<AvxNx8x32>();
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                check_subs::<Sse4x16x8>();
+                check_subs::<SseNx8x16>();
+                check_subs::<SseNx4x32>();
+            }
+        }
+
+        if avx512_supported() {
+            unsafe { check_subs::<Avx512Nx64x8>() };
+        }
+    }
+
+    unsafe fn check_fold<T: Jewel>() {
+        let mut ascii = T::repeating(0, 256);
+        let mut latin1 = T::repeating(0, 256);
+
+        for i in 0..256 {
+            ascii.slow_insert(i, i as u32);
+            latin1.slow_insert(i, i as u32);
+        }
+
+        ascii.ascii_fold_mut();
+        latin1.latin1_fold_mut();
+
+        for i in 0..256 {
+            let c = i as u8;
+            let expected = match c {
+                b'A'..=b'Z' | 0xC0..=0xD6 | 0xD8..=0xDE => c + 0x20,
+                _ => c,
+            };
+            assert_eq!(ascii.slow_extract(i), c.to_ascii_lowercase() as u32);
+            assert_eq!(latin1.slow_extract(i), expected as u32);
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        if std::is_x86_feature_detected!("avx2") {
+            unsafe {
+                check_fold::<Avx8x32x8>();
+                check_fold::<AvxNx16x16>();
+                check_fold::<AvxNx8x32>();
+            }
+        }
+
+        if std::is_x86_feature_detected!("sse4.1") {
+            unsafe {
+                check_fold::<Sse16x16x8>();
+                check_fold::<SseNx8x16>();
+                check_fold::<SseNx4x32>();
+            }
+        }
+
+        if avx512_supported() {
+            unsafe { check_fold::<Avx512Nx64x8>() };
+        }
+    }
+}
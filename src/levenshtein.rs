@@ -5,14 +5,22 @@
 //! * `rdamerau_exp` for low number of edits, otherwise `rdamerau`
 //! * `levenshtein_simd_k`
 //! * `levenshtein_simd_k_with_opts`
+//! * `levenshtein_with_scratch` to reuse buffers across many calls
 //!
 //! These search functions share the same efficient underlying SIMD-accelerated implementation:
 //! * `levenshtein_search`
 //! * `levenshtein_search_simd`
 //! * `levenshtein_search_simd_with_opts`
+//!
+//! The local alignment function `local_align` uses the same Jewel vectors to find the best-scoring
+//! pair of substrings with the Smith-Waterman algorithm.
 
+#[cfg(not(feature = "pure-scalar"))]
 use super::jewel::*;
 use super::*;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use core::fmt::Write;
+use core::ops::Range;
 
 /// A struct holding the edit costs for mismatches, gaps, and possibly transpositions.
 ///
@@ -33,8 +41,8 @@ impl EditCosts {
     /// * `gap_cost` - cost of a gap, which must be positive
     /// * `start_gap_cost` - additional cost of starting a gap, for affine gap costs; this can
     /// be zero for linear gap costs
-    /// * `transpose_cost` - cost of a transpose, which must be cheaper than doing the equivalent
-    /// operation with mismatches and gaps
+    /// * `transpose_cost` - cost of a transpose, which must be positive; transposes are disabled if
+    /// this is not cheaper than two mismatches or two gaps, since they would never be used
     pub fn new(
         mismatch_cost: u8,
         gap_cost: u8,
@@ -46,11 +54,12 @@ impl EditCosts {
 
         if let Some(cost) = transpose_cost {
             assert!(cost > 0);
-            // transpose cost must be cheaper than doing the equivalent with other edits
-            assert!((cost >> 1) < mismatch_cost);
-            assert!((cost >> 1) < gap_cost);
         }
 
+        // transposes are only useful if they are cheaper than doing the equivalent with other edits
+        let transpose_cost =
+            transpose_cost.filter(|&cost| (cost >> 1) < mismatch_cost && (cost >> 1) < gap_cost);
+
         Self {
             mismatch_cost,
             gap_cost,
@@ -69,6 +78,14 @@ impl EditCosts {
             assert!(cost <= self.start_gap_cost + self.gap_cost);
         }
     }
+
+    /// Whether these are the same costs as `LEVENSHTEIN_COSTS`.
+    fn is_levenshtein(&self) -> bool {
+        self.mismatch_cost == 1
+            && self.gap_cost == 1
+            && self.start_gap_cost == 0
+            && self.transpose_cost.is_none()
+    }
 }
 
 /// Costs for Levenshtein distance, where mismatches and gaps both have a cost of 1, and
@@ -87,6 +104,15 @@ pub const RDAMERAU_COSTS: EditCosts = EditCosts {
     start_gap_cost: 0,
     transpose_cost: Some(1),
 };
+/// Costs for the indel-only distance that is used to compute longest common subsequences, where
+/// gaps have a cost of 1 and mismatches are more expensive than an insertion and a deletion, so
+/// they are never used.
+const LCS_COSTS: EditCosts = EditCosts {
+    mismatch_cost: 3,
+    gap_cost: 1,
+    start_gap_cost: 0,
+    transpose_cost: None,
+};
 
 /// Returns the Levenshtein distance between two strings using the naive scalar algorithm.
 ///
@@ -102,6 +128,7 @@ pub const RDAMERAU_COSTS: EditCosts = EditCosts {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn levenshtein_naive<T: PartialEq>(a: &[T], b: &[T]) -> u32 {
     levenshtein_naive_with_opts(a, b, false, LEVENSHTEIN_COSTS).0
 }
@@ -120,6 +147,7 @@ pub fn levenshtein_naive<T: PartialEq>(a: &[T], b: &[T]) -> u32 {
 ///
 /// assert!(dist == 1);
 /// ```
+#[must_use]
 pub fn levenstein_naive_str(a: &str, b: &str) -> u32 {
     let a: Vec<char> = a.chars().collect();
     let b: Vec<char> = b.chars().collect();
@@ -141,10 +169,11 @@ pub fn levenstein_naive_str(a: &str, b: &str) -> u32 {
 /// # use triple_accel::levenshtein::*;
 /// let dist = levenshtein_naive_with_opts(b"abc", b"ab", true, LEVENSHTEIN_COSTS);
 ///
-/// assert!(dist == (1, Some(alloc::vec![Edit{edit: EditType::Match, count: 2},
+/// assert!(dist == (1, Some(vec![Edit{edit: EditType::Match, count: 2},
 ///                               Edit{edit: EditType::BGap, count: 1}])));
 /// ```
 #[inline]
+#[must_use]
 pub fn levenshtein_naive_with_opts<T>(
     a: &[T],
     b: &[T],
@@ -339,6 +368,7 @@ where
 ///
 /// assert!(dist.unwrap() == 1);
 /// ```
+#[must_use]
 pub fn levenshtein_naive_k(a: &[u8], b: &[u8], k: u32) -> Option<u32> {
     let res = levenshtein_naive_k_with_opts(a, b, k, false, LEVENSHTEIN_COSTS);
 
@@ -369,10 +399,11 @@ pub fn levenshtein_naive_k(a: &[u8], b: &[u8], k: u32) -> Option<u32> {
 /// # use triple_accel::levenshtein::*;
 /// let dist = levenshtein_naive_k_with_opts(b"abc", b"ab", 1, true, LEVENSHTEIN_COSTS);
 ///
-/// assert!(dist.unwrap() == (1, Some(alloc::vec![Edit{edit: EditType::Match, count: 2},
+/// assert!(dist.unwrap() == (1, Some(vec![Edit{edit: EditType::Match, count: 2},
 ///                                        Edit{edit: EditType::BGap, count: 1}])));
 /// ```
 #[inline]
+#[must_use]
 pub fn levenshtein_naive_k_with_opts<T>(
     a: &[T],
     b: &[T],
@@ -638,6 +669,7 @@ fn translate_str(chars: &mut Vec<char>, s: &str) -> Option<Vec<u8>> {
 ///
 /// assert!(dist.unwrap() == 1);
 /// ```
+#[must_use]
 pub fn levenshtein_simd_k_str(a: &str, b: &str, k: u32) -> Option<u32> {
     if a.is_ascii() && b.is_ascii() {
         levenshtein_simd_k(a.as_bytes(), b.as_bytes(), k)
@@ -674,6 +706,7 @@ pub fn levenshtein_simd_k_str(a: &str, b: &str, k: u32) -> Option<u32> {
 ///
 /// assert!(dist.unwrap() == 1);
 /// ```
+#[must_use]
 pub fn levenshtein_simd_k(a: &[u8], b: &[u8], k: u32) -> Option<u32> {
     let res = levenshtein_simd_k_with_opts(a, b, k, false, LEVENSHTEIN_COSTS);
 
@@ -683,1831 +716,6623 @@ pub fn levenshtein_simd_k(a: &[u8], b: &[u8], k: u32) -> Option<u32> {
     }
 }
 
-/// Returns the Levenshtein distance, bounded by a cost threshold `k`, between two strings and optionally,
-/// the edit traceback, using SIMD acceleration, with extra options.
+/// Returns the Levenshtein distance, bounded by a cost threshold `k`, between two strings,
+/// rejecting pairs whose lengths are too different before doing any other work.
 ///
 /// This will return `None` if the Levenshtein distance between `a` and `b` is greater than the
 /// threshold `k`.
-/// This should be much faster than `levenshtein_naive_with_opts` and
-/// `levenshtein_naive_k_with_opts`.
-/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
-/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
-/// `levenshtein_naive_k_with_opts`.
+/// Every length difference must be made up with a gap, so if the lengths of `a` and `b` differ by
+/// more than `k`, then `None` is returned immediately, without allocating or touching SIMD.
+/// This is a cheap filter for batch workloads, like comparing a query against a dictionary,
+/// where most candidates can be rejected based on their length alone.
+/// If `a` and `b` have the same length, then their Hamming distance is computed first. It is an
+/// upper bound on the Levenshtein distance, since substituting every mismatching byte is a valid
+/// sequence of edits. It is the exact distance when it is zero or one, so identical strings and
+/// single substitutions are answered without any further work. Otherwise, the Hamming distance is
+/// used to narrow the threshold `k`.
+/// Note that the Hamming distance is not a lower bound, so a Hamming distance greater than `k`
+/// does not mean that `None` is returned.
+/// The remaining work is done by `levenshtein_simd_k`.
 ///
 /// # Arguments
 /// * `a` - first string (slice)
 /// * `b` - second string (slice)
-/// * `k` - maximum number of cost allowed between `a` and `b`
-/// * `trace_on` - whether to return the traceback, the sequence of edits between `a` and `b`
-/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `k` - maximum number of edits allowed between `a` and `b`
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
 /// # use triple_accel::levenshtein::*;
-/// let dist = levenshtein_simd_k_with_opts(b"abc", b"ab", 1, true, LEVENSHTEIN_COSTS);
-///
-/// assert!(dist.unwrap() == (1, Some(alloc::vec![Edit{edit: EditType::Match, count: 2},
-///                                        Edit{edit: EditType::BGap, count: 1}])));
+/// assert!(levenshtein_k(b"abc", b"ab", 1) == Some(1));
+/// assert!(levenshtein_k(b"abcdef", b"ab", 3) == None);
+/// assert!(levenshtein_k(b"abcd", b"abed", 0) == None);
+/// assert!(levenshtein_k(b"abcd", b"bcda", 2) == Some(2));
 /// ```
-pub fn levenshtein_simd_k_with_opts(
-    a: &[u8],
-    b: &[u8],
-    k: u32,
-    trace_on: bool,
-    costs: EditCosts,
-) -> Option<(u32, Option<Vec<Edit>>)> {
-    if a.len() == 0 && b.len() == 0 {
-        return if trace_on {
-            Some((0u32, Some(alloc::vec![])))
-        } else {
-            Some((0u32, None))
-        };
+#[must_use]
+pub fn levenshtein_k(a: &[u8], b: &[u8], k: u32) -> Option<u32> {
+    let len_diff = cmp::max(a.len(), b.len()) - cmp::min(a.len(), b.len());
+
+    if len_diff as u64 > k as u64 {
+        return None;
     }
 
-    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-    {
-        let min_len = cmp::min(a.len(), b.len()) as u32;
-        let max_len = cmp::max(a.len(), b.len()) as u32;
-        // upper bound on the number of edits, in case k is too large
-        let max_k = cmp::min(
-            min_len * (costs.mismatch_cost as u32),
-            (min_len << 1) * (costs.gap_cost as u32)
-                + if min_len == 0 {
-                    0
-                } else {
-                    costs.start_gap_cost as u32
-                        + if max_len == min_len {
-                            costs.start_gap_cost as u32
-                        } else {
-                            0
-                        }
-                },
-        );
-        let max_k = cmp::min(
-            k,
-            max_k
-                + (max_len - min_len) * (costs.gap_cost as u32)
-                + if max_len == min_len {
-                    0
-                } else {
-                    costs.start_gap_cost as u32
-                },
-        );
-        // farthest we can stray from the main diagonal
-        // have to start at least one gap
-        let unit_k = cmp::min(
-            max_k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32),
-            max_len,
-        );
+    if len_diff == 0 {
+        let hamming_dist = hamming(a, b);
 
-        // note: do not use the MAX value, because it indicates overflow/inaccuracy
-        if cfg!(feature = "jewel-avx") {
-            if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Avx1x32x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_avx_1x32x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Avx2x32x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_avx_2x32x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Avx4x32x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_avx_4x32x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Avx8x32x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_avx_8x32x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-16bit") && max_k <= ((u16::MAX - 1) as u32) {
-                return unsafe { levenshtein_simd_core_avx_nx16x16(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-32bit") {
-                return unsafe { levenshtein_simd_core_avx_nx8x32(a, b, max_k, trace_on, costs) };
-            }
-        } else if cfg!(feature = "jewel-sse") {
-            if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Sse1x16x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_sse_1x16x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Sse2x16x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_sse_2x16x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Sse4x16x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_sse_4x16x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Sse8x16x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_sse_8x16x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-8bit")
-                && unit_k <= (Sse16x16x8::static_upper_bound() as u32 - 2)
-                && max_k <= ((u8::MAX - 1) as u32)
-            {
-                return unsafe { levenshtein_simd_core_sse_16x16x8(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-16bit") && max_k <= ((u16::MAX - 1) as u32) {
-                return unsafe { levenshtein_simd_core_sse_nx8x16(a, b, max_k, trace_on, costs) };
-            } else if cfg!(feature = "jewel-32bit") {
-                return unsafe { levenshtein_simd_core_sse_nx4x32(a, b, max_k, trace_on, costs) };
-            }
+        if hamming_dist <= 1 {
+            return if hamming_dist <= k {
+                Some(hamming_dist)
+            } else {
+                None
+            };
         }
+
+        return levenshtein_simd_k(a, b, cmp::min(k, hamming_dist));
     }
 
-    levenshtein_naive_k_with_opts(a, b, k, trace_on, costs)
+    levenshtein_simd_k(a, b, k)
 }
 
-macro_rules! create_levenshtein_simd_core {
-    ($name:ident, $traceback_name:ident, $jewel:ty, $target:literal) => {
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        #[target_feature(enable = $target)]
-        unsafe fn $name(
-            a: &[u8],
-            b: &[u8],
-            k: u32,
-            trace_on: bool,
-            costs: EditCosts,
-        ) -> Option<(u32, Option<Vec<Edit>>)> {
-            #[cfg(feature = "debug")]
-            {
-                println!(
-                    "Debug: Levenshtein Jewel vector type {} for target {}.",
-                    stringify!($jewel),
-                    stringify!($target)
-                );
-            }
+/// A profile of the q-grams (substrings of length `q`) of a string, used as a cheap filter
+/// before calculating the Levenshtein distance.
+///
+/// The profile is a vector of each distinct q-gram and the number of times it occurs, sorted by
+/// the q-gram. Comparing two profiles only requires a single merge pass over the sorted vectors.
+/// A single edit changes the counts of at most `2 * q` q-grams, so `lower_bound` (the L1 count
+/// difference divided by `2 * q`) never exceeds the true Levenshtein distance. This means that a
+/// candidate can be safely skipped if its `lower_bound` is greater than the allowed number of edits.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let query = QgramProfile::new(b"levenshtein", 2);
+/// let candidate = QgramProfile::new(b"levenstein", 2);
+///
+/// assert!(query.distance(&candidate) == 3);
+/// assert!(query.lower_bound(&candidate) == 1);
+/// assert!(query.lower_bound(&candidate) <= levenshtein(b"levenshtein", b"levenstein") as usize);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QgramProfile {
+    q: usize,
+    counts: Vec<(Vec<u8>, usize)>,
+}
 
-            // swap a and b so that a is shorter than b, if applicable
-            // makes operations later on slightly easier, since length of a <= length of b
-            let swap = a.len() > b.len();
-            let (a, b) = if swap { (b, a) } else { (a, b) };
-            let a_len = a.len();
-            let b_len = b.len();
-            let unit_k = cmp::min(
-                (k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32)) as usize,
-                b_len,
-            );
+impl QgramProfile {
+    /// Create a new `QgramProfile` by counting every q-gram in `s`.
+    ///
+    /// If `s` is shorter than `q`, then the profile is empty.
+    ///
+    /// # Arguments
+    /// * `s` - string (slice)
+    /// * `q` - length of each q-gram
+    ///
+    /// # Panics
+    /// * If `q` is zero.
+    pub fn new(s: &[u8], q: usize) -> Self {
+        assert!(q > 0);
 
-            if b_len - a_len > unit_k {
-                return None;
+        let mut grams: Vec<&[u8]> = s.windows(q).collect();
+        grams.sort_unstable();
+
+        let mut counts: Vec<(Vec<u8>, usize)> = Vec::new();
+
+        for gram in grams {
+            match counts.last_mut() {
+                Some((last, count)) if last.as_slice() == gram => *count += 1,
+                _ => counts.push((gram.to_vec(), 1)),
             }
+        }
 
-            // initialized with max values
-            // must use saturated additions afterwards to not overflow
-            let mut dp1 = <$jewel>::repeating_max((unit_k + 2) as usize);
-            let max_len = dp1.upper_bound();
-            let mut dp2 = <$jewel>::repeating_max(max_len);
-            let mut dp0 = <$jewel>::repeating_max(max_len);
-            let mut dp_temp = <$jewel>::repeating_max(max_len);
-            // dp0 -> dp_temp -> dp1 -> dp2 -> current diagonal
+        Self { q, counts }
+    }
 
-            // dp for whether to extend gap or start new gap
-            let mut a_gap_dp = <$jewel>::repeating_max(max_len);
-            let mut b_gap_dp = <$jewel>::repeating_max(max_len);
+    /// Returns the length of each q-gram in this profile.
+    pub fn q(&self) -> usize {
+        self.q
+    }
 
-            // lengths of the (anti) diagonals
-            // assumes max_len is even
-            let k1 = max_len - 1;
-            let k1_div2 = k1 >> 1;
-            let k2 = max_len - 2;
-            let k2_div2 = k2 >> 1;
+    /// Returns the L1 distance between the q-gram counts of this profile and `other`.
+    ///
+    /// This is the sum of the absolute differences in the counts of each q-gram.
+    /// Note that this is not a lower bound on the Levenshtein distance by itself; use
+    /// `lower_bound` for that.
+    ///
+    /// # Arguments
+    /// * `other` - other profile, which must have the same `q`
+    ///
+    /// # Panics
+    /// * If the `q` of `other` does not equal the `q` of this profile.
+    pub fn distance(&self, other: &QgramProfile) -> usize {
+        assert!(self.q == other.q);
+
+        let mut res = 0usize;
+        let mut i = 0;
+        let mut j = 0;
+
+        // merge the two sorted count vectors
+        while i < self.counts.len() && j < other.counts.len() {
+            let (a_gram, a_count) = &self.counts[i];
+            let (b_gram, b_count) = &other.counts[j];
+
+            match a_gram.cmp(b_gram) {
+                cmp::Ordering::Less => {
+                    res += a_count;
+                    i += 1;
+                }
+                cmp::Ordering::Greater => {
+                    res += b_count;
+                    j += 1;
+                }
+                cmp::Ordering::Equal => {
+                    res += cmp::max(a_count, b_count) - cmp::min(a_count, b_count);
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
 
-            // set dp[0][0] = 0
-            dp1.slow_insert(k1_div2, 0);
-            // set dp[0][1] = start_gap_cost + gap_cost and dp[1][0] = start_gap_cost + gap_cost
-            dp2.slow_insert(
-                k2_div2 - 1,
-                costs.start_gap_cost as u32 + costs.gap_cost as u32,
-            );
-            dp2.slow_insert(k2_div2, costs.start_gap_cost as u32 + costs.gap_cost as u32);
-            b_gap_dp.slow_insert(
-                k2_div2 - 1,
-                costs.start_gap_cost as u32 + costs.gap_cost as u32,
-            );
-            a_gap_dp.slow_insert(k2_div2, costs.start_gap_cost as u32 + costs.gap_cost as u32);
+        res += self.counts[i..].iter().map(|c| c.1).sum::<usize>();
+        res += other.counts[j..].iter().map(|c| c.1).sum::<usize>();
+        res
+    }
 
-            // a_k1_window and a_k2_window represent reversed portions of the string a
-            // copy in half of k1/k2 number of characters
-            // these characters are placed in the second half of b windows
-            // since a windows are reversed, the characters are placed in reverse in the first half of b windows
-            let mut a_k1_window = <$jewel>::repeating(0, max_len);
-            a_k1_window.slow_loadu(k1_div2 - 1, a.as_ptr(), cmp::min(k1_div2, a_len), true);
+    /// Returns a lower bound on the Levenshtein distance between the strings of this profile
+    /// and `other`.
+    ///
+    /// Each edit can change the counts of at most `2 * q` q-grams, so this is the `distance`
+    /// divided by `2 * q`, rounded up.
+    ///
+    /// # Arguments
+    /// * `other` - other profile, which must have the same `q`
+    ///
+    /// # Panics
+    /// * If the `q` of `other` does not equal the `q` of this profile.
+    pub fn lower_bound(&self, other: &QgramProfile) -> usize {
+        let div = self.q << 1;
+        self.distance(other).div_ceil(div)
+    }
+}
 
-            let mut b_k1_window = <$jewel>::repeating(0, max_len);
-            b_k1_window.slow_loadu(k1_div2 + 1, b.as_ptr(), cmp::min(k1_div2, b_len), false);
+/// A histogram of the byte values of a string, used as a very cheap filter before calculating
+/// the Levenshtein distance.
+///
+/// A substitution can remove one occurrence of a byte and add one occurrence of another byte,
+/// while a gap only adds or removes one byte. So `lower_bound` (the larger of the total number of
+/// extra bytes in either string) never exceeds the true Levenshtein distance. This is at least
+/// half of the L1 difference between the histograms.
+/// Creating a histogram for a fixed query once and comparing it against many candidates avoids
+/// counting the bytes of the query again for every candidate.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let query = ByteHistogram::new(b"kitten");
+/// let candidate = ByteHistogram::new(b"sitting");
+///
+/// assert!(query.distance(&candidate) == 5);
+/// assert!(query.lower_bound(&candidate) == 3);
+/// assert!(query.lower_bound(&candidate) <= levenshtein(b"kitten", b"sitting"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteHistogram {
+    counts: [u32; 256],
+}
 
-            let mut a_k2_window = <$jewel>::repeating(0, max_len);
-            a_k2_window.slow_loadu(k2_div2 - 1, a.as_ptr(), cmp::min(k2_div2, a_len), true);
+impl ByteHistogram {
+    /// Create a new `ByteHistogram` by counting every byte in `s`.
+    ///
+    /// # Arguments
+    /// * `s` - string (slice)
+    pub fn new(s: &[u8]) -> Self {
+        let mut counts = [0u32; 256];
 
-            let mut b_k2_window = <$jewel>::repeating(0, max_len);
-            b_k2_window.slow_loadu(k2_div2, b.as_ptr(), cmp::min(k2_div2, b_len), false);
+        for &c in s {
+            counts[c as usize] += 1;
+        }
 
-            // used to keep track of the next characters to place in the windows
-            let mut k1_idx = k1_div2 - 1;
-            let mut k2_idx = k2_div2 - 1;
+        Self { counts }
+    }
 
-            let len_diff = b_len - a_len;
-            let len = a_len + b_len + 1;
-            let len_div2 = (len >> 1) + (len & 1);
-            let ends_with_k2 = len & 1 == 0;
-            // every diff between the length of a and b results in a shift from the main diagonal
-            let final_idx = {
-                if ends_with_k2 {
-                    // divisible by 2, ends with k2
-                    k2_div2 + ((len_diff - 1) >> 1)
-                } else {
-                    // not divisible by 2, ends with k1
-                    k1_div2 + (len_diff >> 1)
-                }
-            };
+    /// Returns the number of times that the byte `c` occurs.
+    pub fn count(&self, c: u8) -> u32 {
+        self.counts[c as usize]
+    }
 
-            // 0 = match/mismatch, 1 = a gap, 2 = b gap, 3 = transpose
-            let mut traceback_arr = if trace_on {
-                Vec::with_capacity(len + (len & 1))
-            } else {
-                alloc::vec![]
-            };
+    /// Returns the L1 distance between the byte counts of this histogram and `other`.
+    ///
+    /// This is the sum of the absolute differences in the counts of each byte.
+    ///
+    /// # Arguments
+    /// * `other` - other histogram
+    pub fn distance(&self, other: &ByteHistogram) -> u32 {
+        let (extra_self, extra_other) = self.extra_counts(other);
+        extra_self + extra_other
+    }
 
-            if trace_on {
-                traceback_arr.push(<$jewel>::repeating(0, max_len));
-                traceback_arr.push(<$jewel>::repeating(0, max_len));
-                traceback_arr
-                    .get_unchecked_mut(1)
-                    .slow_insert(k2_div2 - 1, 2);
-                traceback_arr.get_unchecked_mut(1).slow_insert(k2_div2, 1);
-            }
+    /// Returns a lower bound on the Levenshtein distance between the strings of this histogram
+    /// and `other`.
+    ///
+    /// # Arguments
+    /// * `other` - other histogram
+    pub fn lower_bound(&self, other: &ByteHistogram) -> u32 {
+        let (extra_self, extra_other) = self.extra_counts(other);
+        cmp::max(extra_self, extra_other)
+    }
 
-            // reusable constant
-            let threes = <$jewel>::repeating(3, max_len);
+    /// Returns the total number of bytes that occur more often in this histogram than in `other`,
+    /// and the total number of bytes that occur more often in `other`.
+    fn extra_counts(&self, other: &ByteHistogram) -> (u32, u32) {
+        let mut extra_self = 0u32;
+        let mut extra_other = 0u32;
 
-            // used in calculations
-            let mut sub = <$jewel>::repeating(0, max_len);
-            let mut match_mask0 = <$jewel>::repeating(0, max_len);
-            let mut match_mask1 = <$jewel>::repeating(0, max_len);
-            let mut a_gap = <$jewel>::repeating(0, max_len);
-            let mut b_gap = <$jewel>::repeating(0, max_len);
-            let mut transpose = <$jewel>::repeating(0, max_len);
-
-            let mismatch_cost = <$jewel>::repeating(costs.mismatch_cost as u32, max_len);
-            let gap_cost = <$jewel>::repeating(costs.gap_cost as u32, max_len);
-            let start_gap_cost =
-                <$jewel>::repeating(costs.start_gap_cost as u32 + costs.gap_cost as u32, max_len);
-            let transpose_cost = match costs.transpose_cost {
-                Some(cost) => <$jewel>::repeating(cost as u32, max_len),
-                None => <$jewel>::repeating(0, max_len), // value does not matter
-            };
-            let allow_transpose = costs.transpose_cost.is_some();
+        for (&a, &b) in self.counts.iter().zip(other.counts.iter()) {
+            extra_self += a.saturating_sub(b);
+            extra_other += b.saturating_sub(a);
+        }
 
-            // example: allow k = 2 edits for two strings of length 3
-            //      -b--
-            // | xx */*
-            // a  x /*/*
-            // |    */*/ x
-            // |     */* xx
-            //
-            // each (anti) diagonal is represented with '*' or '/'
-            // '/', use k2 = 2
-            // '*', use k1 = 3
-            // 'x' represents cells not in the "traditional" dp array
-            // these out of bounds dp cells are shown because they represent
-            // a horizontal sliding window of length 5 (2 * k + 1)
-            //
-            // dp2 is one diagonal before current
-            // dp1 is two diagonals before current
-            // dp0 is four diagonals before current
-            // dp0 is useful for transpositions
-            // we are trying to calculate the "current" diagonal
-            // note that a k1 '*' dp diagonal has its center cell on the main diagonal
-            // in general, the diagonals are centered on the main diagonal
-            // each diagonal is represented using a Jewel vector
-            // each vector goes from bottom-left to top-right
-            //
-            // the a windows and b windows are queues of a fixed length
-            // a is reversed, so that elementwise comparison can be done between a and b
-            // this operation obtains the comparison of characters along the (anti) diagonal
-            // if transpositions are allowed, then previous match_masks must be saved to calculate
-            // a[i - 1] == b[j] and a[i] == b[j - 1]
-            // for speed, transpositions are done by directly blending using the mask, without calculating
-            // the minimum cost compared to the other edit operations
-            //
-            // example of moving the windows:
-            // a windows: [5 4 3 2 1] -> [6 5 4 3 2] (right shift + insert)
-            // b windows: [1 2 3 4 5] -> [2 3 4 5 6] (left shift + insert)
-            //
-            // initially:
-            // a windows: [2 1 0 0 0]
-            // b windows: [0 0 0 1 2]
-            //
-            // note that there will be left over cells not filled in the Jewel vector
-            // this is because k1 and k2 are not long enough
-            // all of these empty cells should be at the end of the SIMD vectors
-            //
-            // each iteration of the loop below results in processing both a k1 diagonal and a k2 diagonal
-            // this could be done with an alternating state flag but it is unrolled for less branching
-            //
-            // note: in traditional dp array
-            // dp[i][j] -> dp[i + 1][j] is a gap in string b
-            // dp[i][j] -> dp[i][j + 1] is a gap in string a
+        (extra_self, extra_other)
+    }
+}
 
-            for _ in 1..len_div2 {
-                // move indexes in strings forward
-                k1_idx += 1;
-                k2_idx += 1;
+/// Returns a lower bound on the Levenshtein distance between two strings, based on the number of
+/// times that each byte occurs.
+///
+/// This is `ByteHistogram::lower_bound` without precomputing the histograms, and it takes linear
+/// time, so it can be used to quickly reject candidates before calculating the real distance.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let bound = histogram_bound(b"abcd", b"dcba");
+///
+/// assert!(bound == 0);
+/// assert!(histogram_bound(b"abc", b"xyz") == 3);
+/// ```
+#[must_use]
+pub fn histogram_bound(a: &[u8], b: &[u8]) -> u32 {
+    ByteHistogram::new(a).lower_bound(&ByteHistogram::new(b))
+}
 
-                // move windows for the strings a and b
-                a_k1_window.shift_right_1_mut();
+/// Levenshtein distance between a string `a` that is edited at its end, one character at a time,
+/// and a fixed string `b`.
+///
+/// This stores one row of the dynamic programming matrix for each character of `a`, so pushing
+/// or popping a character only takes O(`b.len()`) time, instead of recomputing the whole matrix.
+/// This is useful for updating the distance to a fixed candidate as a query is typed.
+/// The memory used is O(`a.len()` * `b.len()`).
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let mut inc = IncrementalLevenshtein::new(b"abc");
+///
+/// assert!(inc.push_char(b'a') == 2);
+/// assert!(inc.push_char(b'x') == 2);
+/// assert!(inc.pop_char() == Some(2));
+/// assert!(inc.push_char(b'b') == 1);
+/// assert!(inc.distance() == levenshtein(b"ab", b"abc"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncrementalLevenshtein {
+    b: Vec<u8>,
+    a_len: usize,
+    // rows of the dp matrix, concatenated, with one row for each character of a, plus one
+    rows: Vec<u32>,
+}
 
-                if k1_idx < a_len {
-                    a_k1_window.insert_first(*a.get_unchecked(k1_idx) as u32);
-                }
+impl IncrementalLevenshtein {
+    /// Create a new `IncrementalLevenshtein` for the fixed string `b`, with an empty `a`.
+    ///
+    /// # Arguments
+    /// * `b` - fixed string (slice)
+    pub fn new(b: &[u8]) -> Self {
+        Self {
+            b: b.to_vec(),
+            a_len: 0,
+            rows: (0..=b.len() as u32).collect(),
+        }
+    }
 
-                b_k1_window.shift_left_1_mut();
+    /// Appends the character `c` to the end of `a` and returns the updated distance.
+    ///
+    /// # Arguments
+    /// * `c` - character to append
+    pub fn push_char(&mut self, c: u8) -> u32 {
+        let row_len = self.b.len() + 1;
+        let prev_start = self.a_len * row_len;
+        self.rows.reserve(row_len);
+        self.rows.push(self.rows[prev_start] + 1);
+
+        for i in 1..row_len {
+            let sub = self.rows[prev_start + i - 1] + (self.b[i - 1] != c) as u32;
+            let a_gap = self.rows[prev_start + row_len + i - 1] + 1;
+            let b_gap = self.rows[prev_start + i] + 1;
+            self.rows.push(cmp::min(sub, cmp::min(a_gap, b_gap)));
+        }
 
-                if k1_idx < b_len {
-                    b_k1_window.insert_last_1(*b.get_unchecked(k1_idx) as u32); // k1 - 1
-                }
+        self.a_len += 1;
+        self.distance()
+    }
 
-                a_k2_window.shift_right_1_mut();
+    /// Removes the last character of `a` and returns the updated distance.
+    ///
+    /// If `a` is empty, then `None` is returned.
+    pub fn pop_char(&mut self) -> Option<u32> {
+        if self.a_len == 0 {
+            return None;
+        }
 
-                if k2_idx < a_len {
-                    a_k2_window.insert_first(*a.get_unchecked(k2_idx) as u32);
-                }
+        self.a_len -= 1;
+        self.rows.truncate((self.a_len + 1) * (self.b.len() + 1));
+        Some(self.distance())
+    }
 
-                b_k2_window.shift_left_1_mut();
+    /// Returns the Levenshtein distance between the current `a` and `b`.
+    pub fn distance(&self) -> u32 {
+        *self.rows.last().unwrap()
+    }
 
-                if k2_idx < b_len {
-                    b_k2_window.insert_last_2(*b.get_unchecked(k2_idx) as u32); // k2 - 1
-                }
+    /// Returns the current length of `a`.
+    pub fn a_len(&self) -> usize {
+        self.a_len
+    }
+}
 
-                // (anti) diagonal that matches in the a and b windows
-                <$jewel>::cmpeq(&a_k1_window, &b_k1_window, &mut match_mask1);
-                <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut sub);
-                sub.adds_mut(&dp1);
-                // cost of gaps in a
-                // start new gap
-                <$jewel>::adds(&dp2, &start_gap_cost, &mut a_gap);
-                // continue gap
-                a_gap_dp.adds_mut(&gap_cost);
-                a_gap_dp.min_mut(&a_gap);
-                a_gap_dp.shift_right_1_mut();
-                a_gap_dp.insert_first_max();
-                // cost of gaps in b
-                // start new gap
-                <$jewel>::adds(&dp2, &start_gap_cost, &mut b_gap);
-                // continue gap
-                b_gap_dp.adds_mut(&gap_cost);
-                b_gap_dp.min_mut(&b_gap);
+/// A Levenshtein automaton that accepts every string within `k` edits of a query string.
+///
+/// The automaton is walked one byte at a time, starting from `start`, which makes it useful for
+/// pruning the branches of a trie: if `step` returns `None`, then no string with the prefix that
+/// was walked can be within `k` edits of the query, so the whole subtree can be skipped.
+/// Each state is a row of the dynamic programming matrix, with values capped at `k + 1`, so
+/// stepping takes O(`query.len()`) time. Since the values are capped, two states are equal if and
+/// only if they behave the same for all future bytes, so states can be used as keys to cache
+/// transitions, like a lazily built DFA.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let automaton = LevenshteinAutomaton::new(b"abc", 1);
+/// let mut state = automaton.start();
+///
+/// for &c in b"abd" {
+///     state = automaton.step(&state, c).unwrap();
+/// }
+///
+/// assert!(automaton.is_match(&state));
+/// assert!(automaton.step(&state, b'x').is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LevenshteinAutomaton {
+    query: Vec<u8>,
+    k: u32,
+}
 
-                if allow_transpose {
-                    <$jewel>::shift_right_1(&match_mask0, &mut transpose); // reuse transpose, zeros shifted in
-                    transpose.and_mut(&match_mask0);
-                    // make sure that current matching locations are excluded
-                    <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
-                    <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
-                }
+/// A state of a `LevenshteinAutomaton`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LevenshteinState {
+    row: Vec<u32>,
+}
 
-                // min of the cost of all three edit operations
-                if trace_on {
-                    let mut args = <$jewel>::triple_argmin(&sub, &a_gap_dp, &b_gap_dp, &mut dp0);
+impl LevenshteinAutomaton {
+    /// Create a new `LevenshteinAutomaton` that accepts strings within `k` edits of `query`.
+    ///
+    /// # Arguments
+    /// * `query` - query string (slice)
+    /// * `k` - maximum number of edits allowed
+    pub fn new(query: &[u8], k: u32) -> Self {
+        Self {
+            query: query.to_vec(),
+            k,
+        }
+    }
 
-                    if allow_transpose {
-                        // blend using transpose mask
-                        dp0.blendv_mut(&transpose, &match_mask0);
-                        args.blendv_mut(&threes, &match_mask0);
-                        mem::swap(&mut match_mask0, &mut match_mask1);
-                    }
+    /// Returns the state for the empty string.
+    pub fn start(&self) -> LevenshteinState {
+        let cap = self.k.saturating_add(1);
 
-                    traceback_arr.push(args);
-                } else {
-                    <$jewel>::min(&a_gap_dp, &b_gap_dp, &mut dp0);
-                    dp0.min_mut(&sub);
+        LevenshteinState {
+            row: (0..=self.query.len())
+                .map(|i| cmp::min(i as u32, cap))
+                .collect(),
+        }
+    }
 
-                    if allow_transpose {
-                        // blend using transpose mask
-                        dp0.blendv_mut(&transpose, &match_mask0);
-                        mem::swap(&mut match_mask0, &mut match_mask1);
-                    }
-                }
+    /// Returns the state after appending `byte` to the string that was walked to reach `state`.
+    ///
+    /// If no string that starts with the walked string and `byte` can be within `k` edits of the
+    /// query, then `None` is returned.
+    ///
+    /// # Arguments
+    /// * `state` - current state
+    /// * `byte` - next byte of the string
+    pub fn step(&self, state: &LevenshteinState, byte: u8) -> Option<LevenshteinState> {
+        let cap = self.k.saturating_add(1);
+        let mut row = Vec::with_capacity(state.row.len());
+        row.push(cmp::min(state.row[0] + 1, cap));
+
+        for i in 1..state.row.len() {
+            let sub = state.row[i - 1] + (self.query[i - 1] != byte) as u32;
+            let a_gap = row[i - 1] + 1;
+            let b_gap = state.row[i] + 1;
+            row.push(cmp::min(cmp::min(sub, cmp::min(a_gap, b_gap)), cap));
+        }
 
-                mem::swap(&mut dp0, &mut dp_temp);
-                mem::swap(&mut dp_temp, &mut dp1);
-                mem::swap(&mut dp1, &mut dp2);
+        if row.iter().all(|&x| x > self.k) {
+            None
+        } else {
+            Some(LevenshteinState { row })
+        }
+    }
 
-                // (anti) diagonal that matches in the a and b windows
-                <$jewel>::cmpeq(&a_k2_window, &b_k2_window, &mut match_mask1);
-                <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut sub);
-                sub.adds_mut(&dp1);
-                // cost of gaps in b
-                // start new gap
-                <$jewel>::adds(&dp2, &start_gap_cost, &mut b_gap);
-                // continue gap
-                b_gap_dp.adds_mut(&gap_cost);
-                b_gap_dp.min_mut(&b_gap);
-                b_gap_dp.shift_left_1_mut();
-                b_gap_dp.insert_last_max(); // k1, shift in max value
-                                            // cost of gaps in a
-                                            // start new gap
-                <$jewel>::adds(&dp2, &start_gap_cost, &mut a_gap);
-                a_gap_dp.adds_mut(&gap_cost);
-                // continue gap
-                a_gap_dp.min_mut(&a_gap);
+    /// Returns whether the string that was walked to reach `state` is within `k` edits of the query.
+    ///
+    /// # Arguments
+    /// * `state` - current state
+    pub fn is_match(&self, state: &LevenshteinState) -> bool {
+        *state.row.last().unwrap() <= self.k
+    }
 
-                if allow_transpose {
-                    <$jewel>::shift_left_1(&match_mask0, &mut transpose); // reuse transpose, zeros shifted in
-                    transpose.and_mut(&match_mask0);
-                    // make sure that current matching locations are excluded
-                    <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
-                    <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
-                }
+    /// Returns the Levenshtein distance between the query and the string that was walked to reach
+    /// `state`, or `None` if it is greater than `k`.
+    ///
+    /// # Arguments
+    /// * `state` - current state
+    pub fn distance(&self, state: &LevenshteinState) -> Option<u32> {
+        let dist = *state.row.last().unwrap();
 
-                // min of the cost of all three edit operations
-                if trace_on {
-                    let mut args = <$jewel>::triple_argmin(&sub, &a_gap_dp, &b_gap_dp, &mut dp0);
+        if dist <= self.k {
+            Some(dist)
+        } else {
+            None
+        }
+    }
+}
 
-                    if allow_transpose {
-                        // blend using transpose mask
-                        dp0.blendv_mut(&transpose, &match_mask0);
-                        args.blendv_mut(&threes, &match_mask0);
-                        mem::swap(&mut match_mask0, &mut match_mask1);
-                    }
+/// A symmetric deletion index over a dictionary, for fast lookup of words within a small number
+/// of edits, like spelling corrections.
+///
+/// Every string that can be made by deleting up to `max_k` characters from each dictionary word
+/// is precomputed. If two strings are within `k` Levenshtein edits of each other, then deleting
+/// at most `k` characters from each of them yields a common string, so a lookup only has to
+/// generate the deletions of the query word and probe the index. Each candidate is then verified
+/// with `levenshtein_k`, so false positives are never returned.
+/// The size of the index grows quickly with `max_k` and the length of the words, so this is meant
+/// for small values of `max_k`, like 1 or 2.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let index = DeletionIndex::new(&[b"receive", b"recipe", b"deceive"], 2);
+/// let res = index.lookup(b"recieve", 2);
+///
+/// assert!(res == vec![(&b"receive"[..], 2), (&b"recipe"[..], 2)]);
+/// assert!(index.lookup(b"recieve", 1).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct DeletionIndex {
+    max_k: u32,
+    words: Vec<Vec<u8>>,
+    deletions: BTreeMap<Vec<u8>, Vec<usize>>,
+}
 
-                    traceback_arr.push(args);
-                } else {
-                    <$jewel>::min(&a_gap_dp, &b_gap_dp, &mut dp0);
-                    dp0.min_mut(&sub);
+impl DeletionIndex {
+    /// Create a new `DeletionIndex` by precomputing the deletions of each word in `dictionary`.
+    ///
+    /// # Arguments
+    /// * `dictionary` - words to index (slice of slices)
+    /// * `max_k` - maximum number of edits that can be used for lookups
+    pub fn new(dictionary: &[&[u8]], max_k: u32) -> Self {
+        let mut deletions: BTreeMap<Vec<u8>, Vec<usize>> = BTreeMap::new();
+
+        for (i, word) in dictionary.iter().enumerate() {
+            for d in Self::deletions(word, max_k) {
+                deletions.entry(d).or_default().push(i);
+            }
+        }
 
-                    if allow_transpose {
-                        // blend using transpose mask
-                        dp0.blendv_mut(&transpose, &match_mask0);
-                        mem::swap(&mut match_mask0, &mut match_mask1);
-                    }
-                }
+        Self {
+            max_k,
+            words: dictionary.iter().map(|w| w.to_vec()).collect(),
+            deletions,
+        }
+    }
 
-                mem::swap(&mut dp0, &mut dp_temp);
-                mem::swap(&mut dp_temp, &mut dp1);
-                mem::swap(&mut dp1, &mut dp2);
-            }
+    /// Returns the dictionary words that are within `k` edits of `word`, along with their
+    /// Levenshtein distances.
+    ///
+    /// The results are sorted by distance, and then by word. Duplicate words in the dictionary
+    /// are only returned once.
+    ///
+    /// # Arguments
+    /// * `word` - query string (slice)
+    /// * `k` - maximum number of edits allowed
+    ///
+    /// # Panics
+    /// * If `k` is greater than the `max_k` of the index.
+    pub fn lookup(&self, word: &[u8], k: u32) -> Vec<(&[u8], u32)> {
+        assert!(k <= self.max_k);
 
-            let final_res = if ends_with_k2 {
-                dp2.slow_extract(final_idx)
-            } else {
-                dp1.slow_extract(final_idx)
-            };
+        let mut candidates = BTreeSet::new();
 
-            if final_res > k {
-                return None;
+        for d in Self::deletions(word, k) {
+            if let Some(idxs) = self.deletions.get(&d) {
+                candidates.extend(idxs.iter().map(|&i| self.words[i].as_slice()));
             }
+        }
 
-            if !trace_on {
-                return Some((final_res, None));
-            }
-
-            // upper bound the number of edit operations, to reduce memory allocations for saving the traceback
-            let mut upper_bound_edits =
-                final_res / (cmp::min(costs.mismatch_cost, costs.gap_cost) as u32);
-
-            if let Some(cost) = costs.transpose_cost {
-                upper_bound_edits =
-                    cmp::max(upper_bound_edits, (final_res >> 1) / (cost as u32) + 1);
-            }
-
-            Some((
-                final_res,
-                Some($traceback_name(
-                    &traceback_arr,
-                    upper_bound_edits as usize,
-                    final_idx,
-                    a,
-                    b,
-                    swap,
-                    ends_with_k2,
-                )),
-            ))
-        }
-
-        unsafe fn $traceback_name(
-            arr: &[$jewel],
-            k: usize,
-            mut idx: usize,
-            a: &[u8],
-            b: &[u8],
-            swap: bool,
-            mut is_k2: bool,
-        ) -> Vec<Edit> {
-            // keep track of position in traditional dp array and strings
-            let mut i = a.len(); // index in a
-            let mut j = b.len(); // index in b
-
-            // last diagonal may overshoot, so ignore it
-            let mut arr_idx = arr.len() - 1 - (if is_k2 { 0 } else { 1 });
-            let mut res: Vec<Edit> = Vec::with_capacity((k << 1) + 1);
+        let mut res: Vec<(&[u8], u32)> = candidates
+            .into_iter()
+            .filter_map(|w| levenshtein_k(word, w, k).map(|dist| (w, dist)))
+            .collect();
+        res.sort_by_key(|&(w, dist)| (dist, w));
+        res
+    }
 
-            while arr_idx > 0 {
-                // each Jewel vector in arr is only visited once, so extract (which is costly) is fine
-                let edit = arr.get_unchecked(arr_idx).slow_extract(idx);
+    /// Returns the number of words in the dictionary.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
 
-                let e = match edit {
-                    0 => {
-                        // match/mismatch
-                        arr_idx -= 2;
-                        i -= 1;
-                        j -= 1;
-                        if *a.get_unchecked(i) == *b.get_unchecked(j) {
-                            EditType::Match
-                        } else {
-                            EditType::Mismatch
-                        }
-                    }
-                    1 => {
-                        // a gap
-                        arr_idx -= 1;
+    /// Returns whether the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
 
-                        if !is_k2 {
-                            idx -= 1;
-                        }
+    fn deletions(word: &[u8], k: u32) -> BTreeSet<Vec<u8>> {
+        let mut res = BTreeSet::new();
+        res.insert(word.to_vec());
+        let mut curr = alloc::vec![word.to_vec()];
 
-                        j -= 1;
-                        is_k2 = !is_k2; // must account for alternating k1/k2 diagonals
-                        if swap {
-                            EditType::BGap
-                        } else {
-                            EditType::AGap
-                        } // account for the swap in the beginning
-                    }
-                    2 => {
-                        // b gap
-                        arr_idx -= 1;
+        for _ in 0..k {
+            let mut next = Vec::new();
 
-                        if is_k2 {
-                            idx += 1;
-                        }
+            for s in &curr {
+                for i in 0..s.len() {
+                    let mut d = s.clone();
+                    d.remove(i);
 
-                        i -= 1;
-                        is_k2 = !is_k2;
-                        if swap {
-                            EditType::AGap
-                        } else {
-                            EditType::BGap
-                        }
+                    if res.insert(d.clone()) {
+                        next.push(d);
                     }
-                    3 => {
-                        // transpose
-                        arr_idx -= 4;
-                        i -= 2;
-                        j -= 2;
-                        EditType::Transpose
-                    }
-                    _ => unreachable!(),
-                };
-
-                if res.len() > 0 && res.last().unwrap().edit == e {
-                    res.last_mut().unwrap().count += 1;
-                } else {
-                    res.push(Edit { edit: e, count: 1 });
                 }
             }
 
-            res.reverse();
-            res
+            curr = next;
         }
-    };
-}
-
-// create a version of the functions for each Jewel vector
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_1x32x8,
-    traceback_avx_1x32x8,
-    Avx1x32x8,
-    "avx2"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_2x32x8,
-    traceback_avx_2x32x8,
-    Avx2x32x8,
-    "avx2"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_4x32x8,
-    traceback_avx_4x32x8,
-    Avx4x32x8,
-    "avx2"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_8x32x8,
-    traceback_avx_8x32x8,
-    Avx8x32x8,
-    "avx2"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_nx16x16,
-    traceback_avx_nx16x16,
-    AvxNx16x16,
-    "avx2"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_avx_nx8x32,
-    traceback_avx_nx8x32,
-    AvxNx8x32,
-    "avx2"
-);
 
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_1x16x8,
-    traceback_sse_1x16x8,
-    Sse1x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_2x16x8,
-    traceback_sse_2x16x8,
-    Sse2x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_4x16x8,
-    traceback_sse_4x16x8,
-    Sse4x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_8x16x8,
-    traceback_sse_8x16x8,
-    Sse8x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_16x16x8,
-    traceback_sse_16x16x8,
-    Sse16x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_nx8x16,
-    traceback_sse_nx8x16,
-    SseNx8x16,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_simd_core!(
-    levenshtein_simd_core_sse_nx4x32,
-    traceback_sse_nx4x32,
-    SseNx4x32,
-    "sse4.1"
-);
+        res
+    }
+}
 
-/// Returns the Levenshtein distance between two strings using SIMD acceleration.
+/// Returns the Levenshtein distance, bounded by a cost threshold `k`, between two strings and optionally,
+/// the edit traceback, using SIMD acceleration, with extra options.
 ///
-/// Note that `levenshtein_exp` may be much faster if the number of edits between the two strings
-/// is expected to be small.
-/// Internally, this will call `levenshtein_simd_k`.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+/// This will return `None` if the Levenshtein distance between `a` and `b` is greater than the
+/// threshold `k`.
+/// This should be much faster than `levenshtein_naive_with_opts` and
+/// `levenshtein_naive_k_with_opts`.
+/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
+/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
+/// The elements are unsigned and saturate at their maximum value (255 for 8-bit elements), which
+/// is reserved to mark overflow. A bit width is only used if `k`, capped by the largest distance
+/// possible for the lengths of `a` and `b`, is below that maximum. Otherwise, a wider bit width or
+/// the scalar fallback is used, so large distances are never silently capped.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
+/// `levenshtein_naive_k_with_opts`.
 ///
 /// # Arguments
 /// * `a` - first string (slice)
 /// * `b` - second string (slice)
+/// * `k` - maximum number of cost allowed between `a` and `b`
+/// * `trace_on` - whether to return the traceback, the sequence of edits between `a` and `b`
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
-/// let dist = levenshtein(b"abc", b"ab");
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_simd_k_with_opts(b"abc", b"ab", 1, true, LEVENSHTEIN_COSTS);
 ///
-/// assert!(dist == 1);
+/// assert!(dist.unwrap() == (1, Some(vec![Edit{edit: EditType::Match, count: 2},
+///                                        Edit{edit: EditType::BGap, count: 1}])));
 /// ```
-pub fn levenshtein(a: &[u8], b: &[u8]) -> u32 {
-    levenshtein_simd_k(a, b, u32::MAX).unwrap()
+#[must_use]
+pub fn levenshtein_simd_k_with_opts(
+    a: &[u8],
+    b: &[u8],
+    k: u32,
+    trace_on: bool,
+    costs: EditCosts,
+) -> Option<(u32, Option<Vec<Edit>>)> {
+    levenshtein_simd_k_dispatch(a, b, k, trace_on, costs, CaseFold::Exact, None)
 }
 
-/// Returns the restricted Damerau-Levenshtein distance between two strings using SIMD acceleration.
-///
-/// Note that `rdamerau_exp` may be much faster if the number of edits between the two strings
-/// is expected to be small.
-/// Internally, this will call `levenshtein_simd_k_with_opts`.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
-///
-/// # Arguments
-/// * `a` - first string (slice)
-/// * `b` - second string (slice)
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// let dist = rdamerau(b"abc", b"acb");
-///
-/// assert!(dist == 1);
-/// ```
-pub fn rdamerau(a: &[u8], b: &[u8]) -> u32 {
-    levenshtein_simd_k_with_opts(a, b, u32::MAX, false, RDAMERAU_COSTS)
-        .unwrap()
-        .0
+/// How bytes are folded before they are compared in `levenshtein_simd_k_dispatch`.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum CaseFold {
+    Exact,
+    Ascii,
+    Latin1,
 }
 
-/// Returns the Levenshtein distance between two strings using exponential search and SIMD
-/// acceleration.
-///
-/// This may be much more efficient than `levenshtein` if the number of edits between `a` and `b`
-/// is expected to be small.
-/// Internally, this will call `levenshtein_simd_k` with values of `k` determined through
-/// exponential search.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
-///
-/// # Arguments
-/// * `a` - first string (slice)
-/// * `b` - second string (slice)
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// let dist = levenshtein_exp(b"abc", b"ab");
-///
-/// assert!(dist == 1);
-/// ```
-pub fn levenshtein_exp(a: &[u8], b: &[u8]) -> u32 {
-    let mut k = 30;
-    // exponential search
-    loop {
-        if let Some(res) = levenshtein_simd_k(a, b, k) {
-            return res;
+impl CaseFold {
+    #[inline]
+    fn fold(self, c: u8) -> u8 {
+        match self {
+            CaseFold::Exact => c,
+            CaseFold::Ascii => c.to_ascii_lowercase(),
+            CaseFold::Latin1 => LATIN1_LOWERCASE[c as usize],
         }
-        k *= 2;
     }
 }
 
-/// Returns the Levenshtein distance between two strings, and optionally, the edit traceback,
-/// using exponential search and SIMD acceleration. Extra options can be specified.
-///
-/// This may be much more efficient than `levenshtein` if the number of edits between `a` and `b`
-/// is expected to be small.
-/// Internally, this will call `levenshtein_simd_k_with_opts` with values of `k` determined through
-/// exponential search.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
-///
-/// # Arguments
-/// * `a` - first string (slice)
-/// * `b` - second string (slice)
-/// * `trace_on` - whether to return the traceback, the sequence of edits between `a` and `b`
-/// * `costs` - `EditCosts` struct for the cost of each edit operation
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// # use triple_accel::levenshtein::*;
-/// let dist = levenshtein_exp_with_opts(b"abc", b"ab", true, LEVENSHTEIN_COSTS);
-///
-/// assert!(dist == (1, Some(alloc::vec![Edit{edit: EditType::Match, count: 2},
-///                               Edit{edit: EditType::BGap, count: 1}])));
-/// ```
-pub fn levenshtein_exp_with_opts(
+fn levenshtein_simd_k_dispatch(
     a: &[u8],
     b: &[u8],
+    k: u32,
     trace_on: bool,
     costs: EditCosts,
-) -> (u32, Option<Vec<Edit>>) {
-    let mut k = 30;
-    // exponential search
-    loop {
-        if let Some(res) = levenshtein_simd_k_with_opts(a, b, k, trace_on, costs) {
-            return res;
-        }
-        k *= 2;
+    case_fold: CaseFold,
+    #[cfg_attr(feature = "pure-scalar", allow(unused_variables))] scratch: Option<&mut Scratch>,
+) -> Option<(u32, Option<Vec<Edit>>)> {
+    if a.len() == 0 && b.len() == 0 {
+        return if trace_on {
+            Some((0u32, Some(alloc::vec![])))
+        } else {
+            Some((0u32, None))
+        };
     }
-}
 
-/// Returns the restricted Damerau-Levenshtein distance between two strings using exponential
-/// search and SIMD acceleration.
-///
-/// This may be much more efficient than `rdamerau` if the number of edits between `a` and `b`
-/// is expected to be small.
-/// Internally, this will call `levenshtein_simd_k_with_opts` with values of `k` determined through
-/// exponential search.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
-///
-/// # Arguments
-/// * `a` - first string (slice)
-/// * `b` - second string (slice)
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// let dist = rdamerau_exp(b"abc", b"acb");
-///
-/// assert!(dist == 1);
-/// ```
-pub fn rdamerau_exp(a: &[u8], b: &[u8]) -> u32 {
-    let mut k = 30;
+    // the difference in lengths must be made up with gaps, so this is a cheap lower bound
+    let len_diff = (cmp::max(a.len(), b.len()) - cmp::min(a.len(), b.len())) as u64;
 
-    // exponential search
-    loop {
-        if let Some(res) = levenshtein_simd_k_with_opts(a, b, k, false, RDAMERAU_COSTS) {
-            return res.0;
+    if len_diff > 0 && len_diff * (costs.gap_cost as u64) + (costs.start_gap_cost as u64) > k as u64
+    {
+        return None;
+    }
+
+    #[cfg(all(
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "wasm32", target_feature = "simd128")
+        ),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        let min_len = cmp::min(a.len(), b.len()) as u32;
+        let max_len = cmp::max(a.len(), b.len()) as u32;
+        // upper bound on the number of edits, in case k is too large
+        let max_k = cmp::min(
+            min_len * (costs.mismatch_cost as u32),
+            (min_len << 1) * (costs.gap_cost as u32)
+                + if min_len == 0 {
+                    0
+                } else {
+                    costs.start_gap_cost as u32
+                        + if max_len == min_len {
+                            costs.start_gap_cost as u32
+                        } else {
+                            0
+                        }
+                },
+        );
+        let max_k = cmp::min(
+            k,
+            max_k
+                + (max_len - min_len) * (costs.gap_cost as u32)
+                + if max_len == min_len {
+                    0
+                } else {
+                    costs.start_gap_cost as u32
+                },
+        );
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            // farthest we can stray from the main diagonal
+            // have to start at least one gap
+            let unit_k = cmp::min(
+                max_k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32),
+                max_len,
+            );
+
+            // note: do not use the MAX value, because it indicates overflow/inaccuracy
+            if cfg!(feature = "jewel-avx512")
+                && cfg!(feature = "jewel-8bit")
+                && max_k <= ((u8::MAX - 1) as u32)
+                && avx512_supported()
+            {
+                return unsafe {
+                    levenshtein_simd_core_avx512_nx64x8(
+                        a, b, max_k, trace_on, costs, case_fold, scratch,
+                    )
+                };
+            }
+
+            if cfg!(feature = "jewel-avx") && avx2_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Avx1x32x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_avx_1x32x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Avx2x32x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_avx_2x32x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Avx4x32x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_avx_4x32x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Avx8x32x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_avx_8x32x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && max_k <= ((u16::MAX - 1) as u32) {
+                    return unsafe {
+                        levenshtein_simd_core_avx_nx16x16(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") {
+                    return unsafe {
+                        levenshtein_simd_core_avx_nx8x32(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                }
+            } else if cfg!(feature = "jewel-sse") && sse41_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Sse1x16x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_sse_1x16x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Sse2x16x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_sse_2x16x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Sse4x16x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_sse_4x16x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Sse8x16x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_sse_8x16x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && unit_k <= (Sse16x16x8::static_upper_bound() as u32 - 2)
+                    && max_k <= ((u8::MAX - 1) as u32)
+                {
+                    return unsafe {
+                        levenshtein_simd_core_sse_16x16x8(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && max_k <= ((u16::MAX - 1) as u32) {
+                    return unsafe {
+                        levenshtein_simd_core_sse_nx8x16(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") {
+                    return unsafe {
+                        levenshtein_simd_core_sse_nx4x32(
+                            a, b, max_k, trace_on, costs, case_fold, scratch,
+                        )
+                    };
+                }
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        {
+            if cfg!(feature = "jewel-wasm")
+                && cfg!(feature = "jewel-8bit")
+                && max_k <= ((u8::MAX - 1) as u32)
+            {
+                return unsafe {
+                    levenshtein_simd_core_wasm_nx16x8(
+                        a, b, max_k, trace_on, costs, case_fold, scratch,
+                    )
+                };
+            }
         }
-        k *= 2;
     }
+
+    if case_fold != CaseFold::Exact {
+        let a: Vec<u8> = a.iter().map(|&c| case_fold.fold(c)).collect();
+        let b: Vec<u8> = b.iter().map(|&c| case_fold.fold(c)).collect();
+        return levenshtein_naive_k_with_opts(&a, &b, k, trace_on, costs);
+    }
+
+    levenshtein_naive_k_with_opts(a, b, k, trace_on, costs)
 }
 
-/// Returns an iterator over the best `Match`s by searching through the text `haystack` for the
-/// pattern `needle` using the naive algorithm.
-///
-/// The best matches are the matches with the lowest Levenshtein distance.
-/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
-/// If `needle` is empty, then no `Match`es are returned.
-/// Each returned `Match` requires at least half or more bytes of the `needle` to match
-/// somewhere in the `haystack`.
-///
-/// # Arguments
-/// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// # use triple_accel::levenshtein::*;
-/// let matches: Vec<Match> = levenshtein_search_naive(b"abc", b"  abd").collect();
+/// Reusable scratch space for the Jewel vectors used in SIMD Levenshtein distance calculations.
 ///
-/// assert!(matches == alloc::vec![Match{start: 2, end: 5, k: 1}]);
-/// ```
-pub fn levenshtein_search_naive<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    levenshtein_search_naive_with_opts(
-        needle,
-        haystack,
-        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
-        SearchType::Best,
-        LEVENSHTEIN_COSTS,
-        false,
-    )
+/// Passing the same `Scratch` to many calls of `levenshtein_with_scratch` avoids allocating
+/// fresh vectors for every comparison. The buffers only grow when a longer pair of strings
+/// needs more space, and they are replaced if a different Jewel vector type is selected.
+#[derive(Default)]
+pub struct Scratch {
+    buffers: Option<Box<dyn core::any::Any + Send>>,
 }
 
-/// Returns an iterator over `Match`s by searching through the text `haystack` for the
-/// pattern `needle` using the naive algorithm, with extra options.
-///
-/// Note that overlapping matches may be returned.
-/// If multiple matches end at the same position, then the longest match is chosen.
-/// If `needle` is empty and `anchored` is false, then no `Match`es are returned.
-///
-/// # Arguments
-/// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
-/// * `k` - maximum cost threshold for a match to be returned
-/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
-/// the lowest cost (additionally, only the longest matches are retained for matches that fully overlap)
-/// * `costs` - `EditCosts` struct for the cost of each edit operation
-/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
-/// causing any shifts to cost gap edits
-///
-/// # Example
-/// ```
-/// # use triple_accel::*;
-/// # use triple_accel::levenshtein::*;
-/// let matches: Vec<Match> = levenshtein_search_naive_with_opts(b"abc", b"  acb", 1, SearchType::All, RDAMERAU_COSTS, false).collect();
-///
-/// // note: it is possible to end the match at two different positions
-/// assert!(matches == alloc::vec![Match{start: 2, end: 4, k: 1}, Match{start: 2, end: 5, k: 1}]);
-/// ```
-pub fn levenshtein_search_naive_with_opts<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-    k: u32,
-    search_type: SearchType,
-    costs: EditCosts,
-    anchored: bool,
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    let needle_len = needle.len();
-    let haystack_len = haystack.len();
+impl Scratch {
+    /// Create an empty `Scratch`; no memory is allocated until it is first used.
+    pub fn new() -> Self {
+        Self { buffers: None }
+    }
 
-    if needle_len == 0 {
-        // special case when anchored is true: return possible matches
-        if anchored {
-            return match search_type {
-                SearchType::All => {
-                    let mut i = 0;
-                    let mut cost = costs.start_gap_cost as u32;
-                    let mut start = true;
+    #[cfg(all(
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "wasm32", target_feature = "simd128")
+        ),
+        not(feature = "pure-scalar")
+    ))]
+    fn buffers<J: Jewel + Send + 'static>(&mut self) -> &mut LevenshteinBuffers<J> {
+        let reuse = match &self.buffers {
+            Some(buffers) => buffers.is::<LevenshteinBuffers<J>>(),
+            None => false,
+        };
 
-                    Box::new(iter::from_fn(move || {
-                        if start {
-                            start = false;
-                            return Some(Match {
-                                start: 0,
-                                end: 0,
-                                k: 0,
-                            });
-                        }
+        if !reuse {
+            self.buffers = Some(Box::new(unsafe { LevenshteinBuffers::<J>::new() }));
+        }
 
-                        if i < haystack_len {
-                            i += 1;
-                            cost += costs.gap_cost as u32;
+        self.buffers
+            .as_mut()
+            .unwrap()
+            .downcast_mut::<LevenshteinBuffers<J>>()
+            .unwrap()
+    }
+}
 
-                            if cost <= k {
-                                return Some(Match {
-                                    start: 0,
-                                    end: i,
-                                    k: cost,
-                                });
-                            }
-                        }
-
-                        None
-                    }))
-                }
-                SearchType::Best => Box::new(iter::once(Match {
-                    start: 0,
-                    end: 0,
-                    k: 0,
-                })),
-            };
-        } else {
-            return Box::new(iter::empty());
-        }
+impl core::fmt::Debug for Scratch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Scratch")
+            .field("allocated", &self.buffers.is_some())
+            .finish()
     }
+}
 
-    // enforce another constraint on the costs
-    costs.check_search();
+#[cfg(all(
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ),
+    not(feature = "pure-scalar")
+))]
+struct LevenshteinBuffers<J: Jewel> {
+    dp0: J,
+    dp1: J,
+    dp2: J,
+    dp_temp: J,
+    a_gap_dp: J,
+    b_gap_dp: J,
+    a_k1_window: J,
+    b_k1_window: J,
+    a_k2_window: J,
+    b_k2_window: J,
+    threes: J,
+    sub: J,
+    match_mask0: J,
+    match_mask1: J,
+    a_gap: J,
+    b_gap: J,
+    transpose: J,
+    mismatch_cost: J,
+    gap_cost: J,
+    start_gap_cost: J,
+    transpose_cost: J,
+}
 
-    let len = needle_len + 1;
-    let iter_len = if anchored {
-        // start_gap_cost must be incurred at least once
-        cmp::min(
-            haystack_len,
-            needle_len.saturating_add(
-                (k.saturating_sub(costs.start_gap_cost as u32) as usize)
-                    / (costs.gap_cost as usize),
-            ),
-        )
-    } else {
-        haystack_len
-    };
+#[cfg(all(
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ),
+    not(feature = "pure-scalar")
+))]
+impl<J: Jewel> LevenshteinBuffers<J> {
+    unsafe fn new() -> Self {
+        Self {
+            dp0: J::repeating(0, 0),
+            dp1: J::repeating(0, 0),
+            dp2: J::repeating(0, 0),
+            dp_temp: J::repeating(0, 0),
+            a_gap_dp: J::repeating(0, 0),
+            b_gap_dp: J::repeating(0, 0),
+            a_k1_window: J::repeating(0, 0),
+            b_k1_window: J::repeating(0, 0),
+            a_k2_window: J::repeating(0, 0),
+            b_k2_window: J::repeating(0, 0),
+            threes: J::repeating(0, 0),
+            sub: J::repeating(0, 0),
+            match_mask0: J::repeating(0, 0),
+            match_mask1: J::repeating(0, 0),
+            a_gap: J::repeating(0, 0),
+            b_gap: J::repeating(0, 0),
+            transpose: J::repeating(0, 0),
+            mismatch_cost: J::repeating(0, 0),
+            gap_cost: J::repeating(0, 0),
+            start_gap_cost: J::repeating(0, 0),
+            transpose_cost: J::repeating(0, 0),
+        }
+    }
+}
 
-    let mut dp0 = alloc::vec![0u32; len];
-    let mut dp1 = alloc::vec![0u32; len];
-    let mut dp2 = alloc::vec![0u32; len];
-    let mut needle_gap_dp = alloc::vec![u32::MAX; len];
-    let mut haystack_gap_dp = alloc::vec![u32::MAX; len];
-    let mut length0 = alloc::vec![0usize; len];
-    let mut length1 = alloc::vec![0usize; len];
-    let mut length2 = alloc::vec![0usize; len];
-    let mut needle_gap_length = alloc::vec![0usize; len];
-    let mut haystack_gap_length = alloc::vec![0usize; len];
-    let mut curr_k = k;
-    let mismatch_cost = costs.mismatch_cost as u32;
-    let gap_cost = costs.gap_cost as u32;
-    let start_gap_cost = costs.start_gap_cost as u32;
-    let transpose_cost = match costs.transpose_cost {
-        Some(cost) => cost as u32,
-        None => 0,
-    };
-    let allow_transpose = costs.transpose_cost.is_some();
-    let mut first = true;
-    let mut i = 0;
+macro_rules! create_levenshtein_simd_core {
+    ($name:ident, $traceback_name:ident, $jewel:ty, $target:literal) => {
+        #[cfg(not(feature = "pure-scalar"))]
+        #[target_feature(enable = $target)]
+        unsafe fn $name(
+            a: &[u8],
+            b: &[u8],
+            k: u32,
+            trace_on: bool,
+            costs: EditCosts,
+            case_fold: CaseFold,
+            scratch: Option<&mut Scratch>,
+        ) -> Option<(u32, Option<Vec<Edit>>)> {
+            #[cfg(feature = "debug")]
+            {
+                std::println!(
+                    "Debug: Levenshtein Jewel vector type {} for target {}.",
+                    stringify!($jewel),
+                    stringify!($target)
+                );
+            }
 
-    let res = iter::from_fn(move || {
-        if first {
-            first = false;
+            // swap a and b so that a is shorter than b, if applicable
+            // makes operations later on slightly easier, since length of a <= length of b
+            let swap = a.len() > b.len();
+            let (a, b) = if swap { (b, a) } else { (a, b) };
+            let a_len = a.len();
+            let b_len = b.len();
+            let unit_k = cmp::min(
+                (k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32)) as usize,
+                b_len,
+            );
 
-            for j in 0..len {
-                dp1[j] = (j as u32) * gap_cost + if j == 0 { 0 } else { start_gap_cost };
+            if b_len - a_len > unit_k {
+                return None;
             }
 
-            if dp1[len - 1] <= curr_k {
-                if search_type == SearchType::Best {
-                    curr_k = dp1[len - 1];
+            // the Jewel vectors are borrowed from the scratch space, so their memory can be
+            // reused across calls
+            let mut local_buffers;
+            let buffers = match scratch {
+                Some(scratch) => scratch.buffers::<$jewel>(),
+                None => {
+                    local_buffers = LevenshteinBuffers::<$jewel>::new();
+                    &mut local_buffers
                 }
+            };
 
-                return Some((
-                    Match {
-                        start: 0,
-                        end: 0,
-                        k: dp1[len - 1],
-                    },
-                    curr_k,
-                ));
-            }
-        }
+            // initialized with max values
+            // must use saturated additions afterwards to not overflow
+            let mut dp1 = &mut buffers.dp1;
+            dp1.reset_max((unit_k + 2) as usize);
+            let max_len = dp1.upper_bound();
+            let mut dp2 = &mut buffers.dp2;
+            dp2.reset_max(max_len);
+            let mut dp0 = &mut buffers.dp0;
+            dp0.reset_max(max_len);
+            let mut dp_temp = &mut buffers.dp_temp;
+            dp_temp.reset_max(max_len);
+            // dp0 -> dp_temp -> dp1 -> dp2 -> current diagonal
 
-        while i < iter_len {
-            needle_gap_dp[0] = if anchored {
-                (i as u32 + 1) * gap_cost + start_gap_cost
-            } else {
-                0
-            };
-            dp2[0] = if anchored {
-                (i as u32 + 1) * gap_cost + start_gap_cost
-            } else {
-                0
-            };
-            needle_gap_length[0] = 0;
-            length2[0] = 0;
+            // dp for whether to extend gap or start new gap
+            let a_gap_dp = &mut buffers.a_gap_dp;
+            a_gap_dp.reset_max(max_len);
+            let b_gap_dp = &mut buffers.b_gap_dp;
+            b_gap_dp.reset_max(max_len);
 
-            for j in 1..len {
-                let sub = dp1[j - 1] + ((needle[j - 1] != haystack[i]) as u32) * mismatch_cost;
+            // lengths of the (anti) diagonals
+            // assumes max_len is even
+            let k1 = max_len - 1;
+            let k1_div2 = k1 >> 1;
+            let k2 = max_len - 2;
+            let k2_div2 = k2 >> 1;
 
-                let new_gap = dp1[j] + start_gap_cost + gap_cost;
-                let cont_gap = needle_gap_dp[j].saturating_add(gap_cost);
-                if new_gap < cont_gap {
-                    needle_gap_dp[j] = new_gap;
-                    needle_gap_length[j] = length1[j] + 1;
-                } else if new_gap > cont_gap {
-                    needle_gap_dp[j] = cont_gap;
-                    needle_gap_length[j] += 1;
-                } else {
-                    needle_gap_dp[j] = cont_gap;
-                    needle_gap_length[j] = cmp::max(length1[j], needle_gap_length[j]) + 1;
-                }
+            // set dp[0][0] = 0
+            dp1.slow_insert(k1_div2, 0);
+            // set dp[0][1] = start_gap_cost + gap_cost and dp[1][0] = start_gap_cost + gap_cost
+            dp2.slow_insert(
+                k2_div2 - 1,
+                costs.start_gap_cost as u32 + costs.gap_cost as u32,
+            );
+            dp2.slow_insert(k2_div2, costs.start_gap_cost as u32 + costs.gap_cost as u32);
+            b_gap_dp.slow_insert(
+                k2_div2 - 1,
+                costs.start_gap_cost as u32 + costs.gap_cost as u32,
+            );
+            a_gap_dp.slow_insert(k2_div2, costs.start_gap_cost as u32 + costs.gap_cost as u32);
 
-                let new_gap = dp2[j - 1] + start_gap_cost + gap_cost;
-                let cont_gap = haystack_gap_dp[j - 1].saturating_add(gap_cost);
-                if new_gap < cont_gap {
-                    haystack_gap_dp[j] = new_gap;
-                    haystack_gap_length[j] = length2[j - 1];
-                } else if new_gap > cont_gap {
-                    haystack_gap_dp[j] = cont_gap;
-                    haystack_gap_length[j] = haystack_gap_length[j - 1];
-                } else {
-                    haystack_gap_dp[j] = cont_gap;
-                    haystack_gap_length[j] = cmp::max(length2[j - 1], haystack_gap_length[j - 1]);
-                }
+            // a_k1_window and a_k2_window represent reversed portions of the string a
+            // copy in half of k1/k2 number of characters
+            // these characters are placed in the second half of b windows
+            // since a windows are reversed, the characters are placed in reverse in the first half of b windows
+            let a_k1_window = &mut buffers.a_k1_window;
+            a_k1_window.reset(0, max_len);
+            a_k1_window.slow_loadu(k1_div2 - 1, a.as_ptr(), cmp::min(k1_div2, a_len), true);
 
-                dp2[j] = needle_gap_dp[j];
-                length2[j] = needle_gap_length[j];
+            let b_k1_window = &mut buffers.b_k1_window;
+            b_k1_window.reset(0, max_len);
+            b_k1_window.slow_loadu(k1_div2 + 1, b.as_ptr(), cmp::min(k1_div2, b_len), false);
 
-                if (haystack_gap_dp[j] < dp2[j])
-                    || (haystack_gap_dp[j] == dp2[j] && length2[j - 1] > length2[j])
-                {
-                    dp2[j] = haystack_gap_dp[j];
-                    length2[j] = haystack_gap_length[j];
-                }
+            let a_k2_window = &mut buffers.a_k2_window;
+            a_k2_window.reset(0, max_len);
+            a_k2_window.slow_loadu(k2_div2 - 1, a.as_ptr(), cmp::min(k2_div2, a_len), true);
 
-                if (sub < dp2[j]) || (sub == dp2[j] && (length1[j - 1] + 1) > length2[j]) {
-                    dp2[j] = sub;
-                    length2[j] = length1[j - 1] + 1;
-                }
+            let b_k2_window = &mut buffers.b_k2_window;
+            b_k2_window.reset(0, max_len);
+            b_k2_window.slow_loadu(k2_div2, b.as_ptr(), cmp::min(k2_div2, b_len), false);
 
-                if allow_transpose
-                    && i > 0
-                    && j > 1
-                    && needle[j - 1] == haystack[i - 1]
-                    && needle[j - 2] == haystack[i]
-                {
-                    let transpose = dp0[j - 2] + transpose_cost;
+            // characters that are shifted into the windows later are folded one by one
+            let fold = |c: u8| case_fold.fold(c);
 
-                    if transpose <= dp2[j] {
-                        dp2[j] = transpose;
-                        length2[j] = length0[j - 2] + 2;
-                    }
+            match case_fold {
+                CaseFold::Exact => (),
+                CaseFold::Ascii => {
+                    a_k1_window.ascii_fold_mut();
+                    b_k1_window.ascii_fold_mut();
+                    a_k2_window.ascii_fold_mut();
+                    b_k2_window.ascii_fold_mut();
+                }
+                CaseFold::Latin1 => {
+                    a_k1_window.latin1_fold_mut();
+                    b_k1_window.latin1_fold_mut();
+                    a_k2_window.latin1_fold_mut();
+                    b_k2_window.latin1_fold_mut();
                 }
             }
 
-            let final_res = dp2[len - 1];
-            let final_length = length2[len - 1];
-
-            mem::swap(&mut dp0, &mut dp1);
-            mem::swap(&mut dp1, &mut dp2);
-            mem::swap(&mut length0, &mut length1);
-            mem::swap(&mut length1, &mut length2);
-
-            i += 1;
+            // used to keep track of the next characters to place in the windows
+            let mut k1_idx = k1_div2 - 1;
+            let mut k2_idx = k2_div2 - 1;
 
-            if final_res <= curr_k {
-                match search_type {
-                    SearchType::Best => curr_k = final_res,
-                    _ => (),
+            let len_diff = b_len - a_len;
+            let len = a_len + b_len + 1;
+            let len_div2 = (len >> 1) + (len & 1);
+            let ends_with_k2 = len & 1 == 0;
+            // every diff between the length of a and b results in a shift from the main diagonal
+            let final_idx = {
+                if ends_with_k2 {
+                    // divisible by 2, ends with k2
+                    k2_div2 + ((len_diff - 1) >> 1)
+                } else {
+                    // not divisible by 2, ends with k1
+                    k1_div2 + (len_diff >> 1)
                 }
+            };
 
-                return Some((
-                    Match {
-                        start: i - final_length,
-                        end: i,
-                        k: final_res,
-                    },
-                    curr_k,
-                ));
+            // 0 = match/mismatch, 1 = a gap, 2 = b gap, 3 = transpose
+            let mut traceback_arr = if trace_on {
+                Vec::with_capacity(len + (len & 1))
+            } else {
+                alloc::vec![]
+            };
+
+            if trace_on {
+                traceback_arr.push(<$jewel>::repeating(0, max_len));
+                traceback_arr.push(<$jewel>::repeating(0, max_len));
+                traceback_arr
+                    .get_unchecked_mut(1)
+                    .slow_insert(k2_div2 - 1, 2);
+                traceback_arr.get_unchecked_mut(1).slow_insert(k2_div2, 1);
             }
-        }
 
-        None
-    });
+            // reusable constant
+            let threes = &mut buffers.threes;
+            threes.reset(3, max_len);
 
-    if search_type == SearchType::Best {
-        // estimate the number of Matches
-        let mut res_vec = Vec::with_capacity(iter_len / needle_len);
+            // used in calculations
+            let mut sub = &mut buffers.sub;
+            sub.reset(0, max_len);
+            let mut match_mask0 = &mut buffers.match_mask0;
+            match_mask0.reset(0, max_len);
+            let mut match_mask1 = &mut buffers.match_mask1;
+            match_mask1.reset(0, max_len);
+            let mut a_gap = &mut buffers.a_gap;
+            a_gap.reset(0, max_len);
+            let mut b_gap = &mut buffers.b_gap;
+            b_gap.reset(0, max_len);
+            let mut transpose = &mut buffers.transpose;
+            transpose.reset(0, max_len);
+
+            let mismatch_cost = &mut buffers.mismatch_cost;
+            mismatch_cost.reset(costs.mismatch_cost as u32, max_len);
+            let gap_cost = &mut buffers.gap_cost;
+            gap_cost.reset(costs.gap_cost as u32, max_len);
+            let start_gap_cost = &mut buffers.start_gap_cost;
+            start_gap_cost.reset(costs.start_gap_cost as u32 + costs.gap_cost as u32, max_len);
+            let transpose_cost = &mut buffers.transpose_cost;
+            // value does not matter if there are no transpositions
+            transpose_cost.reset(costs.transpose_cost.unwrap_or(0) as u32, max_len);
+            let allow_transpose = costs.transpose_cost.is_some();
 
-        for m in res {
-            match res_vec.len() {
+            // example: allow k = 2 edits for two strings of length 3
+            //      -b--
+            // | xx */*
+            // a  x /*/*
+            // |    */*/ x
+            // |     */* xx
+            //
+            // each (anti) diagonal is represented with '*' or '/'
+            // '/', use k2 = 2
+            // '*', use k1 = 3
+            // 'x' represents cells not in the "traditional" dp array
+            // these out of bounds dp cells are shown because they represent
+            // a horizontal sliding window of length 5 (2 * k + 1)
+            //
+            // dp2 is one diagonal before current
+            // dp1 is two diagonals before current
+            // dp0 is four diagonals before current
+            // dp0 is useful for transpositions
+            // we are trying to calculate the "current" diagonal
+            // note that a k1 '*' dp diagonal has its center cell on the main diagonal
+            // in general, the diagonals are centered on the main diagonal
+            // each diagonal is represented using a Jewel vector
+            // each vector goes from bottom-left to top-right
+            //
+            // the a windows and b windows are queues of a fixed length
+            // a is reversed, so that elementwise comparison can be done between a and b
+            // this operation obtains the comparison of characters along the (anti) diagonal
+            // if transpositions are allowed, then previous match_masks must be saved to calculate
+            // a[i - 1] == b[j] and a[i] == b[j - 1]
+            // for speed, transpositions are done by directly blending using the mask, without calculating
+            // the minimum cost compared to the other edit operations
+            //
+            // example of moving the windows:
+            // a windows: [5 4 3 2 1] -> [6 5 4 3 2] (right shift + insert)
+            // b windows: [1 2 3 4 5] -> [2 3 4 5 6] (left shift + insert)
+            //
+            // initially:
+            // a windows: [2 1 0 0 0]
+            // b windows: [0 0 0 1 2]
+            //
+            // note that there will be left over cells not filled in the Jewel vector
+            // this is because k1 and k2 are not long enough
+            // all of these empty cells should be at the end of the SIMD vectors
+            //
+            // each iteration of the loop below results in processing both a k1 diagonal and a k2 diagonal
+            // this could be done with an alternating state flag but it is unrolled for less branching
+            //
+            // note: in traditional dp array
+            // dp[i][j] -> dp[i + 1][j] is a gap in string b
+            // dp[i][j] -> dp[i][j + 1] is a gap in string a
+
+            for diag_idx in 1..len_div2 {
+                // move indexes in strings forward
+                k1_idx += 1;
+                k2_idx += 1;
+
+                // move windows for the strings a and b
+                a_k1_window.shift_right_1_mut();
+
+                if k1_idx < a_len {
+                    a_k1_window.insert_first(fold(*a.get_unchecked(k1_idx)) as u32);
+                }
+
+                b_k1_window.shift_left_1_mut();
+
+                if k1_idx < b_len {
+                    b_k1_window.insert_last_1(fold(*b.get_unchecked(k1_idx)) as u32); // k1 - 1
+                }
+
+                a_k2_window.shift_right_1_mut();
+
+                if k2_idx < a_len {
+                    a_k2_window.insert_first(fold(*a.get_unchecked(k2_idx)) as u32);
+                }
+
+                b_k2_window.shift_left_1_mut();
+
+                if k2_idx < b_len {
+                    b_k2_window.insert_last_2(fold(*b.get_unchecked(k2_idx)) as u32); // k2 - 1
+                }
+
+                // (anti) diagonal that matches in the a and b windows
+                <$jewel>::cmpeq(&a_k1_window, &b_k1_window, &mut match_mask1);
+                <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut sub);
+                sub.adds_mut(&dp1);
+                // cost of gaps in a
+                // start new gap
+                <$jewel>::adds(&dp2, &start_gap_cost, &mut a_gap);
+                // continue gap
+                a_gap_dp.adds_mut(&gap_cost);
+                a_gap_dp.min_mut(&a_gap);
+                a_gap_dp.shift_right_1_mut();
+                a_gap_dp.insert_first_max();
+                // cost of gaps in b
+                // start new gap
+                <$jewel>::adds(&dp2, &start_gap_cost, &mut b_gap);
+                // continue gap
+                b_gap_dp.adds_mut(&gap_cost);
+                b_gap_dp.min_mut(&b_gap);
+
+                if allow_transpose {
+                    <$jewel>::shift_right_1(&match_mask0, &mut transpose); // reuse transpose, zeros shifted in
+                    transpose.and_mut(&match_mask0);
+                    // make sure that current matching locations are excluded
+                    <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
+                    <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
+                }
+
+                // min of the cost of all three edit operations
+                if trace_on {
+                    let mut args = <$jewel>::triple_argmin(&sub, &a_gap_dp, &b_gap_dp, &mut dp0);
+
+                    if allow_transpose {
+                        // blend using transpose mask
+                        dp0.blendv_mut(&transpose, &match_mask0);
+                        args.blendv_mut(&threes, &match_mask0);
+                        mem::swap(&mut match_mask0, &mut match_mask1);
+                    }
+
+                    traceback_arr.push(args);
+                } else {
+                    <$jewel>::min(&a_gap_dp, &b_gap_dp, &mut dp0);
+                    dp0.min_mut(&sub);
+
+                    if allow_transpose {
+                        // blend using transpose mask
+                        dp0.blendv_mut(&transpose, &match_mask0);
+                        mem::swap(&mut match_mask0, &mut match_mask1);
+                    }
+                }
+
+                mem::swap(&mut dp0, &mut dp_temp);
+                mem::swap(&mut dp_temp, &mut dp1);
+                mem::swap(&mut dp1, &mut dp2);
+
+                // (anti) diagonal that matches in the a and b windows
+                <$jewel>::cmpeq(&a_k2_window, &b_k2_window, &mut match_mask1);
+                <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut sub);
+                sub.adds_mut(&dp1);
+                // cost of gaps in b
+                // start new gap
+                <$jewel>::adds(&dp2, &start_gap_cost, &mut b_gap);
+                // continue gap
+                b_gap_dp.adds_mut(&gap_cost);
+                b_gap_dp.min_mut(&b_gap);
+                b_gap_dp.shift_left_1_mut();
+                b_gap_dp.insert_last_max(); // k1, shift in max value
+                                            // cost of gaps in a
+                                            // start new gap
+                <$jewel>::adds(&dp2, &start_gap_cost, &mut a_gap);
+                a_gap_dp.adds_mut(&gap_cost);
+                // continue gap
+                a_gap_dp.min_mut(&a_gap);
+
+                if allow_transpose {
+                    <$jewel>::shift_left_1(&match_mask0, &mut transpose); // reuse transpose, zeros shifted in
+                    transpose.and_mut(&match_mask0);
+                    // make sure that current matching locations are excluded
+                    <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
+                    <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
+                }
+
+                // min of the cost of all three edit operations
+                if trace_on {
+                    let mut args = <$jewel>::triple_argmin(&sub, &a_gap_dp, &b_gap_dp, &mut dp0);
+
+                    if allow_transpose {
+                        // blend using transpose mask
+                        dp0.blendv_mut(&transpose, &match_mask0);
+                        args.blendv_mut(&threes, &match_mask0);
+                        mem::swap(&mut match_mask0, &mut match_mask1);
+                    }
+
+                    traceback_arr.push(args);
+                } else {
+                    <$jewel>::min(&a_gap_dp, &b_gap_dp, &mut dp0);
+                    dp0.min_mut(&sub);
+
+                    if allow_transpose {
+                        // blend using transpose mask
+                        dp0.blendv_mut(&transpose, &match_mask0);
+                        mem::swap(&mut match_mask0, &mut match_mask1);
+                    }
+                }
+
+                mem::swap(&mut dp0, &mut dp_temp);
+                mem::swap(&mut dp_temp, &mut dp1);
+                mem::swap(&mut dp1, &mut dp2);
+
+                // early exit: costs never decrease along a path, and every path to the last cell
+                // crosses one of the last two (anti) diagonals
+                // a transposition can skip over three diagonals, and the transposition blend may
+                // hide a cheaper gap, so the last four diagonals and the gaps are also checked
+                // this is only done periodically, since it requires horizontal reductions
+                if diag_idx & 7 == 0
+                    && dp2.horizontal_min() > k
+                    && dp1.horizontal_min() > k
+                    && (!allow_transpose
+                        || (dp_temp.horizontal_min() > k
+                            && dp0.horizontal_min() > k
+                            && a_gap_dp.horizontal_min() > k
+                            && b_gap_dp.horizontal_min() > k))
+                {
+                    return None;
+                }
+            }
+
+            let final_res = if ends_with_k2 {
+                dp2.slow_extract(final_idx)
+            } else {
+                dp1.slow_extract(final_idx)
+            };
+
+            if final_res > k {
+                return None;
+            }
+
+            if !trace_on {
+                return Some((final_res, None));
+            }
+
+            // upper bound the number of edit operations, to reduce memory allocations for saving the traceback
+            let mut upper_bound_edits =
+                final_res / (cmp::min(costs.mismatch_cost, costs.gap_cost) as u32);
+
+            if let Some(cost) = costs.transpose_cost {
+                upper_bound_edits =
+                    cmp::max(upper_bound_edits, (final_res >> 1) / (cost as u32) + 1);
+            }
+
+            Some((
+                final_res,
+                Some($traceback_name(
+                    &traceback_arr,
+                    upper_bound_edits as usize,
+                    final_idx,
+                    a,
+                    b,
+                    swap,
+                    ends_with_k2,
+                )),
+            ))
+        }
+
+        #[cfg(not(feature = "pure-scalar"))]
+        unsafe fn $traceback_name(
+            arr: &[$jewel],
+            k: usize,
+            mut idx: usize,
+            a: &[u8],
+            b: &[u8],
+            swap: bool,
+            mut is_k2: bool,
+        ) -> Vec<Edit> {
+            // keep track of position in traditional dp array and strings
+            let mut i = a.len(); // index in a
+            let mut j = b.len(); // index in b
+
+            // last diagonal may overshoot, so ignore it
+            let mut arr_idx = arr.len() - 1 - (if is_k2 { 0 } else { 1 });
+            let mut res: Vec<Edit> = Vec::with_capacity((k << 1) + 1);
+
+            while arr_idx > 0 {
+                // each Jewel vector in arr is only visited once, so extract (which is costly) is fine
+                let edit = arr.get_unchecked(arr_idx).slow_extract(idx);
+
+                let e = match edit {
+                    0 => {
+                        // match/mismatch
+                        arr_idx -= 2;
+                        i -= 1;
+                        j -= 1;
+                        if *a.get_unchecked(i) == *b.get_unchecked(j) {
+                            EditType::Match
+                        } else {
+                            EditType::Mismatch
+                        }
+                    }
+                    1 => {
+                        // a gap
+                        arr_idx -= 1;
+
+                        if !is_k2 {
+                            idx -= 1;
+                        }
+
+                        j -= 1;
+                        is_k2 = !is_k2; // must account for alternating k1/k2 diagonals
+                        if swap {
+                            EditType::BGap
+                        } else {
+                            EditType::AGap
+                        } // account for the swap in the beginning
+                    }
+                    2 => {
+                        // b gap
+                        arr_idx -= 1;
+
+                        if is_k2 {
+                            idx += 1;
+                        }
+
+                        i -= 1;
+                        is_k2 = !is_k2;
+                        if swap {
+                            EditType::AGap
+                        } else {
+                            EditType::BGap
+                        }
+                    }
+                    3 => {
+                        // transpose
+                        arr_idx -= 4;
+                        i -= 2;
+                        j -= 2;
+                        EditType::Transpose
+                    }
+                    _ => unreachable!(),
+                };
+
+                if res.len() > 0 && res.last().unwrap().edit == e {
+                    res.last_mut().unwrap().count += 1;
+                } else {
+                    res.push(Edit { edit: e, count: 1 });
+                }
+            }
+
+            res.reverse();
+            res
+        }
+    };
+}
+
+// create a version of the functions for each Jewel vector
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_1x32x8,
+    traceback_avx_1x32x8,
+    Avx1x32x8,
+    "avx2"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_2x32x8,
+    traceback_avx_2x32x8,
+    Avx2x32x8,
+    "avx2"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_4x32x8,
+    traceback_avx_4x32x8,
+    Avx4x32x8,
+    "avx2"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_8x32x8,
+    traceback_avx_8x32x8,
+    Avx8x32x8,
+    "avx2"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_nx16x16,
+    traceback_avx_nx16x16,
+    AvxNx16x16,
+    "avx2"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx_nx8x32,
+    traceback_avx_nx8x32,
+    AvxNx8x32,
+    "avx2"
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_avx512_nx64x8,
+    traceback_avx512_nx64x8,
+    Avx512Nx64x8,
+    "avx512bw"
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_1x16x8,
+    traceback_sse_1x16x8,
+    Sse1x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_2x16x8,
+    traceback_sse_2x16x8,
+    Sse2x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_4x16x8,
+    traceback_sse_4x16x8,
+    Sse4x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_8x16x8,
+    traceback_sse_8x16x8,
+    Sse8x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_16x16x8,
+    traceback_sse_16x16x8,
+    Sse16x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_nx8x16,
+    traceback_sse_nx8x16,
+    SseNx8x16,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_sse_nx4x32,
+    traceback_sse_nx4x32,
+    SseNx4x32,
+    "sse4.1"
+);
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+create_levenshtein_simd_core!(
+    levenshtein_simd_core_wasm_nx16x8,
+    traceback_wasm_nx16x8,
+    WasmNx16x8,
+    "simd128"
+);
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration.
+///
+/// Note that `levenshtein_exp` may be much faster if the number of edits between the two strings
+/// is expected to be small.
+/// Internally, this will call `levenshtein_with_escalation`, which calls `levenshtein_simd_k`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// If `a` and `b` have the same length, then their Hamming distance is an upper bound on the
+/// Levenshtein distance and it is computed first. Identical strings and single substitutions are
+/// returned directly, and otherwise the Hamming distance bounds the number of edits that the
+/// SIMD routine has to consider.
+/// Long strings are first compared using fast 8-bit vector elements, and the comparison is only
+/// redone with wider elements if the distance does not fit.
+/// If either string is empty, then the distance is the length of the other string.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let dist = levenshtein(b"abc", b"ab");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn levenshtein(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein_with_escalation(a, b).0
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, and whether the
+/// calculation had to be escalated to wider vector elements.
+///
+/// This is the same as `levenshtein`, which calls this function. When the distance could be
+/// larger than what fits in 8-bit elements, the distance is first calculated with a threshold of
+/// 254, so the fast 8-bit vectors can be used. If the distance turns out to be larger than that,
+/// then the calculation is done again without that threshold, using 16-bit or 32-bit elements.
+/// The second value is true if this escalation happened, so the result is always correct, but
+/// that comparison was calculated twice.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let a = [b'a'; 300];
+///
+/// assert!(levenshtein_with_escalation(&a[..200], &a) == (100, false));
+/// assert!(levenshtein_with_escalation(&a, b"") == (300, true));
+/// ```
+#[must_use]
+pub fn levenshtein_with_escalation(a: &[u8], b: &[u8]) -> (u32, bool) {
+    let k = if a.len() == b.len() {
+        let hamming_dist = hamming(a, b);
+
+        if hamming_dist <= 1 {
+            return (hamming_dist, false);
+        }
+
+        hamming_dist
+    } else {
+        u32::MAX
+    };
+
+    // the largest threshold that still allows 8-bit elements
+    let narrow_k = (u8::MAX - 1) as u32;
+    let max_dist = cmp::max(a.len(), b.len()) as u64;
+
+    if (k as u64) > (narrow_k as u64) && max_dist > (narrow_k as u64) {
+        if let Some(dist) = levenshtein_simd_k(a, b, narrow_k) {
+            return (dist, false);
+        }
+
+        return (levenshtein_simd_k(a, b, k).unwrap(), true);
+    }
+
+    (levenshtein_simd_k(a, b, k).unwrap(), false)
+}
+
+/// A distance along with diagnostic information about how it was calculated.
+///
+/// This is returned by `levenshtein_report`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct DistanceReport {
+    /// The edit distance between the two strings.
+    pub distance: u32,
+    /// The length of the first string.
+    pub a_len: usize,
+    /// The length of the second string.
+    pub b_len: usize,
+    /// Whether the distance did not fit in 8-bit vector elements, so it was calculated again with
+    /// wider elements.
+    pub saturated: bool,
+}
+
+impl DistanceReport {
+    /// Returns whether the two strings have the same length.
+    pub fn is_equal_length(&self) -> bool {
+        self.a_len == self.b_len
+    }
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, along with the
+/// lengths of the strings and whether the 8-bit vector elements saturated.
+///
+/// This is useful for debugging unexpected distances without recomputing anything.
+/// Internally, this will call `levenshtein_with_escalation`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let report = levenshtein_report(b"abc", b"abcd");
+///
+/// assert!(report == DistanceReport{distance: 1, a_len: 3, b_len: 4, saturated: false});
+/// assert!(!report.is_equal_length());
+/// ```
+#[must_use]
+pub fn levenshtein_report(a: &[u8], b: &[u8]) -> DistanceReport {
+    let (distance, saturated) = levenshtein_with_escalation(a, b);
+
+    DistanceReport {
+        distance,
+        a_len: a.len(),
+        b_len: b.len(),
+        saturated,
+    }
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, reusing the
+/// vector buffers in `scratch`.
+///
+/// This gives the same result as `levenshtein`, but it avoids allocating new buffers on every call
+/// when many short strings are compared in a loop.
+/// The buffers in `scratch` only grow when the strings need more space than before.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `scratch` - reusable buffers, which can be shared across calls
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let mut scratch = Scratch::new();
+/// let dist1 = levenshtein_with_scratch(b"abc", b"ab", &mut scratch);
+/// let dist2 = levenshtein_with_scratch(b"kitten", b"sitting", &mut scratch);
+///
+/// assert!(dist1 == 1);
+/// assert!(dist2 == 3);
+/// ```
+#[must_use]
+pub fn levenshtein_with_scratch(a: &[u8], b: &[u8], scratch: &mut Scratch) -> u32 {
+    levenshtein_simd_k_dispatch(
+        a,
+        b,
+        u32::MAX,
+        false,
+        LEVENSHTEIN_COSTS,
+        CaseFold::Exact,
+        Some(scratch),
+    )
+    .unwrap()
+    .0
+}
+
+/// Returns the number of bytes of scratch space that `levenshtein_into` needs for strings with
+/// the lengths `a_len` and `b_len`.
+///
+/// This is enough for one row of the dynamic programming matrix, with a 32-bit value for each
+/// character of the shorter string, plus one.
+///
+/// # Arguments
+/// * `a_len` - length of the first string
+/// * `b_len` - length of the second string
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// assert!(levenshtein_scratch_len(3, 5) == 16);
+/// ```
+pub fn levenshtein_scratch_len(a_len: usize, b_len: usize) -> usize {
+    (cmp::min(a_len, b_len) + 1) * mem::size_of::<u32>()
+}
+
+/// Returns the Levenshtein distance between two strings, using a caller-supplied scratch buffer
+/// instead of allocating any memory.
+///
+/// This is useful for real-time or embedded code, where allocating in a hot loop is not
+/// possible. The same buffer can be reused for every call, as long as it is large enough.
+/// The buffer must be at least `levenshtein_scratch_len(a.len(), b.len())` bytes long, and its
+/// start must be aligned to 32 bytes, for example by placing it in a `#[repr(align(32))]` struct.
+/// Its contents are overwritten.
+/// This uses a scalar algorithm that only keeps one row of the dynamic programming matrix, so it
+/// is slower than `levenshtein` and `levenshtein_with_scratch`, which both allocate.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `scratch` - buffer for the dynamic programming row, which must be aligned to 32 bytes
+///
+/// # Panics
+/// * If `scratch` is shorter than `levenshtein_scratch_len(a.len(), b.len())`.
+/// * If `scratch` is not aligned to 32 bytes.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// #[repr(align(32))]
+/// struct Aligned([u8; 64]);
+///
+/// let mut scratch = Aligned([0u8; 64]);
+/// let dist = levenshtein_into(b"kitten", b"sitting", &mut scratch.0);
+///
+/// assert!(dist == 3);
+/// ```
+#[must_use]
+pub fn levenshtein_into(a: &[u8], b: &[u8], scratch: &mut [u8]) -> u32 {
+    // keep the row as short as possible
+    let (a, b) = if a.len() > b.len() { (b, a) } else { (a, b) };
+    let len = levenshtein_scratch_len(a.len(), b.len());
+
+    assert!(
+        scratch.len() >= len,
+        "scratch buffer has {} bytes, but {} bytes are needed",
+        scratch.len(),
+        len
+    );
+    assert!(
+        (scratch.as_ptr() as usize) & 31 == 0,
+        "scratch buffer is not aligned to 32 bytes"
+    );
+
+    levenshtein_into_row(a, b, &mut scratch[..len])
+}
+
+#[cfg(not(feature = "pure-scalar"))]
+fn levenshtein_into_row(a: &[u8], b: &[u8], scratch: &mut [u8]) -> u32 {
+    // every bit pattern is a valid u32, and the alignment was checked by the caller
+    let (_, row, _) = unsafe { scratch.align_to_mut::<u32>() };
+
+    for (i, r) in row.iter_mut().enumerate() {
+        *r = i as u32;
+    }
+
+    for (j, &b_c) in b.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = (j + 1) as u32;
+
+        for i in 1..row.len() {
+            let prev = row[i];
+            row[i] = cmp::min(
+                diag + (a[i - 1] != b_c) as u32,
+                cmp::min(prev, row[i - 1]) + 1,
+            );
+            diag = prev;
+        }
+    }
+
+    row[a.len()]
+}
+
+#[cfg(feature = "pure-scalar")]
+fn levenshtein_into_row(a: &[u8], b: &[u8], scratch: &mut [u8]) -> u32 {
+    use core::convert::TryInto;
+
+    // without unsafe code, the buffer cannot be viewed as u32 values, so each value of the row
+    // is read from and written to four bytes instead
+    let read = |cell: &[u8]| u32::from_ne_bytes(cell.try_into().unwrap());
+
+    for (i, cell) in scratch.chunks_exact_mut(4).enumerate() {
+        cell.copy_from_slice(&(i as u32).to_ne_bytes());
+    }
+
+    for (j, &b_c) in b.iter().enumerate() {
+        let mut cells = scratch.chunks_exact_mut(4);
+        let first = cells.next().unwrap();
+        let mut diag = read(first);
+        let mut left = (j + 1) as u32;
+        first.copy_from_slice(&left.to_ne_bytes());
+
+        for (cell, &a_c) in cells.zip(a) {
+            let prev = read(cell);
+            left = cmp::min(diag + (a_c != b_c) as u32, cmp::min(prev, left) + 1);
+            cell.copy_from_slice(&left.to_ne_bytes());
+            diag = prev;
+        }
+    }
+
+    read(&scratch[a.len() * 4..(a.len() + 1) * 4])
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, after removing
+/// their common prefix and suffix.
+///
+/// Matching characters at the start or the end of both strings never need to be edited, so they
+/// do not affect the distance. Removing them first can greatly shrink the strings when comparing
+/// near matches, like a query against dictionary entries that share a long prefix.
+/// If one string is a prefix or suffix of the other, then the distance is the difference in
+/// lengths, and if the strings are equal, then the distance is zero, without running the
+/// dynamic programming algorithm.
+/// Internally, this will call `levenshtein` on the remaining middle parts.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_skip_common(b"international", b"internationally");
+///
+/// assert!(dist == 2);
+/// ```
+#[must_use]
+pub fn levenshtein_skip_common(a: &[u8], b: &[u8]) -> u32 {
+    let prefix_len = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let a = &a[prefix_len..];
+    let b = &b[prefix_len..];
+
+    let suffix_len = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let a = &a[..a.len() - suffix_len];
+    let b = &b[..b.len() - suffix_len];
+
+    if a.is_empty() || b.is_empty() {
+        return cmp::max(a.len(), b.len()) as u32;
+    }
+
+    levenshtein(a, b)
+}
+
+/// Returns the Levenshtein distance between the bytes of two `&str`s using SIMD acceleration.
+///
+/// This is the same as calling `levenshtein` with `a.as_bytes()` and `b.as_bytes()`, so each byte
+/// counts as a character. A multibyte UTF-8 character that is substituted for another can count
+/// as more than one edit. Use `levenshtein_simd_k_str` to count edits between chars instead.
+///
+/// # Arguments
+/// * `a` - first string (&str)
+/// * `b` - second string (&str)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// assert!(levenshtein_str("abc", "ab") == 1);
+/// // 'é' and 'è' are two bytes each, but only their second bytes differ
+/// assert!(levenshtein_str("café", "cafè") == 1);
+/// assert!(levenshtein_str("café", "cafe") == 2);
+/// ```
+#[must_use]
+pub fn levenshtein_str(a: &str, b: &str) -> u32 {
+    levenshtein(a.as_bytes(), b.as_bytes())
+}
+
+/// Returns the Levenshtein distance between two UTF-16 strings, counting edits between 16-bit
+/// code units.
+///
+/// This is useful for comparing the UTF-16 strings that are produced by Windows APIs or Java
+/// without transcoding them first. Each code unit counts as a character, so a character outside
+/// of the Basic Multilingual Plane, which is encoded as a surrogate pair, counts as two code units.
+/// Inserting or deleting such a character counts as two edits, and substituting it for another
+/// character counts as one or two edits.
+/// This uses the naive scalar algorithm, since the SIMD routines only support bytes.
+///
+/// # Arguments
+/// * `a` - first string (slice of code units)
+/// * `b` - second string (slice of code units)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let a: Vec<u16> = "café".encode_utf16().collect();
+/// let b: Vec<u16> = "cafe".encode_utf16().collect();
+///
+/// assert!(levenshtein_u16(&a, &b) == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_u16(a: &[u16], b: &[u16]) -> u32 {
+    levenshtein_naive(a, b)
+}
+
+/// Returns the Levenshtein distance between two strings using the scalar implementation.
+///
+/// This has the same signature and output as `levenshtein`, but it never uses SIMD, so it is
+/// always available regardless of the target features or crate feature flags that are enabled.
+/// This is useful for reproducible benchmarks or for checking SIMD results against.
+/// The shorter string is used as the inner dimension of the dynamic programming matrix, and only
+/// a single row is kept, so this uses `O(min(a.len(), b.len()))` memory, even for very long
+/// strings. This makes it suitable for `no_std` targets and other environments without SIMD.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let dist = levenshtein_scalar(b"abc", b"ab");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_scalar(a: &[u8], b: &[u8]) -> u32 {
+    // swap so that b is the shorter string, which is the length of the row
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+    let b_len = b.len();
+    let mut row: Vec<u32> = (0..=b_len as u32).collect();
+
+    for (i, &a_c) in a.iter().enumerate() {
+        // the cell that is diagonal to the current cell, from the previous row
+        let mut diag = row[0];
+        row[0] = (i + 1) as u32;
+
+        for j in 1..=b_len {
+            let prev = row[j];
+            row[j] = cmp::min(
+                diag + (a_c != b[j - 1]) as u32,
+                cmp::min(prev, row[j - 1]) + 1,
+            );
+            diag = prev;
+        }
+    }
+
+    row[b_len]
+}
+
+/// Returns the Levenshtein distance between two sequences of bytes that are produced by
+/// iterators, without collecting them first.
+///
+/// This is a scalar convenience for streaming sources, like bytes that are decoded on the fly.
+/// Both iterators are advanced together until one of them ends, so only the shorter sequence
+/// (and a prefix of the longer one with the same length) is buffered, along with one row of the
+/// dynamic programming matrix. The rest of the longer sequence is consumed one byte at a time.
+/// The memory used is O(min(`a.len()`, `b.len()`)).
+///
+/// # Arguments
+/// * `a` - first sequence (iterator of bytes)
+/// * `b` - second sequence (iterator of bytes)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_iter(b"kitten".iter().copied(), "sitting".bytes());
+///
+/// assert!(dist == 3);
+/// ```
+#[must_use]
+pub fn levenshtein_iter<I, J>(mut a: I, mut b: J) -> u32
+where
+    I: Iterator<Item = u8>,
+    J: Iterator<Item = u8>,
+{
+    let mut a_buf = Vec::new();
+    let mut b_buf = Vec::new();
+
+    // the sequence that ends first is the shorter one
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => {
+                a_buf.push(x);
+                b_buf.push(y);
+            }
+            (Some(x), None) => {
+                a_buf.push(x);
+                return levenshtein_iter_rows(&b_buf, a_buf.into_iter().chain(a));
+            }
+            (None, Some(y)) => {
+                b_buf.push(y);
+                return levenshtein_iter_rows(&a_buf, b_buf.into_iter().chain(b));
+            }
+            (None, None) => return levenshtein_iter_rows(&a_buf, b_buf.into_iter()),
+        }
+    }
+}
+
+fn levenshtein_iter_rows<I: Iterator<Item = u8>>(short: &[u8], long: I) -> u32 {
+    let mut row: Vec<u32> = (0..=short.len() as u32).collect();
+
+    for (i, c) in long.enumerate() {
+        let mut diag = row[0];
+        row[0] = (i as u32) + 1;
+
+        for j in 1..=short.len() {
+            let up = row[j];
+            row[j] = cmp::min(
+                diag + ((short[j - 1] != c) as u32),
+                cmp::min(up, row[j - 1]) + 1,
+            );
+            diag = up;
+        }
+    }
+
+    row[short.len()]
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, ignoring ASCII
+/// case.
+///
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// The strings are not copied: the case of each byte is folded inside the SIMD kernel.
+/// Internally, this will call the same underlying implementation as `levenshtein`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_ci(b"ABC", b"ab");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_ci(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein_simd_k_dispatch(
+        a,
+        b,
+        u32::MAX,
+        false,
+        LEVENSHTEIN_COSTS,
+        CaseFold::Ascii,
+        None,
+    )
+    .unwrap()
+    .0
+}
+
+/// Lowercase mapping for Latin-1 (ISO-8859-1) bytes, covering A-Z and À-Þ, except for the
+/// multiplication sign (×), which does not have a lowercase form.
+const LATIN1_LOWERCASE: [u8; 256] = latin1_lowercase_table();
+
+const fn latin1_lowercase_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let c = i as u8;
+        table[i] = match c {
+            b'A'..=b'Z' | 0xC0..=0xD6 | 0xD8..=0xDE => c + 0x20,
+            _ => c,
+        };
+        i += 1;
+    }
+
+    table
+}
+
+/// Returns the Levenshtein distance between two Latin-1 (ISO-8859-1) encoded strings using SIMD
+/// acceleration, ignoring case.
+///
+/// Both the ASCII letters A-Z and the Latin-1 letters À-Þ are treated as equal to their lowercase
+/// counterparts, by looking up each byte in a 256-entry lowercase table. All other bytes are
+/// compared as is. Note that the strings must be Latin-1 bytes, not UTF-8.
+/// Like `levenshtein_ci`, the case is folded inside the SIMD routine as the strings are loaded,
+/// so the strings are not copied.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// // "CAFÉ" and "café" in Latin-1
+/// let dist = levenshtein_latin1_ci(b"CAF\xC9", b"caf\xE9");
+///
+/// assert!(dist == 0);
+/// ```
+#[must_use]
+pub fn levenshtein_latin1_ci(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein_simd_k_dispatch(
+        a,
+        b,
+        u32::MAX,
+        false,
+        LEVENSHTEIN_COSTS,
+        CaseFold::Latin1,
+        None,
+    )
+    .unwrap()
+    .0
+}
+
+/// Returns the Levenshtein distance between two strings using SIMD acceleration, after
+/// normalizing their whitespace.
+///
+/// Every run of consecutive ASCII whitespace bytes (spaces, tabs, line feeds, form feeds, and
+/// carriage returns, like `u8::is_ascii_whitespace`) is collapsed into a single space, and
+/// leading and trailing whitespace is removed. This means that tabs and spaces are treated as
+/// equal, and strings that only contain whitespace are treated as empty strings.
+/// The strings are copied with their whitespace normalized before the comparison.
+/// Internally, this will call `levenshtein`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// assert!(levenshtein_normalized_ws(b"a  b", b"a b") == 0);
+/// assert!(levenshtein_normalized_ws(b" hello\tworld\n", b"hello world!") == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_normalized_ws(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein(&collapse_whitespace(a), &collapse_whitespace(b))
+}
+
+fn collapse_whitespace(s: &[u8]) -> Vec<u8> {
+    let mut res = Vec::with_capacity(s.len());
+
+    for word in s
+        .split(|c| c.is_ascii_whitespace())
+        .filter(|w| !w.is_empty())
+    {
+        if !res.is_empty() {
+            res.push(b' ');
+        }
+
+        res.extend_from_slice(word);
+    }
+
+    res
+}
+
+/// Returns the restricted Damerau-Levenshtein distance between two strings using SIMD acceleration.
+///
+/// Note that `rdamerau_exp` may be much faster if the number of edits between the two strings
+/// is expected to be small.
+/// Internally, this will call `levenshtein_simd_k_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let dist = rdamerau(b"abc", b"acb");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn rdamerau(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein_simd_k_with_opts(a, b, u32::MAX, false, RDAMERAU_COSTS)
+        .unwrap()
+        .0
+}
+
+/// Returns the Levenshtein distance between two strings using exponential search and SIMD
+/// acceleration.
+///
+/// This may be much more efficient than `levenshtein` if the number of edits between `a` and `b`
+/// is expected to be small.
+/// Internally, this will call `levenshtein_simd_k` with values of `k` determined through
+/// exponential search.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let dist = levenshtein_exp(b"abc", b"ab");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_exp(a: &[u8], b: &[u8]) -> u32 {
+    let mut k = 30;
+    // exponential search
+    loop {
+        if let Some(res) = levenshtein_simd_k(a, b, k) {
+            return res;
+        }
+        k *= 2;
+    }
+}
+
+/// Returns the Levenshtein distance between two strings, and optionally, the edit traceback,
+/// using exponential search and SIMD acceleration. Extra options can be specified.
+///
+/// This may be much more efficient than `levenshtein` if the number of edits between `a` and `b`
+/// is expected to be small.
+/// Internally, this will call `levenshtein_simd_k_with_opts` with values of `k` determined through
+/// exponential search.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `trace_on` - whether to return the traceback, the sequence of edits between `a` and `b`
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_exp_with_opts(b"abc", b"ab", true, LEVENSHTEIN_COSTS);
+///
+/// assert!(dist == (1, Some(vec![Edit{edit: EditType::Match, count: 2},
+///                               Edit{edit: EditType::BGap, count: 1}])));
+/// ```
+#[must_use]
+pub fn levenshtein_exp_with_opts(
+    a: &[u8],
+    b: &[u8],
+    trace_on: bool,
+    costs: EditCosts,
+) -> (u32, Option<Vec<Edit>>) {
+    let mut k = 30;
+    // exponential search
+    loop {
+        if let Some(res) = levenshtein_simd_k_with_opts(a, b, k, trace_on, costs) {
+            return res;
+        }
+        k *= 2;
+    }
+}
+
+/// Returns the restricted Damerau-Levenshtein distance between two strings using exponential
+/// search and SIMD acceleration.
+///
+/// This may be much more efficient than `rdamerau` if the number of edits between `a` and `b`
+/// is expected to be small.
+/// Internally, this will call `levenshtein_simd_k_with_opts` with values of `k` determined through
+/// exponential search.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let dist = rdamerau_exp(b"abc", b"acb");
+///
+/// assert!(dist == 1);
+/// ```
+#[must_use]
+pub fn rdamerau_exp(a: &[u8], b: &[u8]) -> u32 {
+    let mut k = 30;
+
+    // exponential search
+    loop {
+        if let Some(res) = levenshtein_simd_k_with_opts(a, b, k, false, RDAMERAU_COSTS) {
+            return res.0;
+        }
+        k *= 2;
+    }
+}
+
+/// Returns the Levenshtein distance between two strings, divided by the length of the longer
+/// string.
+///
+/// The result is in the range [0, 1], where 0 indicates that the strings are equal.
+/// If both `a` and `b` are empty, then 0 is returned.
+/// Internally, this will call `levenshtein_exp`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_normalized(b"abcd", b"abc");
+///
+/// assert!(dist == 0.25);
+/// ```
+#[must_use]
+pub fn levenshtein_normalized(a: &[u8], b: &[u8]) -> f64 {
+    let max_len = cmp::max(a.len(), b.len());
+
+    if max_len == 0 {
+        return 0.0;
+    }
+
+    (levenshtein_exp(a, b) as f64) / (max_len as f64)
+}
+
+/// Returns the Levenshtein similarity between two strings, which is one minus the normalized
+/// Levenshtein distance.
+///
+/// The result is in the range [0, 1], where 1 indicates that the strings are equal.
+/// If both `a` and `b` are empty, then 1 is returned.
+/// Internally, this will call `levenshtein_normalized`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let sim = levenshtein_similarity(b"abcd", b"abc");
+///
+/// assert!(sim == 0.75);
+/// ```
+#[must_use]
+pub fn levenshtein_similarity(a: &[u8], b: &[u8]) -> f64 {
+    1.0 - levenshtein_normalized(a, b)
+}
+
+/// Returns a weighted blend of the normalized Hamming and Levenshtein distances between two
+/// strings, for ranking candidates.
+///
+/// This is `w * hamming_normalized(a, b) + (1 - w) * levenshtein_normalized(a, b)`, so `w = 1`
+/// gives the cheap Hamming score and `w = 0` gives the precise Levenshtein score.
+/// The Hamming distance is only defined for strings of the same length. If the lengths of `a` and
+/// `b` differ, then the Hamming term is skipped and the normalized Levenshtein distance is
+/// returned, regardless of `w`.
+/// The result is in the range [0, 1], where 0 indicates that the strings are equal.
+/// If both `a` and `b` are empty, then 0 is returned.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `w` - weight of the Hamming term, in the range [0, 1]
+///
+/// # Panics
+/// * If `w` is not in the range [0, 1].
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// // Hamming distance of 4, but Levenshtein distance of 2
+/// let score = combined_score(b"abcd", b"bcda", 0.5);
+///
+/// assert!(score == 0.75);
+/// assert!(combined_score(b"abcd", b"abc", 0.5) == 0.25);
+/// ```
+#[must_use]
+pub fn combined_score(a: &[u8], b: &[u8], w: f64) -> f64 {
+    assert!((0.0..=1.0).contains(&w));
+
+    let lev = levenshtein_normalized(a, b);
+
+    if a.len() != b.len() {
+        return lev;
+    }
+
+    w * hamming::hamming_normalized(a, b) + (1.0 - w) * lev
+}
+
+/// Returns the Levenshtein distances between the string `a` and each of the strings in
+/// `candidates`, using exponential search and SIMD acceleration.
+///
+/// The returned distances are in the same order as `candidates`.
+/// Internally, this runs the same exponential search as `levenshtein_exp` for each candidate,
+/// reusing the Jewel buffers in a `Scratch` across candidates.
+/// If the `rayon` feature is enabled, then the candidates are split across multiple threads,
+/// and each thread keeps its own `Scratch`.
+/// Otherwise, the candidates are processed one by one on the current thread.
+///
+/// # Arguments
+/// * `a` - query string (slice)
+/// * `candidates` - strings to compare against `a` (slice of slices)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dists = levenshtein_batch(b"abc", &[b"ab", b"abc", b"xbcd"]);
+///
+/// assert!(dists == vec![1, 0, 2]);
+/// ```
+pub fn levenshtein_batch(a: &[u8], candidates: &[&[u8]]) -> Vec<u32> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        candidates
+            .par_iter()
+            .map_init(Scratch::new, |scratch, b| {
+                levenshtein_exp_with_scratch(a, b, scratch)
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut scratch = Scratch::new();
+        let mut res = Vec::with_capacity(candidates.len());
+
+        for b in candidates {
+            res.push(levenshtein_exp_with_scratch(a, b, &mut scratch));
+        }
+
+        res
+    }
+}
+
+fn levenshtein_exp_with_scratch(a: &[u8], b: &[u8], scratch: &mut Scratch) -> u32 {
+    let mut k = 30;
+    // exponential search
+    loop {
+        if let Some((res, _)) = levenshtein_simd_k_dispatch(
+            a,
+            b,
+            k,
+            false,
+            LEVENSHTEIN_COSTS,
+            CaseFold::Exact,
+            Some(&mut *scratch),
+        ) {
+            return res;
+        }
+        k *= 2;
+    }
+}
+
+/// Returns the index and the Levenshtein distance of the closest string to `query` in `dict`,
+/// using SIMD acceleration.
+///
+/// The candidates are processed one by one, and the distance of the best candidate so far is
+/// used as the threshold for the next candidate, so that worse candidates can be rejected early.
+/// Candidates whose length differs from the length of `query` by at least the current best
+/// distance are skipped without any computation, and the search stops at the first exact match.
+/// This is faster than calling `levenshtein_batch` and then taking the minimum.
+/// If there are multiple closest candidates, then the one with the lowest index is chosen.
+/// If `dict` is empty, then `None` is returned.
+/// Internally, this will call `levenshtein_exp` for the first candidate, and then
+/// `levenshtein_k`.
+///
+/// # Arguments
+/// * `query` - query string (slice)
+/// * `dict` - strings to compare against `query` (slice of slices)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let closest = nearest(b"helo", &[b"world", b"hello", b"help", b"halo"]);
+///
+/// assert!(closest == Some((1, 1)));
+/// ```
+#[must_use]
+pub fn nearest(query: &[u8], dict: &[&[u8]]) -> Option<(usize, u32)> {
+    let mut best: Option<(usize, u32)> = None;
+
+    for (i, b) in dict.iter().enumerate() {
+        let dist = match best {
+            Some((_, 0)) => break,
+            Some((_, best_dist)) => {
+                let len_diff = cmp::max(query.len(), b.len()) - cmp::min(query.len(), b.len());
+
+                if len_diff as u64 >= best_dist as u64 {
+                    continue;
+                }
+
+                match levenshtein_k(query, b, best_dist - 1) {
+                    Some(dist) => dist,
+                    None => continue,
+                }
+            }
+            None => levenshtein_exp(query, b),
+        };
+
+        best = Some((i, dist));
+    }
+
+    best
+}
+
+/// Returns the indexes and the Levenshtein distances of the `n` closest strings to `query` in
+/// `dict`, using SIMD acceleration.
+///
+/// The results are sorted in ascending order by distance, and then by index for candidates with
+/// the same distance, so this returns the first `n` results of sorting every distance.
+/// The `n` best candidates so far are kept in a bounded max-heap, and once it is full, the
+/// distance of the worst candidate in the heap is used as the threshold for the next candidate,
+/// so that worse candidates can be rejected early. Like with `nearest`, candidates whose length
+/// differs from the length of `query` by at least that distance are skipped.
+/// If `dict` has less than `n` strings, then every string is returned.
+/// Internally, this will call `levenshtein_exp` until the heap is full, and then `levenshtein_k`.
+///
+/// # Arguments
+/// * `query` - query string (slice)
+/// * `dict` - strings to compare against `query` (slice of slices)
+/// * `n` - maximum number of results to return
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let closest = top_k_nearest(b"helo", &[b"world", b"hello", b"help", b"halo", b"hell"], 3);
+///
+/// assert!(closest == vec![(1, 1), (2, 1), (3, 1)]);
+/// ```
+#[must_use]
+pub fn top_k_nearest(query: &[u8], dict: &[&[u8]], n: usize) -> Vec<(usize, u32)> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // the worst candidate has the highest distance, and then the highest index
+    let mut heap: BinaryHeap<(u32, usize)> = BinaryHeap::with_capacity(n + 1);
+
+    for (i, b) in dict.iter().enumerate() {
+        if heap.len() < n {
+            heap.push((levenshtein_exp(query, b), i));
+            continue;
+        }
+
+        let worst_dist = heap.peek().unwrap().0;
+
+        if worst_dist == 0 {
+            break;
+        }
+
+        let len_diff = cmp::max(query.len(), b.len()) - cmp::min(query.len(), b.len());
+
+        if len_diff as u64 >= worst_dist as u64 {
+            continue;
+        }
+
+        // a later candidate must be strictly closer to replace the worst candidate
+        if let Some(dist) = levenshtein_k(query, b, worst_dist - 1) {
+            heap.pop();
+            heap.push((dist, i));
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|(dist, i)| (i, dist))
+        .collect()
+}
+
+/// Returns the length of the longest common subsequence of two strings using SIMD acceleration.
+///
+/// This is computed from the edit distance where only insertions and deletions are allowed,
+/// which is equal to `a.len() + b.len() - 2 * lcs(a, b)`.
+/// Internally, this will call the same underlying implementation as `levenshtein`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let len = lcs(b"ABCBDAB", b"BDCAB");
+///
+/// assert!(len == 4);
+/// ```
+#[must_use]
+pub fn lcs(a: &[u8], b: &[u8]) -> usize {
+    let dist = levenshtein_simd_k_with_opts(a, b, u32::MAX, false, LCS_COSTS)
+        .unwrap()
+        .0 as usize;
+    (a.len() + b.len() - dist) >> 1
+}
+
+/// Returns the run-length encoded alignment of a longest common subsequence of two strings
+/// using SIMD acceleration.
+///
+/// The alignment only contains `Match`, `AGap`, and `BGap` edits. The `Match` edits are the
+/// characters of the longest common subsequence, so the total count of the `Match` edits is
+/// equal to `lcs(a, b)`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let edits = lcs_align(b"abc", b"abd");
+///
+/// assert!(edits == vec![Edit{edit: EditType::Match, count: 2},
+///                       Edit{edit: EditType::BGap, count: 1},
+///                       Edit{edit: EditType::AGap, count: 1}]);
+/// ```
+pub fn lcs_align(a: &[u8], b: &[u8]) -> Vec<Edit> {
+    levenshtein_simd_k_with_opts(a, b, u32::MAX, true, LCS_COSTS)
+        .unwrap()
+        .1
+        .unwrap()
+}
+
+/// Returns the CIGAR string for a run-length encoded traceback between `a` and `b`.
+///
+/// The traceback is treated as aligning `b` (the query) against `a` (the reference), so an
+/// `AGap` becomes an insertion (`I`) and a `BGap` becomes a deletion (`D`).
+/// Matches and mismatches are both written as `M`, unless `extended` is true, in which case
+/// they are written as `=` and `X`. Each transpose covers two characters that are written as
+/// `M`, or as `X` in the extended form. Adjacent edits that map to the same operation are merged
+/// and edits with a count of zero are skipped.
+///
+/// # Arguments
+/// * `edits` - run-length encoded traceback, like the one from `levenshtein_simd_k_with_opts`
+/// * `extended` - whether to distinguish matches (`=`) from mismatches (`X`)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let edits = vec![Edit{edit: EditType::Match, count: 3},
+///                  Edit{edit: EditType::Mismatch, count: 1},
+///                  Edit{edit: EditType::AGap, count: 2}];
+///
+/// assert!(edits_to_cigar(&edits, true) == "3=1X2I");
+/// assert!(edits_to_cigar(&edits, false) == "4M2I");
+/// ```
+pub fn edits_to_cigar(edits: &[Edit], extended: bool) -> String {
+    let mut res = String::new();
+    let mut curr_op = None;
+    let mut curr_count = 0usize;
+
+    for e in edits {
+        let (op, count) = match e.edit {
+            EditType::Match => (if extended { '=' } else { 'M' }, e.count),
+            EditType::Mismatch => (if extended { 'X' } else { 'M' }, e.count),
+            EditType::Transpose => (if extended { 'X' } else { 'M' }, e.count * 2),
+            EditType::AGap => ('I', e.count),
+            EditType::BGap => ('D', e.count),
+        };
+
+        if count == 0 {
+            continue;
+        }
+
+        if curr_op == Some(op) {
+            curr_count += count;
+        } else {
+            if let Some(prev_op) = curr_op {
+                write!(res, "{}{}", curr_count, prev_op).unwrap();
+            }
+
+            curr_op = Some(op);
+            curr_count = count;
+        }
+    }
+
+    if let Some(prev_op) = curr_op {
+        write!(res, "{}{}", curr_count, prev_op).unwrap();
+    }
+
+    res
+}
+
+/// Expands a run-length encoded traceback between `a` and `b` into single `EditOp`s, with the
+/// position of each edit in both strings.
+///
+/// There is one `EditOp` for each character that is matched, mismatched, or gapped, and one for
+/// each transpose.
+///
+/// # Arguments
+/// * `edits` - run-length encoded traceback, like the one from `levenshtein_simd_k_with_opts`
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let edits = vec![Edit{edit: EditType::Match, count: 1},
+///                  Edit{edit: EditType::BGap, count: 1},
+///                  Edit{edit: EditType::Mismatch, count: 1}];
+///
+/// assert!(edit_ops(&edits) == vec![EditOp{edit: EditType::Match, a_idx: 0, b_idx: 0},
+///                                  EditOp{edit: EditType::BGap, a_idx: 1, b_idx: 1},
+///                                  EditOp{edit: EditType::Mismatch, a_idx: 2, b_idx: 1}]);
+/// ```
+pub fn edit_ops(edits: &[Edit]) -> Vec<EditOp> {
+    let mut res = Vec::with_capacity(edits.iter().map(|e| e.count).sum());
+    let mut a_idx = 0;
+    let mut b_idx = 0;
+
+    for e in edits {
+        let (a_step, b_step) = match e.edit {
+            EditType::Match | EditType::Mismatch => (1, 1),
+            EditType::AGap => (0, 1),
+            EditType::BGap => (1, 0),
+            EditType::Transpose => (2, 2),
+        };
+
+        for _ in 0..e.count {
+            res.push(EditOp {
+                edit: e.edit,
+                a_idx,
+                b_idx,
+            });
+            a_idx += a_step;
+            b_idx += b_step;
+        }
+    }
+
+    res
+}
+
+/// Renders a run-length encoded traceback between `a` and `b` as two gap-padded sequences that
+/// are aligned column by column.
+///
+/// Both returned sequences have the same length. Each `AGap` is a gap in `a`, so it inserts the
+/// `gap` byte into the first sequence, and each `BGap` inserts it into the second sequence.
+/// Matches, mismatches, and transposes copy the bytes from both strings as is.
+/// Removing the `gap` bytes that were inserted recovers `a` and `b`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `edits` - run-length encoded traceback, like the one from `levenshtein_simd_k_with_opts`
+/// * `gap` - byte that is used to fill gaps
+///
+/// # Panics
+/// * If `edits` does not consume exactly all of `a` and `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let edits = vec![Edit{edit: EditType::Match, count: 1},
+///                  Edit{edit: EditType::BGap, count: 1},
+///                  Edit{edit: EditType::Mismatch, count: 1},
+///                  Edit{edit: EditType::AGap, count: 1}];
+/// let (a, b) = render_alignment(b"abc", b"adx", &edits, b'-');
+///
+/// assert!(a == b"abc-");
+/// assert!(b == b"a-dx");
+/// ```
+pub fn render_alignment(a: &[u8], b: &[u8], edits: &[Edit], gap: u8) -> (Vec<u8>, Vec<u8>) {
+    let len: usize = edits.iter().map(|e| e.count).sum();
+    let mut a_res = Vec::with_capacity(len);
+    let mut b_res = Vec::with_capacity(len);
+    let mut a_idx = 0;
+    let mut b_idx = 0;
+
+    for e in edits {
+        match e.edit {
+            EditType::Match | EditType::Mismatch => {
+                a_res.extend_from_slice(&a[a_idx..a_idx + e.count]);
+                b_res.extend_from_slice(&b[b_idx..b_idx + e.count]);
+                a_idx += e.count;
+                b_idx += e.count;
+            }
+            EditType::AGap => {
+                a_res.resize(a_res.len() + e.count, gap);
+                b_res.extend_from_slice(&b[b_idx..b_idx + e.count]);
+                b_idx += e.count;
+            }
+            EditType::BGap => {
+                a_res.extend_from_slice(&a[a_idx..a_idx + e.count]);
+                b_res.resize(b_res.len() + e.count, gap);
+                a_idx += e.count;
+            }
+            EditType::Transpose => {
+                a_res.extend_from_slice(&a[a_idx..a_idx + 2 * e.count]);
+                b_res.extend_from_slice(&b[b_idx..b_idx + 2 * e.count]);
+                a_idx += 2 * e.count;
+                b_idx += 2 * e.count;
+            }
+        }
+    }
+
+    assert!(a_idx == a.len() && b_idx == b.len());
+    (a_res, b_res)
+}
+
+/// Returns the Levenshtein distance between two strings and the position of every edit
+/// operation, using SIMD acceleration.
+///
+/// Internally, this will call `levenshtein_simd_k_with_opts` with the traceback enabled, and then
+/// `edit_ops`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let (dist, ops) = levenshtein_ops(b"abc", b"abd");
+///
+/// assert!(dist == 1);
+/// assert!(ops[2] == EditOp{edit: EditType::Mismatch, a_idx: 2, b_idx: 2});
+/// ```
+#[must_use]
+pub fn levenshtein_ops(a: &[u8], b: &[u8]) -> (u32, Vec<EditOp>) {
+    let (dist, edits) =
+        levenshtein_simd_k_with_opts(a, b, u32::MAX, true, LEVENSHTEIN_COSTS).unwrap();
+    (dist, edit_ops(&edits.unwrap()))
+}
+
+/// Subproblems with at most this many cells in the dynamic programming matrix are aligned
+/// directly with a full traceback instead of being split further.
+const HIRSCHBERG_BASE_CELLS: usize = 1 << 16;
+
+/// Returns the Levenshtein distance between two strings and the edit traceback, using
+/// Hirschberg's divide-and-conquer algorithm.
+///
+/// The traceback from `levenshtein_simd_k_with_opts` needs memory proportional to the product
+/// of the lengths of `a` and `b` (or the product of the length and the number of edits), which
+/// is not possible for very long strings with many edits. This only keeps two rows of the dynamic
+/// programming matrix, so it uses memory proportional to the length of the shorter string,
+/// at the cost of about twice the computation.
+/// The longer string is repeatedly split in half, and the split point in the shorter string is
+/// found by computing the last row of the matrix forwards for the first half and backwards for
+/// the second half. Small subproblems are aligned with `levenshtein_simd_k_with_opts`.
+/// The returned traceback is always optimal, but when there are multiple optimal alignments, it
+/// may not be the same one that `levenshtein_naive_with_opts` would return.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let (dist, edits) = levenshtein_align(b"abcd", b"abd");
+///
+/// assert!(dist == 1);
+/// assert!(edits == vec![Edit{edit: EditType::Match, count: 2},
+///                       Edit{edit: EditType::BGap, count: 1},
+///                       Edit{edit: EditType::Match, count: 1}]);
+/// ```
+#[must_use]
+pub fn levenshtein_align(a: &[u8], b: &[u8]) -> (u32, Vec<Edit>) {
+    let len = cmp::min(a.len(), b.len()) + 1;
+    let mut fwd = alloc::vec![0u32; len];
+    let mut rev = alloc::vec![0u32; len];
+    let mut res = Vec::new();
+
+    hirschberg(a, b, false, &mut fwd, &mut rev, &mut res);
+
+    let dist = res
+        .iter()
+        .filter(|e| e.edit != EditType::Match)
+        .map(|e| e.count as u32)
+        .sum();
+    (dist, res)
+}
+
+fn hirschberg(
+    a: &[u8],
+    b: &[u8],
+    swap: bool,
+    fwd: &mut [u32],
+    rev: &mut [u32],
+    res: &mut Vec<Edit>,
+) {
+    // always split the longer string, so the rows are only as long as the shorter string
+    if a.len() < b.len() {
+        hirschberg(b, a, !swap, fwd, rev, res);
+        return;
+    }
+
+    if b.is_empty() {
+        push_edit(res, EditType::BGap, a.len(), swap);
+        return;
+    }
+
+    if a.len() * b.len() <= HIRSCHBERG_BASE_CELLS {
+        let edits = levenshtein_simd_k_with_opts(a, b, u32::MAX, true, LEVENSHTEIN_COSTS)
+            .unwrap()
+            .1
+            .unwrap();
+
+        for e in edits {
+            push_edit(res, e.edit, e.count, swap);
+        }
+
+        return;
+    }
+
+    let mid = a.len() >> 1;
+    let fwd = &mut fwd[..b.len() + 1];
+    let rev = &mut rev[..b.len() + 1];
+    levenshtein_last_row(a[..mid].iter(), b.iter(), fwd);
+    levenshtein_last_row(a[mid..].iter().rev(), b.iter().rev(), rev);
+
+    let mut split = 0;
+
+    for j in 1..(b.len() + 1) {
+        if fwd[j] + rev[b.len() - j] < fwd[split] + rev[b.len() - split] {
+            split = j;
+        }
+    }
+
+    hirschberg(&a[..mid], &b[..split], swap, fwd, rev, res);
+    hirschberg(&a[mid..], &b[split..], swap, fwd, rev, res);
+}
+
+/// Fills `row` with the Levenshtein distances between all of `a` and each prefix of `b`.
+fn levenshtein_last_row<'a>(
+    a: impl Iterator<Item = &'a u8>,
+    b: impl Iterator<Item = &'a u8> + Clone,
+    row: &mut [u32],
+) {
+    for (j, r) in row.iter_mut().enumerate() {
+        *r = j as u32;
+    }
+
+    for (i, &a_c) in a.enumerate() {
+        let mut diag = row[0];
+        row[0] = (i as u32) + 1;
+
+        for (j, &b_c) in b.clone().enumerate() {
+            let sub = diag + ((a_c != b_c) as u32);
+            diag = row[j + 1];
+            row[j + 1] = cmp::min(sub, cmp::min(row[j + 1], row[j]) + 1);
+        }
+    }
+}
+
+fn push_edit(res: &mut Vec<Edit>, edit: EditType, count: usize, swap: bool) {
+    let edit = match (edit, swap) {
+        (EditType::AGap, true) => EditType::BGap,
+        (EditType::BGap, true) => EditType::AGap,
+        (e, _) => e,
+    };
+
+    if count == 0 {
+        return;
+    }
+
+    match res.last_mut() {
+        Some(last) if last.edit == edit => last.count += count,
+        _ => res.push(Edit { edit, count }),
+    }
+}
+
+/// Returns the minimum Levenshtein distance between `needle` and any prefix of `haystack`.
+///
+/// Unmatched characters at the end of `haystack` are not penalized, so this is useful for
+/// ranking autocomplete candidates. This is the minimum over the last row of the dynamic
+/// programming matrix, instead of the bottom-right cell.
+/// This uses a scalar algorithm that stops early once longer prefixes of `haystack` cannot
+/// lead to a lower distance.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_prefix(b"app", b"application");
+///
+/// assert!(dist == 0);
+/// ```
+#[must_use]
+pub fn levenshtein_prefix(needle: &[u8], haystack: &[u8]) -> u32 {
+    let needle_len = needle.len();
+    // column of the dp matrix for the current prefix of haystack
+    let mut col: Vec<u32> = (0..=needle_len as u32).collect();
+    let mut best = needle_len as u32;
+
+    for (j, &c) in haystack.iter().enumerate() {
+        // the distance for a prefix is at least the difference in lengths
+        if (j + 1) as u32 >= needle_len as u32 + best {
+            break;
+        }
+
+        let mut diag = col[0];
+        col[0] = (j + 1) as u32;
+
+        for i in 1..=needle_len {
+            let prev = col[i];
+            col[i] = cmp::min(
+                diag + (needle[i - 1] != c) as u32,
+                cmp::min(prev, col[i - 1]) + 1,
+            );
+            diag = prev;
+        }
+
+        best = cmp::min(best, col[needle_len]);
+    }
+
+    best
+}
+
+/// Returns the best `Match` of `needle` that starts at the beginning of `haystack`.
+///
+/// This is like an anchored search, but only one starting position is considered, so only the
+/// first `needle.len() + k` characters of `haystack` are ever examined. This is useful for
+/// fuzzy matching things like command prefixes.
+/// The best match is the one with the lowest Levenshtein distance that is less than or equal to
+/// `k`. If there are multiple best matches, then the longest one is chosen, like the default
+/// for search routines.
+/// If there are no matches within `k`, then `None` is returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let m = levenshtein_match_prefix(b"git", b"gti status", 1);
+///
+/// assert!(m == Some(Match{start: 0, end: 2, k: 1}));
+/// ```
+pub fn levenshtein_match_prefix(needle: &[u8], haystack: &[u8], k: u32) -> Option<Match> {
+    let needle_len = needle.len();
+    // column of the dp matrix for the current prefix of haystack
+    let mut col: Vec<u32> = (0..=needle_len as u32).collect();
+    let mut best = if col[needle_len] <= k {
+        Some(Match {
+            start: 0,
+            end: 0,
+            k: col[needle_len],
+        })
+    } else {
+        None
+    };
+
+    for (j, &c) in haystack.iter().enumerate() {
+        // the distance for a prefix is at least the difference in lengths
+        if (j + 1) as u64 > needle_len as u64 + k as u64 {
+            break;
+        }
+
+        let mut diag = col[0];
+        col[0] = (j + 1) as u32;
+
+        for i in 1..=needle_len {
+            let prev = col[i];
+            col[i] = cmp::min(
+                diag + (needle[i - 1] != c) as u32,
+                cmp::min(prev, col[i - 1]) + 1,
+            );
+            diag = prev;
+        }
+
+        let dist = col[needle_len];
+
+        let better = match &best {
+            Some(b) => dist <= b.k,
+            None => dist <= k,
+        };
+
+        if better {
+            best = Some(Match {
+                start: 0,
+                end: j + 1,
+                k: dist,
+            });
+        }
+    }
+
+    best
+}
+
+/// Returns the Levenshtein distance between two strings, where the cost of substituting each
+/// pair of bytes is looked up in a full substitution cost table.
+///
+/// The cost of substituting the byte `x` in `a` with the byte `y` in `b` is `cost[x][y]`, so the
+/// table does not have to be symmetric. Matching bytes are also looked up, so the diagonal of the
+/// table should usually be zero.
+/// This is useful for things like keyboard-distance matrices, where adjacent keys cost less.
+/// Each row of the dynamic programming matrix is computed in two passes: substitutions and gaps
+/// in `a` do not depend on each other within a row, so that pass can be vectorized by the
+/// compiler, while the substitution costs themselves are scalar lookups. Gaps in `b` are then
+/// resolved with a second scalar pass.
+/// This returns the same result as `levenshtein_matrix_naive`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `cost` - substitution cost table, indexed by the byte in `a` and then the byte in `b`
+/// * `gap_cost` - cost of inserting or deleting a single byte
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let mut cost = [[1u8; 256]; 256];
+/// for i in 0..256 {
+///     cost[i][i] = 0;
+/// }
+/// cost[b'a' as usize][b'b' as usize] = 0;
+///
+/// assert!(levenshtein_matrix(b"abc", b"bbc", &cost, 1) == 0);
+/// assert!(levenshtein_matrix(b"bbc", b"abc", &cost, 1) == 1);
+/// ```
+#[must_use]
+pub fn levenshtein_matrix(a: &[u8], b: &[u8], cost: &[[u8; 256]; 256], gap_cost: u8) -> u32 {
+    let gap_cost = gap_cost as u32;
+    let b_len = b.len();
+    let mut prev: Vec<u32> = (0..=b_len as u32).map(|j| j * gap_cost).collect();
+    let mut curr = alloc::vec![0u32; b_len + 1];
+
+    for (i, &a_c) in a.iter().enumerate() {
+        let row = &cost[a_c as usize];
+
+        // substitutions and gaps in a only depend on the previous row
+        curr[0] = (i + 1) as u32 * gap_cost;
+
+        for j in 1..=b_len {
+            curr[j] = cmp::min(
+                prev[j - 1] + row[b[j - 1] as usize] as u32,
+                prev[j] + gap_cost,
+            );
+        }
+
+        // gaps in b depend on the previous cell in the same row
+        for j in 1..=b_len {
+            curr[j] = cmp::min(curr[j], curr[j - 1] + gap_cost);
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_len]
+}
+
+/// Returns the Levenshtein distance between two strings, where the cost of substituting each
+/// pair of bytes is looked up in a full substitution cost table, using the naive scalar algorithm.
+///
+/// This is the reference implementation for `levenshtein_matrix`.
+/// The cost of substituting the byte `x` in `a` with the byte `y` in `b` is `cost[x][y]`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `cost` - substitution cost table, indexed by the byte in `a` and then the byte in `b`
+/// * `gap_cost` - cost of inserting or deleting a single byte
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let mut cost = [[1u8; 256]; 256];
+/// for i in 0..256 {
+///     cost[i][i] = 0;
+/// }
+/// cost[b'a' as usize][b'b' as usize] = 0;
+///
+/// assert!(levenshtein_matrix_naive(b"abc", b"bbc", &cost, 1) == 0);
+/// ```
+#[must_use]
+pub fn levenshtein_matrix_naive(a: &[u8], b: &[u8], cost: &[[u8; 256]; 256], gap_cost: u8) -> u32 {
+    let gap_cost = gap_cost as u32;
+    let b_len = b.len();
+    let mut row: Vec<u32> = (0..=b_len as u32).map(|j| j * gap_cost).collect();
+
+    for (i, &a_c) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = (i + 1) as u32 * gap_cost;
+
+        for j in 1..=b_len {
+            let prev = row[j];
+            row[j] = cmp::min(
+                diag + cost[a_c as usize][b[j - 1] as usize] as u32,
+                cmp::min(prev, row[j - 1]) + gap_cost,
+            );
+            diag = prev;
+        }
+    }
+
+    row[b_len]
+}
+
+/// Returns the optimal string alignment (OSA) distance between two strings.
+///
+/// This is the restricted Damerau-Levenshtein distance: mismatches, gaps, and swaps of two
+/// adjacent characters all cost 1, but no substring can be edited more than once. For example,
+/// "CA" to "ABC" costs 3, because the swapped "AC" cannot have "B" inserted between it
+/// afterwards. Use `damerau_levenshtein` for the unrestricted distance, where that is allowed.
+/// This uses the exact scalar algorithm, which always calculates the minimum cost over all edits.
+/// Internally, this will call `levenshtein_naive_with_opts` with `RDAMERAU_COSTS`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// assert!(osa_distance(b"abc", b"acb") == 1);
+/// assert!(osa_distance(b"CA", b"ABC") == 3);
+/// ```
+#[must_use]
+pub fn osa_distance(a: &[u8], b: &[u8]) -> u32 {
+    levenshtein_naive_with_opts(a, b, false, RDAMERAU_COSTS).0
+}
+
+/// Returns the unrestricted Damerau-Levenshtein distance between two strings.
+///
+/// Mismatches, gaps, and swaps of two adjacent characters all cost 1. Unlike `osa_distance`,
+/// characters can still be inserted or deleted between two swapped characters, so this is a
+/// true metric. For example, "CA" to "ABC" costs 2 (swap "CA" to "AC", then insert "B"), while
+/// the optimal string alignment distance is 3.
+/// This uses a scalar algorithm that keeps track of the last row each byte was seen in, so it
+/// takes O(len(a) * len(b)) time and space.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// assert!(damerau_levenshtein(b"abc", b"acb") == 1);
+/// assert!(damerau_levenshtein(b"CA", b"ABC") == 2);
+/// ```
+#[must_use]
+pub fn damerau_levenshtein(a: &[u8], b: &[u8]) -> u32 {
+    damerau_weighted(a, b, RDAMERAU_COSTS)
+}
+
+/// Returns the unrestricted Damerau-Levenshtein distance between two strings, with custom costs
+/// for each edit operation.
+///
+/// This is `damerau_levenshtein` with the mismatch, gap, and transpose costs from `costs`, which
+/// is useful for things like keyboard typo models, where swapped characters are common.
+/// The cost of a transpose that has characters inserted or deleted between the swapped
+/// characters is the transpose cost plus the gap cost of each of those characters.
+/// If `costs` does not allow transposes, then this is the weighted Levenshtein distance.
+/// This uses a scalar algorithm that keeps track of the last row each byte was seen in, so it
+/// takes O(len(a) * len(b)) time and space.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `costs` - `EditCosts` struct for the cost of each edit operation, with linear gap costs
+///
+/// # Panics
+/// * If the `start_gap_cost` of `costs` is not zero.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let costs = EditCosts::new(2, 2, 0, Some(3));
+///
+/// assert!(damerau_weighted(b"abc", b"acb", costs) == 3);
+/// assert!(damerau_weighted(b"CA", b"ABC", costs) == 5);
+/// ```
+#[must_use]
+pub fn damerau_weighted(a: &[u8], b: &[u8], costs: EditCosts) -> u32 {
+    assert!(costs.start_gap_cost == 0);
+
+    let a_len = a.len();
+    let b_len = b.len();
+    let mismatch_cost = costs.mismatch_cost as u32;
+    let gap_cost = costs.gap_cost as u32;
+    // every alignment costs at most this much, since all characters can be gapped
+    let max_dist = ((a_len + b_len) as u32) * gap_cost;
+    let width = b_len + 2;
+    // dp matrix with an extra border row and column of max_dist
+    let mut dp = alloc::vec![0u32; (a_len + 2) * width];
+    // last row where each byte was seen in a
+    let mut last_row = [0usize; 256];
+
+    dp[0] = max_dist;
+
+    for i in 0..=a_len {
+        dp[(i + 1) * width] = max_dist;
+        dp[(i + 1) * width + 1] = (i as u32) * gap_cost;
+    }
+
+    for j in 0..=b_len {
+        dp[j + 1] = max_dist;
+        dp[width + j + 1] = (j as u32) * gap_cost;
+    }
+
+    for i in 1..=a_len {
+        // last column where a[i - 1] matched in b
+        let mut last_col = 0usize;
+
+        for j in 1..=b_len {
+            let k = last_row[b[j - 1] as usize];
+            let l = last_col;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_col = j;
+                0
+            } else {
+                mismatch_cost
+            };
+
+            let sub = dp[i * width + j] + cost;
+            let a_gap = dp[(i + 1) * width + j] + gap_cost;
+            let b_gap = dp[i * width + j + 1] + gap_cost;
+            let mut res = cmp::min(sub, cmp::min(a_gap, b_gap));
+
+            if let Some(transpose_cost) = costs.transpose_cost {
+                // swap the characters, while allowing gaps between them
+                let transpose = dp[k * width + l]
+                    + ((i - k - 1) + (j - l - 1)) as u32 * gap_cost
+                    + transpose_cost as u32;
+                res = cmp::min(res, transpose);
+            }
+
+            dp[(i + 1) * width + j + 1] = res;
+        }
+
+        last_row[a[i - 1] as usize] = i;
+    }
+
+    dp[(a_len + 1) * width + b_len + 1]
+}
+
+/// Returns the Levenshtein distance between two strings, where bytes are compared using a custom
+/// equality function.
+///
+/// This is useful for treating certain classes of bytes as equal, like all digits.
+/// Since the equality function can be arbitrary, this cannot be SIMD-accelerated, so it uses a
+/// scalar algorithm and is slower than `levenshtein`. The function is called with a byte from `a`
+/// and then a byte from `b`.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `eq` - function that returns whether a byte from `a` matches a byte from `b`
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let dist = levenshtein_by(b"a1b", b"a7b", |x, y| x == y || (x.is_ascii_digit() && y.is_ascii_digit()));
+///
+/// assert!(dist == 0);
+/// ```
+pub fn levenshtein_by(a: &[u8], b: &[u8], eq: impl Fn(u8, u8) -> bool) -> u32 {
+    let b_len = b.len();
+    let mut row: Vec<u32> = (0..=b_len as u32).collect();
+
+    for (i, &a_c) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = (i + 1) as u32;
+
+        for j in 1..=b_len {
+            let prev = row[j];
+            row[j] = cmp::min(
+                diag + (!eq(a_c, b[j - 1])) as u32,
+                cmp::min(prev, row[j - 1]) + 1,
+            );
+            diag = prev;
+        }
+    }
+
+    row[b_len]
+}
+
+/// Returns the best local alignment score between two strings, along with the aligned spans
+/// in `a` and in `b`, using the Smith-Waterman algorithm.
+///
+/// Unlike the Levenshtein distance routines, which compare the whole strings, this finds the pair
+/// of substrings with the highest alignment score. Each matching byte adds `match_bonus` to the
+/// score, while each mismatch subtracts `mismatch_penalty` and each gap subtracts `gap_penalty`.
+/// Scores that drop below zero are clamped to zero, which starts a new alignment, so dissimilar
+/// regions around a conserved region do not lower its score.
+/// The returned tuple contains the score, the `Match` that spans the aligned region of `a`, and
+/// the `Match` that spans the aligned region of `b`. The `k` field of both `Match`es is the
+/// number of mismatches and gaps in the alignment.
+/// If multiple alignments have the best score, then the alignment that ends first along the
+/// anti-diagonals of the dynamic programming matrix (the smallest sum of the end indexes) is
+/// chosen, and then the one that ends first in `a`. Among alignments that end at the same cell,
+/// the shortest one is chosen. If no bytes match, then the score is zero and both spans are
+/// empty at index 0.
+/// This is the scalar reference implementation for `local_align`, and it returns exactly the same
+/// results.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `match_bonus` - score added for each matching byte
+/// * `mismatch_penalty` - score subtracted for each mismatched byte
+/// * `gap_penalty` - score subtracted for each gap
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let (score, a_span, b_span) = local_align_naive(b"xxxxabcdeyyy", b"zzabcxezz", 2, 1, 1);
+///
+/// assert!(score == 7);
+/// assert!(a_span == Match{start: 4, end: 9, k: 1});
+/// assert!(b_span == Match{start: 2, end: 7, k: 1});
+/// ```
+pub fn local_align_naive(
+    a: &[u8],
+    b: &[u8],
+    match_bonus: u8,
+    mismatch_penalty: u8,
+    gap_penalty: u8,
+) -> (i32, Match, Match) {
+    // each cell holds (score, length in a, length in b, number of edits)
+    let mut row = alloc::vec![(0u32, 0usize, 0usize, 0u32); b.len() + 1];
+    let mut best = (0u32, 0usize, 0usize, (0usize, 0usize, 0u32));
+
+    for i in 1..=a.len() {
+        let mut diag = row[0];
+
+        for j in 1..=b.len() {
+            let up = row[j];
+            let left = row[j - 1];
+            let is_match = a[i - 1] == b[j - 1];
+            let sub = if is_match {
+                diag.0 + match_bonus as u32
+            } else {
+                diag.0.saturating_sub(mismatch_penalty as u32)
+            };
+            let up_score = up.0.saturating_sub(gap_penalty as u32);
+            let left_score = left.0.saturating_sub(gap_penalty as u32);
+            let score = cmp::max(sub, cmp::max(up_score, left_score));
+
+            // an alignment with a score of zero is empty, and ties prefer the diagonal
+            let cell = if score == 0 {
+                (0, 0, 0, 0)
+            } else if score == sub {
+                (score, diag.1 + 1, diag.2 + 1, diag.3 + (!is_match) as u32)
+            } else if score == up_score {
+                (score, up.1 + 1, up.2, up.3 + 1)
+            } else {
+                (score, left.1, left.2 + 1, left.3 + 1)
+            };
+
+            if score > best.0
+                || (score == best.0 && score > 0 && (i + j, i) < (best.1 + best.2, best.1))
+            {
+                best = (score, i, j, (cell.1, cell.2, cell.3));
+            }
+
+            diag = up;
+            row[j] = cell;
+        }
+    }
+
+    let (score, i, j, (a_len, b_len, edits)) = best;
+    (
+        score as i32,
+        Match {
+            start: i - a_len,
+            end: i,
+            k: edits,
+        },
+        Match {
+            start: j - b_len,
+            end: j,
+            k: edits,
+        },
+    )
+}
+
+/// Returns the best local alignment score between two strings, along with the aligned spans
+/// in `a` and in `b`, using the Smith-Waterman algorithm.
+///
+/// Unlike the Levenshtein distance routines, which compare the whole strings, this finds the pair
+/// of substrings with the highest alignment score. Each matching byte adds `match_bonus` to the
+/// score, while each mismatch subtracts `mismatch_penalty` and each gap subtracts `gap_penalty`.
+/// Scores that drop below zero are clamped to zero, which starts a new alignment, so dissimilar
+/// regions around a conserved region do not lower its score.
+/// The returned tuple contains the score, the `Match` that spans the aligned region of `a`, and
+/// the `Match` that spans the aligned region of `b`. The `k` field of both `Match`es is the
+/// number of mismatches and gaps in the alignment.
+/// Ties are broken in the same way as `local_align_naive`.
+/// This uses the same anti-diagonal Jewel vectors as the Levenshtein search routines, with `a`
+/// laid out along each vector, but the minimums are replaced with maximums. The vector lanes are
+/// unsigned, so subtracting the penalties with saturation clamps the scores at zero for free.
+/// The length of each alignment in `a` and `b` is tracked alongside its score, so the spans are
+/// known without a traceback.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `a` - first string (slice)
+/// * `b` - second string (slice)
+/// * `match_bonus` - score added for each matching byte
+/// * `mismatch_penalty` - score subtracted for each mismatched byte
+/// * `gap_penalty` - score subtracted for each gap
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let (score, a_span, b_span) = local_align(b"xxxxabcdeyyy", b"zzabcxezz", 2, 1, 1);
+///
+/// assert!(score == 7);
+/// assert!(a_span == Match{start: 4, end: 9, k: 1});
+/// assert!(b_span == Match{start: 2, end: 7, k: 1});
+/// ```
+pub fn local_align(
+    a: &[u8],
+    b: &[u8],
+    match_bonus: u8,
+    mismatch_penalty: u8,
+    gap_penalty: u8,
+) -> (i32, Match, Match) {
+    if a.is_empty() || b.is_empty() {
+        return local_align_naive(a, b, match_bonus, mismatch_penalty, gap_penalty);
+    }
+
+    #[cfg(all(
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "wasm32", target_feature = "simd128")
+        ),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        // the scores, lengths, and number of edits must all fit in the lanes without saturating
+        let upper_bound = cmp::max(
+            (cmp::min(a.len(), b.len()) as u64) * (match_bonus as u64),
+            (a.len() + b.len()) as u64,
+        );
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if cfg!(feature = "jewel-avx512")
+                && cfg!(feature = "jewel-8bit")
+                && upper_bound < u8::MAX as u64
+                && avx512_supported()
+            {
+                return unsafe {
+                    local_align_simd_core_avx512_nx64x8(
+                        a,
+                        b,
+                        match_bonus,
+                        mismatch_penalty,
+                        gap_penalty,
+                    )
+                };
+            }
+
+            if cfg!(feature = "jewel-avx") && avx2_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Avx1x32x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_avx_1x32x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Avx2x32x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_avx_2x32x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Avx4x32x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_avx_4x32x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Avx8x32x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_avx_8x32x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && upper_bound < u16::MAX as u64 {
+                    return unsafe {
+                        local_align_simd_core_avx_nx16x16(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") && upper_bound < u32::MAX as u64 {
+                    return unsafe {
+                        local_align_simd_core_avx_nx8x32(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                }
+            } else if cfg!(feature = "jewel-sse") && sse41_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Sse1x16x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_sse_1x16x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Sse2x16x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_sse_2x16x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Sse4x16x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_sse_4x16x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Sse8x16x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_sse_8x16x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && a.len() <= Sse16x16x8::static_upper_bound()
+                    && upper_bound < u8::MAX as u64
+                {
+                    return unsafe {
+                        local_align_simd_core_sse_16x16x8(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && upper_bound < u16::MAX as u64 {
+                    return unsafe {
+                        local_align_simd_core_sse_nx8x16(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") && upper_bound < u32::MAX as u64 {
+                    return unsafe {
+                        local_align_simd_core_sse_nx4x32(
+                            a,
+                            b,
+                            match_bonus,
+                            mismatch_penalty,
+                            gap_penalty,
+                        )
+                    };
+                }
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        {
+            if cfg!(feature = "jewel-wasm")
+                && cfg!(feature = "jewel-8bit")
+                && upper_bound < u8::MAX as u64
+            {
+                return unsafe {
+                    local_align_simd_core_wasm_nx16x8(
+                        a,
+                        b,
+                        match_bonus,
+                        mismatch_penalty,
+                        gap_penalty,
+                    )
+                };
+            }
+        }
+    }
+
+    local_align_naive(a, b, match_bonus, mismatch_penalty, gap_penalty)
+}
+
+macro_rules! create_local_align_simd_core {
+    ($name:ident, $jewel:ty, $target:literal) => {
+        #[cfg(not(feature = "pure-scalar"))]
+        #[target_feature(enable = $target)]
+        unsafe fn $name(
+            a: &[u8],
+            b: &[u8],
+            match_bonus: u8,
+            mismatch_penalty: u8,
+            gap_penalty: u8,
+        ) -> (i32, Match, Match) {
+            #[cfg(feature = "debug")]
+            {
+                std::println!(
+                    "Debug: Local alignment Jewel vector type {} for target {}.",
+                    stringify!($jewel),
+                    stringify!($target)
+                );
+            }
+
+            let a_len = a.len();
+            let b_len = b.len();
+
+            // scores, lengths in a and b, and number of edits for the current anti-diagonal
+            // (0) and the previous two anti-diagonals (1 and 2)
+            let mut dp0 = <$jewel>::repeating(0, a_len);
+            let mut dp1 = <$jewel>::repeating(0, a_len);
+            let mut dp2 = <$jewel>::repeating(0, a_len);
+            let mut a_length0 = <$jewel>::repeating(0, a_len);
+            let mut a_length1 = <$jewel>::repeating(0, a_len);
+            let mut a_length2 = <$jewel>::repeating(0, a_len);
+            let mut b_length0 = <$jewel>::repeating(0, a_len);
+            let mut b_length1 = <$jewel>::repeating(0, a_len);
+            let mut b_length2 = <$jewel>::repeating(0, a_len);
+            let mut edits0 = <$jewel>::repeating(0, a_len);
+            let mut edits1 = <$jewel>::repeating(0, a_len);
+            let mut edits2 = <$jewel>::repeating(0, a_len);
+
+            // used in calculations
+            let mut match_mask = <$jewel>::repeating(0, a_len);
+            let mut sub = <$jewel>::repeating(0, a_len);
+            let mut up = <$jewel>::repeating(0, a_len);
+            let mut left = <$jewel>::repeating(0, a_len);
+            let mut mask = <$jewel>::repeating(0, a_len);
+            let mut temp = <$jewel>::repeating(0, a_len);
+
+            let zeros = <$jewel>::repeating(0, a_len);
+            let ones = <$jewel>::repeating(1, a_len);
+            let match_bonus_vec = <$jewel>::repeating(match_bonus as u32, a_len);
+            let mismatch_penalty_vec = <$jewel>::repeating(mismatch_penalty as u32, a_len);
+            let gap_penalty_vec = <$jewel>::repeating(gap_penalty as u32, a_len);
+
+            // load a into a_window in reversed order, so the last lane is the first row
+            let last = dp0.upper_bound() - 1;
+            let mut a_window = <$jewel>::repeating(0, a_len);
+            a_window.slow_loadu(last, a.as_ptr(), a_len, true);
+            let mut b_window = <$jewel>::repeating(0, a_len);
+
+            // lanes that are rows of the dp matrix, so the padding is always zero
+            let mut row_mask = <$jewel>::repeating(0, a_len);
+
+            for i in 0..a_len {
+                row_mask.slow_insert(last - i, u32::MAX);
+            }
+
+            // lanes that are inside the dp matrix for the current anti-diagonal
+            let mut valid = <$jewel>::repeating(0, a_len);
+
+            let mut best = 0u32;
+            let mut best_res = (0usize, 0usize, 0u32, 0u32, 0u32);
+
+            //       --b--
+            // |     /////
+            // a    /////
+            // |   /////
+            //
+            // each anti-diagonal is calculated simultaneously using Jewel vectors, like the
+            // Levenshtein search routines
+            // the lane at index last - i holds row i + 1, so the cells on anti-diagonal d + 1
+            // are (i + 1, d - i)
+            // the first row and column are all zeros, which are shifted in or masked out
+            // dp1 holds the previous anti-diagonal, which has the up and left cells, and dp2
+            // holds the anti-diagonal before that, which has the diagonal cells
+
+            for d in 1..(a_len + b_len) {
+                b_window.shift_left_1_mut();
+                valid.shift_left_1_mut();
+
+                if d <= b_len {
+                    b_window.insert_last_0(*b.get_unchecked(d - 1) as u32);
+                    valid.insert_last_max();
+                }
+
+                valid.and_mut(&row_mask);
+
+                <$jewel>::cmpeq(&a_window, &b_window, &mut match_mask);
+
+                // match/mismatch
+                <$jewel>::shift_left_1(&dp2, &mut sub);
+                <$jewel>::min(&match_mask, &match_bonus_vec, &mut temp);
+                sub.adds_mut(&temp);
+                <$jewel>::andnot(&match_mask, &mismatch_penalty_vec, &mut temp);
+                sub.subs_mut(&temp);
+
+                // gap in b
+                <$jewel>::shift_left_1(&dp1, &mut up);
+                up.subs_mut(&gap_penalty_vec);
+
+                // gap in a
+                <$jewel>::subs(&dp1, &gap_penalty_vec, &mut left);
+
+                <$jewel>::max(&sub, &up, &mut dp0);
+                dp0.max_mut(&left);
+                dp0.and_mut(&valid);
+
+                // start with the lengths for a gap in a, then override with the lengths for a
+                // gap in b and then a match/mismatch, so ties prefer the diagonal
+                <$jewel>::adds(&a_length1, &zeros, &mut a_length0);
+                <$jewel>::add(&b_length1, &ones, &mut b_length0);
+                <$jewel>::add(&edits1, &ones, &mut edits0);
+
+                <$jewel>::cmpeq(&dp0, &up, &mut mask);
+                <$jewel>::shift_left_1(&a_length1, &mut temp);
+                temp.add_mut(&ones);
+                a_length0.blendv_mut(&temp, &mask);
+                <$jewel>::shift_left_1(&b_length1, &mut temp);
+                b_length0.blendv_mut(&temp, &mask);
+                <$jewel>::shift_left_1(&edits1, &mut temp);
+                temp.add_mut(&ones);
+                edits0.blendv_mut(&temp, &mask);
+
+                <$jewel>::cmpeq(&dp0, &sub, &mut mask);
+                <$jewel>::shift_left_1(&a_length2, &mut temp);
+                temp.add_mut(&ones);
+                a_length0.blendv_mut(&temp, &mask);
+                <$jewel>::shift_left_1(&b_length2, &mut temp);
+                temp.add_mut(&ones);
+                b_length0.blendv_mut(&temp, &mask);
+                <$jewel>::shift_left_1(&edits2, &mut temp);
+                // reuse left for the mismatches
+                <$jewel>::andnot(&match_mask, &ones, &mut left);
+                temp.add_mut(&left);
+                edits0.blendv_mut(&temp, &mask);
+
+                // alignments with a score of zero are empty
+                <$jewel>::cmpeq(&dp0, &zeros, &mut mask);
+                a_length0.blendv_mut(&zeros, &mask);
+                b_length0.blendv_mut(&zeros, &mask);
+                edits0.blendv_mut(&zeros, &mask);
+
+                let curr_max = dp0.horizontal_max();
+
+                if curr_max > best {
+                    best = curr_max;
+
+                    // find the first row with the best score
+                    for i in 0..a_len {
+                        if dp0.slow_extract(last - i) == curr_max {
+                            best_res = (
+                                i + 1,
+                                d - i,
+                                a_length0.slow_extract(last - i),
+                                b_length0.slow_extract(last - i),
+                                edits0.slow_extract(last - i),
+                            );
+                            break;
+                        }
+                    }
+                }
+
+                mem::swap(&mut dp2, &mut dp1);
+                mem::swap(&mut dp1, &mut dp0);
+                mem::swap(&mut a_length2, &mut a_length1);
+                mem::swap(&mut a_length1, &mut a_length0);
+                mem::swap(&mut b_length2, &mut b_length1);
+                mem::swap(&mut b_length1, &mut b_length0);
+                mem::swap(&mut edits2, &mut edits1);
+                mem::swap(&mut edits1, &mut edits0);
+            }
+
+            let (i, j, a_length, b_length, edits) = best_res;
+            (
+                best as i32,
+                Match {
+                    start: i - a_length as usize,
+                    end: i,
+                    k: edits,
+                },
+                Match {
+                    start: j - b_length as usize,
+                    end: j,
+                    k: edits,
+                },
+            )
+        }
+    };
+}
+
+// create a version of the functions for each Jewel vector
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_1x32x8, Avx1x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_2x32x8, Avx2x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_4x32x8, Avx4x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_8x32x8, Avx8x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_nx16x16, AvxNx16x16, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_avx_nx8x32, AvxNx8x32, "avx2");
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(
+    local_align_simd_core_avx512_nx64x8,
+    Avx512Nx64x8,
+    "avx512bw"
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_1x16x8, Sse1x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_2x16x8, Sse2x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_4x16x8, Sse4x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_8x16x8, Sse8x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_16x16x8, Sse16x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_nx8x16, SseNx8x16, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_local_align_simd_core!(local_align_simd_core_sse_nx4x32, SseNx4x32, "sse4.1");
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+create_local_align_simd_core!(local_align_simd_core_wasm_nx16x8, WasmNx16x8, "simd128");
+
+/// Returns an iterator over the best `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using the naive algorithm.
+///
+/// The best matches are the matches with the lowest Levenshtein distance.
+/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_naive(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_naive<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_naive_with_opts(
+        needle,
+        haystack,
+        ((needle.len() as u32) >> 1) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using the naive algorithm, with extra options.
+///
+/// Note that overlapping matches may be returned.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty and `anchored` is false, then no `Match`es are returned.
+/// If `anchored` is false and `k` is at least the cost of deleting all of `needle`, then a match
+/// ends at every position in `haystack` (some may be empty), and `k` is clamped to that cost.
+/// The `needle` may be longer than the `haystack`: matches can still be found by deleting
+/// the extra characters of `needle`, as long as the cost stays within `k`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
+/// the lowest cost (additionally, only the longest matches are retained for matches that fully overlap)
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
+/// causing any shifts to cost gap edits
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_naive_with_opts(b"abc", b"  acb", 1, SearchType::All, RDAMERAU_COSTS, false).collect();
+///
+/// // note: it is possible to end the match at two different positions
+/// assert!(matches == vec![Match{start: 2, end: 4, k: 1}, Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_naive_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_naive_core(
+        needle,
+        haystack,
+        k,
+        SearchOpts {
+            search_type,
+            costs,
+            anchored,
+            case_insensitive: false,
+            length_pref: LengthPreference::Longest,
+        },
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using the naive algorithm, with extra options and a preference for the
+/// length of matches with equal costs.
+///
+/// This is the same as `levenshtein_search_naive_with_opts`, which always uses
+/// `LengthPreference::Longest`, except that shorter matches can be preferred instead.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
+/// the lowest cost
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
+/// causing any shifts to cost gap edits
+/// * `length_pref` - whether to prefer the longest or the shortest match when edit costs are equal
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_naive_with_length_pref(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Shortest).collect();
+///
+/// assert!(matches == vec![Match{start: 1, end: 3, k: 1}]);
+/// ```
+pub fn levenshtein_search_naive_with_length_pref<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+    length_pref: LengthPreference,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_naive_core(
+        needle,
+        haystack,
+        k,
+        SearchOpts {
+            search_type,
+            costs,
+            anchored,
+            case_insensitive: false,
+            length_pref,
+        },
+    )
+}
+
+/// The options of a Levenshtein search that are passed through the internal search routines,
+/// other than `k`, which may be clamped along the way.
+#[derive(Copy, Clone, Debug)]
+struct SearchOpts {
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+    case_insensitive: bool,
+    length_pref: LengthPreference,
+}
+
+/// Clamps `k` for searches that are not anchored to the cost of deleting the whole needle.
+///
+/// An empty match that deletes the whole needle is possible at every position in the haystack,
+/// so no match can cost more than that, and a larger `k` would only waste work (and possibly
+/// force wider SIMD lanes).
+fn clamp_search_k(needle_len: usize, k: u32, costs: EditCosts, anchored: bool) -> u32 {
+    if anchored {
+        return k;
+    }
+
+    let delete_cost = (needle_len as u32)
+        .saturating_mul(costs.gap_cost as u32)
+        .saturating_add(costs.start_gap_cost as u32);
+    cmp::min(k, delete_cost)
+}
+
+fn levenshtein_search_naive_core<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    opts: SearchOpts,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let SearchOpts {
+        search_type,
+        costs,
+        anchored,
+        case_insensitive,
+        length_pref,
+    } = opts;
+    let needle_len = needle.len();
+    let haystack_len = haystack.len();
+
+    if needle_len == 0 {
+        // special case when anchored is true: return possible matches
+        if anchored {
+            return match search_type {
+                SearchType::All => {
+                    let mut i = 0;
+                    let mut cost = costs.start_gap_cost as u32;
+                    let mut start = true;
+
+                    Box::new(iter::from_fn(move || {
+                        if start {
+                            start = false;
+                            return Some(Match {
+                                start: 0,
+                                end: 0,
+                                k: 0,
+                            });
+                        }
+
+                        if i < haystack_len {
+                            i += 1;
+                            cost += costs.gap_cost as u32;
+
+                            if cost <= k {
+                                return Some(Match {
+                                    start: 0,
+                                    end: i,
+                                    k: cost,
+                                });
+                            }
+                        }
+
+                        None
+                    }))
+                }
+                SearchType::Best => Box::new(iter::once(Match {
+                    start: 0,
+                    end: 0,
+                    k: 0,
+                })),
+            };
+        } else {
+            return Box::new(iter::empty());
+        }
+    }
+
+    // enforce another constraint on the costs
+    costs.check_search();
+    let k = clamp_search_k(needle_len, k, costs, anchored);
+
+    let len = needle_len + 1;
+    let iter_len = if anchored {
+        // start_gap_cost must be incurred at least once
+        cmp::min(
+            haystack_len,
+            needle_len.saturating_add(
+                (k.saturating_sub(costs.start_gap_cost as u32) as usize)
+                    / (costs.gap_cost as usize),
+            ),
+        )
+    } else {
+        haystack_len
+    };
+
+    let mut dp0 = alloc::vec![0u32; len];
+    let mut dp1 = alloc::vec![0u32; len];
+    let mut dp2 = alloc::vec![0u32; len];
+    let mut needle_gap_dp = alloc::vec![u32::MAX; len];
+    let mut haystack_gap_dp = alloc::vec![u32::MAX; len];
+    let mut length0 = alloc::vec![0usize; len];
+    let mut length1 = alloc::vec![0usize; len];
+    let mut length2 = alloc::vec![0usize; len];
+    let mut needle_gap_length = alloc::vec![0usize; len];
+    let mut haystack_gap_length = alloc::vec![0usize; len];
+    let mut curr_k = k;
+    let mismatch_cost = costs.mismatch_cost as u32;
+    let gap_cost = costs.gap_cost as u32;
+    let start_gap_cost = costs.start_gap_cost as u32;
+    let transpose_cost = match costs.transpose_cost {
+        Some(cost) => cost as u32,
+        None => 0,
+    };
+    let allow_transpose = costs.transpose_cost.is_some();
+    let fold = move |c: u8| {
+        if case_insensitive {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    };
+    // secondary objective for the length of a match if edit costs are equal
+    let prefer_length = move |new_length: usize, old_length: usize| match length_pref {
+        LengthPreference::Longest => new_length > old_length,
+        LengthPreference::Shortest => new_length < old_length,
+    };
+    let mut first = true;
+    let mut i = 0;
+
+    let res = iter::from_fn(move || {
+        if first {
+            first = false;
+
+            for j in 0..len {
+                dp1[j] = (j as u32) * gap_cost + if j == 0 { 0 } else { start_gap_cost };
+            }
+
+            if dp1[len - 1] <= curr_k {
+                if search_type == SearchType::Best {
+                    curr_k = dp1[len - 1];
+                }
+
+                return Some((
+                    Match {
+                        start: 0,
+                        end: 0,
+                        k: dp1[len - 1],
+                    },
+                    curr_k,
+                ));
+            }
+        }
+
+        while i < iter_len {
+            needle_gap_dp[0] = if anchored {
+                (i as u32 + 1) * gap_cost + start_gap_cost
+            } else {
+                0
+            };
+            dp2[0] = if anchored {
+                (i as u32 + 1) * gap_cost + start_gap_cost
+            } else {
+                0
+            };
+            needle_gap_length[0] = 0;
+            length2[0] = 0;
+
+            for j in 1..len {
+                let sub = dp1[j - 1]
+                    + ((fold(needle[j - 1]) != fold(haystack[i])) as u32) * mismatch_cost;
+
+                let new_gap = dp1[j] + start_gap_cost + gap_cost;
+                let cont_gap = needle_gap_dp[j].saturating_add(gap_cost);
+                if new_gap < cont_gap {
+                    needle_gap_dp[j] = new_gap;
+                    needle_gap_length[j] = length1[j] + 1;
+                } else if new_gap > cont_gap {
+                    needle_gap_dp[j] = cont_gap;
+                    needle_gap_length[j] += 1;
+                } else {
+                    needle_gap_dp[j] = cont_gap;
+                    needle_gap_length[j] = cmp::max(length1[j], needle_gap_length[j]) + 1;
+                }
+
+                let new_gap = dp2[j - 1] + start_gap_cost + gap_cost;
+                let cont_gap = haystack_gap_dp[j - 1].saturating_add(gap_cost);
+                if new_gap < cont_gap {
+                    haystack_gap_dp[j] = new_gap;
+                    haystack_gap_length[j] = length2[j - 1];
+                } else if new_gap > cont_gap {
+                    haystack_gap_dp[j] = cont_gap;
+                    haystack_gap_length[j] = haystack_gap_length[j - 1];
+                } else {
+                    haystack_gap_dp[j] = cont_gap;
+                    haystack_gap_length[j] = cmp::max(length2[j - 1], haystack_gap_length[j - 1]);
+                }
+
+                dp2[j] = needle_gap_dp[j];
+                length2[j] = needle_gap_length[j];
+
+                if (haystack_gap_dp[j] < dp2[j])
+                    || (haystack_gap_dp[j] == dp2[j] && prefer_length(length2[j - 1], length2[j]))
+                {
+                    dp2[j] = haystack_gap_dp[j];
+                    length2[j] = haystack_gap_length[j];
+                }
+
+                if (sub < dp2[j])
+                    || (sub == dp2[j] && prefer_length(length1[j - 1] + 1, length2[j]))
+                {
+                    dp2[j] = sub;
+                    length2[j] = length1[j - 1] + 1;
+                }
+
+                if allow_transpose
+                    && i > 0
+                    && j > 1
+                    && fold(needle[j - 1]) == fold(haystack[i - 1])
+                    && fold(needle[j - 2]) == fold(haystack[i])
+                {
+                    let transpose = dp0[j - 2] + transpose_cost;
+
+                    if transpose <= dp2[j] {
+                        dp2[j] = transpose;
+                        length2[j] = length0[j - 2] + 2;
+                    }
+                }
+            }
+
+            let final_res = dp2[len - 1];
+            let final_length = length2[len - 1];
+
+            mem::swap(&mut dp0, &mut dp1);
+            mem::swap(&mut dp1, &mut dp2);
+            mem::swap(&mut length0, &mut length1);
+            mem::swap(&mut length1, &mut length2);
+
+            i += 1;
+
+            if final_res <= curr_k {
+                match search_type {
+                    SearchType::Best => curr_k = final_res,
+                    _ => (),
+                }
+
+                return Some((
+                    Match {
+                        start: i - final_length,
+                        end: i,
+                        k: final_res,
+                    },
+                    curr_k,
+                ));
+            }
+        }
+
+        None
+    });
+
+    if search_type == SearchType::Best {
+        // only matches with the lowest k so far are kept, so the memory used does not grow
+        // with the length of the haystack
+        let mut res_vec: Vec<Match> = Vec::new();
+
+        for m in res {
+            if m.1 < curr_k {
+                res_vec.clear();
+            }
+
+            match res_vec.len() {
                 0 => res_vec.push(m.0),
                 _ => {
                     let last = res_vec.last_mut().unwrap();
 
-                    // replace previous if fully overlapping
-                    if m.0.start <= last.start {
-                        *last = m.0;
-                    } else {
-                        res_vec.push(m.0);
-                    }
-                }
-            }
+                    // replace previous if fully overlapping
+                    if m.0.start <= last.start {
+                        *last = m.0;
+                    } else {
+                        res_vec.push(m.0);
+                    }
+                }
+            }
+
+            curr_k = m.1;
+        }
+
+        return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
+    }
+
+    Box::new(res.map(|m| m.0))
+}
+
+/// Returns an iterator over the best `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration.
+///
+/// The best matches are the matches with the lowest Levenshtein distance.
+/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+/// This should be much faster than `levenshtein_search_naive`.
+/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
+/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
+/// `levenshtein_search_naive_with_opts`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_simd(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_simd<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        ((needle.len() >> 1) as u32) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration, with extra options.
+///
+/// Note that overlapping matches may be returned.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty and `anchored` is false, then no `Match`es are returned.
+/// If `anchored` is false and `k` is at least the cost of deleting all of `needle`, then a match
+/// ends at every position in `haystack` (some may be empty), and `k` is clamped to that cost.
+/// The `needle` may be longer than the `haystack`: matches can still be found by deleting
+/// the extra characters of `needle`, as long as the cost stays within `k`.
+/// This should be much faster than `levenshtein_search_naive_with_opts`.
+/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
+/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
+/// `levenshtein_search_naive_with_opts`.
+/// For needles of up to 32 bytes with `LEVENSHTEIN_COSTS`, `anchored` set to false, and a `k` of
+/// at most a quarter of the length of `needle`, the bit-parallel `bitap::bitap_search` is used
+/// instead, which is usually much faster and returns the same matches.
+/// The `haystack` is only read in place and never copied, so it can be a memory-mapped file.
+/// Apart from the returned matches, the extra memory used is O(`needle.len()`). With
+/// `SearchType::All`, the matches are lazily generated. With `SearchType::Best`, the
+/// haystack is searched before the first match is returned, and only the matches with the lowest
+/// cost so far are kept.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
+/// the lowest cost (additionally, only the longest matches are retained for matches that fully overlap)
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
+/// causing any shifts to cost gap edits
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_simd_with_opts(b"abc", b"  acb", 1, SearchType::All, RDAMERAU_COSTS, false).collect();
+///
+/// // note: it is possible to end the match at two different positions
+/// assert!(matches == vec![Match{start: 2, end: 4, k: 1}, Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_simd_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_simd_dispatch(
+        needle,
+        haystack,
+        k,
+        SearchOpts {
+            search_type,
+            costs,
+            anchored,
+            case_insensitive: false,
+            length_pref: LengthPreference::Longest,
+        },
+        None,
+    )
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration, with extra options and a preference for the
+/// length of matches with equal costs.
+///
+/// This is the same as `levenshtein_search_simd_with_opts`, which always uses
+/// `LengthPreference::Longest`, except that shorter matches can be preferred instead, for
+/// example, to prefer mismatches at the start of a match over gaps.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
+/// `levenshtein_search_naive_with_length_pref`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
+/// the lowest cost
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
+/// causing any shifts to cost gap edits
+/// * `length_pref` - whether to prefer the longest or the shortest match when edit costs are equal
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let longest: Vec<Match> = levenshtein_search_simd_with_length_pref(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Longest).collect();
+/// let shortest: Vec<Match> = levenshtein_search_simd_with_length_pref(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Shortest).collect();
+///
+/// assert!(longest == vec![Match{start: 0, end: 3, k: 1}]);
+/// assert!(shortest == vec![Match{start: 1, end: 3, k: 1}]);
+/// ```
+pub fn levenshtein_search_simd_with_length_pref<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+    length_pref: LengthPreference,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_simd_dispatch(
+        needle,
+        haystack,
+        k,
+        SearchOpts {
+            search_type,
+            costs,
+            anchored,
+            case_insensitive: false,
+            length_pref,
+        },
+        None,
+    )
+}
+
+/// Longest needle that is searched for with the bit-parallel algorithm, if there are few edits.
+const BITAP_SEARCH_MAX_NEEDLE_LEN: usize = 32;
+
+/// If `compiled` is not `None`, then it must be compiled from `needle`, and `case_insensitive`
+/// must be false, so its precomputed vectors and masks can be used instead of loading `needle`.
+fn levenshtein_search_simd_dispatch<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    opts: SearchOpts,
+    compiled: Option<&'a CompiledNeedle>,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let SearchOpts {
+        search_type,
+        costs,
+        anchored,
+        case_insensitive,
+        length_pref,
+    } = opts;
+    if needle.len() == 0 {
+        // special case when anchored is true: return possible matches
+        if anchored {
+            return match search_type {
+                SearchType::All => {
+                    let mut i = 0;
+                    let mut cost = costs.start_gap_cost as u32;
+                    let mut start = true;
+
+                    Box::new(iter::from_fn(move || {
+                        if start {
+                            start = false;
+                            return Some(Match {
+                                start: 0,
+                                end: 0,
+                                k: 0,
+                            });
+                        }
+
+                        if i < haystack.len() {
+                            i += 1;
+                            cost += costs.gap_cost as u32;
+
+                            if cost <= k {
+                                return Some(Match {
+                                    start: 0,
+                                    end: i,
+                                    k: cost,
+                                });
+                            }
+                        }
+
+                        None
+                    }))
+                }
+                SearchType::Best => Box::new(iter::once(Match {
+                    start: 0,
+                    end: 0,
+                    k: 0,
+                })),
+            };
+        } else {
+            return Box::new(iter::empty());
+        }
+    }
+
+    costs.check_search();
+    let k = clamp_search_k(needle.len(), k, costs, anchored);
+
+    // the bit-parallel algorithm is much faster for short needles and few edits; it slows down
+    // when almost every position matches, since the start of each match is found separately
+    if needle.len() <= BITAP_SEARCH_MAX_NEEDLE_LEN
+        && (k as usize) * 4 <= needle.len()
+        && costs.is_levenshtein()
+        && !anchored
+        && !case_insensitive
+        && length_pref == LengthPreference::Longest
+    {
+        return bitap::bitap_search_with_type(
+            needle,
+            haystack,
+            k,
+            search_type,
+            compiled.and_then(|c| c.bitap_masks.as_ref()),
+        );
+    }
+
+    #[cfg(all(
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "wasm32", target_feature = "simd128")
+        ),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        let unit_k = k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32);
+        // either the length of the match or the number of edits may exceed the maximum
+        // available int size; additionally, MAX value is used to indicate overflow
+        let upper_bound = cmp::max(
+            (needle.len() as u32).saturating_add(unit_k),
+            k.saturating_add(1),
+        );
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if cfg!(feature = "jewel-avx512")
+                && cfg!(feature = "jewel-8bit")
+                && upper_bound <= u8::MAX as u32
+                && avx512_supported()
+            {
+                return unsafe {
+                    levenshtein_search_simd_core_avx512_nx64x8(
+                        needle,
+                        haystack,
+                        k,
+                        opts,
+                        compiled_window(compiled),
+                    )
+                };
+            }
+
+            if cfg!(feature = "jewel-avx") && avx2_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Avx1x32x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_1x32x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Avx2x32x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_2x32x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Avx4x32x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_4x32x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Avx8x32x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_8x32x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && upper_bound <= u16::MAX as u32 {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_nx16x16(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") {
+                    return unsafe {
+                        levenshtein_search_simd_core_avx_nx8x32(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                }
+            } else if cfg!(feature = "jewel-sse") && sse41_supported() {
+                if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Sse1x16x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_1x16x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Sse2x16x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_2x16x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Sse4x16x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_4x16x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Sse8x16x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_8x16x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-8bit")
+                    && needle.len() <= Sse16x16x8::static_upper_bound()
+                    && upper_bound <= u8::MAX as u32
+                {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_16x16x8(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-16bit") && upper_bound <= u16::MAX as u32 {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_nx8x16(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                } else if cfg!(feature = "jewel-32bit") {
+                    return unsafe {
+                        levenshtein_search_simd_core_sse_nx4x32(
+                            needle,
+                            haystack,
+                            k,
+                            opts,
+                            compiled_window(compiled),
+                        )
+                    };
+                }
+            }
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        {
+            if cfg!(feature = "jewel-wasm")
+                && cfg!(feature = "jewel-8bit")
+                && upper_bound <= u8::MAX as u32
+            {
+                return unsafe {
+                    levenshtein_search_simd_core_wasm_nx16x8(
+                        needle,
+                        haystack,
+                        k,
+                        opts,
+                        compiled_window(compiled),
+                    )
+                };
+            }
+        }
+    }
+
+    levenshtein_search_naive_core(needle, haystack, k, opts)
+}
+
+/// Load the needle characters into a Jewel vector in reversed order, for searching.
+#[cfg(all(
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ),
+    not(feature = "pure-scalar")
+))]
+#[inline]
+unsafe fn load_needle_window<J: Jewel>(needle: &[u8], case_insensitive: bool) -> J {
+    let mut needle_window = J::repeating(0, needle.len());
+    needle_window.slow_loadu(
+        needle_window.upper_bound() - 1,
+        needle.as_ptr(),
+        needle.len(),
+        true,
+    );
+
+    if case_insensitive {
+        needle_window.ascii_fold_mut();
+    }
+
+    needle_window
+}
+
+/// Returns the needle vector of type `J` that was precomputed by `compiled`, if there is one.
+#[cfg(all(
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ),
+    not(feature = "pure-scalar")
+))]
+#[inline]
+fn compiled_window<J: Jewel + 'static>(compiled: Option<&CompiledNeedle>) -> Option<&J> {
+    compiled?
+        .needle_windows
+        .iter()
+        .find_map(|window| window.downcast_ref::<J>())
+}
+
+macro_rules! create_levenshtein_search_simd_core {
+    ($name:ident, $jewel:ty, $target:literal) => {
+        #[cfg(not(feature = "pure-scalar"))]
+        #[target_feature(enable = $target)]
+        unsafe fn $name<'a>(
+            needle: &'a [u8],
+            haystack: &'a [u8],
+            k: u32,
+            opts: SearchOpts,
+            compiled_window: Option<&'a $jewel>,
+        ) -> Box<dyn Iterator<Item = Match> + 'a> {
+            let SearchOpts {
+                search_type,
+                costs,
+                anchored,
+                case_insensitive,
+                length_pref,
+            } = opts;
+            #[cfg(feature = "debug")]
+            {
+                std::println!(
+                    "Debug: Levenshtein search Jewel vector type {} for target {}.",
+                    stringify!($jewel),
+                    stringify!($target)
+                );
+            }
+
+            let needle_len = needle.len();
+            let haystack_len = haystack.len();
+            let mut dp0 = <$jewel>::repeating_max(needle_len);
+            let mut dp_temp = <$jewel>::repeating_max(needle_len);
+            let mut dp1 = <$jewel>::repeating_max(needle_len);
+            let mut dp2 = <$jewel>::repeating_max(needle_len);
+            let mut needle_gap_dp = <$jewel>::repeating_max(needle_len);
+            let mut haystack_gap_dp = <$jewel>::repeating_max(needle_len);
+            dp2.slow_insert(
+                dp2.upper_bound() - 1,
+                costs.start_gap_cost as u32 + costs.gap_cost as u32,
+            ); // last cell
+            haystack_gap_dp.slow_insert(
+                dp2.upper_bound() - 1,
+                costs.start_gap_cost as u32 + costs.gap_cost as u32,
+            );
+
+            // save length instead of start idx due to int size constraints
+            let mut length0 = <$jewel>::repeating(0, needle_len);
+            let mut length_temp = <$jewel>::repeating(0, needle_len);
+            let mut length1 = <$jewel>::repeating(0, needle_len);
+            let mut length2 = <$jewel>::repeating(0, needle_len);
+            let mut needle_gap_length = <$jewel>::repeating(0, needle_len);
+            let mut haystack_gap_length = <$jewel>::repeating(0, needle_len);
+
+            let ones = <$jewel>::repeating(1, needle_len);
+            let twos = <$jewel>::repeating(2, needle_len);
+
+            // the suffix of haystack can be ignored if needle must be anchored
+            let len = if anchored {
+                needle_len
+                    + cmp::min(
+                        haystack_len,
+                        needle_len.saturating_add(
+                            (k.saturating_sub(costs.start_gap_cost as u32) as usize)
+                                / (costs.gap_cost as usize),
+                        ),
+                    )
+            } else {
+                needle_len + haystack_len
+            };
+
+            let final_idx = dp1.upper_bound() - needle_len;
+
+            // the needle only has to be loaded if it was not already loaded by a CompiledNeedle
+            let loaded_window = match compiled_window {
+                Some(_) => None,
+                None => Some(load_needle_window::<$jewel>(needle, case_insensitive)),
+            };
+
+            let mut haystack_window = <$jewel>::repeating(0, needle_len);
+            let mut haystack_idx = 0usize;
+            let mut curr_k = k;
+
+            // used in calculations
+            let mut match_mask0 = <$jewel>::repeating(0, needle_len);
+            let mut match_mask1 = <$jewel>::repeating(0, needle_len);
+            let mut match_mask_cost = <$jewel>::repeating(0, needle_len);
+            let mut sub = <$jewel>::repeating(0, needle_len);
+            let mut sub_length = <$jewel>::repeating(0, needle_len);
+            let mut needle_gap = <$jewel>::repeating(0, needle_len);
+            let mut haystack_gap = <$jewel>::repeating(0, needle_len);
+            let mut transpose = <$jewel>::repeating(0, needle_len);
+            let mut transpose_length = <$jewel>::repeating(0, needle_len);
+
+            let mismatch_cost = <$jewel>::repeating(costs.mismatch_cost as u32, needle_len);
+            let gap_cost = <$jewel>::repeating(costs.gap_cost as u32, needle_len);
+            let start_gap_cost = <$jewel>::repeating(costs.start_gap_cost as u32, needle_len);
+            let transpose_cost = match costs.transpose_cost {
+                Some(cost) => <$jewel>::repeating(cost as u32, needle_len),
+                None => <$jewel>::repeating(0, needle_len),
+            };
+            let allow_transpose = costs.transpose_cost.is_some();
+
+            //       ..i...
+            //       --h---
+            // |     //////xxxx
+            // |    x//////xxx
+            // n   xx//////xx
+            // |  xxx//////x
+            // | xxxx//////
+            //
+            // 'n' = needle, 'h' = haystack
+            // each (anti) diagonal is denoted using '/' and 'x'
+            // 'x' marks cells that are not in the traditional dp array
+            // every (anti) diagonal is calculated simultaneously using Jewel vectors
+            // note: each vector goes from bottom-left to top-right, ending at the first row in the
+            // DP matrix
+            // similar to levenshtein_simd_k, but without alternating anti-diagonals
+            // note that the first row, which should be all zeros for searching, is not saved in the
+            // Jewel vectors, for space concerns
+            // therefore, starting at i = 1 does not include the first diagonal that only contains
+            // a zero from the initial row of zeros
+            // when left shift are required, then zeros must be shifted in
+            // if anchored = true, then the number of gaps times the gap cost plus the starting gap
+            // cost must be shifted in
+            // for speed, transpositions are done by directly blending using the mask, without calculating
+            // the minimum cost compared to the other edit operations
+
+            let mut i = 1;
+            // with a needle of length 1, the loop starts past the diagonal with the cell for the
+            // empty match at the start of the haystack, so that cell must be checked separately
+            let mut check_first_cell = needle_len == 1;
+
+            let res = iter::from_fn(move || {
+                if check_first_cell {
+                    check_first_cell = false;
+                    let final_res = costs.start_gap_cost as u32 + costs.gap_cost as u32;
+
+                    if final_res <= curr_k {
+                        if search_type == SearchType::Best {
+                            curr_k = final_res;
+                        }
+
+                        return Some((
+                            Match {
+                                start: 0,
+                                end: 0,
+                                k: final_res,
+                            },
+                            curr_k,
+                        ));
+                    }
+                }
+
+                let needle_window = match compiled_window {
+                    Some(window) => window,
+                    None => loaded_window.as_ref().unwrap(),
+                };
+
+                while i < len {
+                    // shift the haystack window
+                    haystack_window.shift_left_1_mut();
+
+                    if haystack_idx < haystack_len {
+                        let c = *haystack.get_unchecked(haystack_idx);
+                        haystack_window.insert_last_0(if case_insensitive {
+                            c.to_ascii_lowercase()
+                        } else {
+                            c
+                        } as u32);
+                        haystack_idx += 1;
+                    }
+
+                    <$jewel>::cmpeq(needle_window, &haystack_window, &mut match_mask1);
+                    <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut match_mask_cost);
+
+                    // match/mismatch
+                    <$jewel>::shift_left_1(&dp1, &mut sub);
+
+                    if anchored && i > 1 {
+                        // dp1 is 2 diagonals behind the current i
+                        // must be capped at k to prevent overflow when inserting
+                        sub.insert_last_0(cmp::min(
+                            (i as u32 - 1) * (costs.gap_cost as u32) + costs.start_gap_cost as u32,
+                            k + 1,
+                        ));
+                    }
+
+                    sub.adds_mut(&match_mask_cost);
+
+                    <$jewel>::shift_left_1(&length1, &mut sub_length); // zeros are shifted in
+                    sub_length.add_mut(&ones);
+
+                    // gap in needle
+                    <$jewel>::adds(&dp2, &start_gap_cost, &mut needle_gap);
+                    <$jewel>::double_min_length(
+                        &needle_gap,
+                        &mut needle_gap_dp,
+                        &length2,
+                        &mut needle_gap_length,
+                    );
+                    needle_gap_dp.adds_mut(&gap_cost);
+                    needle_gap_length.add_mut(&ones);
+
+                    // gap in haystack
+                    <$jewel>::adds(&dp2, &start_gap_cost, &mut haystack_gap);
+                    <$jewel>::double_min_length(
+                        &haystack_gap,
+                        &mut haystack_gap_dp,
+                        &length2,
+                        &mut haystack_gap_length,
+                    );
+                    haystack_gap_dp.shift_left_1_mut(); // zeros are shifted in
+
+                    if anchored {
+                        // dp2 is one diagonal behind the current i
+                        haystack_gap_dp.insert_last_0(cmp::min(
+                            (i as u32) * (costs.gap_cost as u32) + costs.start_gap_cost as u32,
+                            k + 1,
+                        ));
+                    } else {
+                        haystack_gap_dp.insert_last_0(costs.start_gap_cost as u32);
+                    }
+
+                    haystack_gap_dp.adds_mut(&gap_cost);
+                    haystack_gap_length.shift_left_1_mut(); // zeros are shifted in
+
+                    if allow_transpose {
+                        <$jewel>::shift_left_1(&match_mask0, &mut transpose); // reuse transpose, shift in zeros
+                        transpose.and_mut(&match_mask0);
+                        // ensure that current matches are excluded
+                        <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
+                        dp0.shift_left_2_mut();
+
+                        if anchored && i > 3 {
+                            // dp0 is four diagonals behind the current i
+                            dp0.insert_last_1(cmp::min(
+                                (i as u32 - 3) * (costs.gap_cost as u32)
+                                    + costs.start_gap_cost as u32,
+                                k + 1,
+                            ));
+                        }
+
+                        length0.shift_left_2_mut();
+                        <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
+                        <$jewel>::add(&length0, &twos, &mut transpose_length);
+                    }
+
+                    if length_pref == LengthPreference::Shortest {
+                        <$jewel>::triple_min_length_short(
+                            &sub,
+                            &needle_gap_dp,
+                            &haystack_gap_dp,
+                            &sub_length,
+                            &needle_gap_length,
+                            &haystack_gap_length,
+                            &mut dp0,
+                            &mut length0,
+                        );
+                    } else {
+                        <$jewel>::triple_min_length(
+                            &sub,
+                            &needle_gap_dp,
+                            &haystack_gap_dp,
+                            &sub_length,
+                            &needle_gap_length,
+                            &haystack_gap_length,
+                            &mut dp0,
+                            &mut length0,
+                        );
+                    }
+
+                    if allow_transpose {
+                        // blend using transpose mask
+                        dp0.blendv_mut(&transpose, &match_mask0);
+                        length0.blendv_mut(&transpose_length, &match_mask0);
+                        mem::swap(&mut match_mask0, &mut match_mask1);
+                    }
+
+                    mem::swap(&mut dp0, &mut dp_temp);
+                    mem::swap(&mut dp_temp, &mut dp1);
+                    mem::swap(&mut dp1, &mut dp2);
+                    mem::swap(&mut length0, &mut length_temp);
+                    mem::swap(&mut length_temp, &mut length1);
+                    mem::swap(&mut length1, &mut length2);
+
+                    i += 1;
+
+                    if i >= needle_len {
+                        let final_res = dp2.slow_extract(final_idx);
+                        let final_length = length2.slow_extract(final_idx) as usize;
+
+                        if final_res <= curr_k {
+                            let end_idx = i - needle_len;
+
+                            match search_type {
+                                // if we want the best, then we can shrink the k threshold
+                                SearchType::Best => curr_k = final_res,
+                                _ => (),
+                            }
+
+                            return Some((
+                                Match {
+                                    start: end_idx - final_length,
+                                    end: end_idx,
+                                    k: final_res,
+                                },
+                                curr_k,
+                            ));
+                        }
+                    }
+                }
+
+                None
+            });
+
+            if search_type == SearchType::Best {
+                // only matches with the lowest k so far are kept, so the memory used does not grow
+                // with the length of the haystack
+                let mut res_vec: Vec<Match> = Vec::new();
+
+                for m in res {
+                    if m.1 < curr_k {
+                        res_vec.clear();
+                    }
+
+                    match res_vec.len() {
+                        0 => res_vec.push(m.0),
+                        _ => {
+                            let last = res_vec.last_mut().unwrap();
+
+                            // replace previous if fully overlapping
+                            if m.0.start <= last.start {
+                                *last = m.0;
+                            } else {
+                                res_vec.push(m.0);
+                            }
+                        }
+                    }
+
+                    curr_k = m.1;
+                }
+
+                // only retain matches with the lowest k
+                return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
+            }
+
+            Box::new(res.map(|m| m.0))
+        }
+    };
+}
+
+// duplicate functions for each Jewel vector type
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_1x32x8, Avx1x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_2x32x8, Avx2x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_4x32x8, Avx4x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_8x32x8, Avx8x32x8, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_nx16x16, AvxNx16x16, "avx2");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_nx8x32, AvxNx8x32, "avx2");
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(
+    levenshtein_search_simd_core_avx512_nx64x8,
+    Avx512Nx64x8,
+    "avx512bw"
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_1x16x8, Sse1x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_2x16x8, Sse2x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_4x16x8, Sse4x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_8x16x8, Sse8x16x8, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(
+    levenshtein_search_simd_core_sse_16x16x8,
+    Sse16x16x8,
+    "sse4.1"
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_nx8x16, SseNx8x16, "sse4.1");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_nx4x32, SseNx4x32, "sse4.1");
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+create_levenshtein_search_simd_core!(
+    levenshtein_search_simd_core_wasm_nx16x8,
+    WasmNx16x8,
+    "simd128"
+);
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration.
+///
+/// The best matches are the matches with the lowest Levenshtein distance.
+/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+/// Internally, this will call `levenshtein_search_simd`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches: Vec<Match> = levenshtein_search(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_simd(needle, haystack)
+}
+
+/// Returns an iterator over best `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using the scalar implementation.
+///
+/// This has the same signature and output as `levenshtein_search`, but it never uses SIMD, so it
+/// is always available regardless of the target features or crate feature flags that are enabled.
+/// The best matches are the matches with the lowest Levenshtein distance.
+/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Each returned `Match` requires at least half or more bytes of the `needle` to match
+/// somewhere in the `haystack`.
+/// Internally, this will call `levenshtein_search_naive`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches: Vec<Match> = levenshtein_search_scalar(b"abc", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_scalar<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_naive(needle, haystack)
+}
+
+/// Returns the single best `Match` by searching through the text `haystack` for the pattern
+/// `needle` using SIMD acceleration.
+///
+/// The best match is the match with the lowest Levenshtein distance that is less than or equal
+/// to `max_k`. If there are multiple best matches, then the one with the earliest start is chosen,
+/// and then the longest of those.
+/// The search starts with `max_k` as the bound, and the bound is lowered to the lowest distance
+/// found so far, so the rest of the haystack is pruned more aggressively as better matches are
+/// found.
+/// If there are no matches within `max_k`, or `needle` is empty, then `None` is returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts` with `SearchType::Best`.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `max_k` - maximum cost threshold for a match to be returned
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let best = levenshtein_search_best(b"abc", b"  abd  abc", 2);
+///
+/// assert!(best == Some(Match{start: 7, end: 10, k: 0}));
+/// ```
+pub fn levenshtein_search_best(needle: &[u8], haystack: &[u8], max_k: u32) -> Option<Match> {
+    // all of the best matches have the same k, and a match that is dropped for being fully
+    // overlapped by a longer match never starts before that match, so the earliest start is kept
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        max_k,
+        SearchType::Best,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .min_by_key(|m| (m.start, cmp::Reverse(m.end)))
+}
+
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, where transpositions count as a single edit.
+///
+/// This uses the restricted Damerau-Levenshtein distance, so mismatches, gaps, and swaps of
+/// adjacent characters all have a cost of 1.
+/// Every match with a distance less than or equal to `k` is returned.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts` with `RDAMERAU_COSTS`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches = damerau_search(b"recieve", b"please receive it", 1);
+///
+/// assert!(matches == vec![Match{start: 7, end: 14, k: 1}]);
+/// ```
+pub fn damerau_search(needle: &[u8], haystack: &[u8], k: u32) -> Vec<Match> {
+    levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, RDAMERAU_COSTS, false)
+        .collect()
+}
+
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, sorted so that the closest matches come first.
+///
+/// Every match with a Levenshtein distance less than or equal to `k` is returned.
+/// The matches are sorted in ascending order by `k`, and then by `start` for matches with
+/// the same `k`. The sort is stable, so matches that are still tied keep the order in which
+/// they were found in `haystack`.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches = levenshtein_search_ranked(b"abcd", b"abxd  abcd", 1);
+///
+/// assert!(matches == vec![Match{start: 6, end: 10, k: 0},
+///                         Match{start: 0, end: 4, k: 1},
+///                         Match{start: 6, end: 9, k: 1}]);
+/// ```
+pub fn levenshtein_search_ranked(needle: &[u8], haystack: &[u8], k: u32) -> Vec<Match> {
+    let mut res: Vec<Match> = levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .collect();
+    // stable sort
+    res.sort_by_key(|m| (m.k, m.start));
+    res
+}
+
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, where the number of edits allowed is derived from a similarity ratio.
+///
+/// The number of edits allowed is `k = floor((1 - min_similarity) * needle.len())`, so every
+/// match has a similarity of at least `min_similarity` relative to the length of `needle`.
+/// A small tolerance is added before rounding down, so that ratios that cannot be exactly
+/// represented as floating point numbers do not lose an edit. For example, a 0.8 threshold on a
+/// 10 character needle allows 2 edits, even though `(1.0 - 0.8) * 10.0` is slightly less than 2.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `min_similarity` - minimum similarity of each match, in the range [0, 1]
+///
+/// # Panics
+/// * If `min_similarity` is not in the range [0, 1].
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches = levenshtein_search_ratio(b"abcdefghij", b"abcdeXghYj", 0.8);
+///
+/// assert!(matches.iter().any(|m| m.k == 2));
+/// assert!(levenshtein_search_ratio(b"abcdefghij", b"abcdeXghYj", 0.9).is_empty());
+/// ```
+pub fn levenshtein_search_ratio(needle: &[u8], haystack: &[u8], min_similarity: f64) -> Vec<Match> {
+    assert!((0.0..=1.0).contains(&min_similarity));
+
+    // the value is not negative, so truncating is the same as rounding down
+    let k = ((1.0 - min_similarity) * (needle.len() as f64) + 1e-9) as u32;
+
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .collect()
+}
+
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, along with a window of surrounding context for each match.
+///
+/// Every match with a Levenshtein distance less than or equal to `k` is returned.
+/// The window for each match is the range `start - context..end + context` of indexes into
+/// `haystack`, which is clamped to the bounds of `haystack`. This means that the window is
+/// shorter than `context` bytes on either side if the match is close to the start or the end of
+/// `haystack`, and it can always be used to directly slice `haystack`.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+/// * `context` - number of bytes of context to include before and after each match
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let haystack = b"abc the quick brown fox";
+/// let matches = levenshtein_search_context(b"quick", haystack, 0, 4);
+///
+/// assert!(matches == vec![(Match{start: 8, end: 13, k: 0}, 4..17)]);
+/// assert!(&haystack[matches[0].1.clone()] == b"the quick bro");
+/// ```
+pub fn levenshtein_search_context(
+    needle: &[u8],
+    haystack: &[u8],
+    k: u32,
+    context: usize,
+) -> Vec<(Match, Range<usize>)> {
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .map(|m| {
+        let window = m.start.saturating_sub(context)
+            ..cmp::min(m.end.saturating_add(context), haystack.len());
+        (m, window)
+    })
+    .collect()
+}
+
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, along with the edit script that aligns `needle` to each match.
+///
+/// Every match with a Levenshtein distance less than or equal to `k` is returned. The search only
+/// finds where each match is, so for every match, the dynamic programming matrix between `needle`
+/// and the bytes of `haystack` in the match is computed again with traceback, which takes
+/// O(`needle.len()` * `k`) time and space. This is much slower than `levenshtein_search_count`
+/// or only collecting the matches when there are many matches.
+/// The edit script is a run-length encoded traceback from `needle` (`a`) to the bytes of the match
+/// (`b`), like `levenshtein_simd_k_with_opts`, so `AGap` is a byte that is inserted into `needle`
+/// and `BGap` is a byte of `needle` that is deleted. The total cost of the edits is the `k` of
+/// the match. If there are multiple alignments with the lowest number of edits, then only one of
+/// them is returned.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts` and
+/// `levenshtein_simd_k_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches = levenshtein_search_align(b"abcd", b"xxabxdxx", 1);
+///
+/// assert!(matches == vec![(Match{start: 2, end: 6, k: 1},
+///                          vec![Edit{edit: EditType::Match, count: 2},
+///                               Edit{edit: EditType::Mismatch, count: 1},
+///                               Edit{edit: EditType::Match, count: 1}])]);
+/// ```
+pub fn levenshtein_search_align(needle: &[u8], haystack: &[u8], k: u32) -> Vec<(Match, Vec<Edit>)> {
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .map(|m| {
+        // the match was found with exactly m.k edits, so the traceback always exists
+        let (_, edits) =
+            levenshtein_simd_k_with_opts(needle, m.slice(haystack), m.k, true, LEVENSHTEIN_COSTS)
+                .unwrap();
+        (m, edits.unwrap())
+    })
+    .collect()
+}
+
+/// A `Match` along with the number of bytes of the needle that are aligned to the haystack.
+///
+/// This is returned by `levenshtein_search_detailed`.
+/// With gaps, the length of the match in the haystack may be different from the length of the
+/// needle. Bytes of the needle that are deleted are not aligned, so `end - start - needle_aligned`
+/// is the number of bytes that are inserted into the needle, and the length of the needle minus
+/// `needle_aligned` is the number of bytes that are deleted from the needle.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct MatchDetailed {
+    /// The start index of the match (inclusive).
+    pub start: usize,
+    /// The end index of the match (exclusive).
+    pub end: usize,
+    /// Number of edits for the match.
+    pub k: u32,
+    /// Number of bytes of the needle that are matched or mismatched with bytes of the haystack.
+    pub needle_aligned: usize,
+}
 
-            curr_k = m.1;
+impl From<MatchDetailed> for Match {
+    fn from(m: MatchDetailed) -> Self {
+        Match {
+            start: m.start,
+            end: m.end,
+            k: m.k,
         }
-
-        return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
     }
+}
 
-    Box::new(res.map(|m| m.0))
+/// Returns all `Match`s by searching through the text `haystack` for the pattern `needle`
+/// using SIMD acceleration, along with the number of bytes of `needle` that are aligned in each
+/// match.
+///
+/// Every match with a Levenshtein distance less than or equal to `k` is returned, as a
+/// `MatchDetailed`. For each match, the matched and mismatched bytes in the edit script from
+/// `levenshtein_search_align` are counted. This shows whether a
+/// match is insertion-heavy or deletion-heavy. If there are multiple alignments with the lowest
+/// number of edits, then only one of them is counted.
+/// If multiple matches end at the same position, then the longest match is chosen.
+/// If `needle` is empty, then no `Match`es are returned.
+/// Internally, this will call `levenshtein_search_align`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches = levenshtein_search_detailed(b"abcdef", b"xxabdefxx", 1);
+///
+/// assert!(matches == vec![MatchDetailed{start: 2, end: 7, k: 1, needle_aligned: 5}]);
+/// ```
+pub fn levenshtein_search_detailed(needle: &[u8], haystack: &[u8], k: u32) -> Vec<MatchDetailed> {
+    levenshtein_search_align(needle, haystack, k)
+        .into_iter()
+        .map(|(m, edits)| {
+            let needle_aligned = edits
+                .iter()
+                .filter(|e| e.edit == EditType::Match || e.edit == EditType::Mismatch)
+                .map(|e| e.count)
+                .sum();
+
+            MatchDetailed {
+                start: m.start,
+                end: m.end,
+                k: m.k,
+                needle_aligned,
+            }
+        })
+        .collect()
 }
 
-/// Returns an iterator over the best `Match`s by searching through the text `haystack` for the
+/// Returns a lazy iterator over all `Match`s by searching through the text `haystack` for the
 /// pattern `needle` using SIMD acceleration.
 ///
-/// The best matches are the matches with the lowest Levenshtein distance.
-/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
+/// Every match with a Levenshtein distance less than or equal to `k` is returned.
+/// The dynamic programming state, including the Jewel vectors for the current anti-diagonals,
+/// lives inside the iterator, and each call to `next` only advances through `haystack` until the
+/// next match is found. This means that no matches are collected up front, so it is cheap to stop
+/// early, like with `take` or `find`.
+/// This returns the same matches as `levenshtein_search_simd_with_opts` with `SearchType::All`.
 /// If `needle` is empty, then no `Match`es are returned.
-/// Each returned `Match` requires at least half or more bytes of the `needle` to match
-/// somewhere in the `haystack`.
-/// This should be much faster than `levenshtein_search_naive`.
-/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
-/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
-/// `levenshtein_search_naive_with_opts`.
 ///
 /// # Arguments
 /// * `needle` - pattern string (slice)
 /// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
 /// # use triple_accel::levenshtein::*;
-/// let matches: Vec<Match> = levenshtein_search_simd(b"abc", b"  abd").collect();
+/// let first = levenshtein_search_iter(b"abc", b"  abc  abc", 0).next();
 ///
-/// assert!(matches == alloc::vec![Match{start: 2, end: 5, k: 1}]);
+/// assert!(first == Some(Match{start: 2, end: 5, k: 0}));
 /// ```
-pub fn levenshtein_search_simd<'a>(
+pub fn levenshtein_search_iter<'a>(
     needle: &'a [u8],
     haystack: &'a [u8],
-) -> Box<dyn Iterator<Item = Match> + 'a> {
+    k: u32,
+) -> impl Iterator<Item = Match> + 'a {
     levenshtein_search_simd_with_opts(
         needle,
         haystack,
-        ((needle.len() >> 1) as u32) + ((needle.len() as u32) & 1),
-        SearchType::Best,
+        k,
+        SearchType::All,
         LEVENSHTEIN_COSTS,
         false,
     )
 }
 
-/// Returns an iterator over `Match`s by searching through the text `haystack` for the
-/// pattern `needle` using SIMD acceleration, with extra options.
+/// Returns the number of `Match`s found by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration.
 ///
-/// Note that overlapping matches may be returned.
-/// If multiple matches end at the same position, then the longest match is chosen.
-/// If `needle` is empty and `anchored` is false, then no `Match`es are returned.
-/// This should be much faster than `levenshtein_search_naive_with_opts`.
-/// Internally, this will automatically use AVX or SSE vectors with 8-bit, 16-bit, or 32-bit elements
-/// to represent anti-diagonals in the dynamic programming matrix for calculating Levenshtein distance.
-/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to
-/// `levenshtein_search_naive_with_opts`.
+/// Every match with a Levenshtein distance less than or equal to `k` is counted, without
+/// allocating a vector of `Match`s. Overlapping matches are handled like in
+/// `levenshtein_search_simd_with_opts`, so each end position is counted at most once.
+/// If `needle` is empty, then zero is returned.
+/// Internally, this will call `levenshtein_search_simd_with_opts`.
 ///
 /// # Arguments
 /// * `needle` - pattern string (slice)
 /// * `haystack` - text string (slice)
-/// * `k` - maximum cost threshold for a match to be returned
-/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
-/// the lowest cost (additionally, only the longest matches are retained for matches that fully overlap)
-/// * `costs` - `EditCosts` struct for the cost of each edit operation
-/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
-/// causing any shifts to cost gap edits
+/// * `k` - number of edits allowed
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
 /// # use triple_accel::levenshtein::*;
-/// let matches: Vec<Match> = levenshtein_search_simd_with_opts(b"abc", b"  acb", 1, SearchType::All, RDAMERAU_COSTS, false).collect();
+/// let count = levenshtein_search_count(b"abc", b"  abc  abc  xyz", 0);
 ///
-/// // note: it is possible to end the match at two different positions
-/// assert!(matches == alloc::vec![Match{start: 2, end: 4, k: 1}, Match{start: 2, end: 5, k: 1}]);
+/// assert!(count == 2);
 /// ```
-pub fn levenshtein_search_simd_with_opts<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-    k: u32,
-    search_type: SearchType,
-    costs: EditCosts,
-    anchored: bool,
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    if needle.len() == 0 {
-        // special case when anchored is true: return possible matches
-        if anchored {
-            return match search_type {
-                SearchType::All => {
-                    let mut i = 0;
-                    let mut cost = costs.start_gap_cost as u32;
-                    let mut start = true;
-
-                    Box::new(iter::from_fn(move || {
-                        if start {
-                            start = false;
-                            return Some(Match {
-                                start: 0,
-                                end: 0,
-                                k: 0,
-                            });
-                        }
-
-                        if i < haystack.len() {
-                            i += 1;
-                            cost += costs.gap_cost as u32;
-
-                            if cost <= k {
-                                return Some(Match {
-                                    start: 0,
-                                    end: i,
-                                    k: cost,
-                                });
-                            }
-                        }
-
-                        None
-                    }))
-                }
-                SearchType::Best => Box::new(iter::once(Match {
-                    start: 0,
-                    end: 0,
-                    k: 0,
-                })),
-            };
-        } else {
-            return Box::new(iter::empty());
-        }
-    }
-
-    costs.check_search();
-
-    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-    {
-        let unit_k = k.saturating_sub(costs.start_gap_cost as u32) / (costs.gap_cost as u32);
-        // either the length of the match or the number of edits may exceed the maximum
-        // available int size; additionally, MAX value is used to indicate overflow
-        let upper_bound = cmp::max(
-            (needle.len() as u32).saturating_add(unit_k),
-            k.saturating_add(1),
-        );
-
-        if cfg!(feature = "jewel-avx") {
-            if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Avx1x32x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_1x32x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Avx2x32x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_2x32x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Avx4x32x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_4x32x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Avx8x32x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_8x32x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-16bit") && upper_bound <= u16::MAX as u32 {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_nx16x16(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-32bit") {
-                return unsafe {
-                    levenshtein_search_simd_core_avx_nx8x32(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            }
-        } else if cfg!(feature = "jewel-sse") {
-            if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Sse1x16x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_1x16x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Sse2x16x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_2x16x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Sse4x16x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_4x16x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Sse8x16x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_8x16x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-8bit")
-                && needle.len() <= Sse16x16x8::static_upper_bound()
-                && upper_bound <= u8::MAX as u32
-            {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_16x16x8(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-16bit") && upper_bound <= u16::MAX as u32 {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_nx8x16(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            } else if cfg!(feature = "jewel-32bit") {
-                return unsafe {
-                    levenshtein_search_simd_core_sse_nx4x32(
-                        needle,
-                        haystack,
-                        k,
-                        search_type,
-                        costs,
-                        anchored,
-                    )
-                };
-            }
+pub fn levenshtein_search_count(needle: &[u8], haystack: &[u8], k: u32) -> usize {
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .count()
+}
+
+/// Returns whether the pattern `needle` occurs anywhere in the text `haystack`, within a
+/// Levenshtein distance of `k`, using SIMD acceleration.
+///
+/// The search stops as soon as the first match is found, so the rest of the haystack is skipped.
+/// This is faster than collecting every match and checking whether there are any.
+/// This always returns the same result as checking whether `levenshtein_search_simd_with_opts`
+/// with `SearchType::All` returns any `Match`s, so an empty `needle` is never contained in any
+/// haystack.
+/// Internally, this will call `levenshtein_search_simd_with_opts`, which finds matches lazily.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// assert!(levenshtein_contains(b"abc", b"  abd  xyz", 1));
+/// assert!(!levenshtein_contains(b"abc", b"  abd  xyz", 0));
+/// ```
+pub fn levenshtein_contains(needle: &[u8], haystack: &[u8], k: u32) -> bool {
+    levenshtein_search_simd_with_opts(
+        needle,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .next()
+    .is_some()
+}
+
+/// Returns the best Levenshtein distance of `needle` ending at each position in `haystack`.
+///
+/// This is the last row of the dynamic programming matrix used for searching (Sellers'
+/// algorithm), before any thresholding. The value at index `i` is the lowest Levenshtein distance
+/// between `needle` and any substring of `haystack` that ends right after `haystack[i]`, so the
+/// returned vector has the same length as `haystack`.
+/// Keeping only the positions where the value is less than or equal to `k` gives the end
+/// positions of the `Match`s from `levenshtein_search_simd_with_opts` with `SearchType::All`.
+/// This uses a scalar algorithm that keeps a single column of the dynamic programming matrix.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let profile = levenshtein_search_profile(b"abc", b"xabcx");
+///
+/// assert!(profile == vec![3, 2, 1, 0, 1]);
+/// ```
+pub fn levenshtein_search_profile(needle: &[u8], haystack: &[u8]) -> Vec<u32> {
+    let needle_len = needle.len();
+    // column of the dp matrix, where the first row is always zero since matches can start anywhere
+    let mut col: Vec<u32> = (0..=needle_len as u32).collect();
+    let mut res = Vec::with_capacity(haystack.len());
+
+    for &c in haystack {
+        let mut diag = col[0];
+
+        for i in 1..=needle_len {
+            let prev = col[i];
+            col[i] = cmp::min(
+                diag + (needle[i - 1] != c) as u32,
+                cmp::min(prev, col[i - 1]) + 1,
+            );
+            diag = prev;
         }
+
+        res.push(col[needle_len]);
     }
 
-    levenshtein_search_naive_with_opts(needle, haystack, k, search_type, costs, anchored)
+    res
 }
 
-macro_rules! create_levenshtein_search_simd_core {
-    ($name:ident, $jewel:ty, $target:literal) => {
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        #[target_feature(enable = $target)]
-        unsafe fn $name<'a>(
-            needle: &'a [u8],
-            haystack: &'a [u8],
-            k: u32,
-            search_type: SearchType,
-            costs: EditCosts,
-            anchored: bool,
-        ) -> Box<dyn Iterator<Item = Match> + 'a> {
-            #[cfg(feature = "debug")]
-            {
-                println!(
-                    "Debug: Levenshtein search Jewel vector type {} for target {}.",
-                    stringify!($jewel),
-                    stringify!($target)
-                );
-            }
+/// Returns an iterator over best `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration, ignoring ASCII case.
+///
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// Other than the case insensitivity, this behaves exactly like `levenshtein_search`.
+/// If `needle` is empty, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_ci(b"ABC", b"  abd").collect();
+///
+/// assert!(matches == vec![Match{start: 2, end: 5, k: 1}]);
+/// ```
+pub fn levenshtein_search_ci<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_ci_with_opts(
+        needle,
+        haystack,
+        ((needle.len() >> 1) as u32) + ((needle.len() as u32) & 1),
+        SearchType::Best,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+}
 
-            let needle_len = needle.len();
-            let haystack_len = haystack.len();
-            let mut dp0 = <$jewel>::repeating_max(needle_len);
-            let mut dp_temp = <$jewel>::repeating_max(needle_len);
-            let mut dp1 = <$jewel>::repeating_max(needle_len);
-            let mut dp2 = <$jewel>::repeating_max(needle_len);
-            let mut needle_gap_dp = <$jewel>::repeating_max(needle_len);
-            let mut haystack_gap_dp = <$jewel>::repeating_max(needle_len);
-            dp2.slow_insert(
-                dp2.upper_bound() - 1,
-                costs.start_gap_cost as u32 + costs.gap_cost as u32,
-            ); // last cell
-            haystack_gap_dp.slow_insert(
-                dp2.upper_bound() - 1,
-                costs.start_gap_cost as u32 + costs.gap_cost as u32,
-            );
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration, ignoring ASCII case, with extra options.
+///
+/// Only the ASCII letters A-Z are treated as equal to their lowercase counterparts; all other
+/// bytes are compared as is.
+/// Other than the case insensitivity, this behaves exactly like `levenshtein_search_simd_with_opts`.
+/// If `needle` is empty and `anchored` is false, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `k` - maximum cost threshold for a match to be returned
+/// * `search_type` - indicates whether to return all matches (within a cost of `k`), or the best matches with
+///   the lowest cost (additionally, only the longest matches are retained for matches that fully overlap)
+/// * `costs` - `EditCosts` struct for the cost of each edit operation
+/// * `anchored` - whether the `needle` should be anchored to the start of the `haystack` string,
+///   causing any shifts to cost gap edits
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let matches: Vec<Match> = levenshtein_search_ci_with_opts(b"HELLO", b"say hello", 0, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+///
+/// assert!(matches == vec![Match{start: 4, end: 9, k: 0}]);
+/// ```
+pub fn levenshtein_search_ci_with_opts<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    k: u32,
+    search_type: SearchType,
+    costs: EditCosts,
+    anchored: bool,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    levenshtein_search_simd_dispatch(
+        needle,
+        haystack,
+        k,
+        SearchOpts {
+            search_type,
+            costs,
+            anchored,
+            case_insensitive: true,
+            length_pref: LengthPreference::Longest,
+        },
+        None,
+    )
+}
 
-            // save length instead of start idx due to int size constraints
-            let mut length0 = <$jewel>::repeating(0, needle_len);
-            let mut length_temp = <$jewel>::repeating(0, needle_len);
-            let mut length1 = <$jewel>::repeating(0, needle_len);
-            let mut length2 = <$jewel>::repeating(0, needle_len);
-            let mut needle_gap_length = <$jewel>::repeating(0, needle_len);
-            let mut haystack_gap_length = <$jewel>::repeating(0, needle_len);
+/// A builder for the options of a Levenshtein search.
+///
+/// This gathers the options that are otherwise spread across `levenshtein_search_simd_with_opts`,
+/// `levenshtein_search_simd_with_length_pref`, `levenshtein_search_ci_with_opts`,
+/// `levenshtein_search_ranked`, and `merge_overlapping`, so they can be combined freely.
+/// The resulting config is passed to `levenshtein_search_with`.
+///
+/// By default, this behaves like `levenshtein_search`: the best matches with up to half of the
+/// needle's length in Levenshtein edits are returned, and overlapping matches are kept.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let config = SearchConfig::new()
+///     .k(1)
+///     .search_type(SearchType::All)
+///     .case_insensitive(true)
+///     .overlap(MergePolicy::LowestK)
+///     .build();
+/// let matches: Vec<Match> = levenshtein_search_with(b"abc", b"xABC ABD", &config).collect();
+///
+/// assert!(matches == vec![Match{start: 1, end: 4, k: 0}, Match{start: 5, end: 7, k: 1}]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SearchConfig {
+    k: Option<u32>,
+    opts: SearchOpts,
+    overlap: Option<MergePolicy>,
+    ranked: bool,
+    min_len: usize,
+}
 
-            let ones = <$jewel>::repeating(1, needle_len);
-            let twos = <$jewel>::repeating(2, needle_len);
+impl SearchConfig {
+    /// Creates a config with the default options.
+    pub fn new() -> Self {
+        Self {
+            k: None,
+            opts: SearchOpts {
+                search_type: SearchType::Best,
+                costs: LEVENSHTEIN_COSTS,
+                anchored: false,
+                case_insensitive: false,
+                length_pref: LengthPreference::Longest,
+            },
+            overlap: None,
+            ranked: false,
+            min_len: 0,
+        }
+    }
 
-            // the suffix of haystack can be ignored if needle must be anchored
-            let len = if anchored {
-                needle_len
-                    + cmp::min(
-                        haystack_len,
-                        needle_len.saturating_add(
-                            (k.saturating_sub(costs.start_gap_cost as u32) as usize)
-                                / (costs.gap_cost as usize),
-                        ),
-                    )
-            } else {
-                needle_len + haystack_len
-            };
+    /// Sets the maximum cost threshold for a match to be returned.
+    ///
+    /// If this is not set, then half of the needle's length (rounded up) is used.
+    pub fn k(mut self, k: u32) -> Self {
+        self.k = Some(k);
+        self
+    }
 
-            let final_idx = dp1.upper_bound() - needle_len;
+    /// Sets whether to return all matches within a cost of `k`, or only the best matches.
+    pub fn search_type(mut self, search_type: SearchType) -> Self {
+        self.opts.search_type = search_type;
+        self
+    }
 
-            // load needle characters into needle_window in reversed order
-            let mut needle_window = <$jewel>::repeating(0, needle_len);
-            needle_window.slow_loadu(
-                needle_window.upper_bound() - 1,
-                needle.as_ptr(),
-                needle_len,
-                true,
-            );
+    /// Sets the cost of each edit operation.
+    pub fn costs(mut self, costs: EditCosts) -> Self {
+        self.opts.costs = costs;
+        self
+    }
 
-            let mut haystack_window = <$jewel>::repeating(0, needle_len);
-            let mut haystack_idx = 0usize;
-            let mut curr_k = k;
+    /// Sets whether the needle should be anchored to the start of the haystack.
+    pub fn anchored(mut self, anchored: bool) -> Self {
+        self.opts.anchored = anchored;
+        self
+    }
 
-            // used in calculations
-            let mut match_mask0 = <$jewel>::repeating(0, needle_len);
-            let mut match_mask1 = <$jewel>::repeating(0, needle_len);
-            let mut match_mask_cost = <$jewel>::repeating(0, needle_len);
-            let mut sub = <$jewel>::repeating(0, needle_len);
-            let mut sub_length = <$jewel>::repeating(0, needle_len);
-            let mut needle_gap = <$jewel>::repeating(0, needle_len);
-            let mut haystack_gap = <$jewel>::repeating(0, needle_len);
-            let mut transpose = <$jewel>::repeating(0, needle_len);
-            let mut transpose_length = <$jewel>::repeating(0, needle_len);
+    /// Sets whether ASCII letters are compared case insensitively.
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.opts.case_insensitive = case_insensitive;
+        self
+    }
 
-            let mismatch_cost = <$jewel>::repeating(costs.mismatch_cost as u32, needle_len);
-            let gap_cost = <$jewel>::repeating(costs.gap_cost as u32, needle_len);
-            let start_gap_cost = <$jewel>::repeating(costs.start_gap_cost as u32, needle_len);
-            let transpose_cost = match costs.transpose_cost {
-                Some(cost) => <$jewel>::repeating(cost as u32, needle_len),
-                None => <$jewel>::repeating(0, needle_len),
-            };
-            let allow_transpose = costs.transpose_cost.is_some();
+    /// Sets whether to prefer the longest or the shortest match when edit costs are equal.
+    pub fn length_pref(mut self, length_pref: LengthPreference) -> Self {
+        self.opts.length_pref = length_pref;
+        self
+    }
 
-            //       ..i...
-            //       --h---
-            // |     //////xxxx
-            // |    x//////xxx
-            // n   xx//////xx
-            // |  xxx//////x
-            // | xxxx//////
-            //
-            // 'n' = needle, 'h' = haystack
-            // each (anti) diagonal is denoted using '/' and 'x'
-            // 'x' marks cells that are not in the traditional dp array
-            // every (anti) diagonal is calculated simultaneously using Jewel vectors
-            // note: each vector goes from bottom-left to top-right, ending at the first row in the
-            // DP matrix
-            // similar to levenshtein_simd_k, but without alternating anti-diagonals
-            // note that the first row, which should be all zeros for searching, is not saved in the
-            // Jewel vectors, for space concerns
-            // therefore, starting at i = 1 does not include the first diagonal that only contains
-            // a zero from the initial row of zeros
-            // when left shift are required, then zeros must be shifted in
-            // if anchored = true, then the number of gaps times the gap cost plus the starting gap
-            // cost must be shifted in
-            // for speed, transpositions are done by directly blending using the mask, without calculating
-            // the minimum cost compared to the other edit operations
+    /// Sets how overlapping matches are merged, like with `merge_overlapping`.
+    ///
+    /// Note that the matches are sorted by their start and end positions after merging.
+    pub fn overlap(mut self, policy: MergePolicy) -> Self {
+        self.overlap = Some(policy);
+        self
+    }
 
-            let mut i = 1;
+    /// Sets whether the matches are sorted by `k` and then by `start`, like with
+    /// `levenshtein_search_ranked`.
+    ///
+    /// This is applied after merging overlapping matches.
+    pub fn ranked(mut self, ranked: bool) -> Self {
+        self.ranked = ranked;
+        self
+    }
 
-            let res = iter::from_fn(move || {
-                while i < len {
-                    // shift the haystack window
-                    haystack_window.shift_left_1_mut();
+    /// Sets the minimum length of a match in the haystack, so matches with
+    /// `end - start < min_len` are discarded.
+    ///
+    /// This is applied to the matches of the search before merging overlapping matches, so short
+    /// matches do not affect the merged matches. With `SearchType::Best`, the best matches are
+    /// chosen first, so a short match that is discarded is not replaced by a longer match with
+    /// more edits. By default, this is zero and no matches are discarded.
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
 
-                    if haystack_idx < haystack_len {
-                        haystack_window.insert_last_0(*haystack.get_unchecked(haystack_idx) as u32);
-                        haystack_idx += 1;
-                    }
+    /// Checks the options and returns the finished config.
+    ///
+    /// # Panics
+    /// * If the edit costs are not valid for searching.
+    pub fn build(self) -> Self {
+        self.opts.costs.check_search();
+        self
+    }
+}
 
-                    <$jewel>::cmpeq(&needle_window, &haystack_window, &mut match_mask1);
-                    <$jewel>::andnot(&match_mask1, &mismatch_cost, &mut match_mask_cost);
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns an iterator over `Match`s by searching through the text `haystack` for the
+/// pattern `needle` using SIMD acceleration, with the options in `config`.
+///
+/// Without merging or ranking, the matches are generated exactly like with
+/// `levenshtein_search_simd_with_opts` (lazily, with `SearchType::All`), and then matches that
+/// are shorter than the minimum length are discarded.
+/// Otherwise, all matches are found and filtered first, and then merged and sorted.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+/// If `needle` is empty and the search is not anchored, then no `Match`es are returned.
+///
+/// # Arguments
+/// * `needle` - pattern string (slice)
+/// * `haystack` - text string (slice)
+/// * `config` - `SearchConfig` with the search options
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let config = SearchConfig::new().k(1).search_type(SearchType::All).ranked(true).build();
+/// let matches: Vec<Match> = levenshtein_search_with(b"abcd", b"abxd  abcd", &config).collect();
+///
+/// assert!(matches == levenshtein_search_ranked(b"abcd", b"abxd  abcd", 1));
+/// ```
+pub fn levenshtein_search_with<'a>(
+    needle: &'a [u8],
+    haystack: &'a [u8],
+    config: &SearchConfig,
+) -> Box<dyn Iterator<Item = Match> + 'a> {
+    let k = config
+        .k
+        .unwrap_or(((needle.len() >> 1) as u32) + ((needle.len() as u32) & 1));
+    let res = levenshtein_search_simd_dispatch(needle, haystack, k, config.opts, None);
+    let res: Box<dyn Iterator<Item = Match> + 'a> = if config.min_len > 0 {
+        let min_len = config.min_len;
+        Box::new(res.filter(move |m| m.len() >= min_len))
+    } else {
+        res
+    };
 
-                    // match/mismatch
-                    <$jewel>::shift_left_1(&dp1, &mut sub);
+    if config.overlap.is_none() && !config.ranked {
+        return res;
+    }
 
-                    if anchored && i > 1 {
-                        // dp1 is 2 diagonals behind the current i
-                        // must be capped at k to prevent overflow when inserting
-                        sub.insert_last_0(cmp::min(
-                            (i as u32 - 1) * (costs.gap_cost as u32) + costs.start_gap_cost as u32,
-                            k + 1,
-                        ));
-                    }
+    let mut res: Vec<Match> = res.collect();
 
-                    sub.adds_mut(&match_mask_cost);
+    if let Some(policy) = config.overlap {
+        merge_overlapping(&mut res, policy);
+    }
 
-                    <$jewel>::shift_left_1(&length1, &mut sub_length); // zeros are shifted in
-                    sub_length.add_mut(&ones);
+    if config.ranked {
+        // stable sort
+        res.sort_by_key(|m| (m.k, m.start));
+    }
 
-                    // gap in needle
-                    <$jewel>::adds(&dp2, &start_gap_cost, &mut needle_gap);
-                    <$jewel>::double_min_length(
-                        &needle_gap,
-                        &mut needle_gap_dp,
-                        &length2,
-                        &mut needle_gap_length,
-                    );
-                    needle_gap_dp.adds_mut(&gap_cost);
-                    needle_gap_length.add_mut(&ones);
+    Box::new(res.into_iter())
+}
 
-                    // gap in haystack
-                    <$jewel>::adds(&dp2, &start_gap_cost, &mut haystack_gap);
-                    <$jewel>::double_min_length(
-                        &haystack_gap,
-                        &mut haystack_gap_dp,
-                        &length2,
-                        &mut haystack_gap_length,
-                    );
-                    haystack_gap_dp.shift_left_1_mut(); // zeros are shifted in
+/// Splits the text `haystack` at approximate matches of the pattern `delimiter`, and returns
+/// the segments between them.
+///
+/// Matches with a Levenshtein distance less than or equal to `k` are chosen greedily so they do
+/// not overlap: matches with a lower cost are chosen first, and ties are broken by choosing the
+/// match that starts (and then ends) first.
+/// Like `str::split`, the segments before the first match and after the last match are always
+/// returned, and empty segments between adjacent matches are preserved, so there is always one
+/// more segment than the number of matches.
+/// If `delimiter` is empty, then `haystack` is returned as the only segment.
+/// Internally, this will call `levenshtein_search_simd_with_opts`.
+/// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `haystack` - text string (slice)
+/// * `delimiter` - pattern string (slice)
+/// * `k` - number of edits allowed
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let segments = fuzzy_split(b"a<XX>b<XY>c<YX><XX>", b"<XX>", 1);
+///
+/// assert!(segments == vec![&b"a"[..], b"b", b"c", b"", b""]);
+/// ```
+pub fn fuzzy_split<'a>(haystack: &'a [u8], delimiter: &[u8], k: u32) -> Vec<&'a [u8]> {
+    let mut matches: Vec<Match> = levenshtein_search_simd_with_opts(
+        delimiter,
+        haystack,
+        k,
+        SearchType::All,
+        LEVENSHTEIN_COSTS,
+        false,
+    )
+    .filter(|m| m.start < m.end)
+    .collect();
+    matches.sort_unstable_by_key(|m| (m.k, m.start, m.end));
+
+    // chosen matches, keyed by their start positions
+    let mut chosen: BTreeMap<usize, usize> = BTreeMap::new();
+
+    for m in matches {
+        // only the closest chosen matches on either side can overlap
+        let overlaps_prev = match chosen.range(..=m.start).next_back() {
+            Some((_, &end)) => end > m.start,
+            None => false,
+        };
+        let overlaps_next = match chosen.range(m.start..).next() {
+            Some((&start, _)) => start < m.end,
+            None => false,
+        };
 
-                    if anchored {
-                        // dp2 is one diagonal behind the current i
-                        haystack_gap_dp.insert_last_0(cmp::min(
-                            (i as u32) * (costs.gap_cost as u32) + costs.start_gap_cost as u32,
-                            k + 1,
-                        ));
-                    } else {
-                        haystack_gap_dp.insert_last_0(costs.start_gap_cost as u32);
-                    }
+        if !overlaps_prev && !overlaps_next {
+            chosen.insert(m.start, m.end);
+        }
+    }
 
-                    haystack_gap_dp.adds_mut(&gap_cost);
-                    haystack_gap_length.shift_left_1_mut(); // zeros are shifted in
+    let mut res = Vec::with_capacity(chosen.len() + 1);
+    let mut prev_end = 0;
 
-                    if allow_transpose {
-                        <$jewel>::shift_left_1(&match_mask0, &mut transpose); // reuse transpose, shift in zeros
-                        transpose.and_mut(&match_mask0);
-                        // ensure that current matches are excluded
-                        <$jewel>::andnot(&match_mask1, &transpose, &mut match_mask0); // reuse match_mask0 to represent transpose mask
-                        dp0.shift_left_2_mut();
+    for (start, end) in chosen {
+        res.push(&haystack[prev_end..start]);
+        prev_end = end;
+    }
 
-                        if anchored && i > 3 {
-                            // dp0 is four diagonals behind the current i
-                            dp0.insert_last_1(cmp::min(
-                                (i as u32 - 3) * (costs.gap_cost as u32)
-                                    + costs.start_gap_cost as u32,
-                                k + 1,
-                            ));
-                        }
+    res.push(&haystack[prev_end..]);
+    res
+}
 
-                        length0.shift_left_2_mut();
-                        <$jewel>::adds(&dp0, &transpose_cost, &mut transpose);
-                        <$jewel>::add(&length0, &twos, &mut transpose_length);
-                    }
+/// A needle that is prepared once for searching through many haystacks.
+///
+/// The needle is loaded into the Jewel vectors used by the SIMD search once, for each lane width
+/// (8, 16, and 32 bits) that may be selected. Each search picks the lane width based on `k`, like
+/// `levenshtein_search_simd_with_opts`, and then uses the preloaded vector instead of loading the
+/// needle again. For short needles, the match masks of the bit-parallel search are also built
+/// once.
+/// The byte histogram of the needle is precomputed too. Before each search, the histogram of the
+/// haystack is compared against it: every byte of the needle that occurs more often than in the
+/// whole haystack must be edited in any match, so haystacks that cannot possibly contain a match
+/// are rejected in linear time without running the dynamic programming search.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// # use triple_accel::levenshtein::*;
+/// let needle = CompiledNeedle::new(b"abcd");
+///
+/// assert!(needle.search(b"xxabxdxx", 1) == vec![Match{start: 2, end: 6, k: 1}]);
+/// assert!(needle.search(b"xxxxxxxx", 3).is_empty());
+/// ```
+pub struct CompiledNeedle {
+    needle: Vec<u8>,
+    histogram: ByteHistogram,
+    bitap_masks: Option<bitap::BitapMasks>,
+    #[cfg(all(
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            all(target_arch = "wasm32", target_feature = "simd128")
+        ),
+        not(feature = "pure-scalar")
+    ))]
+    needle_windows: Vec<Box<dyn core::any::Any + Send + Sync>>,
+}
 
-                    <$jewel>::triple_min_length(
-                        &sub,
-                        &needle_gap_dp,
-                        &haystack_gap_dp,
-                        &sub_length,
-                        &needle_gap_length,
-                        &haystack_gap_length,
-                        &mut dp0,
-                        &mut length0,
-                    );
+impl CompiledNeedle {
+    /// Create a new `CompiledNeedle` from the pattern string `needle`.
+    ///
+    /// # Arguments
+    /// * `needle` - pattern string (slice)
+    pub fn new(needle: &[u8]) -> Self {
+        Self {
+            needle: needle.to_vec(),
+            histogram: ByteHistogram::new(needle),
+            bitap_masks: if needle.len() <= BITAP_SEARCH_MAX_NEEDLE_LEN {
+                Some(bitap::BitapMasks::new(needle))
+            } else {
+                None
+            },
+            #[cfg(all(
+                any(
+                    target_arch = "x86",
+                    target_arch = "x86_64",
+                    all(target_arch = "wasm32", target_feature = "simd128")
+                ),
+                not(feature = "pure-scalar")
+            ))]
+            needle_windows: unsafe { load_needle_windows(needle) },
+        }
+    }
 
-                    if allow_transpose {
-                        // blend using transpose mask
-                        dp0.blendv_mut(&transpose, &match_mask0);
-                        length0.blendv_mut(&transpose_length, &match_mask0);
-                        mem::swap(&mut match_mask0, &mut match_mask1);
-                    }
+    /// Returns the pattern string.
+    pub fn needle(&self) -> &[u8] {
+        &self.needle
+    }
 
-                    mem::swap(&mut dp0, &mut dp_temp);
-                    mem::swap(&mut dp_temp, &mut dp1);
-                    mem::swap(&mut dp1, &mut dp2);
-                    mem::swap(&mut length0, &mut length_temp);
-                    mem::swap(&mut length_temp, &mut length1);
-                    mem::swap(&mut length1, &mut length2);
+    /// Returns all `Match`s with a Levenshtein distance less than or equal to `k` by searching
+    /// through the text `haystack`.
+    ///
+    /// This returns the same matches as `levenshtein_search_simd_with_opts` with
+    /// `SearchType::All`, `LEVENSHTEIN_COSTS`, and no anchoring.
+    ///
+    /// # Arguments
+    /// * `haystack` - text string (slice)
+    /// * `k` - number of edits allowed
+    #[must_use]
+    pub fn search(&self, haystack: &[u8], k: u32) -> Vec<Match> {
+        // the extra bytes in the needle are a lower bound for every substring of the haystack
+        let (extra_needle, _) = self.histogram.extra_counts(&ByteHistogram::new(haystack));
+
+        if extra_needle > k {
+            return Vec::new();
+        }
 
-                    i += 1;
+        levenshtein_search_simd_dispatch(
+            &self.needle,
+            haystack,
+            k,
+            SearchOpts {
+                search_type: SearchType::All,
+                costs: LEVENSHTEIN_COSTS,
+                anchored: false,
+                case_insensitive: false,
+                length_pref: LengthPreference::Longest,
+            },
+            Some(self),
+        )
+        .collect()
+    }
+}
 
-                    if i >= needle_len {
-                        let final_res = dp2.slow_extract(final_idx);
-                        let final_length = length2.slow_extract(final_idx) as usize;
+impl Clone for CompiledNeedle {
+    fn clone(&self) -> Self {
+        Self::new(&self.needle)
+    }
+}
 
-                        if final_res <= curr_k {
-                            let end_idx = i - needle_len;
+impl PartialEq for CompiledNeedle {
+    fn eq(&self, other: &Self) -> bool {
+        self.needle == other.needle
+    }
+}
 
-                            match search_type {
-                                // if we want the best, then we can shrink the k threshold
-                                SearchType::Best => curr_k = final_res,
-                                _ => (),
-                            }
+impl Eq for CompiledNeedle {}
 
-                            return Some((
-                                Match {
-                                    start: end_idx - final_length,
-                                    end: end_idx,
-                                    k: final_res,
-                                },
-                                curr_k,
-                            ));
-                        }
-                    }
-                }
+impl core::fmt::Debug for CompiledNeedle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CompiledNeedle")
+            .field("needle", &self.needle)
+            .finish()
+    }
+}
 
-                None
-            });
+/// Load the needle into the Jewel vector for each lane width that
+/// `levenshtein_search_simd_dispatch` may select for it, in the same order.
+#[cfg(all(
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        all(target_arch = "wasm32", target_feature = "simd128")
+    ),
+    not(feature = "pure-scalar")
+))]
+unsafe fn load_needle_windows(needle: &[u8]) -> Vec<Box<dyn core::any::Any + Send + Sync>> {
+    let mut windows: Vec<Box<dyn core::any::Any + Send + Sync>> = Vec::new();
+
+    if needle.is_empty() {
+        return windows;
+    }
 
-            if search_type == SearchType::Best {
-                // estimate the number of Matches
-                let mut res_vec = Vec::with_capacity((len - needle_len) / needle_len);
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if cfg!(feature = "jewel-avx512") && cfg!(feature = "jewel-8bit") && avx512_supported() {
+            windows.push(Box::new(load_needle_window::<Avx512Nx64x8>(needle, false)));
+        }
 
-                for m in res {
-                    match res_vec.len() {
-                        0 => res_vec.push(m.0),
-                        _ => {
-                            let last = res_vec.last_mut().unwrap();
+        if cfg!(feature = "jewel-avx") && avx2_supported() {
+            if cfg!(feature = "jewel-8bit") {
+                if needle.len() <= Avx1x32x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Avx1x32x8>(needle, false)));
+                } else if needle.len() <= Avx2x32x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Avx2x32x8>(needle, false)));
+                } else if needle.len() <= Avx4x32x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Avx4x32x8>(needle, false)));
+                } else if needle.len() <= Avx8x32x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Avx8x32x8>(needle, false)));
+                }
+            }
 
-                            // replace previous if fully overlapping
-                            if m.0.start <= last.start {
-                                *last = m.0;
-                            } else {
-                                res_vec.push(m.0);
-                            }
-                        }
-                    }
+            if cfg!(feature = "jewel-16bit") {
+                windows.push(Box::new(load_needle_window::<AvxNx16x16>(needle, false)));
+            }
 
-                    curr_k = m.1;
+            if cfg!(feature = "jewel-32bit") {
+                windows.push(Box::new(load_needle_window::<AvxNx8x32>(needle, false)));
+            }
+        } else if cfg!(feature = "jewel-sse") && sse41_supported() {
+            if cfg!(feature = "jewel-8bit") {
+                if needle.len() <= Sse1x16x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Sse1x16x8>(needle, false)));
+                } else if needle.len() <= Sse2x16x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Sse2x16x8>(needle, false)));
+                } else if needle.len() <= Sse4x16x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Sse4x16x8>(needle, false)));
+                } else if needle.len() <= Sse8x16x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Sse8x16x8>(needle, false)));
+                } else if needle.len() <= Sse16x16x8::static_upper_bound() {
+                    windows.push(Box::new(load_needle_window::<Sse16x16x8>(needle, false)));
                 }
+            }
 
-                // only retain matches with the lowest k
-                return Box::new(res_vec.into_iter().filter(move |m| m.k == curr_k));
+            if cfg!(feature = "jewel-16bit") {
+                windows.push(Box::new(load_needle_window::<SseNx8x16>(needle, false)));
             }
 
-            Box::new(res.map(|m| m.0))
+            if cfg!(feature = "jewel-32bit") {
+                windows.push(Box::new(load_needle_window::<SseNx4x32>(needle, false)));
+            }
         }
-    };
-}
+    }
 
-// duplicate functions for each Jewel vector type
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_1x32x8, Avx1x32x8, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_2x32x8, Avx2x32x8, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_4x32x8, Avx4x32x8, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_8x32x8, Avx8x32x8, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_nx16x16, AvxNx16x16, "avx2");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_avx_nx8x32, AvxNx8x32, "avx2");
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        if cfg!(feature = "jewel-wasm") && cfg!(feature = "jewel-8bit") {
+            windows.push(Box::new(load_needle_window::<WasmNx16x8>(needle, false)));
+        }
+    }
 
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_1x16x8, Sse1x16x8, "sse4.1");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_2x16x8, Sse2x16x8, "sse4.1");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_4x16x8, Sse4x16x8, "sse4.1");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_8x16x8, Sse8x16x8, "sse4.1");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(
-    levenshtein_search_simd_core_sse_16x16x8,
-    Sse16x16x8,
-    "sse4.1"
-);
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_nx8x16, SseNx8x16, "sse4.1");
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-create_levenshtein_search_simd_core!(levenshtein_search_simd_core_sse_nx4x32, SseNx4x32, "sse4.1");
+    windows
+}
 
-/// Returns an iterator over best `Match`s by searching through the text `haystack` for the
-/// pattern `needle` using SIMD acceleration.
+/// Returns all `Match`s with a Levenshtein distance less than or equal to `k` by searching
+/// through each of the texts in `haystacks` for the pattern `needle`, using SIMD acceleration.
 ///
-/// The best matches are the matches with the lowest Levenshtein distance.
-/// If multiple best matches end at the same position or fully overlap, then the longest match is chosen.
-/// If `needle` is empty, then no `Match`es are returned.
-/// Each returned `Match` requires at least half or more bytes of the `needle` to match
-/// somewhere in the `haystack`.
-/// Internally, this will call `levenshtein_search_simd`.
+/// This is useful for scanning many documents for the same query. The needle is compiled once
+/// into a `CompiledNeedle`, so haystacks that cannot contain a match are rejected with a cheap
+/// histogram check before running the search.
+/// The returned vectors of matches are in the same order as `haystacks`, and each one is the same
+/// as the result of `levenshtein_search_simd_with_opts` with `SearchType::All`,
+/// `LEVENSHTEIN_COSTS`, and no anchoring.
+/// If the `rayon` feature is enabled, then the haystacks are split across multiple threads.
+/// Otherwise, the haystacks are processed one by one on the current thread.
 /// If AVX2 or SSE4.1 is not supported, then this will automatically fall back to a scalar alternative.
 ///
 /// # Arguments
 /// * `needle` - pattern string (slice)
-/// * `haystack` - text string (slice)
+/// * `haystacks` - text strings (slice of slices)
+/// * `k` - number of edits allowed
 ///
 /// # Example
 /// ```
 /// # use triple_accel::*;
-/// let matches: Vec<Match> = levenshtein_search(b"abc", b"  abd").collect();
+/// # use triple_accel::levenshtein::*;
+/// let res = levenshtein_search_many(b"abcd", &[b"xxabxdxx", b"xxxxxxxx", b"abcd"], 1);
 ///
-/// assert!(matches == alloc::vec![Match{start: 2, end: 5, k: 1}]);
+/// assert!(res.len() == 3);
+/// assert!(res[0] == vec![Match{start: 2, end: 6, k: 1}]);
+/// assert!(res[1].is_empty());
+/// assert!(res[2] == vec![Match{start: 0, end: 3, k: 1}, Match{start: 0, end: 4, k: 0}]);
 /// ```
-pub fn levenshtein_search<'a>(
-    needle: &'a [u8],
-    haystack: &'a [u8],
-) -> Box<dyn Iterator<Item = Match> + 'a> {
-    levenshtein_search_simd(needle, haystack)
+pub fn levenshtein_search_many(needle: &[u8], haystacks: &[&[u8]], k: u32) -> Vec<Vec<Match>> {
+    let compiled = CompiledNeedle::new(needle);
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        haystacks
+            .par_iter()
+            .map(|haystack| compiled.search(haystack, k))
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut res = Vec::with_capacity(haystacks.len());
+
+        for haystack in haystacks {
+            res.push(compiled.search(haystack, k));
+        }
+
+        res
+    }
 }
@@ -11,9 +11,17 @@
 //! so the user knows exactly what to expect.
 //! Finally, this library should lead to performance boosts on both short and longer strings, so it
 //! can be used for a variety of tasks, from bioinformatics to natural language processing.
-//! `triple_accel` is very lightweight: it only has dependencies on other crates for benchmarking.
+//! `triple_accel` is very lightweight: it only has dependencies on other crates for benchmarking,
+//! except for the optional `rayon` dependency that is used to parallelize batch routines and the
+//! optional `serde` dependency that is used to serialize `Match` and `Edit`.
 //! It can be built on machines without CPUs that have AVX2 or SSE4.1 support. It can also run on
 //! machines without SIMD support by automatically using scalar alternatives.
+//! `triple_accel` is `no_std` compatible, as long as an allocator is available through `alloc`.
+//! To use it in a `no_std` environment, disable the default `std` feature flag.
+//! To build it without any `unsafe` code, for example for auditing, enable the `pure-scalar`
+//! feature flag, which compiles out all SIMD code and always uses the scalar routines.
+//! To memoize distances for workloads that compare the same pairs of strings many times, enable
+//! the `cache` feature flag and use `cache::DistanceCache`.
 //!
 //! ## Features
 //!
@@ -27,22 +35,35 @@
 //! At runtime, the implementation for a certain algorithm is selected based on CPU support, going
 //! down the list:
 //!
-//! 1. Vectorized implementation with 256-bit AVX vectors, if AVX2 is supported.
-//! 2. Vectorized implementation with 128-bit SSE vectors, if SSE4.1 is supported.
-//! 3. Scalar implementation.
+//! 1. Vectorized implementation with 512-bit AVX-512 vectors, if AVX-512BW is supported.
+//! 2. Vectorized implementation with 256-bit AVX vectors, if AVX2 is supported.
+//! 3. Vectorized implementation with 128-bit SSE vectors, if SSE4.1 is supported.
+//! 4. Scalar implementation.
 //!
 //! Currently, vectorized SIMD implementations are only available for x86 or x86-64 CPUs. However,
 //! after compiling this library on a machine that supports those SIMD intrinsics, the library can
 //! be used on other machines.
+//! On `wasm32` targets compiled with the `simd128` target feature (for example, with
+//! `RUSTFLAGS="-C target-feature=+simd128"`), a vectorized implementation with 128-bit WASM SIMD
+//! vectors is used instead. There is no runtime detection for WASM, so this is decided at compile time.
 //! Additionally, the internal data structure for storing vectors and the bit width of the values
 //! in the vectors are selected at runtime for maximum efficiency and accuracy, given the lengths
-//! of the input strings.
+//! of the input strings. The values saturate instead of overflowing, so 8-bit values are only used
+//! when the distance cannot reach 255, and wider values are used otherwise.
+//! For long strings, `levenshtein` tries 8-bit values first and only redoes the comparison with
+//! wider values if the distance turns out to be too large.
 //!
 //! ## Limitations
 //!
 //! Due to the use of SIMD intrinsics, only binary strings that are represented with `u8` bytes
 //! are supported. Unicode strings are not currently supported.
 //!
+//! Distances are not limited by the width of the SIMD lanes. The lanes are unsigned and saturating,
+//! and each routine picks 8-bit, 16-bit, or 32-bit lanes based on an upper bound on the distance,
+//! so it only uses narrow lanes when the distance cannot reach their maximum value. The maximum
+//! value of a lane is reserved to mark saturation, so the largest distance that can be computed
+//! exactly is `MAX_RELIABLE_DISTANCE`, which is `u32::MAX - 1`.
+//!
 //! ## Notation
 //!
 //! Quick notation notes that will often appear in the code/documentation:
@@ -111,35 +132,69 @@
 //! ```
 //! Don't let the name of the function fool you! `levenshtein_simd_k_with_opts` will still fall back to
 //! the scalar implementation if AVX2 or SSE4.1 support is not available. It just prefers to use SIMD
-//! where possible.
+//! where possible. To always use the scalar implementation, for example for reproducible benchmarks,
+//! call `levenshtein_scalar`, `hamming_scalar`, `levenshtein_search_scalar`, or
+//! `hamming_search_scalar` instead.
 //!
 //! For most common cases, the re-exported functions are enough, and the low level functions do not
 //! have to be used directly.
 
 #![no_std]
+#![cfg_attr(feature = "pure-scalar", forbid(unsafe_code))]
 
 #[cfg(feature = "std")]
 extern crate std;
 
-use core::{cmp, iter, mem, ptr};
+use core::{cmp, iter, mem};
 
 extern crate alloc;
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
 
+pub mod bitap;
+#[cfg(feature = "cache")]
+pub mod cache;
 pub mod hamming;
+#[cfg(not(feature = "pure-scalar"))]
 mod jewel;
 pub mod levenshtein;
 
 // re-export common functions
-pub use hamming::{hamming, hamming_search};
-pub use levenshtein::{levenshtein, levenshtein_exp, levenshtein_search, rdamerau, rdamerau_exp};
+pub use hamming::{
+    hamming, hamming_checked, hamming_scalar, hamming_search, hamming_search_scalar,
+    hamming_search_str,
+};
+pub use levenshtein::{
+    levenshtein, levenshtein_exp, levenshtein_scalar, levenshtein_search,
+    levenshtein_search_scalar, levenshtein_str, rdamerau, rdamerau_exp,
+};
 
 // some shared utility stuff below
 
+/// The largest distance that the SIMD routines can compute exactly.
+///
+/// The widest SIMD lanes hold 32-bit unsigned values, and the maximum value is reserved to mark
+/// saturation. Narrower 8-bit and 16-bit lanes are only used when the distance is guaranteed to
+/// fit, so they do not lower this limit. Any distance that fits in a `u32`, other than `u32::MAX`,
+/// is exact.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let a = vec![b'a'; 300];
+/// let b = vec![b'b'; 300];
+///
+/// // much larger than the maximum value of an 8-bit lane
+/// assert!(levenshtein(&a, &b) == 300);
+/// assert!(300 <= MAX_RELIABLE_DISTANCE);
+/// ```
+pub const MAX_RELIABLE_DISTANCE: u32 = u32::MAX - 1;
+
 /// A struct that describes a single matching location.
 ///
 /// This is usually returned as part of searching routines.
-#[derive(Debug, PartialEq)]
+#[must_use]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Match {
     /// The start index of the match (inclusive).
     pub start: usize,
@@ -149,10 +204,85 @@ pub struct Match {
     pub k: u32,
 }
 
+impl Match {
+    /// Returns the bytes of `haystack` that are covered by this match.
+    ///
+    /// # Arguments
+    /// * `haystack` - the text string (slice) that was searched to find this match
+    ///
+    /// # Panics
+    /// * If the match is out of bounds of `haystack`.
+    ///
+    /// # Example
+    /// ```
+    /// # use triple_accel::*;
+    /// let haystack = b"  abd";
+    /// let m = Match{start: 2, end: 5, k: 1};
+    ///
+    /// assert!(m.slice(haystack) == b"abd");
+    /// ```
+    pub fn slice<'a>(&self, haystack: &'a [u8]) -> &'a [u8] {
+        &haystack[self.start..self.end]
+    }
+
+    /// Returns the number of bytes of the haystack that are covered by this match.
+    ///
+    /// # Example
+    /// ```
+    /// # use triple_accel::*;
+    /// let m = Match{start: 2, end: 5, k: 1};
+    ///
+    /// assert!(m.len() == 3);
+    /// ```
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns the range of char indices in `haystack` that are covered by this match.
+    ///
+    /// The search routines return byte offsets, which do not line up with char positions when
+    /// `haystack` contains multibyte UTF-8 characters. This converts the byte offsets by counting
+    /// the chars that start before them.
+    /// If the start or end of the match falls inside a multibyte character, or past the end of
+    /// `haystack`, then `None` is returned.
+    ///
+    /// # Arguments
+    /// * `haystack` - the text string (&str) that was searched to find this match
+    ///
+    /// # Example
+    /// ```
+    /// # use triple_accel::*;
+    /// // 'é' is two bytes
+    /// let haystack = "café au lait";
+    /// let m = Match{start: 6, end: 8, k: 0};
+    ///
+    /// assert!(m.slice(haystack.as_bytes()) == b"au");
+    /// assert!(m.char_range(haystack) == Some(5..7));
+    /// assert!(Match{start: 4, end: 8, k: 0}.char_range(haystack) == None);
+    /// ```
+    pub fn char_range(&self, haystack: &str) -> Option<core::ops::Range<usize>> {
+        if self.start > self.end
+            || !haystack.is_char_boundary(self.start)
+            || !haystack.is_char_boundary(self.end)
+        {
+            return None;
+        }
+
+        let start = haystack[..self.start].chars().count();
+        let end = start + haystack[self.start..self.end].chars().count();
+        Some(start..end)
+    }
+}
+
 /// An enum describing possible edit operations.
 ///
 /// This is usually returned as part of the traceback for edit distance routines.
-#[derive(Debug, PartialEq)]
+/// With the `serde` feature, the variants are serialized as lowercase strings, like `"match"`
+/// and `"a_gap"`.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EditType {
     Match,
     Mismatch,
@@ -164,7 +294,8 @@ pub enum EditType {
 /// A struct representing a sequence of edits of the same type.
 ///
 /// This is returned in the run-length encoded traceback of edit distance routines.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edit {
     /// The type of edit operation.
     pub edit: EditType,
@@ -172,6 +303,24 @@ pub struct Edit {
     pub count: usize,
 }
 
+/// A struct representing a single edit operation and where it happened in both strings.
+///
+/// This is the expanded form of the run-length encoded traceback, returned by `edit_ops`.
+/// For matches, mismatches, and transposes, the indices point to the characters that are
+/// consumed in `a` and `b`; a transpose consumes two characters in each string. For gaps, the
+/// index in the string with the character points to that character and the other index is the
+/// gap position, which is the index of the next character in that string.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EditOp {
+    /// The type of edit operation.
+    pub edit: EditType,
+    /// The index in `a` of the edit.
+    pub a_idx: usize,
+    /// The index in `b` of the edit.
+    pub b_idx: usize,
+}
+
 /// An enum representing whether to return all matches or just the best matches.
 ///
 /// This is used as an argument for searching routines.
@@ -181,6 +330,264 @@ pub enum SearchType {
     Best,
 }
 
+/// An enum representing whether to prefer longer or shorter matches when there are multiple
+/// alignments with the same cost.
+///
+/// This is used as an argument for Levenshtein searching routines. For example, with the
+/// needle `abc`, the text `zbc` can be matched with one mismatch (`zbc`) or with one gap (`bc`).
+/// `Longest` chooses `zbc` and `Shortest` chooses `bc`.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LengthPreference {
+    Longest,
+    Shortest,
+}
+
+/// An enum representing how `merge_overlapping` combines matches that overlap.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MergePolicy {
+    /// Keep only the match with the lowest `k`. If there are ties, then the match that starts
+    /// first (and then ends first) is kept.
+    LowestK,
+    /// Replace the matches with a single match that spans all of them, with the lowest `k` of
+    /// the merged matches. Note that this `k` is not recalculated for the larger span.
+    Union,
+}
+
+/// An error returned when two strings that must have the same length do not.
+///
+/// This is returned by checked routines like `hamming_checked`, instead of panicking.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct LengthMismatch {
+    /// The length of the first string.
+    pub a_len: usize,
+    /// The length of the second string.
+    pub b_len: usize,
+}
+
+impl core::fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "length mismatch: first string has length {} but second string has length {}",
+            self.a_len, self.b_len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LengthMismatch {}
+
+/// A trait for string distance metrics, so generic code can be parameterized over the metric.
+///
+/// This is implemented by the unit structs `Hamming`, `Levenshtein`, `RDamerau`, and `Damerau`,
+/// which call the corresponding distance functions.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// fn closest<D: Distance>(metric: &D, query: &[u8], candidates: &[&[u8]]) -> usize {
+///     (0..candidates.len()).min_by_key(|&i| metric.distance(query, candidates[i])).unwrap()
+/// }
+///
+/// assert!(closest(&Levenshtein, b"abc", &[b"xyz", b"abcd", b"ab"]) == 1);
+/// assert!(closest(&RDamerau, b"abc", &[b"xyz", b"acb", b"ab"]) == 1);
+/// ```
+pub trait Distance {
+    /// Returns the distance between two strings.
+    ///
+    /// # Arguments
+    /// * `a` - first string (slice)
+    /// * `b` - second string (slice)
+    fn distance(&self, a: &[u8], b: &[u8]) -> u32;
+}
+
+/// A trait for string distance metrics that can stop early once the distance is known to be
+/// larger than a threshold.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// assert!(Levenshtein.distance_within(b"abc", b"abd", 1) == Some(1));
+/// assert!(Levenshtein.distance_within(b"abc", b"xyz", 2) == None);
+/// ```
+pub trait BoundedDistance: Distance {
+    /// Returns the distance between two strings if it is less than or equal to `k`, or `None`
+    /// otherwise.
+    ///
+    /// # Arguments
+    /// * `a` - first string (slice)
+    /// * `b` - second string (slice)
+    /// * `k` - maximum distance allowed
+    fn distance_within(&self, a: &[u8], b: &[u8], k: u32) -> Option<u32>;
+}
+
+/// The Hamming distance metric, which uses `hamming`.
+///
+/// Like `hamming`, this panics if the strings have different lengths.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Hamming;
+
+/// The Levenshtein distance metric, which uses `levenshtein` and `levenshtein_k`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Levenshtein;
+
+/// The restricted Damerau-Levenshtein distance metric, which uses `rdamerau`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct RDamerau;
+
+/// The unrestricted Damerau-Levenshtein distance metric, which uses
+/// `levenshtein::damerau_levenshtein`.
+///
+/// This is scalar only, so `RDamerau` is faster if restricted transpositions are enough.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Damerau;
+
+impl Distance for Hamming {
+    fn distance(&self, a: &[u8], b: &[u8]) -> u32 {
+        hamming(a, b)
+    }
+}
+
+impl BoundedDistance for Hamming {
+    fn distance_within(&self, a: &[u8], b: &[u8], k: u32) -> Option<u32> {
+        Some(hamming(a, b)).filter(|&dist| dist <= k)
+    }
+}
+
+impl Distance for Levenshtein {
+    fn distance(&self, a: &[u8], b: &[u8]) -> u32 {
+        levenshtein(a, b)
+    }
+}
+
+impl BoundedDistance for Levenshtein {
+    fn distance_within(&self, a: &[u8], b: &[u8], k: u32) -> Option<u32> {
+        levenshtein::levenshtein_k(a, b, k)
+    }
+}
+
+impl Distance for RDamerau {
+    fn distance(&self, a: &[u8], b: &[u8]) -> u32 {
+        rdamerau(a, b)
+    }
+}
+
+impl BoundedDistance for RDamerau {
+    fn distance_within(&self, a: &[u8], b: &[u8], k: u32) -> Option<u32> {
+        levenshtein::levenshtein_simd_k_with_opts(a, b, k, false, levenshtein::RDAMERAU_COSTS)
+            .map(|(dist, _)| dist)
+    }
+}
+
+impl Distance for Damerau {
+    fn distance(&self, a: &[u8], b: &[u8]) -> u32 {
+        levenshtein::damerau_levenshtein(a, b)
+    }
+}
+
+impl BoundedDistance for Damerau {
+    fn distance_within(&self, a: &[u8], b: &[u8], k: u32) -> Option<u32> {
+        Some(levenshtein::damerau_levenshtein(a, b)).filter(|&dist| dist <= k)
+    }
+}
+
+/// An enum describing the SIMD backend that is used for vectorized routines.
+///
+/// This is returned by `active_backend`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Backend {
+    /// Scalar routines, without SIMD.
+    Scalar,
+    /// 128-bit SSE4.1 vectors.
+    Sse41,
+    /// 256-bit AVX2 vectors.
+    Avx2,
+    /// 512-bit AVX-512BW vectors.
+    Avx512,
+    /// 128-bit WASM SIMD vectors.
+    Wasm,
+}
+
+/// Returns the SIMD backend that the Levenshtein routines select for the current CPU.
+///
+/// This goes through the same checks as the internal dispatcher, in the same order, so it can be
+/// logged to verify which backend is engaged. A backend is only selected if its `jewel-*` feature
+/// flag is enabled and the current CPU supports it. AVX-512, AVX2, and SSE4.1 support is detected
+/// at runtime if the `std` feature is enabled. Otherwise, this depends on whether the target
+/// features were enabled at compile time.
+/// If the `pure-scalar` feature is enabled, then this always returns `Backend::Scalar`.
+/// Note that for very large distances, a routine may fall back to a different backend than the
+/// one that is returned, since AVX-512 and WASM vectors are only used with 8-bit values.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let backend = active_backend();
+///
+/// if cfg!(feature = "pure-scalar") {
+///     assert!(backend == Backend::Scalar);
+/// }
+/// ```
+pub fn active_backend() -> Backend {
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        let any_bits = cfg!(feature = "jewel-8bit")
+            || cfg!(feature = "jewel-16bit")
+            || cfg!(feature = "jewel-32bit");
+
+        if cfg!(feature = "jewel-avx512")
+            && cfg!(feature = "jewel-8bit")
+            && jewel::avx512_supported()
+        {
+            return Backend::Avx512;
+        }
+
+        if cfg!(feature = "jewel-avx") && jewel::avx2_supported() {
+            if any_bits {
+                return Backend::Avx2;
+            }
+        } else if cfg!(feature = "jewel-sse") && jewel::sse41_supported() && any_bits {
+            return Backend::Sse41;
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        if cfg!(feature = "jewel-wasm") && cfg!(feature = "jewel-8bit") {
+            return Backend::Wasm;
+        }
+    }
+
+    Backend::Scalar
+}
+
+/// Returns the width in bytes of the SIMD vectors that the Levenshtein routines select for the
+/// current CPU.
+///
+/// This is 64 for AVX-512, 32 for AVX2, 16 for SSE4.1 and WASM, and 1 for scalar routines.
+/// It can be used to size buffers for `alloc_str` or the scratch APIs to a multiple of the
+/// vector width. This is based on `active_backend`, so the returned width may be different on
+/// other machines or with different feature flags, since CPU support is detected at runtime.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let lane_bytes = simd_lane_bytes();
+///
+/// assert!(lane_bytes.is_power_of_two());
+/// ```
+pub fn simd_lane_bytes() -> usize {
+    match active_backend() {
+        Backend::Avx512 => 64,
+        Backend::Avx2 => 32,
+        Backend::Sse41 | Backend::Wasm => 16,
+        Backend::Scalar => 1,
+    }
+}
+
 /// This creates a vector with the alignment and padding for `u128` values, and
 /// then convert it to a vector of `u8` values that is returned.
 ///
@@ -190,6 +597,8 @@ pub enum SearchType {
 /// The returned vector can be edited by copying `u8` values into it.
 /// However, do not do any operation (like `push`) that may cause the the vector to be
 /// reallocated.
+/// If the `pure-scalar` feature is enabled, then the vector is not aligned, since no routine
+/// reads it as `u128` or `u64` values.
 ///
 /// # Arguments
 /// * `len` - the length of the resulting array of u8 values
@@ -203,6 +612,19 @@ pub enum SearchType {
 /// ```
 #[inline]
 pub fn alloc_str(len: usize) -> Vec<u8> {
+    // without unsafe code, the vector cannot be reinterpreted, but no routine reads it as wider
+    // values either
+    #[cfg(feature = "pure-scalar")]
+    {
+        alloc::vec![0u8; len]
+    }
+
+    #[cfg(not(feature = "pure-scalar"))]
+    alloc_str_words(len)
+}
+
+#[cfg(not(feature = "pure-scalar"))]
+fn alloc_str_words(len: usize) -> Vec<u8> {
     let words_len = (len >> 4) + (if (len & 15) > 0 { 1 } else { 0 });
     let words = alloc::vec![0u128; words_len];
     let mut words = mem::ManuallyDrop::new(words);
@@ -235,9 +657,273 @@ pub fn alloc_str(len: usize) -> Vec<u8> {
 pub fn fill_str(dest: &mut [u8], src: &[u8]) {
     assert!(dest.len() >= src.len());
 
-    unsafe {
-        ptr::copy_nonoverlapping(src.as_ptr(), dest.as_mut_ptr(), src.len());
+    dest[..src.len()].copy_from_slice(src);
+}
+
+/// Merge matches whose spans overlap, so that each cluster of overlapping matches is collapsed
+/// into a single match.
+///
+/// Search routines often report many approximate matches around the same occurrence, with
+/// slightly different start or end positions. This sorts `matches` by their start and end
+/// indices, and then merges every match that overlaps with the previous ones into a cluster.
+/// Overlapping is transitive, so a chain of overlapping matches forms a single cluster, even if
+/// the first and last matches do not overlap each other. Matches that only touch (where one
+/// ends exactly where the next starts) are not merged.
+/// How each cluster is collapsed is determined by `policy`.
+///
+/// # Arguments
+/// * `matches` - matches to merge in place, which are left sorted by their start index
+/// * `policy` - whether to keep the match with the lowest `k`, or the union of the spans
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let mut matches = vec![Match{start: 3, end: 7, k: 1},
+///                        Match{start: 2, end: 6, k: 0},
+///                        Match{start: 10, end: 12, k: 2}];
+/// let mut union = vec![Match{start: 3, end: 7, k: 1},
+///                      Match{start: 2, end: 6, k: 0},
+///                      Match{start: 10, end: 12, k: 2}];
+///
+/// merge_overlapping(&mut matches, MergePolicy::LowestK);
+/// merge_overlapping(&mut union, MergePolicy::Union);
+///
+/// assert!(matches == vec![Match{start: 2, end: 6, k: 0}, Match{start: 10, end: 12, k: 2}]);
+/// assert!(union == vec![Match{start: 2, end: 7, k: 0}, Match{start: 10, end: 12, k: 2}]);
+/// ```
+pub fn merge_overlapping(matches: &mut Vec<Match>, policy: MergePolicy) {
+    matches.sort_unstable_by_key(|m| (m.start, m.end));
+
+    let mut res: Vec<Match> = Vec::with_capacity(matches.len());
+    // end of the span of the current cluster, which may be past the end of the kept match
+    let mut cluster_end = 0;
+
+    for m in matches.drain(..) {
+        match res.last_mut() {
+            Some(last) if m.start < cluster_end => {
+                cluster_end = cmp::max(cluster_end, m.end);
+
+                match policy {
+                    MergePolicy::LowestK => {
+                        if m.k < last.k {
+                            *last = m;
+                        }
+                    }
+                    MergePolicy::Union => {
+                        last.end = cmp::max(last.end, m.end);
+                        last.k = cmp::min(last.k, m.k);
+                    }
+                }
+            }
+            _ => {
+                cluster_end = m.end;
+                res.push(m);
+            }
+        }
+    }
+
+    *matches = res;
+}
+
+/// Returns the set of non-overlapping matches that covers the most bytes, which is useful for
+/// highlighting matches.
+///
+/// Unlike `merge_overlapping`, which collapses each cluster of overlapping matches into one match,
+/// this chooses the best subset of `matches` where no two matches overlap. The best subset has the
+/// largest total length of the spans, and if there are ties, then the lowest total `k`. This is
+/// solved exactly with dynamic programming over the matches sorted by their end indices (weighted
+/// interval scheduling), in `O(n log n)` time, so it may keep matches that a greedy choice would
+/// drop. Matches that only touch (where one ends exactly where the next starts) do not overlap.
+/// The returned matches are sorted by their start index.
+///
+/// # Arguments
+/// * `matches` - matches to choose from
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let matches = vec![Match{start: 0, end: 6, k: 1}, Match{start: 5, end: 10, k: 0},
+///                    Match{start: 9, end: 16, k: 1}];
+///
+/// // the match with the lowest k overlaps both of the others
+/// assert!(best_non_overlapping(matches) == vec![Match{start: 0, end: 6, k: 1},
+///                                                Match{start: 9, end: 16, k: 1}]);
+/// ```
+pub fn best_non_overlapping(mut matches: Vec<Match>) -> Vec<Match> {
+    matches.sort_unstable_by_key(|m| (m.end, m.start, m.k));
+
+    let ends: Vec<usize> = matches.iter().map(|m| m.end).collect();
+    // best (total length, total k) and whether the match was taken, using the first i matches
+    let mut best: Vec<(usize, u64)> = Vec::with_capacity(matches.len() + 1);
+    let mut taken = Vec::with_capacity(matches.len());
+    best.push((0, 0));
+
+    for (i, m) in matches.iter().enumerate() {
+        // number of matches that end before this match starts
+        let prev = ends[..i]
+            .binary_search_by(|&end| {
+                if end <= m.start {
+                    cmp::Ordering::Less
+                } else {
+                    cmp::Ordering::Greater
+                }
+            })
+            .unwrap_err();
+        let take = (best[prev].0 + (m.end - m.start), best[prev].1 + m.k as u64);
+        let skip = best[i];
+
+        if take.0 > skip.0 || (take.0 == skip.0 && take.1 < skip.1) {
+            best.push(take);
+            taken.push(Some(prev));
+        } else {
+            best.push(skip);
+            taken.push(None);
+        }
+    }
+
+    let mut res = Vec::new();
+    let mut i = matches.len();
+
+    while i > 0 {
+        match taken[i - 1] {
+            Some(prev) => {
+                res.push(matches[i - 1]);
+                i = prev;
+            }
+            None => i -= 1,
+        }
+    }
+
+    res.reverse();
+    res
+}
+
+/// Convert the ASCII uppercase letters A-Z in `buf` to lowercase in place, using SIMD acceleration.
+///
+/// All other bytes, including non-ASCII bytes, are left untouched. This gives the same result as
+/// `<[u8]>::make_ascii_lowercase`, but with AVX2, 32 bytes are folded at a time by masking the
+/// bytes that are in the range A-Z and flipping their case bit.
+/// If AVX2 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `buf` - the bytes to convert
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let mut buf = b"Hello, World!".to_vec();
+///
+/// ascii_to_lower_simd(&mut buf);
+///
+/// assert!(buf == b"hello, world!");
+/// ```
+pub fn ascii_to_lower_simd(buf: &mut [u8]) {
+    ascii_flip_case(buf, b'A');
+}
+
+/// Convert the ASCII lowercase letters a-z in `buf` to uppercase in place, using SIMD acceleration.
+///
+/// All other bytes, including non-ASCII bytes, are left untouched. This gives the same result as
+/// `<[u8]>::make_ascii_uppercase`, but with AVX2, 32 bytes are folded at a time by masking the
+/// bytes that are in the range a-z and flipping their case bit.
+/// If AVX2 is not supported, then this will automatically fall back to a scalar alternative.
+///
+/// # Arguments
+/// * `buf` - the bytes to convert
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let mut buf = b"Hello, World!".to_vec();
+///
+/// ascii_to_upper_simd(&mut buf);
+///
+/// assert!(buf == b"HELLO, WORLD!");
+/// ```
+pub fn ascii_to_upper_simd(buf: &mut [u8]) {
+    ascii_flip_case(buf, b'a');
+}
+
+/// Flip the case bit of every byte in the 26 letter range starting at `first`.
+fn ascii_flip_case(buf: &mut [u8], first: u8) {
+    #[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+    let buf = if cfg!(feature = "jewel-avx") {
+        let (simd, rem) = buf.split_at_mut(buf.len() & !31);
+        unsafe { ascii_flip_case_avx(simd, first) };
+        rem
+    } else {
+        buf
+    };
+
+    for c in buf.iter_mut() {
+        if c.wrapping_sub(first) < 26 {
+            *c ^= 0x20;
+        }
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(feature = "pure-scalar")))]
+#[target_feature(enable = "avx2")]
+unsafe fn ascii_flip_case_avx(buf: &mut [u8], first: u8) {
+    use core::arch::x86_64::*;
+
+    // shift the letters to the bottom of the signed range, so a single signed comparison checks
+    // whether a byte is a letter
+    let shift = _mm256_set1_epi8(0x80u8.wrapping_sub(first) as i8);
+    let limit = _mm256_set1_epi8((0x80u8 + 26) as i8);
+    let case_bit = _mm256_set1_epi8(0x20);
+
+    for chunk in buf.chunks_exact_mut(32) {
+        let ptr = chunk.as_mut_ptr() as *mut __m256i;
+        let v = _mm256_loadu_si256(ptr);
+        let letters = _mm256_cmpgt_epi8(limit, _mm256_add_epi8(v, shift));
+        let res = _mm256_xor_si256(v, _mm256_and_si256(letters, case_bit));
+        _mm256_storeu_si256(ptr, res);
+    }
+}
+
+/// Returns the number of positions where the bytes of two equal-length buffers differ, using SIMD
+/// acceleration.
+///
+/// This is a plain count of differing bytes, without going through any distance routine.
+/// There are no constraints on how `a` and `b` are aligned and padded.
+/// With AVX2 or SSE4.1, the matching bytes are counted in 8-bit counters that are periodically
+/// summed into wider counters. Otherwise, this will automatically fall back to a scalar
+/// alternative.
+///
+/// # Arguments
+/// * `a` - first buffer (slice)
+/// * `b` - second buffer (slice)
+///
+/// # Panics
+/// * If the length of `a` does not equal the length of `b`.
+///
+/// # Example
+/// ```
+/// # use triple_accel::*;
+/// let count = byte_mismatches(b"abcdef", b"abxdyf");
+///
+/// assert!(count == 2);
+/// ```
+#[must_use]
+pub fn byte_mismatches(a: &[u8], b: &[u8]) -> u32 {
+    assert!(a.len() == b.len());
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(feature = "pure-scalar")
+    ))]
+    {
+        use jewel::HammingJewel;
+
+        if cfg!(feature = "jewel-avx") {
+            return unsafe { jewel::Avx::count_mismatches(a.as_ptr(), b.as_ptr(), a.len(), false) };
+        } else if cfg!(feature = "jewel-sse") {
+            return unsafe { jewel::Sse::count_mismatches(a.as_ptr(), b.as_ptr(), a.len(), false) };
+        }
     }
+
+    a.iter().zip(b).filter(|(x, y)| x != y).count() as u32
 }
 
 fn check_no_null_bytes(s: &[u8]) {
@@ -0,0 +1,82 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use triple_accel::*;
+use triple_accel::hamming::*;
+use triple_accel::levenshtein::*;
+use rand::prelude::*;
+
+// counts the live and peak number of bytes allocated through the global allocator
+struct CountingAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(live, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// returns the peak number of extra bytes that are allocated while running f
+fn peak_alloc<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let start = LIVE.load(Ordering::SeqCst);
+    PEAK.store(start, Ordering::SeqCst);
+    let res = f();
+    (res, PEAK.load(Ordering::SeqCst) - start)
+}
+
+// only one test in this file, so that allocations from other tests running in parallel are not counted
+#[test]
+fn test_search_bounded_alloc() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let haystack: Vec<u8> = (0..(1 << 20)).map(|_| b"ACGT"[rng.gen_range(0, 4)]).collect();
+    let needle = b"ACGTTGCA";
+    // the haystack is 1 MiB, so anything proportional to it would be far larger than this
+    let limit = 1 << 16;
+
+    let (count, peak) = peak_alloc(|| levenshtein_search_simd_with_opts(needle, &haystack, 1, SearchType::All, LEVENSHTEIN_COSTS, false).count());
+    assert!(count > 0);
+    assert!(peak < limit);
+
+    let (count, peak) = peak_alloc(|| levenshtein_search(needle, &haystack).count());
+    assert!(count > 0);
+    assert!(peak < limit);
+
+    let (count, peak) = peak_alloc(|| hamming_search_simd_with_opts(needle, &haystack, 1, SearchType::All).count());
+    assert!(count > 0);
+    assert!(peak < limit);
+
+    let (count, peak) = peak_alloc(|| hamming_search_simd_with_opts(needle, &haystack, 2, SearchType::Best).count());
+    assert!(count > 0);
+    assert!(peak < limit);
+
+    // a distance into a caller-supplied buffer must not allocate at all
+    #[repr(align(32))]
+    struct Aligned([u8; 4096]);
+    let mut scratch = Aligned([0u8; 4096]);
+    let (dist, peak) = peak_alloc(|| levenshtein_into(&haystack[..1000], &haystack[500..1500], &mut scratch.0));
+    assert!(dist == levenshtein(&haystack[..1000], &haystack[500..1500]));
+    assert!(peak == 0);
+
+    // the scalar distance only keeps one row for the shorter string, instead of a matrix
+    let a = &haystack[..10_000];
+    let mut b = haystack[200_000..201_000].to_vec();
+    b.extend_from_slice(&a[1000..]);
+    let expected = levenshtein_exp(a, &b);
+    let (dist, peak) = peak_alloc(|| levenshtein_scalar(a, &b));
+    assert!(dist == expected);
+    assert!(peak <= 10_001 * 4);
+    let (dist, peak) = peak_alloc(|| levenshtein_scalar(&b[..9000], a));
+    assert!(dist == levenshtein_exp(a, &b[..9000]));
+    assert!(peak <= 9_001 * 4);
+}
@@ -1,6 +1,12 @@
 use triple_accel::*;
+use triple_accel::bitap::*;
 use triple_accel::hamming::*;
 use triple_accel::levenshtein::*;
+use rand::prelude::*;
+
+fn rand_dna(len: usize, rng: &mut StdRng) -> Vec<u8> {
+    (0..len).map(|_| b"ACGT"[rng.gen_range(0, 4)]).collect()
+}
 
 #[test]
 fn test_basic_hamming_naive() {
@@ -97,6 +103,50 @@ fn test_basic_hamming_simd_parallel() {
     assert!(dist == 0);
 }
 
+#[test]
+fn test_basic_hamming_normalized() {
+    let dist = hamming_normalized(b"abcd", b"abdd");
+    assert!(dist == 0.25);
+
+    let dist = hamming_normalized(b"abcd", b"wxyz");
+    assert!(dist == 1.0);
+
+    let dist = hamming_normalized(b"", b"");
+    assert!(dist == 0.0);
+}
+
+#[test]
+fn test_basic_hamming_identity() {
+    let a = vec![b'A'; 100];
+    let mut b = a.clone();
+    assert!(hamming_identity(&a, &b) == 1.0);
+
+    b[42] = b'C';
+    assert!(hamming_identity(&a, &b) == 0.99);
+
+    assert!(hamming_identity(b"abcd", b"wxyz") == 0.0);
+    assert!(hamming_identity(b"", b"") == 1.0);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_identity_length_mismatch() {
+    let _ = hamming_identity(b"abc", b"ab");
+}
+
+#[test]
+fn test_basic_hamming_batch() {
+    let a = b"abcaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let candidates: Vec<&[u8]> = vec![b"abdaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", b"abcaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", b"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb", b"abcaaaaaaaaaaaaaaaaaaaaaaaaaaaaab"];
+    let res = hamming_batch(a, &candidates);
+    let seq: Vec<u32> = candidates.iter().map(|b| hamming(a, b)).collect();
+    assert!(res == seq);
+    assert!(res == vec![1, 0, 32, 1]);
+
+    let res = hamming_batch(a, &[]);
+    assert!(res.is_empty());
+}
+
 #[test]
 fn test_basic_levenshtein_naive() {
     let a1 = b"abcde";
@@ -250,6 +300,83 @@ fn test_basic_levenshtein_exp() {
     assert!(res == 1);
 }
 
+#[test]
+fn test_basic_levenshtein_normalized() {
+    let dist = levenshtein_normalized(b"abcd", b"abc");
+    assert!(dist == 0.25);
+    assert!(levenshtein_similarity(b"abcd", b"abc") == 0.75);
+
+    let dist = levenshtein_normalized(b"abc", b"");
+    assert!(dist == 1.0);
+    assert!(levenshtein_similarity(b"abc", b"") == 0.0);
+
+    let dist = levenshtein_normalized(b"", b"");
+    assert!(dist == 0.0);
+    assert!(levenshtein_similarity(b"", b"") == 1.0);
+}
+
+#[test]
+fn test_basic_combined_score() {
+    let pairs: [(&[u8], &[u8]); 4] = [(b"abcd", b"bcda"), (b"kitten", b"sittin"), (b"abcd", b"abc"), (b"", b"")];
+
+    for &(a, b) in pairs.iter() {
+        assert!(combined_score(a, b, 0.0) == levenshtein_normalized(a, b));
+
+        if a.len() == b.len() {
+            assert!(combined_score(a, b, 1.0) == hamming_normalized(a, b));
+        } else {
+            assert!(combined_score(a, b, 1.0) == levenshtein_normalized(a, b));
+        }
+    }
+
+    assert!(combined_score(b"abcd", b"bcda", 0.5) == 0.75);
+    assert!(combined_score(b"abcd", b"bcda", 0.25) == 0.625);
+    assert!(combined_score(b"abcd", b"abc", 0.5) == 0.25);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_combined_score_invalid_weight() {
+    let _ = combined_score(b"abc", b"abd", 1.5);
+}
+
+#[test]
+fn test_basic_levenshtein_batch() {
+    let a = b"abcde";
+    let candidates: Vec<&[u8]> = vec![b" ab cde", b"", b"abcdee", b"acde", b"abcde", b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx"];
+    let res = levenshtein_batch(a, &candidates);
+    let seq: Vec<u32> = candidates.iter().map(|b| levenshtein(a, b)).collect();
+    assert!(res == seq);
+    assert!(res == vec![2, 5, 1, 1, 0, 40]);
+
+    let res = levenshtein_batch(a, &[]);
+    assert!(res.is_empty());
+}
+
+#[test]
+fn test_basic_nearest() {
+    assert!(nearest(b"abc", &[]) == None);
+    assert!(nearest(b"", &[b"ab", b"a"]) == Some((1, 1)));
+    assert!(nearest(b"abc", &[b"xyz", b"abc", b"abc"]) == Some((1, 0)));
+
+    // same argmin as a brute force scan, with the first index on ties
+    let mut rng = StdRng::seed_from_u64(4242);
+
+    for _ in 0..50 {
+        let words: Vec<Vec<u8>> = (0..30).map(|_| {
+            let len = rng.gen_range(0, 20);
+            (0..len).map(|_| b'a' + rng.gen_range(0, 4)).collect()
+        }).collect();
+        let dict: Vec<&[u8]> = words.iter().map(|w| w.as_slice()).collect();
+        let query: Vec<u8> = (0..rng.gen_range(0, 20)).map(|_| b'a' + rng.gen_range(0, 4)).collect();
+
+        let dists: Vec<u32> = dict.iter().map(|b| levenshtein_naive(&query, b)).collect();
+        let min = *dists.iter().min().unwrap();
+        let idx = dists.iter().position(|&d| d == min).unwrap();
+        assert!(nearest(&query, &dict) == Some((idx, min)));
+    }
+}
+
 #[test]
 fn test_basic_rdamerau() {
     let a1 = b"abcde";
@@ -814,3 +941,2246 @@ fn test_basic_levenshtein_search_simd() {
     assert!(res == vec![Match{start: 0, end: 0, k: 0}, Match{start: 0, end: 1, k: 1}, Match{start: 0, end: 2, k: 2}]);
 }
 
+#[test]
+fn test_basic_case_insensitive() {
+    assert!(hamming_ci(b"HELLO", b"hello") == 0);
+    assert!(hamming_ci(b"@[", b"`{") == 2);
+    assert!(levenshtein_ci(b"HELLO", b"hello") == 0);
+    assert!(levenshtein_ci(b"HeLLo", b"hell") == 1);
+    assert!(levenshtein_ci(b"@[", b"`{") == 2);
+
+    let a1 = b"ABCDEFGHIJKLMNOPQRSTUVWXYZABCDEFGHIJKLMNOPQRSTUVWXYZABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let b1 = b"abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz";
+    assert!(levenshtein_ci(a1, b1) == 0);
+    assert!(levenshtein(a1, b1) == 78);
+
+    let a2 = [b'A'; 300];
+    let b2 = [b'a'; 299];
+    assert!(levenshtein_ci(&a2, &b2) == 1);
+
+    let mut res: Vec<Match> = hamming_search_ci_with_opts(b"HELLO", b"hello", 0, SearchType::All).collect();
+    assert!(res == vec![Match{start: 0, end: 5, k: 0}]);
+    res = hamming_search_ci(b"abc", b"  ABC  abb").collect();
+    assert!(res == vec![Match{start: 2, end: 5, k: 0}]);
+
+    res = levenshtein_search_ci_with_opts(b"HELLO", b"hello", 0, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res == vec![Match{start: 0, end: 5, k: 0}]);
+    res = levenshtein_search_ci(b"abc", b"  ABC  abb").collect();
+    assert!(res == vec![Match{start: 2, end: 5, k: 0}]);
+    res = levenshtein_search_ci_with_opts(b"@", b"`", 0, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res.is_empty());
+
+    let needle = [b'X'; 100];
+    let mut haystack = vec![b' '; 50];
+    haystack.extend_from_slice(&[b'x'; 100]);
+    res = levenshtein_search_ci_with_opts(&needle, &haystack, 0, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res == vec![Match{start: 50, end: 150, k: 0}]);
+}
+
+#[test]
+fn test_basic_hamming_ci_simd() {
+    let mut rng = StdRng::seed_from_u64(1729);
+    // letters in both cases, and the bytes next to the letter ranges
+    let alphabet = b"aAbBzZ@[`{";
+
+    // lengths that cross the vector boundaries and the periodic refresh of the 8-bit counters
+    for &len in [1usize, 15, 16, 17, 31, 32, 33, 100, 8160, 8200].iter() {
+        let a: Vec<u8> = (0..len).map(|_| alphabet[rng.gen_range(0, alphabet.len())]).collect();
+        let b: Vec<u8> = (0..len).map(|_| alphabet[rng.gen_range(0, alphabet.len())]).collect();
+        assert!(hamming_ci(&a, &b) == hamming_naive(&a.to_ascii_lowercase(), &b.to_ascii_lowercase()));
+
+        let needle = &a[..len.min(40)];
+        let needle_lower = needle.to_ascii_lowercase();
+        let haystack_lower = b.to_ascii_lowercase();
+
+        for &k in [0u32, 3, 20].iter() {
+            for &search_type in [SearchType::All, SearchType::Best].iter() {
+                let res: Vec<Match> = hamming_search_ci_with_opts(needle, &b, k, search_type).collect();
+                let expected: Vec<Match> = hamming_search_naive_with_opts(&needle_lower, &haystack_lower, k, search_type).collect();
+                assert!(res == expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_latin1_ci() {
+    // "CAFÉ" and "café" in Latin-1
+    assert!(levenshtein_latin1_ci(b"CAF\xC9", b"caf\xE9") == 0);
+    assert!(levenshtein_ci(b"CAF\xC9", b"caf\xE9") == 1);
+    // "ÀÞ" and "àþ"
+    assert!(levenshtein_latin1_ci(b"\xC0\xDE", b"\xE0\xFE") == 0);
+    // the multiplication and division signs are not letters
+    assert!(levenshtein_latin1_ci(b"\xD7", b"\xF7") == 1);
+    // bytes below A and above Z are not folded, and lowercase letters stay the same
+    assert!(levenshtein_latin1_ci(b"@[\xBF", b"`{\xDF") == 3);
+    assert!(levenshtein_latin1_ci(b"stra\xDFe", b"STRA\xDFE") == 0);
+    assert!(levenshtein_latin1_ci(b"", b"\xC9") == 1);
+
+    // long strings go through the wider Jewel vectors
+    let fold = |s: &[u8]| -> Vec<u8> { s.iter().map(|&c| match c { b'A'..=b'Z' | 0xC0..=0xD6 | 0xD8..=0xDE => c + 0x20, _ => c }).collect() };
+    let alphabet = b"aAzZ\xC0\xE0\xC9\xE9\xD7\xF7\xDE\xFE\xDF@";
+    let mut rng = StdRng::seed_from_u64(571);
+
+    for _ in 0..50 {
+        let a: Vec<u8> = (0..rng.gen_range(0, 400)).map(|_| alphabet[rng.gen_range(0, alphabet.len())]).collect();
+        let b: Vec<u8> = (0..rng.gen_range(0, 400)).map(|_| alphabet[rng.gen_range(0, alphabet.len())]).collect();
+        assert!(levenshtein_latin1_ci(&a, &b) == levenshtein_naive(&fold(&a), &fold(&b)));
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_normalized_ws() {
+    assert!(levenshtein_normalized_ws(b"a  b", b"a b") == 0);
+    assert!(levenshtein_normalized_ws(b"a\tb", b"a b") == 0);
+    assert!(levenshtein_normalized_ws(b"a \t\r\n b", b"a\tb") == 0);
+    assert!(levenshtein_normalized_ws(b"  a b  ", b"a b") == 0);
+    assert!(levenshtein_normalized_ws(b"ab", b"a b") == 1);
+    assert!(levenshtein_normalized_ws(b"a  b", b"a  c") == 1);
+
+    // all whitespace is the same as an empty string
+    assert!(levenshtein_normalized_ws(b" \t\n ", b"") == 0);
+    assert!(levenshtein_normalized_ws(b" \t\n ", b"  ") == 0);
+    assert!(levenshtein_normalized_ws(b"   ", b" abc ") == 3);
+    assert!(levenshtein_normalized_ws(b"", b"") == 0);
+}
+
+#[test]
+fn test_basic_levenshtein_search_best() {
+    let needle = b"abcde";
+    let haystack = b"  abxde  abcdd  abcd  xbcde  bcdea";
+
+    for max_k in 0..6 {
+        let all: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, max_k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        let min_k = all.iter().map(|m| m.k).min();
+        let expected = min_k.map(|k| all.into_iter().filter(|m| m.k == k).min_by_key(|m| (m.start, std::cmp::Reverse(m.end))).unwrap());
+        assert!(levenshtein_search_best(needle, haystack, max_k) == expected);
+    }
+
+    assert!(levenshtein_search_best(needle, haystack, 0) == None);
+    assert!(levenshtein_search_best(needle, haystack, 1) == Some(Match{start: 2, end: 7, k: 1}));
+    assert!(levenshtein_search_best(b"abc", b"xabcxabcx", 1) == Some(Match{start: 1, end: 4, k: 0}));
+    assert!(levenshtein_search_best(b"", b"abc", 1) == None);
+
+    // longer needles and larger bounds, so the SIMD search is used instead of the bit-parallel one
+    let mut rng = StdRng::seed_from_u64(520);
+
+    for _ in 0..50 {
+        let needle = rand_dna(rng.gen_range(5, 50), &mut rng);
+        let haystack = rand_dna(rng.gen_range(0, 300), &mut rng);
+
+        for &max_k in [0, 2, 10, 40].iter() {
+            let all: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, max_k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            let min_k = all.iter().map(|m| m.k).min();
+            let expected = min_k.map(|k| all.into_iter().filter(|m| m.k == k).min_by_key(|m| (m.start, std::cmp::Reverse(m.end))).unwrap());
+            assert!(levenshtein_search_best(&needle, &haystack, max_k) == expected);
+        }
+    }
+}
+
+#[test]
+fn test_basic_hamming_search_rev() {
+    let matches: Vec<Match> = hamming_search_rev(b"abc", b"xxabcxxabc").collect();
+    assert!(matches == vec![Match{start: 7, end: 10, k: 0}, Match{start: 2, end: 5, k: 0}]);
+
+    let mut forward: Vec<Match> = hamming_search_simd_with_opts(b"abc", b"xxabcxxabd", 2, SearchType::All).collect();
+    let reverse: Vec<Match> = hamming_search_rev_with_opts(b"abc", b"xxabcxxabd", 2, SearchType::All).collect();
+    forward.reverse();
+    assert!(forward == reverse);
+
+    let matches: Vec<Match> = hamming_search_rev(b"abc", b"ab").collect();
+    assert!(matches == vec![]);
+
+    // long haystacks go through both the vector and the scalar positions
+    let mut rng = StdRng::seed_from_u64(1);
+
+    for _ in 0..100 {
+        let needle_len = rng.gen_range(1, 40);
+        let haystack_len = rng.gen_range(needle_len, 200);
+        let needle = rand_dna(needle_len, &mut rng);
+        let haystack = rand_dna(haystack_len, &mut rng);
+        let k = rng.gen_range(0, needle_len as u32 + 1);
+
+        for &search_type in &[SearchType::All, SearchType::Best] {
+            let mut forward: Vec<Match> = hamming_search_naive_with_opts(&needle, &haystack, k, search_type).collect();
+            let reverse: Vec<Match> = hamming_search_rev_with_opts(&needle, &haystack, k, search_type).collect();
+            forward.reverse();
+            assert!(forward == reverse);
+        }
+    }
+}
+
+#[test]
+fn test_basic_lcs() {
+    assert!(lcs(b"ABCBDAB", b"BDCAB") == 4);
+    assert!(lcs(b"abc", b"xyz") == 0);
+    assert!(lcs(b"", b"abc") == 0);
+    assert!(lcs(b"abc", b"abc") == 3);
+
+    let edits = lcs_align(b"ABCBDAB", b"BDCAB");
+    assert!(edits.iter().all(|e| e.edit == EditType::Match || e.edit == EditType::AGap || e.edit == EditType::BGap));
+    assert!(edits.iter().filter(|e| e.edit == EditType::Match).map(|e| e.count).sum::<usize>() == 4);
+
+    let edits = lcs_align(b"abc", b"xyz");
+    assert!(edits.iter().all(|e| e.edit != EditType::Match));
+    assert!(edits.iter().map(|e| e.count).sum::<usize>() == 6);
+}
+
+#[test]
+fn test_basic_levenshtein_prefix() {
+    assert!(levenshtein_prefix(b"app", b"application") == 0);
+    assert!(levenshtein_prefix(b"apl", b"application") == 1);
+    assert!(levenshtein_prefix(b"application", b"app") == 8);
+    assert!(levenshtein_prefix(b"", b"abc") == 0);
+    assert!(levenshtein_prefix(b"abc", b"") == 3);
+    assert!(levenshtein_prefix(b"xyz", b"abcdefgh") == 3);
+
+    let needle = b"abcde";
+    let haystack = b"abxdeabcde";
+    let expected = (0..=haystack.len()).map(|j| levenshtein_naive(needle, &haystack[..j])).min().unwrap();
+    assert!(levenshtein_prefix(needle, haystack) == expected);
+}
+
+#[test]
+fn test_basic_levenshtein_match_prefix() {
+    assert!(levenshtein_match_prefix(b"git", b"gti status", 1) == Some(Match{start: 0, end: 2, k: 1}));
+    assert!(levenshtein_match_prefix(b"git", b"git status", 1) == Some(Match{start: 0, end: 3, k: 0}));
+    assert!(levenshtein_match_prefix(b"git", b"xx git", 1) == None);
+    assert!(levenshtein_match_prefix(b"", b"abc", 0) == Some(Match{start: 0, end: 0, k: 0}));
+    assert!(levenshtein_match_prefix(b"abc", b"", 3) == Some(Match{start: 0, end: 0, k: 3}));
+
+    // the longest prefix is chosen on ties
+    assert!(levenshtein_match_prefix(b"abc", b"abxc", 1) == Some(Match{start: 0, end: 4, k: 1}));
+
+    let needle = b"abcde";
+    let haystack = b"abxdeabcde";
+    let expected = (0..=haystack.len()).map(|j| levenshtein_naive(needle, &haystack[..j])).min().unwrap();
+    assert!(levenshtein_match_prefix(needle, haystack, 5).unwrap().k == expected);
+}
+
+#[test]
+fn test_basic_search_count() {
+    let haystack = b"abcxabdxabcxxyzx";
+
+    for k in 0..3 {
+        assert!(levenshtein_search_count(b"abc", haystack, k) == levenshtein_search_simd_with_opts(b"abc", haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).count());
+        assert!(hamming_search_count(b"abc", haystack, k) == hamming_search_simd_with_opts(b"abc", haystack, k, SearchType::All).count());
+    }
+
+    assert!(levenshtein_search_count(b"abc", haystack, 0) == 2);
+    assert!(hamming_search_count(b"abc", haystack, 1) == 3);
+    assert!(levenshtein_search_count(b"", haystack, 1) == 0);
+    assert!(hamming_search_count(b"abc", b"ab", 1) == 0);
+}
+
+#[test]
+fn test_basic_levenshtein_contains() {
+    let haystack = b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxhelloxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxhxllo";
+
+    assert!(levenshtein_contains(b"hello", haystack, 0));
+    assert!(levenshtein_contains(b"hxllo", haystack, 0));
+    assert!(levenshtein_contains(b"hallo", haystack, 1));
+    assert!(!levenshtein_contains(b"hallo", haystack, 0));
+    assert!(!levenshtein_contains(b"world", haystack, 2));
+    assert!(levenshtein_contains(b"hello", b"hell", 1));
+    assert!(!levenshtein_contains(b"hello", b"", 4));
+    assert!(!levenshtein_contains(b"", b"", 0));
+    assert!(!levenshtein_contains(b"", haystack, 1));
+
+    for k in 0..4 {
+        for needle in [&b"hello"[..], b"help", b"yellow", b"xxh", b""].iter() {
+            let expected = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).count() > 0;
+            assert!(levenshtein_contains(needle, haystack, k) == expected);
+        }
+    }
+}
+
+#[test]
+fn test_basic_hamming_bits() {
+    let a: Vec<u8> = (0..200u32).map(|i| (i.wrapping_mul(97) ^ (i >> 3)) as u8).collect();
+    let b: Vec<u8> = (0..200u32).map(|i| (i.wrapping_mul(31) + 7) as u8).collect();
+
+    for len in 0..a.len() {
+        let expected: u32 = a[..len].iter().zip(&b[..len]).map(|(x, y)| (x ^ y).count_ones()).sum();
+        assert!(hamming_bits(&a[..len], &b[..len]) == expected);
+    }
+
+    assert!(hamming_bits(&[0xffu8; 64], &[0u8; 64]) == 512);
+    assert!(hamming_bits(b"", b"") == 0);
+}
+
+#[test]
+fn test_basic_hamming_mismatches() {
+    let a: Vec<u8> = (0..200u32).map(|i| (i % 7) as u8).collect();
+    // isolated mismatches, a run of consecutive mismatches that crosses a 32-byte block, and a fully mismatched block
+    let mut b = a.clone();
+    for &i in &[0usize, 5, 31, 32, 63, 199] { b[i] ^= 1; }
+    for i in 60..70 { b[i] ^= 2; }
+    for i in 128..160 { b[i] ^= 4; }
+
+    for len in 0..a.len() {
+        let expected: Vec<usize> = (0..len).filter(|&i| a[i] != b[i]).collect();
+        let res = hamming_mismatches(&a[..len], &b[..len]);
+        assert!(res == expected);
+        assert!(res.len() as u32 == hamming(&a[..len], &b[..len]));
+    }
+
+    assert!(hamming_mismatches(b"", b"").is_empty());
+    assert!(hamming_mismatches(&a, &a).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_mismatches_len() {
+    hamming_mismatches(b"abc", b"ab");
+}
+
+#[test]
+fn test_basic_bytes_eq() {
+    let a: Vec<u8> = (0..150u32).map(|i| (i % 7) as u8).collect();
+
+    for len in 0..a.len() {
+        assert!(bytes_eq(&a[..len], &a[..len]));
+
+        // a single mismatch at the start, in the middle of a 32-byte block, and at the end
+        for &i in &[0, len / 2, len.saturating_sub(1)] {
+            if i < len {
+                let mut b = a[..len].to_vec();
+                b[i] ^= 0x80;
+                assert!(!bytes_eq(&a[..len], &b));
+                assert!(bytes_eq(&a[..len], &b) == (a[..len] == b[..]));
+            }
+        }
+
+        if len > 0 {
+            assert!(!bytes_eq(&a[..len], &a[..len - 1]));
+        }
+    }
+
+    assert!(bytes_eq(b"", b""));
+}
+
+#[test]
+fn test_basic_hamming_stream() {
+    let a: Vec<u8> = (0..100u32).map(|i| (i % 7) as u8 + b'a').collect();
+    let b: Vec<u8> = (0..100u32).map(|i| (i % 5) as u8 + b'a').collect();
+    let expected = hamming(&a, &b);
+
+    for &(a_size, b_size) in [(1, 1), (3, 5), (32, 7), (33, 64), (100, 1), (17, 100)].iter() {
+        let mut stream = HammingStream::new();
+        let mut a_chunks = a.chunks(a_size);
+        let mut b_chunks = b.chunks(b_size);
+
+        loop {
+            let a_chunk = a_chunks.next().unwrap_or(&[]);
+            let b_chunk = b_chunks.next().unwrap_or(&[]);
+
+            if a_chunk.is_empty() && b_chunk.is_empty() {
+                break;
+            }
+
+            stream.update(a_chunk, b_chunk);
+        }
+
+        assert!(stream.finish() == expected);
+    }
+
+    assert!(HammingStream::new().finish() == 0);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_stream_length_mismatch() {
+    let mut stream = HammingStream::new();
+    stream.update(b"abc", b"ab");
+    stream.finish();
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_basic_serde() {
+    let m = Match{start: 2, end: 5, k: 1};
+    let json = serde_json::to_string(&m).unwrap();
+    assert!(json == r#"{"start":2,"end":5,"k":1}"#);
+    assert!(serde_json::from_str::<Match>(&json).unwrap() == m);
+
+    let edits = vec![Edit{edit: EditType::Match, count: 2}, Edit{edit: EditType::Mismatch, count: 1},
+                     Edit{edit: EditType::AGap, count: 3}, Edit{edit: EditType::BGap, count: 4}];
+    let json = serde_json::to_string(&edits).unwrap();
+    assert!(json == r#"[{"edit":"match","count":2},{"edit":"mismatch","count":1},{"edit":"a_gap","count":3},{"edit":"b_gap","count":4}]"#);
+    assert!(serde_json::from_str::<Vec<Edit>>(&json).unwrap() == edits);
+}
+
+#[test]
+fn test_basic_levenshtein_search_iter() {
+    let needle = b"abcde";
+    let haystack = b"  abxde  abcdd  abcd  xbcde  bcdea";
+
+    for k in 0..4 {
+        let eager: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        let lazy: Vec<Match> = levenshtein_search_iter(needle, haystack, k).collect();
+        assert!(lazy == eager);
+
+        let first: Vec<Match> = levenshtein_search_iter(needle, haystack, k).take(2).collect();
+        assert!(first.len() == eager.len().min(2));
+        assert!(first[..] == eager[..first.len()]);
+    }
+
+    assert!(levenshtein_search_iter(b"", haystack, 1).next() == None);
+}
+
+#[test]
+fn test_basic_damerau_search() {
+    let haystack = b"did you recieve it? please receive the package";
+
+    let matches = damerau_search(b"recieve", haystack, 1);
+    assert!(matches.contains(&Match{start: 8, end: 15, k: 0}));
+    assert!(matches.contains(&Match{start: 27, end: 34, k: 1}));
+    assert!(matches.iter().all(|m| m.k <= 1));
+    assert!(damerau_search(b"recieve", haystack, 0) == vec![Match{start: 8, end: 15, k: 0}]);
+    assert!(levenshtein_search_simd_with_opts(b"recieve", b"please receive the package", 1, SearchType::All, LEVENSHTEIN_COSTS, false).next() == None);
+    assert!(damerau_search(b"", haystack, 1) == vec![]);
+}
+
+#[test]
+fn test_basic_match_slice() {
+    let haystack = b"abcxxxabd";
+    let matches: Vec<Match> = levenshtein_search(b"abc", haystack).collect();
+
+    // matches at both boundaries of the haystack
+    assert!(matches == vec![Match{start: 0, end: 3, k: 0}]);
+    assert!(matches[0].slice(haystack) == b"abc");
+    assert!(matches[0].len() == 3);
+
+    let matches: Vec<Match> = hamming_search_simd_with_opts(b"abc", haystack, 1, SearchType::All).collect();
+    let last = matches.last().unwrap();
+    assert!(last.end == haystack.len());
+    assert!(last.slice(haystack) == b"abd");
+    assert!(last.len() == 3);
+
+    let empty = Match{start: 9, end: 9, k: 3};
+    assert!(empty.slice(haystack) == b"");
+    assert!(empty.len() == 0);
+}
+
+
+#[test]
+fn test_basic_scalar() {
+    let a = b"the quick brown fox jumps over the lazy dog, again and again and again";
+    let b = b"the quikc brown fx jumps over teh lazy dog, again and agian and again!";
+    assert!(levenshtein_scalar(a, b) == levenshtein(a, b));
+    assert!(levenshtein_scalar(b"abc", b"") == 3);
+    assert!(levenshtein_scalar(b"", b"") == 0);
+
+    let b = b"the quikc brown fx! jumps over teh lazy dog, again and agian and again";
+    assert!(hamming_scalar(a, b) == hamming(a, b));
+
+    let needle = b"jumps over";
+    let simd: Vec<Match> = levenshtein_search(needle, b).collect();
+    let scalar: Vec<Match> = levenshtein_search_scalar(needle, b).collect();
+    assert!(simd == scalar);
+    assert!(scalar == vec![Match{start: 20, end: 30, k: 0}]);
+
+    let simd: Vec<Match> = hamming_search(b"agian", a).collect();
+    let scalar: Vec<Match> = hamming_search_scalar(b"agian", a).collect();
+    assert!(simd == scalar);
+}
+
+#[test]
+fn test_basic_levenshtein_matrix() {
+    let mut cost = [[1u8; 256]; 256];
+    for i in 0..256 {
+        cost[i][i] = 0;
+    }
+    // 'a' -> 'b' is free, but not the other way around
+    cost[b'a' as usize][b'b' as usize] = 0;
+
+    assert!(levenshtein_matrix(b"aaaa", b"bbbb", &cost, 1) == 0);
+    assert!(levenshtein_matrix(b"bbbb", b"aaaa", &cost, 1) == 4);
+    assert!(levenshtein_matrix(b"abcd", b"bbd", &cost, 1) == 1);
+    assert!(levenshtein_matrix(b"", b"abc", &cost, 2) == 6);
+    assert!(levenshtein_matrix(b"abc", b"", &cost, 2) == 6);
+
+    // uniform costs are the same as regular Levenshtein distance
+    let mut uniform = [[1u8; 256]; 256];
+    for i in 0..256 {
+        uniform[i][i] = 0;
+    }
+    let a = b"the quick brown fox jumps over the lazy dog";
+    let b = b"teh quikc brown fx jumped over a lazy dog!";
+    assert!(levenshtein_matrix(a, b, &uniform, 1) == levenshtein(a, b));
+
+    for &(a, b) in &[(&b"aaab"[..], &b"bbba"[..]), (b"abcabc", b"bcbc"), (b"xyz", b"abxbz")] {
+        assert!(levenshtein_matrix(a, b, &cost, 1) == levenshtein_matrix_naive(a, b, &cost, 1));
+        assert!(levenshtein_matrix(a, b, &cost, 3) == levenshtein_matrix_naive(a, b, &cost, 3));
+    }
+}
+
+#[test]
+fn test_basic_osa_damerau_levenshtein() {
+    // the classic case where the two variants differ
+    assert!(osa_distance(b"CA", b"ABC") == 3);
+    assert!(damerau_levenshtein(b"CA", b"ABC") == 2);
+
+    assert!(osa_distance(b"abc", b"acb") == 1);
+    assert!(damerau_levenshtein(b"abc", b"acb") == 1);
+    assert!(osa_distance(b"ab", b"aba") == 1);
+    assert!(damerau_levenshtein(b"ab", b"aba") == 1);
+    assert!(osa_distance(b"", b"abc") == 3);
+    assert!(damerau_levenshtein(b"", b"abc") == 3);
+    assert!(damerau_levenshtein(b"abc", b"") == 3);
+    assert!(damerau_levenshtein(b"", b"") == 0);
+
+    // without transpositions, both are the same as Levenshtein distance
+    let a = b"kitten sitting";
+    let b = b"sitting kitten";
+    assert!(osa_distance(a, b) <= levenshtein(a, b));
+    assert!(damerau_levenshtein(a, b) <= osa_distance(a, b));
+    assert!(damerau_levenshtein(b"kitten", b"sitting") == 3);
+    assert!(osa_distance(b"kitten", b"sitting") == 3);
+}
+
+#[test]
+fn test_basic_damerau_weighted() {
+    // an expensive transpose is never better than two substitutions
+    assert!(damerau_weighted(b"ab", b"ba", EditCosts::new(1, 1, 0, Some(3))) == 2);
+    assert!(damerau_weighted(b"ab", b"ba", EditCosts::new(1, 1, 0, Some(1))) == 1);
+    assert!(damerau_weighted(b"ab", b"ba", EditCosts::new(2, 2, 0, Some(3))) == 3);
+    assert!(damerau_weighted(b"ab", b"ba", EditCosts::new(2, 2, 0, None)) == 4);
+
+    // gaps between the swapped characters are weighted too
+    assert!(damerau_weighted(b"CA", b"ABC", EditCosts::new(2, 2, 0, Some(3))) == 5);
+    assert!(damerau_weighted(b"CA", b"ABC", EditCosts::new(3, 1, 0, Some(1))) == 2);
+    assert!(damerau_weighted(b"", b"abc", EditCosts::new(2, 3, 0, Some(1))) == 9);
+
+    let words: [&[u8]; 8] = [b"", b"a", b"ab", b"ba", b"abc", b"cab", b"kitten", b"sitting"];
+
+    for &x in &words {
+        for &y in &words {
+            assert!(damerau_weighted(x, y, RDAMERAU_COSTS) == damerau_levenshtein(x, y));
+            assert!(damerau_weighted(x, y, LEVENSHTEIN_COSTS) == levenshtein(x, y));
+            let costs = EditCosts::new(3, 2, 0, None);
+            assert!(damerau_weighted(x, y, costs) == levenshtein_naive_with_opts(x, y, false, costs).0);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_basic_damerau_weighted_affine() {
+    let _ = damerau_weighted(b"ab", b"ba", EditCosts::new(1, 1, 1, Some(1)));
+}
+
+#[test]
+fn test_basic_levenshtein_search_ranked() {
+    let haystack = b"abxde abcde abcxe abcde";
+    let matches = levenshtein_search_ranked(b"abcde", haystack, 2);
+
+    assert!(matches.windows(2).all(|w| (w[0].k, w[0].start) <= (w[1].k, w[1].start)));
+    assert!(matches[0] == Match{start: 6, end: 11, k: 0});
+    assert!(matches[1] == Match{start: 18, end: 23, k: 0});
+    assert!(matches.contains(&Match{start: 0, end: 5, k: 1}));
+    assert!(matches.contains(&Match{start: 12, end: 17, k: 1}));
+    assert!(matches.iter().position(|m| m.start == 0 && m.k == 1) < matches.iter().position(|m| m.start == 12 && m.k == 1));
+
+    // same matches as the unsorted search
+    let mut unsorted: Vec<Match> = levenshtein_search_simd_with_opts(b"abcde", haystack, 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(unsorted.len() == matches.len());
+    unsorted.sort_by_key(|m| (m.k, m.start));
+    assert!(unsorted == matches);
+
+    assert!(levenshtein_search_ranked(b"", haystack, 2) == vec![]);
+}
+
+#[test]
+fn test_basic_levenshtein_k() {
+    assert!(levenshtein_k(b"abc", b"ab", 1) == Some(1));
+    assert!(levenshtein_k(b"abc", b"ab", 0) == None);
+    assert!(levenshtein_k(b"abcdef", b"ab", 3) == None);
+    assert!(levenshtein_k(b"", b"abc", 3) == Some(3));
+    assert!(levenshtein_k(b"", b"abc", 2) == None);
+    assert!(levenshtein_k(b"", b"", 0) == Some(0));
+
+    // the prefilter never rejects a pair within k
+    let words: [&[u8]; 8] = [b"", b"a", b"ab", b"abc", b"abcd", b"bcd", b"abcdefgh", b"xbcdefg"];
+
+    for &a in &words {
+        for &b in &words {
+            let dist = levenshtein_naive(a, b);
+
+            for k in 0..10 {
+                let expected = if dist <= k { Some(dist) } else { None };
+                assert!(levenshtein_k(a, b, k) == expected);
+                assert!(levenshtein_simd_k(a, b, k) == expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_qgram_profile() {
+    let a = QgramProfile::new(b"abcab", 2);
+    assert!(a.q() == 2);
+    assert!(a.distance(&a) == 0);
+    assert!(a.distance(&QgramProfile::new(b"abcab", 2)) == 0);
+    assert!(a.distance(&QgramProfile::new(b"", 2)) == 4);
+    assert!(QgramProfile::new(b"a", 2).distance(&QgramProfile::new(b"", 2)) == 0);
+
+    // the bound never exceeds the true edit distance
+    let words: [&[u8]; 10] = [b"", b"a", b"ab", b"abc", b"abcabc", b"bca", b"aaaa", b"kitten", b"sitting", b"mitten"];
+
+    for q in 1..4 {
+        for &x in &words {
+            for &y in &words {
+                let px = QgramProfile::new(x, q);
+                let py = QgramProfile::new(y, q);
+                assert!(px.distance(&py) == py.distance(&px));
+                assert!(px.lower_bound(&py) <= levenshtein(x, y) as usize);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_histogram_bound() {
+    let a = ByteHistogram::new(b"abcab");
+    assert!(a.count(b'a') == 2 && a.count(b'c') == 1 && a.count(b'z') == 0);
+    assert!(a.distance(&a) == 0);
+    assert!(a.lower_bound(&ByteHistogram::new(b"")) == 5);
+    assert!(histogram_bound(b"", b"") == 0);
+    assert!(histogram_bound(b"listen", b"silent") == 0);
+
+    // the bound never exceeds the true edit distance, across random pairs
+    let mut rng = StdRng::seed_from_u64(777);
+
+    for _ in 0..500 {
+        let a_len = rng.gen_range(0, 40);
+        let b_len = rng.gen_range(0, 40);
+        let alphabet = rng.gen_range(1, 7);
+        let a: Vec<u8> = (0..a_len).map(|_| b'a' + rng.gen_range(0, alphabet)).collect();
+        let b: Vec<u8> = (0..b_len).map(|_| b'a' + rng.gen_range(0, alphabet)).collect();
+        let bound = histogram_bound(&a, &b);
+        assert!(bound <= levenshtein(&a, &b));
+        assert!(bound >= (a_len as i64 - b_len as i64).unsigned_abs() as u32);
+        assert!(bound * 2 >= ByteHistogram::new(&a).distance(&ByteHistogram::new(&b)));
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_search_profile() {
+    let needle = b"abcde";
+    let haystack = b"xxabxdexxabcdexxxbcdxxabcdeabcdf";
+    let profile = levenshtein_search_profile(needle, haystack);
+    assert!(profile.len() == haystack.len());
+
+    // thresholding the profile gives the same end positions as the search
+    for k in 0..4 {
+        let ends: Vec<usize> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).map(|m| m.end).collect();
+        let expected: Vec<usize> = (0..haystack.len()).filter(|&i| profile[i] <= k).map(|i| i + 1).collect();
+        assert!(ends == expected);
+
+        let ks: Vec<u32> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).map(|m| m.k).collect();
+        let expected: Vec<u32> = profile.iter().cloned().filter(|&d| d <= k).collect();
+        assert!(ks == expected);
+    }
+
+    // the best matches are at the minimum of the profile
+    let min = *profile.iter().min().unwrap();
+    let best: Vec<usize> = levenshtein_search(needle, haystack).map(|m| m.end).collect();
+    let expected: Vec<usize> = (0..haystack.len()).filter(|&i| profile[i] == min).map(|i| i + 1).collect();
+    assert!(best == expected);
+
+    assert!(levenshtein_search_profile(b"", b"abc") == vec![0, 0, 0]);
+    assert!(levenshtein_search_profile(b"abc", b"").is_empty());
+}
+
+#[test]
+fn test_basic_hamming_search_wildcard() {
+    let matches: Vec<Match> = hamming_search_wildcard(b"a\0c", b"axc", 0, 0u8).collect();
+    assert!(matches == vec![Match{start: 0, end: 3, k: 0}]);
+
+    let matches: Vec<Match> = hamming_search_wildcard(b"a\0c", b"abc axc ayd", 1, 0u8).collect();
+    assert!(matches == vec![Match{start: 0, end: 3, k: 0}, Match{start: 4, end: 7, k: 0}, Match{start: 8, end: 11, k: 1}]);
+
+    // wildcards in the haystack are not special
+    let matches: Vec<Match> = hamming_search_wildcard(b"abc", b"a\0c", 0, 0u8).collect();
+    assert!(matches == vec![]);
+
+    let matches: Vec<Match> = hamming_search_wildcard(b"a?c?", b"abcd", 0, b'?').collect();
+    assert!(matches == vec![Match{start: 0, end: 4, k: 0}]);
+    assert!(hamming_search_wildcard(b"", b"abc", 0, 0u8).next() == None);
+    assert!(hamming_search_wildcard(b"abcd", b"abc", 4, 0u8).next() == None);
+
+    // long haystacks go through both the vector and the scalar positions
+    let mut rng = StdRng::seed_from_u64(542);
+
+    for _ in 0..100 {
+        let needle_len = rng.gen_range(1, 40);
+        let haystack_len = rng.gen_range(needle_len, 200);
+        let mut needle = rand_dna(needle_len, &mut rng);
+        let mut haystack = rand_dna(haystack_len, &mut rng);
+        let k = rng.gen_range(0, needle_len as u32 + 1);
+
+        for _ in 0..rng.gen_range(0, needle_len + 1) {
+            needle[rng.gen_range(0, needle_len)] = 0u8;
+        }
+
+        // null bytes in the haystack fall back to the scalar search
+        if rng.gen_range(0, 4) == 0 {
+            haystack[rng.gen_range(0, haystack_len)] = 0u8;
+        }
+
+        let expected: Vec<Match> = (0..=haystack_len - needle_len)
+            .map(|i| Match{start: i, end: i + needle_len, k: needle.iter().zip(&haystack[i..]).filter(|&(&n, &h)| n != 0u8 && n != h).count() as u32})
+            .filter(|m| m.k <= k)
+            .collect();
+        let matches: Vec<Match> = hamming_search_wildcard(&needle, &haystack, k, 0u8).collect();
+        assert!(matches == expected);
+    }
+}
+
+#[test]
+fn test_basic_hamming_search_multi() {
+    let needles: [&[u8]; 3] = [b"abab", b"baba", b"abcb"];
+    let matches = hamming_search_multi(&needles, b"ababab", 1);
+
+    // ordered by start, then by needle index
+    assert!(matches == vec![(0, Match{start: 0, end: 4, k: 0}), (2, Match{start: 0, end: 4, k: 1}),
+                            (1, Match{start: 1, end: 5, k: 0}),
+                            (0, Match{start: 2, end: 6, k: 0}), (2, Match{start: 2, end: 6, k: 1})]);
+
+    // same matches as searching for each needle separately
+    for (i, &needle) in needles.iter().enumerate() {
+        let single: Vec<Match> = hamming_search_naive_with_opts(needle, b"ababab", 1, SearchType::All).collect();
+        let multi: Vec<&Match> = matches.iter().filter(|m| m.0 == i).map(|m| &m.1).collect();
+        assert!(single.iter().collect::<Vec<&Match>>() == multi);
+    }
+
+    assert!(hamming_search_multi(&[], b"ababab", 1) == vec![]);
+    assert!(hamming_search_multi(&[b"abcdefgh"], b"ababab", 1) == vec![]);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_search_multi_length_mismatch() {
+    hamming_search_multi(&[b"abc", b"ab"], b"ababab", 1);
+}
+
+#[test]
+fn test_basic_levenshtein_by() {
+    let digits = |x: u8, y: u8| x == y || (x.is_ascii_digit() && y.is_ascii_digit());
+    assert!(levenshtein_by(b"a1b", b"a7b", digits) == 0);
+    assert!(levenshtein_by(b"a1b", b"a7c", digits) == 1);
+    assert!(levenshtein_by(b"2024-01-01", b"1999-12-31", digits) == 0);
+    assert!(levenshtein_by(b"a1b", b"ab", digits) == 1);
+    assert!(levenshtein_by(b"", b"123", digits) == 3);
+
+    // plain equality is the same as Levenshtein distance
+    let a = b"the quick brown fox";
+    let b = b"teh quick brwn fox!";
+    assert!(levenshtein_by(a, b, |x, y| x == y) == levenshtein(a, b));
+}
+
+#[test]
+fn test_basic_levenshtein_with_scratch() {
+    let mut scratch = Scratch::new();
+
+    // grow, shrink, and switch between Jewel vector types with the same scratch
+    for &len in &[0usize, 3, 40, 5, 300, 17, 1000, 2, 64] {
+        let a: Vec<u8> = (0..len).map(|i| b"acgt"[(i * 7 + i / 3) % 4]).collect();
+        let b: Vec<u8> = (0..len + len / 5).map(|i| b"acgt"[(i * 5 + i / 2) % 4]).collect();
+        assert!(levenshtein_with_scratch(&a, &b, &mut scratch) == levenshtein(&a, &b));
+        assert!(levenshtein_with_scratch(&b, &a, &mut scratch) == levenshtein_naive(&b, &a));
+    }
+
+    assert!(levenshtein_with_scratch(b"abc", b"", &mut scratch) == 3);
+}
+
+#[repr(align(32))]
+struct AlignedScratch([u8; 4096]);
+
+#[test]
+fn test_basic_levenshtein_into() {
+    let mut scratch = AlignedScratch([0u8; 4096]);
+
+    for &len in &[0usize, 3, 40, 5, 300, 17, 800, 2, 64] {
+        let a: Vec<u8> = (0..len).map(|i| b"acgt"[(i * 7 + i / 3) % 4]).collect();
+        let b: Vec<u8> = (0..len + len / 5).map(|i| b"acgt"[(i * 5 + i / 2) % 4]).collect();
+        assert!(levenshtein_scratch_len(a.len(), b.len()) <= scratch.0.len());
+        assert!(levenshtein_into(&a, &b, &mut scratch.0) == levenshtein(&a, &b));
+        assert!(levenshtein_into(&b, &a, &mut scratch.0) == levenshtein(&b, &a));
+    }
+
+    // the buffer only needs to fit the shorter string
+    let long = [b'a'; 10000];
+    assert!(levenshtein_into(&long, b"ab", &mut scratch.0[..levenshtein_scratch_len(10000, 2)]) == 9999);
+    assert!(levenshtein_into(b"", b"", &mut scratch.0[..4]) == 0);
+}
+
+#[test]
+#[should_panic(expected = "scratch buffer has 8 bytes, but 16 bytes are needed")]
+fn test_basic_levenshtein_into_too_small() {
+    let mut scratch = AlignedScratch([0u8; 4096]);
+    let _ = levenshtein_into(b"abc", b"abcd", &mut scratch.0[..8]);
+}
+
+#[test]
+#[should_panic(expected = "scratch buffer is not aligned to 32 bytes")]
+fn test_basic_levenshtein_into_misaligned() {
+    let mut scratch = AlignedScratch([0u8; 4096]);
+    let _ = levenshtein_into(b"abc", b"abcd", &mut scratch.0[4..]);
+}
+
+#[test]
+fn test_basic_levenshtein_equal_length() {
+    // identical strings
+    assert!(levenshtein(b"", b"") == 0);
+    assert!(levenshtein(b"abcdefgh", b"abcdefgh") == 0);
+    assert!(levenshtein_k(b"abcdefgh", b"abcdefgh", 0) == Some(0));
+
+    // single substitution
+    assert!(levenshtein(b"abcdefgh", b"abcdxfgh") == 1);
+    assert!(levenshtein_k(b"abcdefgh", b"abcdxfgh", 1) == Some(1));
+    assert!(levenshtein_k(b"abcdefgh", b"abcdxfgh", 0) == None);
+
+    // the Hamming distance is only an upper bound, so a shift is cheaper than it suggests
+    assert!(hamming(b"abcdefgh", b"bcdefgha") == 8);
+    assert!(levenshtein(b"abcdefgh", b"bcdefgha") == 2);
+    assert!(levenshtein_k(b"abcdefgh", b"bcdefgha", 2) == Some(2));
+    assert!(levenshtein_k(b"abcdefgh", b"bcdefgha", 1) == None);
+    assert!(levenshtein_k(b"abcdefgh", b"bcdefgha", 100) == Some(2));
+}
+
+#[test]
+fn test_basic_edits_to_cigar() {
+    let edits = vec![Edit{edit: EditType::Match, count: 3}, Edit{edit: EditType::Mismatch, count: 1}, Edit{edit: EditType::AGap, count: 2}];
+    assert!(edits_to_cigar(&edits, true) == "3=1X2I");
+    assert!(edits_to_cigar(&edits, false) == "4M2I");
+
+    let edits = vec![Edit{edit: EditType::Match, count: 2}, Edit{edit: EditType::Transpose, count: 1}, Edit{edit: EditType::BGap, count: 0}, Edit{edit: EditType::Match, count: 1}, Edit{edit: EditType::BGap, count: 1}];
+    assert!(edits_to_cigar(&edits, true) == "2=2X1=1D");
+    assert!(edits_to_cigar(&edits, false) == "5M1D");
+
+    assert!(edits_to_cigar(&[], true) == "");
+
+    let (_, edits) = levenshtein_simd_k_with_opts(b"abcde", b"abxdeff", u32::MAX, true, LEVENSHTEIN_COSTS).unwrap();
+    assert!(edits_to_cigar(&edits.unwrap(), true) == "2=1X2=2I");
+}
+
+#[test]
+fn test_basic_levenshtein_search_ratio() {
+    // a 0.8 threshold on a 10 character needle allows 2 edits
+    let needle = b"abcdefghij";
+    let haystack = b"xxabcdeXghYjxx";
+    let matches = levenshtein_search_ratio(needle, haystack, 0.8);
+    let expected: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(matches == expected);
+    assert!(matches.iter().any(|m| m.k == 2 && m.start == 2 && m.end == 12));
+    assert!(matches.iter().all(|m| m.k <= 2));
+
+    assert!(levenshtein_search_ratio(needle, haystack, 0.9).is_empty());
+    assert!(levenshtein_search_ratio(needle, b"abcdefghij", 1.0) == vec![Match{start: 0, end: 10, k: 0}]);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_levenshtein_search_ratio_invalid() {
+    levenshtein_search_ratio(b"abc", b"abc", 1.5);
+}
+
+#[test]
+fn test_basic_levenshtein_search_context() {
+    let haystack = b"abcd  xxxxxxxxxx  abcd";
+    let res = levenshtein_search_context(b"abcd", haystack, 0, 3);
+    // clamped at the start and at the end of the haystack
+    assert!(res == vec![(Match{start: 0, end: 4, k: 0}, 0..7), (Match{start: 18, end: 22, k: 0}, 15..22)]);
+
+    let res = levenshtein_search_context(b"xxxx", b"ab  xxxx  cd", 0, 2);
+    assert!(res == vec![(Match{start: 4, end: 8, k: 0}, 2..10)]);
+    assert!(&b"ab  xxxx  cd"[res[0].1.clone()] == b"  xxxx  ");
+
+    // the context can be larger than the haystack
+    let res = levenshtein_search_context(b"abc", b"xabdx", 1, usize::MAX);
+    let expected: Vec<Match> = levenshtein_search_simd_with_opts(b"abc", b"xabdx", 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res.iter().map(|r| r.0).collect::<Vec<Match>>() == expected);
+    assert!(res.iter().all(|r| r.1 == (0..5)));
+
+    let res = levenshtein_search_context(b"abcd", haystack, 0, 0);
+    assert!(res.iter().all(|r| r.1 == (r.0.start..r.0.end)));
+    assert!(levenshtein_search_context(b"", haystack, 0, 3).is_empty());
+}
+
+fn apply_edits(needle: &[u8], window: &[u8], edits: &[Edit]) -> Vec<u8> {
+    let mut res = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    for e in edits {
+        for _ in 0..e.count {
+            match e.edit {
+                EditType::Match => {
+                    assert!(needle[i] == window[j]);
+                    res.push(needle[i]);
+                    i += 1;
+                    j += 1;
+                },
+                EditType::Mismatch => {
+                    assert!(needle[i] != window[j]);
+                    res.push(window[j]);
+                    i += 1;
+                    j += 1;
+                },
+                EditType::AGap => {
+                    res.push(window[j]);
+                    j += 1;
+                },
+                EditType::BGap => i += 1,
+                EditType::Transpose => unreachable!(),
+            }
+        }
+    }
+
+    assert!(i == needle.len() && j == window.len());
+    res
+}
+
+#[test]
+fn test_basic_levenshtein_search_align() {
+    let needle = b"the quick brown fox";
+    let haystack = b"a quick brwn fox jumped over the quick brownn fox and the qick brown ffox";
+    let res = levenshtein_search_align(needle, haystack, 3);
+    let expected: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, 3, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res.iter().map(|r| r.0).collect::<Vec<Match>>() == expected);
+    assert!(!res.is_empty());
+
+    for (m, edits) in res.iter() {
+        assert!(apply_edits(needle, m.slice(haystack), edits) == m.slice(haystack));
+        let cost: usize = edits.iter().filter(|e| e.edit != EditType::Match).map(|e| e.count).sum();
+        assert!(cost as u32 == m.k);
+    }
+
+    let res = levenshtein_search_align(b"abcdef", b"xxabdefxx", 1);
+    assert!(res == vec![(Match{start: 2, end: 7, k: 1}, vec![Edit{edit: EditType::Match, count: 2}, Edit{edit: EditType::BGap, count: 1}, Edit{edit: EditType::Match, count: 3}])]);
+
+    let res = levenshtein_search_align(b"abc", b"abc", 0);
+    assert!(res == vec![(Match{start: 0, end: 3, k: 0}, vec![Edit{edit: EditType::Match, count: 3}])]);
+
+    assert!(levenshtein_search_align(b"", b"abc", 1).is_empty());
+}
+
+#[test]
+fn test_basic_levenshtein_search_detailed() {
+    // one deletion from the needle, so the match is shorter than the needle
+    let res = levenshtein_search_detailed(b"abcdef", b"xxabdefxx", 1);
+    assert!(res == vec![MatchDetailed{start: 2, end: 7, k: 1, needle_aligned: 5}]);
+    assert!(res[0].end - res[0].start < 6);
+
+    // one insertion into the needle, so the match is longer than the needle
+    let res = levenshtein_search_detailed(b"abcdef", b"xxabcZdefxx", 1);
+    assert!(res.contains(&MatchDetailed{start: 2, end: 9, k: 1, needle_aligned: 6}));
+
+    // one mismatch, so every byte of the needle is aligned
+    let res = levenshtein_search_detailed(b"abcdef", b"xxabcZefxx", 1);
+    assert!(res.contains(&MatchDetailed{start: 2, end: 8, k: 1, needle_aligned: 6}));
+
+    let needle = b"the quick brown fox";
+    let haystack = b"a quick brwn fox jumped over the quick brownn fox and the qick brown ffox";
+    let res = levenshtein_search_detailed(needle, haystack, 3);
+    let expected: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, 3, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res.iter().map(|&m| Match::from(m)).collect::<Vec<Match>>() == expected);
+
+    for m in res.iter() {
+        let insertions = (m.end - m.start) - m.needle_aligned;
+        let deletions = needle.len() - m.needle_aligned;
+        assert!(m.needle_aligned <= needle.len() && m.needle_aligned <= m.end - m.start);
+        assert!((insertions + deletions) as u32 <= m.k);
+    }
+
+    assert!(levenshtein_search_detailed(b"", b"abc", 1).is_empty());
+}
+
+#[test]
+fn test_basic_hamming_checked() {
+    assert!(hamming_checked(b"abc", b"abd") == Ok(1));
+    assert!(hamming_checked(b"", b"") == Ok(0));
+
+    let err = hamming_checked(b"abcd", b"ab").unwrap_err();
+    assert!(err == LengthMismatch{a_len: 4, b_len: 2});
+    assert!(err.to_string() == "length mismatch: first string has length 4 but second string has length 2");
+
+    #[cfg(feature = "std")]
+    {
+        let err: Box<dyn std::error::Error> = Box::new(err);
+        assert!(err.to_string().contains("length mismatch"));
+    }
+}
+
+#[test]
+fn test_basic_hamming_search_profile() {
+    let needle = b"acgtac";
+    let haystack = b"ttacgtacgtaacgtacggacgtac";
+    let profile = hamming_search_profile(needle, haystack);
+    assert!(profile.len() == haystack.len() - needle.len() + 1);
+
+    for (i, &dist) in profile.iter().enumerate() {
+        let naive = needle.iter().zip(&haystack[i..]).filter(|(a, b)| a != b).count() as u32;
+        assert!(dist == naive);
+    }
+
+    assert!(hamming_search_profile(b"abc", b"ab").is_empty());
+    assert!(hamming_search_profile(b"abc", b"abc") == vec![0]);
+    assert!(hamming_search_profile(b"", b"ab") == vec![0, 0, 0]);
+}
+
+#[test]
+fn test_basic_hamming_dna() {
+    let a = b"ACGTACGTTGCAACGTAAAACCCCGGGGTTTTACGTACGTTGCAACGTAAAACCCCGGGGTTTTACGTACGTTGCAACGTAAAACCCCGGGGTTTTACGTACGTTGCAACGTAAAACCCCGGGGTTTTACGTACGT";
+    let mut b = a.to_vec();
+    b[0] = b'T';
+    b[7] = b'A';
+    b[64] = b'C';
+    b[130] = b'T';
+    b[a.len() - 1] = b'C';
+
+    let a_packed = pack_dna(a);
+    let b_packed = pack_dna(&b);
+    assert!(a_packed.len() == (a.len() + 3) / 4);
+    assert!(unpack_dna(&a_packed, a.len()) == a.to_vec());
+    assert!(unpack_dna(&b_packed, b.len()) == b);
+
+    assert!(hamming_dna(&a_packed, &b_packed) == hamming(a, &b));
+    assert!(hamming_dna(&a_packed, &b_packed) == 5);
+    assert!(hamming_dna(&a_packed, &a_packed) == 0);
+
+    // every pair of different bases counts once
+    let a = b"AAAACCCCGGGGTTTT";
+    let b = b"CGTAAGTCACTGACGT";
+    assert!(hamming_dna(&pack_dna(a), &pack_dna(b)) == 12);
+
+    assert!(pack_dna(b"").is_empty());
+    assert!(hamming_dna(&[], &[]) == 0);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_pack_dna_invalid() {
+    pack_dna(b"ACGN");
+}
+
+#[test]
+fn test_basic_levenshtein_no_saturation() {
+    // two 200-byte strings that share no characters
+    let a = vec![b'a'; 200];
+    let b = vec![b'b'; 200];
+    assert!(levenshtein(&a, &b) == 200);
+    assert!(levenshtein_exp(&a, &b) == 200);
+    assert!(levenshtein_simd_k(&a, &b, 250) == Some(200));
+    assert!(levenshtein_simd_k(&a, &b, 199) == None);
+
+    // distances past the range of 8-bit values
+    let c: Vec<u8> = (0..300).map(|i| b"xyz"[i % 3]).collect();
+    assert!(levenshtein(&a, &c) == 300);
+    assert!(levenshtein_simd_k(&c, &a, u32::MAX) == Some(levenshtein_naive(&c, &a)));
+    assert!(rdamerau(&a, &c) == 300);
+}
+
+#[test]
+fn test_basic_levenshtein_large_16bit() {
+    // long strings with too many edits for 8-bit values, but few enough for 16-bit values
+    let mut rng = StdRng::seed_from_u64(12345);
+    let a = rand_dna(10000, &mut rng);
+    let mut b = a.clone();
+
+    // isolated substitutions, insertions, and deletions
+    for i in 0..300 {
+        let idx = i * 30 + 17;
+        match i % 3 {
+            0 => b[idx] = b'N',
+            1 => b.insert(idx, b'N'),
+            _ => { b.remove(idx); }
+        }
+    }
+
+    let naive = levenshtein_naive_k(&a, &b, 400).unwrap();
+    assert!(naive == 300);
+    assert!(levenshtein_simd_k(&a, &b, 1000) == Some(naive));
+    assert!(levenshtein_simd_k(&a, &b, 299) == None);
+    assert!(levenshtein_exp(&a, &b) == naive);
+}
+
+#[test]
+fn test_basic_levenshtein_escalation() {
+    let mut rng = StdRng::seed_from_u64(999);
+    let a = rand_dna(1000, &mut rng);
+
+    // long strings, but few enough edits for 8-bit values
+    let mut b = a.clone();
+    for i in 0..50 {
+        b[i * 20] = b'N';
+    }
+    b.truncate(990);
+    let naive = levenshtein_naive(&a, &b);
+    assert!(naive == 60);
+    assert!(levenshtein_with_escalation(&a, &b) == (naive, false));
+
+    // too many edits for 8-bit values, so the comparison is redone with wider values
+    let b: Vec<u8> = a.iter().enumerate().filter(|(i, _)| i % 3 != 0).map(|(_, &c)| c).collect();
+    let naive = levenshtein_naive(&a, &b);
+    assert!(naive == 334);
+    assert!(levenshtein_with_escalation(&a, &b) == (naive, true));
+    assert!(levenshtein(&a, &b) == naive);
+
+    // equal lengths, where the Hamming distance is large but the Levenshtein distance is small
+    let mut b = a[1..].to_vec();
+    b.push(b'N');
+    assert!(levenshtein_with_escalation(&a, &b) == (2, false));
+
+    // short strings never need to escalate
+    assert!(levenshtein_with_escalation(b"abc", b"xyzw") == (4, false));
+}
+
+#[test]
+fn test_basic_levenshtein_simd_k_early_exit() {
+    let mut rng = StdRng::seed_from_u64(8128);
+
+    // thresholds around the true distance, so some comparisons exit early and some do not
+    for _ in 0..200 {
+        let a = rand_dna(rng.gen_range(16, 300), &mut rng);
+        let mut b = a.clone();
+
+        for _ in 0..rng.gen_range(0, 40) {
+            let idx = rng.gen_range(0, b.len());
+            match rng.gen_range(0, 4) {
+                0 => b[idx] = b'N',
+                1 => b.insert(idx, b'N'),
+                2 if idx + 1 < b.len() => b.swap(idx, idx + 1),
+                _ => { b.remove(idx); }
+            }
+        }
+
+        assert!(levenshtein_simd_k(&a, &b, u32::MAX) == Some(levenshtein_naive(&a, &b)));
+
+        for &costs in [LEVENSHTEIN_COSTS, RDAMERAU_COSTS].iter() {
+            // without a threshold, the early exit is never taken
+            let dist = levenshtein_simd_k_with_opts(&a, &b, u32::MAX, false, costs).unwrap().0;
+
+            for &k in [0, dist / 2, dist.saturating_sub(1), dist, dist + 1].iter() {
+                let simd = levenshtein_simd_k_with_opts(&a, &b, k, false, costs).map(|r| r.0);
+                assert!(simd == if dist <= k { Some(dist) } else { None });
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_skip_common() {
+    let pairs: [(&[u8], &[u8]); 10] = [
+        (b"", b""),
+        (b"abc", b"abc"),
+        (b"abc", b"abcdef"),
+        (b"def", b"abcdef"),
+        (b"abc", b""),
+        (b"prefix_kitten_suffix", b"prefix_sitting_suffix"),
+        (b"aaaa", b"aaa"),
+        (b"abcba", b"abba"),
+        (b"autocomplete", b"autocmoplete"),
+        (b"xabcx", b"yabcy")
+    ];
+
+    for (a, b) in pairs.iter() {
+        assert!(levenshtein_skip_common(a, b) == levenshtein(a, b));
+        assert!(levenshtein_skip_common(b, a) == levenshtein(b, a));
+    }
+
+    assert!(levenshtein_skip_common(b"abcdef", b"abcdef") == 0);
+    assert!(levenshtein_skip_common(b"abc", b"abcdef") == 3);
+}
+
+#[test]
+fn test_basic_active_backend() {
+    let backend = active_backend();
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        let any_bits = cfg!(feature = "jewel-8bit") || cfg!(feature = "jewel-16bit") || cfg!(feature = "jewel-32bit");
+        let avx512 = cfg!(feature = "jewel-avx512") && cfg!(feature = "jewel-8bit") && is_x86_feature_detected!("avx512bw");
+        let avx2 = cfg!(feature = "jewel-avx") && is_x86_feature_detected!("avx2");
+        let sse41 = cfg!(feature = "jewel-sse") && is_x86_feature_detected!("sse4.1");
+
+        // the selected backend must be supported by the current CPU, and it must be the widest one
+        match backend {
+            Backend::Avx512 => assert!(!cfg!(feature = "pure-scalar") && avx512),
+            Backend::Avx2 => assert!(!cfg!(feature = "pure-scalar") && !avx512 && avx2 && any_bits),
+            Backend::Sse41 => assert!(!cfg!(feature = "pure-scalar") && !avx512 && !avx2 && sse41 && any_bits),
+            Backend::Scalar => assert!(cfg!(feature = "pure-scalar") || (!avx512 && ((!avx2 && !sse41) || !any_bits))),
+            Backend::Wasm => panic!("WASM backend on x86!")
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        assert!(backend == Backend::Scalar || backend == Backend::Wasm);
+    }
+}
+
+#[test]
+fn test_basic_simd_lane_bytes() {
+    let lane_bytes = simd_lane_bytes();
+    assert!(lane_bytes.is_power_of_two());
+
+    match active_backend() {
+        Backend::Avx512 => assert!(lane_bytes == 64),
+        Backend::Avx2 => assert!(lane_bytes == 32),
+        Backend::Sse41 | Backend::Wasm => assert!(lane_bytes == 16),
+        Backend::Scalar => assert!(lane_bytes == 1),
+    }
+
+    if cfg!(feature = "pure-scalar") {
+        assert!(lane_bytes == 1);
+    }
+}
+
+#[test]
+fn test_basic_incremental_levenshtein() {
+    let candidates: [&[u8]; 4] = [b"", b"kitten", b"sitting", b"mitten and sitting"];
+    // positive bytes are pushed and zeros are pops
+    let keys = b"sitt\0\0\0\0\0kx\0itting\0\0 and s\0\0\0\0\0\0\0\0";
+
+    for &b in candidates.iter() {
+        let mut inc = IncrementalLevenshtein::new(b);
+        let mut a = Vec::new();
+        assert!(inc.distance() == b.len() as u32);
+
+        for &c in keys.iter() {
+            let dist = if c == 0 {
+                a.pop();
+                inc.pop_char().unwrap_or(inc.distance())
+            } else {
+                a.push(c);
+                inc.push_char(c)
+            };
+
+            assert!(inc.a_len() == a.len());
+            assert!(dist == levenshtein(&a, b));
+        }
+    }
+
+    let mut inc = IncrementalLevenshtein::new(b"abc");
+    assert!(inc.pop_char() == None);
+}
+
+#[test]
+fn test_basic_edit_ops() {
+    let pairs: [(&[u8], &[u8]); 5] = [(b"kitten", b"sitting"), (b"abc", b""), (b"", b"abc"), (b"the quick brown fox", b"teh quikc brwn fox jumps"), (b"abcd", b"abcd")];
+
+    for (a, b) in pairs.iter() {
+        let (dist, ops) = levenshtein_ops(a, b);
+        assert!(dist == levenshtein(a, b));
+
+        // rebuild both strings from the positions of the edits
+        let mut new_a = Vec::new();
+        let mut new_b = Vec::new();
+        let mut cost = 0;
+
+        for op in ops.iter() {
+            match op.edit {
+                EditType::Match => {
+                    assert!(a[op.a_idx] == b[op.b_idx]);
+                    new_a.push(a[op.a_idx]);
+                    new_b.push(b[op.b_idx]);
+                },
+                EditType::Mismatch => {
+                    assert!(a[op.a_idx] != b[op.b_idx]);
+                    new_a.push(a[op.a_idx]);
+                    new_b.push(b[op.b_idx]);
+                    cost += 1;
+                },
+                EditType::AGap => {
+                    assert!(op.a_idx == new_a.len());
+                    new_b.push(b[op.b_idx]);
+                    cost += 1;
+                },
+                EditType::BGap => {
+                    assert!(op.b_idx == new_b.len());
+                    new_a.push(a[op.a_idx]);
+                    cost += 1;
+                },
+                EditType::Transpose => unreachable!()
+            }
+        }
+
+        assert!(new_a == a.to_vec());
+        assert!(new_b == b.to_vec());
+        assert!(cost == dist);
+    }
+
+    let edits = vec![Edit{edit: EditType::Transpose, count: 2}, Edit{edit: EditType::AGap, count: 1}];
+    assert!(edit_ops(&edits) == vec![EditOp{edit: EditType::Transpose, a_idx: 0, b_idx: 0}, EditOp{edit: EditType::Transpose, a_idx: 2, b_idx: 2}, EditOp{edit: EditType::AGap, a_idx: 4, b_idx: 4}]);
+}
+
+#[test]
+fn test_basic_levenshtein_automaton() {
+    let words: [&[u8]; 12] = [b"", b"a", b"ab", b"abc", b"abd", b"acb", b"bc", b"abcd", b"xyz", b"abcde", b"aabbcc", b"cba"];
+
+    for &k in &[0u32, 1, 2, 3] {
+        let automaton = LevenshteinAutomaton::new(b"abc", k);
+
+        for &word in words.iter() {
+            let mut state = Some(automaton.start());
+
+            for &c in word {
+                state = state.and_then(|s| automaton.step(&s, c));
+            }
+
+            let dist = levenshtein(b"abc", word);
+            assert!(state.as_ref().map_or(false, |s| automaton.is_match(s)) == (dist <= k));
+
+            if dist <= k {
+                assert!(automaton.distance(state.as_ref().unwrap()) == Some(dist));
+            }
+        }
+    }
+
+    // walk a small trie, given as a sorted word list, and prune dead prefixes
+    let automaton = LevenshteinAutomaton::new(b"abc", 1);
+    let mut matches = Vec::new();
+    let mut stack = vec![(Vec::new(), automaton.start())];
+
+    while let Some((prefix, state)) = stack.pop() {
+        if words.contains(&prefix.as_slice()) && automaton.is_match(&state) {
+            matches.push(prefix.clone());
+        }
+
+        for &c in b"abcdexyz" {
+            let mut next = prefix.clone();
+            next.push(c);
+
+            if words.iter().any(|w| w.starts_with(&next)) {
+                if let Some(next_state) = automaton.step(&state, c) {
+                    stack.push((next, next_state));
+                }
+            }
+        }
+    }
+
+    matches.sort();
+    let mut expected: Vec<Vec<u8>> = words.iter().filter(|w| levenshtein(b"abc", w) <= 1).map(|w| w.to_vec()).collect();
+    expected.sort();
+    assert!(matches == expected);
+}
+
+#[test]
+fn test_basic_deletion_index() {
+    let dictionary: [&[u8]; 10] = [b"receive", b"recipe", b"deceive", b"relieve", b"believe", b"receiver", b"perceive", b"a", b"", b"receive"];
+    let index = DeletionIndex::new(&dictionary, 2);
+    assert!(index.len() == 10);
+
+    let res = index.lookup(b"recieve", 2);
+    assert!(res.iter().any(|&(w, dist)| w == b"receive" && dist == 2));
+
+    for &query in &[&b"recieve"[..], b"beleive", b"recive", b"b", b"", b"xyz"] {
+        for k in 0..=2 {
+            let res = index.lookup(query, k);
+            let mut expected: Vec<(&[u8], u32)> = dictionary.iter().map(|&w| (w, levenshtein(query, w))).filter(|&(_, dist)| dist <= k).collect();
+            expected.sort_by_key(|&(w, dist)| (dist, w));
+            expected.dedup();
+            assert!(res == expected);
+        }
+    }
+
+    assert!(index.lookup(b"recieve", 1) == vec![(&b"relieve"[..], 1)]);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_deletion_index_k_too_large() {
+    DeletionIndex::new(&[b"abc"], 1).lookup(b"abc", 2);
+}
+
+#[test]
+fn test_basic_levenshtein_search_length_pref() {
+    // "zbc" can be one mismatch or one gap away from "abc"
+    let longest: Vec<Match> = levenshtein_search_simd_with_length_pref(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Longest).collect();
+    let shortest: Vec<Match> = levenshtein_search_simd_with_length_pref(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Shortest).collect();
+    assert!(longest == vec![Match{start: 0, end: 3, k: 1}]);
+    assert!(shortest == vec![Match{start: 1, end: 3, k: 1}]);
+
+    let longest: Vec<Match> = levenshtein_search_simd_with_opts(b"abc", b"zbc", 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(longest == vec![Match{start: 0, end: 3, k: 1}]);
+
+    let cases: [(&[u8], &[u8]); 4] = [(b"abcde", b"xxabxdexxabcdxxbcde"), (b"aaaa", b"baaab aaaaa abaa"), (b"ACGTACGT", b"TTACGACGTTACGTACCTGGACGTAGT"), (b"abcdefghijklmnopqrstuvwxyz0123456789", b"--abcdefghijklmnoqrstuvwxyz01234567890--abcdefghijklmnopqrstuvwxy0123456789")];
+
+    for &(needle, haystack) in cases.iter() {
+        for &k in &[1u32, 2, 3] {
+            for &search_type in &[SearchType::All, SearchType::Best] {
+                let mut all = Vec::new();
+
+                for &length_pref in &[LengthPreference::Longest, LengthPreference::Shortest] {
+                    let simd: Vec<Match> = levenshtein_search_simd_with_length_pref(needle, haystack, k, search_type, LEVENSHTEIN_COSTS, false, length_pref).collect();
+                    let naive: Vec<Match> = levenshtein_search_naive_with_length_pref(needle, haystack, k, search_type, LEVENSHTEIN_COSTS, false, length_pref).collect();
+                    assert!(simd == naive);
+                    all.push(simd);
+                }
+
+                if search_type == SearchType::All {
+                    // the same end positions and costs, but the shortest matches never start earlier
+                    assert!(all[0].len() == all[1].len());
+
+                    for (l, s) in all[0].iter().zip(all[1].iter()) {
+                        assert!(l.end == s.end && l.k == s.k && l.start <= s.start);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_align() {
+    let mut rng = StdRng::seed_from_u64(54321);
+    let a = rand_dna(2000, &mut rng);
+    let mut b = a.clone();
+
+    // a mix of scattered edits and a long deleted block, so the strings are split many times
+    for i in 0..100 {
+        let idx = rng.gen_range(0, b.len());
+        match i % 3 {
+            0 => b[idx] = b'N',
+            1 => b.insert(idx, b'N'),
+            _ => { b.remove(idx); }
+        }
+    }
+    b.drain(500..800);
+
+    let pairs: [(&[u8], &[u8]); 6] = [(&a, &b), (&b, &a), (&a[..1500], &b[200..]), (b"kitten", b"sitting"), (b"", b"abc"), (b"abc", b"")];
+
+    for (a, b) in pairs.iter() {
+        let (dist, edits) = levenshtein_align(a, b);
+        let (naive_dist, naive_edits) = levenshtein_naive_with_opts(a, b, true, LEVENSHTEIN_COSTS);
+        let naive_edits = naive_edits.unwrap();
+        assert!(dist == naive_dist);
+
+        // ties may be broken differently, but both tracebacks must be valid alignments with the same cost
+        for e in [&edits, &naive_edits].iter() {
+            let mut cost = 0;
+            let mut i = 0;
+            let mut j = 0;
+
+            for op in edit_ops(e).iter() {
+                match op.edit {
+                    EditType::Match => { assert!(a[op.a_idx] == b[op.b_idx]); i += 1; j += 1; },
+                    EditType::Mismatch => { assert!(a[op.a_idx] != b[op.b_idx]); i += 1; j += 1; cost += 1; },
+                    EditType::AGap => { j += 1; cost += 1; },
+                    EditType::BGap => { i += 1; cost += 1; },
+                    EditType::Transpose => unreachable!(),
+                }
+            }
+
+            assert!(i == a.len() && j == b.len());
+            assert!(cost == dist);
+        }
+    }
+
+    assert!(levenshtein_align(b"kitten", b"sitting").1 == levenshtein_naive_with_opts(b"kitten", b"sitting", true, LEVENSHTEIN_COSTS).1.unwrap());
+}
+
+#[test]
+fn test_basic_search_match_at_haystack_end() {
+    // the haystack length is not a multiple of the vector width, so the last vector is padded
+    let needle = b"WXYZWXYZ";
+    let mut haystack: Vec<u8> = (0..45).map(|i| b"acgt"[i % 4]).collect();
+    let len = haystack.len();
+    haystack[len - needle.len()..].copy_from_slice(needle);
+    let expected = vec![Match{start: len - needle.len(), end: len, k: 0}];
+
+    let result: Vec<Match> = levenshtein_search(needle, &haystack).filter(|m| m.k == 0).collect();
+    assert!(result == expected);
+    let result: Vec<Match> = levenshtein_search_simd_with_opts(needle, &haystack, 0, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+    assert!(result == expected);
+    let result: Vec<Match> = levenshtein_search_naive_with_opts(needle, &haystack, 0, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+    assert!(result == expected);
+    let result: Vec<Match> = hamming_search(needle, &haystack).filter(|m| m.k == 0).collect();
+    assert!(result == expected);
+    let result: Vec<Match> = hamming_search_simd_with_opts(needle, &haystack, 0, SearchType::All).collect();
+    assert!(result == expected);
+}
+
+#[test]
+fn test_basic_merge_overlapping() {
+    // a chain of three overlapping matches collapses to one, and the touching match is kept
+    let cluster = || vec![Match{start: 14, end: 18, k: 0}, Match{start: 5, end: 9, k: 2}, Match{start: 2, end: 6, k: 1}, Match{start: 8, end: 11, k: 1}];
+
+    let mut matches = cluster();
+    merge_overlapping(&mut matches, MergePolicy::LowestK);
+    assert!(matches == vec![Match{start: 2, end: 6, k: 1}, Match{start: 14, end: 18, k: 0}]);
+
+    let mut matches = cluster();
+    merge_overlapping(&mut matches, MergePolicy::Union);
+    assert!(matches == vec![Match{start: 2, end: 11, k: 1}, Match{start: 14, end: 18, k: 0}]);
+
+    let mut matches = vec![Match{start: 0, end: 3, k: 1}, Match{start: 3, end: 6, k: 1}];
+    merge_overlapping(&mut matches, MergePolicy::Union);
+    assert!(matches.len() == 2);
+
+    let mut matches = vec![];
+    merge_overlapping(&mut matches, MergePolicy::LowestK);
+    assert!(matches.is_empty());
+
+    // all the approximate matches around each occurrence are merged
+    let mut matches: Vec<Match> = levenshtein_search_simd_with_opts(b"abcd", b"xxabcdxxxxxabxdxx", 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(matches.len() > 2);
+    merge_overlapping(&mut matches, MergePolicy::LowestK);
+    assert!(matches == vec![Match{start: 2, end: 6, k: 0}, Match{start: 11, end: 15, k: 1}]);
+}
+
+#[test]
+fn test_basic_ascii_case_simd() {
+    let mut rng = StdRng::seed_from_u64(31337);
+
+    // all byte values, including non-ASCII bytes and the bytes next to the letter ranges
+    let all: Vec<u8> = (0..=255u8).collect();
+
+    for len in [0usize, 1, 31, 32, 33, 100, 1000].iter() {
+        let buf: Vec<u8> = (0..*len).map(|_| rng.gen()).collect();
+
+        for buf in [&buf, &all].iter() {
+            let mut lower = buf.to_vec();
+            ascii_to_lower_simd(&mut lower);
+            assert!(lower == buf.to_ascii_lowercase());
+
+            let mut upper = buf.to_vec();
+            ascii_to_upper_simd(&mut upper);
+            assert!(upper == buf.to_ascii_uppercase());
+        }
+    }
+
+    let mut s = b"@AZ[`az{".to_vec();
+    ascii_to_lower_simd(&mut s);
+    assert!(s == b"@az[`az{");
+    ascii_to_upper_simd(&mut s);
+    assert!(s == b"@AZ[`AZ{");
+}
+
+#[test]
+fn test_basic_search_config() {
+    let needle = b"abcd";
+    let haystack = b"xxABCD abd abcd aBxd abcdd";
+
+    // defaults match levenshtein_search
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &SearchConfig::new().build()).collect();
+    assert!(res == levenshtein_search(needle, haystack).collect::<Vec<Match>>());
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &SearchConfig::default()).collect();
+    assert!(res == levenshtein_search(needle, haystack).collect::<Vec<Match>>());
+
+    let config = SearchConfig::new().k(2).search_type(SearchType::All).costs(RDAMERAU_COSTS).anchored(true).build();
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+    assert!(res == levenshtein_search_simd_with_opts(needle, haystack, 2, SearchType::All, RDAMERAU_COSTS, true).collect::<Vec<Match>>());
+
+    let config = SearchConfig::new().k(1).search_type(SearchType::All).case_insensitive(true).build();
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+    assert!(res == levenshtein_search_ci_with_opts(needle, haystack, 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>());
+
+    let config = SearchConfig::new().k(1).search_type(SearchType::All).length_pref(LengthPreference::Shortest).build();
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+    assert!(res == levenshtein_search_simd_with_length_pref(needle, haystack, 1, SearchType::All, LEVENSHTEIN_COSTS, false, LengthPreference::Shortest).collect::<Vec<Match>>());
+
+    let config = SearchConfig::new().k(1).search_type(SearchType::All).ranked(true).build();
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+    assert!(res == levenshtein_search_ranked(needle, haystack, 1));
+
+    for &policy in &[MergePolicy::LowestK, MergePolicy::Union] {
+        let config = SearchConfig::new().k(2).search_type(SearchType::All).case_insensitive(true).overlap(policy).build();
+        let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+        let mut expected: Vec<Match> = levenshtein_search_ci_with_opts(needle, haystack, 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        merge_overlapping(&mut expected, policy);
+        assert!(res == expected);
+
+        let config = config.ranked(true);
+        let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+        expected.sort_by_key(|m| (m.k, m.start));
+        assert!(res == expected);
+    }
+}
+
+#[test]
+fn test_basic_search_config_min_len() {
+    let needle = b"abcd";
+    let haystack = b"xxABCD abd abcd aBxd abcdd";
+    let all: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(all.iter().any(|m| m.len() < 4) && all.iter().any(|m| m.len() >= 4));
+
+    // zero keeps every match
+    let config = SearchConfig::new().k(2).search_type(SearchType::All).min_len(0).build();
+    let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+    assert!(res == all);
+
+    for min_len in 1..=6 {
+        let config = SearchConfig::new().k(2).search_type(SearchType::All).min_len(min_len).build();
+        let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+        assert!(res == all.iter().cloned().filter(|m| m.len() >= min_len).collect::<Vec<Match>>());
+    }
+
+    // short matches are discarded before merging, so they cannot win a cluster or widen a union
+    for &policy in &[MergePolicy::LowestK, MergePolicy::Union] {
+        let config = SearchConfig::new().k(2).search_type(SearchType::All).min_len(4).overlap(policy).ranked(true).build();
+        let res: Vec<Match> = levenshtein_search_with(needle, haystack, &config).collect();
+        let mut expected: Vec<Match> = all.iter().cloned().filter(|m| m.len() >= 4).collect();
+        merge_overlapping(&mut expected, policy);
+        expected.sort_by_key(|m| (m.k, m.start));
+        assert!(res == expected);
+    }
+
+    // the best matches are only filtered, not replaced by longer matches with more edits
+    let config = SearchConfig::new().k(1).min_len(4).build();
+    let res: Vec<Match> = levenshtein_search_with(b"abcd", b"xxabdxx", &config).collect();
+    assert!(res.is_empty());
+    let config = SearchConfig::new().k(1).min_len(3).build();
+    let res: Vec<Match> = levenshtein_search_with(b"abcd", b"xxabdxx", &config).collect();
+    assert!(res == vec![Match{start: 2, end: 5, k: 1}]);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_search_config_invalid_costs() {
+    SearchConfig::new().costs(EditCosts::new(3, 2, 0, Some(3))).build();
+}
+
+#[test]
+fn test_basic_fuzzy_split() {
+    assert!(fuzzy_split(b"a<XX>b<XY>c", b"<XX>", 1) == vec![&b"a"[..], b"b", b"c"]);
+    // adjacent matches and matches at the ends keep the empty segments
+    assert!(fuzzy_split(b"<XY>a<XX><YX>b<XX>", b"<XX>", 1) == vec![&b""[..], b"a", b"", b"b", b""]);
+    // the exact match is chosen over the overlapping matches with one edit
+    assert!(fuzzy_split(b"aXXbYYc", b"XX", 1) == vec![&b"a"[..], b"bYYc"]);
+    assert!(fuzzy_split(b"aXYbYXc", b"XY", 0) == vec![&b"a"[..], b"bYXc"]);
+    // ties are broken by choosing the leftmost match
+    assert!(fuzzy_split(b"aXXbYYc", b"YYY", 1) == vec![&b"aXX"[..], b"c"]);
+    assert!(fuzzy_split(b"abc", b"<XX>", 1) == vec![&b"abc"[..]]);
+    assert!(fuzzy_split(b"abc", b"", 1) == vec![&b"abc"[..]]);
+    assert!(fuzzy_split(b"", b"ab", 1) == vec![&b""[..]]);
+}
+
+#[test]
+fn test_basic_search_k_at_least_needle_len() {
+    let needle = b"abc";
+    let haystack = b"xaby";
+    // every end position has a match, including the empty match at the start
+    let expected = vec![Match{start: 0, end: 0, k: 3}, Match{start: 0, end: 1, k: 3}, Match{start: 1, end: 2, k: 2}, Match{start: 1, end: 3, k: 1}, Match{start: 1, end: 4, k: 1}];
+
+    for &k in &[3u32, 4, 254, 255, 1000, u32::MAX] {
+        let res: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == expected);
+        let res: Vec<Match> = levenshtein_search_naive_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == expected);
+
+        let res: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == vec![Match{start: 1, end: 4, k: 1}]);
+    }
+
+    // with affine gaps, deleting the needle costs 1 + 3 * 2 = 7
+    let costs = EditCosts::new(1, 2, 1, None);
+    let expected: Vec<Match> = levenshtein_search_naive_with_opts(needle, haystack, 7, SearchType::All, costs, false).collect();
+    assert!(expected.len() == haystack.len() + 1);
+
+    for &k in &[7u32, 8, 300, u32::MAX] {
+        let res: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, costs, false).collect();
+        assert!(res == expected);
+        let res: Vec<Match> = levenshtein_search_naive_with_opts(needle, haystack, k, SearchType::All, costs, false).collect();
+        assert!(res == expected);
+    }
+
+    // a needle of length 1 also has an empty match at the start of the haystack
+    let expected = vec![Match{start: 0, end: 0, k: 1}, Match{start: 0, end: 1, k: 1}, Match{start: 1, end: 2, k: 0}];
+    let res: Vec<Match> = levenshtein_search_simd_with_opts(b"a", b"ba", 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res == expected);
+    let res: Vec<Match> = levenshtein_search_naive_with_opts(b"a", b"ba", 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    assert!(res == expected);
+
+    let expected = vec![Match{start: 0, end: 0, k: 1}, Match{start: 0, end: 1, k: 1}, Match{start: 1, end: 2, k: 1}];
+    let res: Vec<Match> = levenshtein_search_simd_with_opts(b"a", b"ba", 1, SearchType::All, LEVENSHTEIN_COSTS, true).collect();
+    assert!(res == expected);
+    let res: Vec<Match> = levenshtein_search_naive_with_opts(b"a", b"ba", 1, SearchType::All, LEVENSHTEIN_COSTS, true).collect();
+    assert!(res == expected);
+
+    // anchored searches are not clamped, since shifting the needle costs more gaps
+    let res: Vec<Match> = levenshtein_search_simd_with_opts(needle, b"xxxxxxabc", 1000, SearchType::All, LEVENSHTEIN_COSTS, true).collect();
+    assert!(res.len() == 10 && res[9] == Match{start: 6, end: 9, k: 6});
+}
+
+#[test]
+fn test_basic_match_edit_hash() {
+    use std::collections::HashSet;
+
+    let haystack = b"abcxxabdxxabc";
+    let mut set: HashSet<Match> = levenshtein_search_simd_with_opts(b"abc", haystack, 1, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    let len = set.len();
+
+    // the same matches from the scalar search are already in the set
+    set.extend(levenshtein_search_naive_with_opts(b"abc", haystack, 1, SearchType::All, LEVENSHTEIN_COSTS, false));
+    assert!(set.len() == len);
+    assert!(set.contains(&Match{start: 0, end: 3, k: 0}));
+    assert!(set.contains(&Match{start: 10, end: 13, k: 0}));
+    assert!(!set.contains(&Match{start: 0, end: 3, k: 1}));
+
+    // copies compare equal and hash the same
+    let m = Match{start: 5, end: 8, k: 1};
+    let copy = m;
+    assert!(m == copy && set.contains(&copy));
+
+    let (_, edits) = levenshtein_exp_with_opts(b"kitten", b"sitting", true, LEVENSHTEIN_COSTS);
+    let edits = edits.unwrap();
+    let set: HashSet<Edit> = edits.iter().copied().collect();
+    assert!(set.contains(&Edit{edit: EditType::Mismatch, count: 1}));
+    assert!(set.contains(&Edit{edit: EditType::AGap, count: 1}) || set.contains(&Edit{edit: EditType::BGap, count: 1}));
+}
+
+#[test]
+fn test_basic_compiled_needle() {
+    let needle = CompiledNeedle::new(b"abcd");
+    assert!(needle.needle() == b"abcd");
+
+    let haystacks: Vec<&[u8]> = vec![b"", b"a", b"abcd", b"xxabxdxx", b"dcba", b"xyzw", b"abcabcdabd", b"aaaa", b"bcda"];
+
+    for &haystack in &haystacks {
+        for k in 0..6 {
+            let expected: Vec<Match> = levenshtein_search_simd_with_opts(b"abcd", haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            assert!(needle.search(haystack, k) == expected);
+        }
+    }
+
+    // rejected by the histogram before searching
+    assert!(needle.search(b"xxxxxxxxxx", 3).is_empty());
+    assert!(CompiledNeedle::new(b"").search(b"abc", 1).is_empty());
+}
+
+#[test]
+fn test_basic_rand_compiled_needle() {
+    let mut rng = StdRng::seed_from_u64(23);
+
+    for _ in 0..50 {
+        // long needles skip the bit-parallel search, and large k selects the wider lanes
+        let needle_len = rng.gen_range(1, 300);
+        let needle = rand_dna(needle_len, &mut rng);
+        let compiled = CompiledNeedle::new(&needle);
+        let mut haystack = rand_dna(rng.gen_range(0, 200), &mut rng);
+        haystack.extend_from_slice(&needle);
+        haystack.extend(rand_dna(rng.gen_range(0, 200), &mut rng));
+
+        for &k in &[0, 1, needle_len as u32 / 4, 300, 70000] {
+            let expected: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            assert!(compiled.search(&haystack, k) == expected);
+        }
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_iter() {
+    let strs: Vec<&[u8]> = vec![b"", b"a", b"abc", b"kitten", b"sitting", b"abcdefghij", b"jihgfedcba", b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab"];
+
+    for &a in &strs {
+        for &b in &strs {
+            let expected = levenshtein(a, b);
+            assert!(levenshtein_iter(a.iter().copied(), b.iter().copied()) == expected);
+            // iterators that produce bytes on the fly
+            assert!(levenshtein_iter(a.iter().map(|c| c ^ 0x20), b.iter().map(|c| c ^ 0x20)) == expected);
+            assert!(levenshtein_iter(a.iter().rev().copied(), b.iter().rev().copied()) == expected);
+        }
+    }
+
+    let a: Vec<u8> = (0..500u32).map(|i| (i % 7) as u8).collect();
+    let b: Vec<u8> = (0..700u32).map(|i| (i % 5) as u8).collect();
+    assert!(levenshtein_iter((0..500u32).map(|i| (i % 7) as u8), (0..700u32).map(|i| (i % 5) as u8)) == levenshtein(&a, &b));
+}
+
+#[test]
+fn test_basic_top_k_nearest() {
+    let dict: Vec<&[u8]> = vec![b"hello", b"world", b"help", b"halo", b"hell", b"", b"yellow", b"helo", b"shell", b"hello there", b"held", b"h"];
+
+    for query in [&b"helo"[..], b"", b"world", b"xyz", b"hello"].iter() {
+        let mut expected: Vec<(usize, u32)> = dict.iter().enumerate().map(|(i, b)| (i, levenshtein(query, b))).collect();
+        expected.sort_by_key(|&(i, dist)| (dist, i));
+
+        for n in 0..(dict.len() + 2) {
+            let res = top_k_nearest(query, &dict, n);
+            assert!(res == expected[..n.min(dict.len())]);
+        }
+
+        assert!(top_k_nearest(query, &dict, 1).first().copied() == nearest(query, &dict));
+    }
+
+    assert!(top_k_nearest(b"abc", &[], 3).is_empty());
+}
+
+#[test]
+fn test_basic_levenshtein_report() {
+    assert!(levenshtein_report(b"kitten", b"sitting") == DistanceReport{distance: 3, a_len: 6, b_len: 7, saturated: false});
+    assert!(levenshtein_report(b"abcd", b"abxd") == DistanceReport{distance: 1, a_len: 4, b_len: 4, saturated: false});
+    assert!(levenshtein_report(b"", b"") == DistanceReport{distance: 0, a_len: 0, b_len: 0, saturated: false});
+    assert!(levenshtein_report(b"abcd", b"abxd").is_equal_length());
+    assert!(!levenshtein_report(b"kitten", b"sitting").is_equal_length());
+
+    // the distance does not fit in 8 bits, so it is calculated again
+    let a = vec![b'a'; 300];
+    let b = vec![b'b'; 20];
+    let report = levenshtein_report(&a, &b);
+    assert!(report == DistanceReport{distance: 300, a_len: 300, b_len: 20, saturated: true});
+    assert!(report.distance == levenshtein(&a, &b));
+
+    // long strings with a small distance do not saturate
+    let report = levenshtein_report(&a, &a[..280]);
+    assert!(report == DistanceReport{distance: 20, a_len: 300, b_len: 280, saturated: false});
+}
+
+#[test]
+fn test_basic_search_needle_longer_than_haystack() {
+    for &search_type in &[SearchType::All, SearchType::Best] {
+        for &anchored in &[false, true] {
+            let res: Vec<Match> = levenshtein_search_simd_with_opts(b"abcd", b"abd", 1, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+            assert!(res == vec![Match{start: 0, end: 3, k: 1}]);
+            let res: Vec<Match> = levenshtein_search_naive_with_opts(b"abcd", b"abd", 1, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+            assert!(res == vec![Match{start: 0, end: 3, k: 1}]);
+        }
+    }
+
+    assert!(levenshtein_search(b"abcd", b"abd").collect::<Vec<Match>>() == vec![Match{start: 0, end: 3, k: 1}]);
+    // too many characters of the needle are missing
+    assert!(levenshtein_search_simd_with_opts(b"abcd", b"ab", 1, SearchType::All, LEVENSHTEIN_COSTS, false).next().is_none());
+    assert!(levenshtein_search_simd_with_opts(b"abcd", b"ab", 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>() == vec![Match{start: 0, end: 2, k: 2}]);
+
+    let needles: Vec<&[u8]> = vec![b"abcdef", b"aabbcc", b"abcabcabc"];
+    let haystacks: Vec<&[u8]> = vec![b"", b"a", b"acf", b"abce", b"bca"];
+
+    for &needle in &needles {
+        for &haystack in &haystacks {
+            for k in 0..(needle.len() as u32 + 1) {
+                let expected: Vec<Match> = levenshtein_search_naive_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+                assert!(levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>() == expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_basic_hamming_revcomp() {
+    fn revcomp(s: &[u8]) -> Vec<u8> {
+        s.iter().rev().map(|&c| match c { b'A' => b'T', b'T' => b'A', b'C' => b'G', b'G' => b'C', b'a' => b't', b't' => b'a', b'c' => b'g', b'g' => b'c', _ => c }).collect()
+    }
+
+    let a: Vec<u8> = (0..300u32).map(|i| b"ACGTacgtNACGT"[((i * 7 + i / 5) % 13) as usize]).collect();
+    let b: Vec<u8> = (0..300u32).map(|i| b"ACGTacgtNQ"[((i * 3 + i / 7) % 10) as usize]).collect();
+
+    for len in 0..a.len() {
+        let expected = hamming(&a[..len], &revcomp(&b[..len]));
+        assert!(hamming_revcomp(&a[..len], &b[..len]) == expected);
+        // a sequence matches its own reverse complement exactly
+        assert!(hamming_revcomp(&revcomp(&b[..len]), &b[..len]) == 0);
+    }
+
+    // bytes that only share the low bits of a base are not complemented
+    let odd: Vec<u8> = (0u8..=255).collect();
+    assert!(hamming_revcomp(&revcomp(&odd), &odd) == 0);
+    assert!(hamming_revcomp(b"ACGT", b"ACGT") == 0);
+    assert!(hamming_revcomp(b"AAAA", b"AAAA") == 4);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_revcomp_len() {
+    let _ = hamming_revcomp(b"ACGT", b"ACG");
+}
+
+#[test]
+fn test_basic_hamming_iupac() {
+    fn bases(c: u8) -> &'static [u8] {
+        match c.to_ascii_uppercase() {
+            b'A' => b"A", b'C' => b"C", b'G' => b"G", b'T' | b'U' => b"T",
+            b'R' => b"AG", b'Y' => b"CT", b'S' => b"CG", b'W' => b"AT", b'K' => b"GT", b'M' => b"AC",
+            b'B' => b"CGT", b'D' => b"AGT", b'H' => b"ACT", b'V' => b"ACG", b'N' => b"ACGT",
+            _ => b"",
+        }
+    }
+
+    fn naive(a: &[u8], b: &[u8]) -> u32 {
+        a.iter().zip(b).filter(|&(&x, &y)| x != y && !bases(x).iter().any(|c| bases(y).contains(c))).count() as u32
+    }
+
+    assert!(hamming_iupac(b"ACGT", b"ACGN") == 0);
+    assert!(hamming_iupac(b"ACGT", b"ACGT") == 0);
+    assert!(hamming_iupac(b"ACGT", b"acgu") == 0);
+    assert!(hamming_iupac(b"RY", b"YR") == 2);
+    assert!(hamming_iupac(b"NNNN", b"ACGT") == 0);
+    assert!(hamming_iupac(b"--", b"-N") == 1);
+    assert!(hamming_iupac(b"", b"") == 0);
+
+    // every pair of bytes, so that non-codes next to the letters in each shuffle are covered
+    let all: Vec<u8> = (0u8..=255).collect();
+
+    for shift in 0..256 {
+        let b: Vec<u8> = all.iter().map(|&c| c.wrapping_add(shift as u8)).collect();
+        assert!(hamming_iupac(&all, &b) == naive(&all, &b));
+    }
+
+    let codes = b"ACGTURYSWKMBDHVNacgturyswkmbdhvnX-.";
+    let a: Vec<u8> = (0..500u32).map(|i| codes[((i * 7 + i / 5) % 35) as usize]).collect();
+    let b: Vec<u8> = (0..500u32).map(|i| codes[((i * 11 + i / 3) % 35) as usize]).collect();
+
+    for len in 0..a.len() {
+        assert!(hamming_iupac(&a[..len], &b[..len]) == naive(&a[..len], &b[..len]));
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_basic_hamming_iupac_len() {
+    let _ = hamming_iupac(b"ACGT", b"ACG");
+}
+
+#[test]
+fn test_basic_render_alignment() {
+    let strs: Vec<&[u8]> = vec![b"", b"a", b"kitten", b"sitting", b"abcdef", b"badcfe", b"xxabcxx", b"abc"];
+
+    for &a in &strs {
+        for &b in &strs {
+            for &costs in &[LEVENSHTEIN_COSTS, RDAMERAU_COSTS] {
+                let edits = levenshtein_naive_with_opts(a, b, true, costs).1.unwrap();
+                let (a_res, b_res) = render_alignment(a, b, &edits, b'-');
+                assert!(a_res.len() == b_res.len());
+
+                // none of the strings contain the gap byte
+                assert!(a_res.iter().copied().filter(|&c| c != b'-').collect::<Vec<u8>>() == a);
+                assert!(b_res.iter().copied().filter(|&c| c != b'-').collect::<Vec<u8>>() == b);
+                // a column never has two gaps
+                assert!(a_res.iter().zip(&b_res).all(|(&x, &y)| x != b'-' || y != b'-'));
+            }
+        }
+    }
+
+    let (_, edits) = levenshtein_exp_with_opts(b"kitten", b"sitting", true, LEVENSHTEIN_COSTS);
+    let (a_res, b_res) = render_alignment(b"kitten", b"sitting", &edits.unwrap(), b'_');
+    assert!(a_res == b"kitten_" && b_res == b"sitting");
+}
+
+#[test]
+#[should_panic]
+fn test_basic_render_alignment_short_edits() {
+    let _ = render_alignment(b"abc", b"abc", &[Edit{edit: EditType::Match, count: 2}], b'-');
+}
+
+#[test]
+fn test_basic_byte_mismatches() {
+    // long enough for the 8-bit counters to be summed more than once
+    let a: Vec<u8> = (0..20000u32).map(|i| (i % 251) as u8).collect();
+    let b: Vec<u8> = (0..20000u32).map(|i| if i % 3 == 0 { (i % 7) as u8 } else { (i % 251) as u8 }).collect();
+
+    for &len in &[0, 1, 15, 16, 17, 31, 32, 33, 100, 255 * 32 - 1, 255 * 32, 255 * 32 + 1, 255 * 64 + 40, 20000] {
+        let expected = a[..len].iter().zip(&b[..len]).filter(|(x, y)| x != y).count() as u32;
+        assert!(byte_mismatches(&a[..len], &b[..len]) == expected);
+        assert!(byte_mismatches(&a[..len], &a[..len]) == 0);
+    }
+
+    // every byte differs
+    assert!(byte_mismatches(&[0u8; 10000], &[1u8; 10000]) == 10000);
+}
+
+#[test]
+#[should_panic]
+fn test_basic_byte_mismatches_len() {
+    let _ = byte_mismatches(b"abc", b"ab");
+}
+
+#[test]
+fn test_basic_bitap_search() {
+    let mut rng = StdRng::seed_from_u64(2718);
+
+    for &needle_len in [8, 32, 64].iter() {
+        for _ in 0..20 {
+            let needle = rand_dna(needle_len, &mut rng);
+            let mut haystack = rand_dna(300, &mut rng);
+
+            // plant a copy of the needle with a few edits
+            let mut planted = needle.clone();
+            planted[needle_len / 2] = b'N';
+            planted.remove(needle_len / 4);
+            haystack.splice(100..100, planted);
+
+            for k in 0..=2 {
+                let res: Vec<Match> = bitap_search(&needle, &haystack, k).collect();
+                let expected: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+                assert!(res == expected);
+                assert!(res == levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>());
+                assert!(res.iter().any(|m| m.end == 100 + needle_len - 1) || k < 2);
+
+                // levenshtein_search automatically uses the bit-parallel algorithm for short needles
+                let best: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+                assert!(best == levenshtein_search_naive_with_opts(&needle, &haystack, k, SearchType::Best, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>());
+            }
+        }
+    }
+
+    // every position matches, and the whole needle can be deleted
+    let res: Vec<Match> = bitap_search(b"aa", b"aaaa", 2).collect();
+    assert!(res == levenshtein_search_naive_with_opts(b"aa", b"aaaa", 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect::<Vec<Match>>());
+    assert!(res[0] == Match{start: 0, end: 0, k: 2});
+
+    // null bytes are allowed
+    let res: Vec<Match> = bitap_search(b"a\0c", b"xxa\0cxx", 0).collect();
+    assert!(res == vec![Match{start: 2, end: 5, k: 0}]);
+    assert!(bitap_search(b"", b"abc", 1).next().is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_basic_bitap_search_long_needle() {
+    let _ = bitap_search(&[b'a'; BITAP_MAX_NEEDLE_LEN + 1], b"aaa", 1);
+}
+
+#[test]
+#[cfg(feature = "cache")]
+fn test_basic_distance_cache() {
+    let pairs: [(&[u8], &[u8]); 4] = [(b"kitten", b"sitting"), (b"abcd", b"abdc"), (b"", b"abc"), (b"abcd", b"abcc")];
+    let mut cache = triple_accel::cache::DistanceCache::new(8);
+
+    for _ in 0..3 {
+        for &(a, b) in &pairs {
+            assert!(cache.levenshtein(a, b) == levenshtein(a, b));
+            assert!(cache.rdamerau(a, b) == rdamerau(a, b));
+        }
+    }
+
+    assert!(cache.hamming(b"abcd", b"abcc") == 1);
+    assert!(cache.len() == 8);
+    assert!(cache.misses() == 9);
+    assert!(cache.hits() == 16);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(cache.hits() == 0 && cache.misses() == 0);
+}
+
+#[test]
+#[cfg(feature = "cache")]
+fn test_basic_distance_cache_evict() {
+    let mut cache = triple_accel::cache::DistanceCache::new(2);
+    assert!(cache.levenshtein(b"a", b"b") == 1);
+    assert!(cache.levenshtein(b"a", b"c") == 1);
+    assert!(cache.levenshtein(b"a", b"b") == 1);
+    assert!(cache.hits() == 1);
+
+    // ("a", "c") is the least recently used, so it is evicted
+    assert!(cache.levenshtein(b"a", b"dd") == 2);
+    assert!(cache.len() == 2);
+    assert!(cache.levenshtein(b"a", b"b") == 1);
+    assert!(cache.hits() == 2);
+    assert!(cache.levenshtein(b"a", b"c") == 1);
+    assert!(cache.hits() == 2);
+    assert!(cache.misses() == 4);
+    assert!(cache.capacity() == 2);
+}
+
+#[test]
+#[cfg(feature = "cache")]
+#[should_panic]
+fn test_basic_distance_cache_zero_capacity() {
+    let _ = triple_accel::cache::DistanceCache::new(0);
+}
+
+#[test]
+fn test_basic_local_align() {
+    // a conserved region inside dissimilar flanks
+    let a = b"TTTTTTTTGATTACAGGGGGGG";
+    let b = b"CCCCCGATTACACCCCC";
+    let res = local_align(a, b, 2, 1, 1);
+    assert!(res == (14, Match{start: 8, end: 15, k: 0}, Match{start: 5, end: 12, k: 0}));
+    assert!(local_align_naive(a, b, 2, 1, 1) == res);
+
+    // a gap in the conserved region
+    let res = local_align(b"xxxxGATTACAxxxx", b"yyGATACAyy", 3, 2, 1);
+    assert!(res == (17, Match{start: 4, end: 11, k: 1}, Match{start: 2, end: 8, k: 1}));
+
+    let empty = (0, Match{start: 0, end: 0, k: 0}, Match{start: 0, end: 0, k: 0});
+    assert!(local_align(b"abc", b"xyz", 2, 1, 1) == empty);
+    assert!(local_align(b"", b"xyz", 2, 1, 1) == empty);
+    assert!(local_align(b"abc", b"", 2, 1, 1) == empty);
+
+    let mut rng = StdRng::seed_from_u64(4321);
+
+    // lengths and bonuses that need 8-bit, 16-bit, and 32-bit lanes
+    for &(max_len, bonus) in &[(20usize, 2u8), (70, 3), (300, 1), (300, 255)] {
+        for _ in 0..50 {
+            let a_len = rng.gen_range(0, max_len);
+            let b_len = rng.gen_range(0, max_len);
+            let alphabet = rng.gen_range(1, 5);
+            let a: Vec<u8> = (0..a_len).map(|_| rng.gen_range(0, alphabet)).collect();
+            let b: Vec<u8> = (0..b_len).map(|_| rng.gen_range(0, alphabet)).collect();
+            let mismatch = rng.gen_range(0, 4);
+            let gap = rng.gen_range(0, 4);
+            let expected = local_align_naive(&a, &b, bonus, mismatch, gap);
+            assert!(local_align(&a, &b, bonus, mismatch, gap) == expected);
+            assert!(expected.1.end - expected.1.start <= a_len && expected.2.end <= b_len);
+        }
+    }
+}
+
+#[test]
+fn test_basic_levenshtein_weighted_saturation() {
+    // large costs push the dp values past 127 and up to the saturated u8::MAX sentinel in the 8-bit vectors
+    let costs = EditCosts::new(200, 120, 10, None);
+    let pairs: [(&[u8], &[u8]); 5] = [(b"abc", b"abd"), (b"abc", b"abcd"), (b"abcd", b"abdc"), (b"abc", b"xyz"), (b"aaaa", b"a")];
+
+    for &(a, b) in &pairs {
+        for k in [0u32, 100, 127, 128, 200, 253, 254, 255, 400, 1000].iter().copied() {
+            let simd = levenshtein_simd_k_with_opts(a, b, k, true, costs);
+            let naive = levenshtein_naive_k_with_opts(a, b, k, true, costs);
+            assert!(simd.as_ref().map(|r| r.0) == naive.as_ref().map(|r| r.0));
+        }
+    }
+
+    assert!(levenshtein_simd_k_with_opts(b"abc", b"abd", 254, false, costs) == Some((200, None)));
+    assert!(levenshtein_simd_k_with_opts(b"abc", b"abcd", 254, false, costs) == Some((130, None)));
+    assert!(levenshtein_simd_k_with_opts(b"abc", b"abcd", 129, false, costs).is_none());
+    assert!(levenshtein_simd_k_with_opts(b"abc", b"xyz", 1000, false, costs) == Some((600, None)));
+}
+
+#[test]
+fn test_basic_levenshtein_u16() {
+    let utf16 = |s: &str| -> Vec<u16> { s.encode_utf16().collect() };
+
+    // 'é' is a single code unit in the Basic Multilingual Plane
+    assert!(levenshtein_u16(&utf16("café"), &utf16("cafe")) == 1);
+    assert!(levenshtein_u16(&utf16("café"), &utf16("café")) == 0);
+
+    // '😀' is a surrogate pair, so it counts as two code units
+    assert!(utf16("😀").len() == 2);
+    assert!(levenshtein_u16(&utf16("a😀b"), &utf16("ab")) == 2);
+    assert!(levenshtein_u16(&utf16("a😀b"), &utf16("aéb")) == 2);
+    // '😁' shares the high surrogate with '😀'
+    assert!(levenshtein_u16(&utf16("a😀b"), &utf16("a😁b")) == 1);
+
+    assert!(levenshtein_u16(&[], &utf16("abc")) == 3);
+    assert!(levenshtein_u16(&utf16("kitten"), &utf16("sitting")) == levenshtein(b"kitten", b"sitting"));
+}
+
+fn rank_by<D: Distance>(metric: &D, query: &[u8], candidates: &[&[u8]]) -> Vec<usize> {
+    let mut idxs: Vec<usize> = (0..candidates.len()).collect();
+    idxs.sort_by_key(|&i| (metric.distance(query, candidates[i]), i));
+    idxs
+}
+
+fn within_by<D: BoundedDistance>(metric: &D, query: &[u8], candidates: &[&[u8]], k: u32) -> Vec<(usize, u32)> {
+    candidates.iter().enumerate().filter_map(|(i, c)| metric.distance_within(query, c, k).map(|dist| (i, dist))).collect()
+}
+
+#[test]
+fn test_basic_distance_trait() {
+    let candidates: [&[u8]; 4] = [b"abcd", b"abdc", b"badc", b"wxyz"];
+
+    assert!(rank_by(&Hamming, b"abcd", &candidates) == vec![0, 1, 2, 3]);
+    assert!(rank_by(&Levenshtein, b"bacd", &candidates) == vec![0, 2, 1, 3]);
+    assert!(rank_by(&RDamerau, b"bacd", &candidates) == vec![0, 2, 1, 3]);
+    assert!(rank_by(&Damerau, b"ca", &[b"abc", b"xyz"]) == vec![0, 1]);
+
+    assert!(within_by(&Hamming, b"abcd", &candidates, 2) == vec![(0, 0), (1, 2)]);
+    assert!(within_by(&Levenshtein, b"abcd", &candidates, 2) == vec![(0, 0), (1, 2)]);
+    assert!(within_by(&RDamerau, b"abcd", &candidates, 1) == vec![(0, 0), (1, 1)]);
+    assert!(within_by(&Damerau, b"abcd", &candidates, 2) == vec![(0, 0), (1, 1), (2, 2)]);
+
+    for &(a, b) in &[(&b"kitten"[..], &b"sitting"[..]), (b"ca", b"abc"), (b"", b"abc")] {
+        assert!(Levenshtein.distance(a, b) == levenshtein(a, b));
+        assert!(RDamerau.distance(a, b) == rdamerau(a, b));
+        assert!(Damerau.distance(a, b) == damerau_levenshtein(a, b));
+        assert!(Levenshtein.distance_within(a, b, u32::MAX) == Some(levenshtein(a, b)));
+        assert!(RDamerau.distance_within(a, b, u32::MAX) == Some(rdamerau(a, b)));
+    }
+}
+
+#[test]
+fn test_basic_large_distances_are_exact() {
+    assert!(MAX_RELIABLE_DISTANCE == u32::MAX - 1);
+
+    // distances around and past the maximum values of 8-bit lanes are not saturated
+    for &len in &[126usize, 127, 128, 200, 254, 255, 256, 300] {
+        let a = vec![b'a'; len];
+        let b = vec![b'b'; len];
+        let c: Vec<u8> = (0..len + 10).map(|i| if i % 3 == 0 { b'a' } else { b'c' }).collect();
+
+        assert!(levenshtein(&a, &b) == len as u32);
+        assert!(levenshtein_exp(&a, &b) == len as u32);
+        assert!(rdamerau(&a, &b) == len as u32);
+        assert!(hamming(&a, &b) == len as u32);
+        assert!(levenshtein(&a, &c) == levenshtein_naive(&a, &c));
+        assert!(levenshtein_simd_k(&a, &b, len as u32 - 1).is_none());
+    }
+
+    let a = vec![b'a'; 70000];
+    let b = vec![b'b'; 70000];
+    assert!(hamming(&a, &b) == 70000);
+}
+
+#[test]
+fn test_basic_best_non_overlapping() {
+    let m = |start, end, k| Match{start, end, k};
+
+    // greedily taking the earliest ending match gives a total length of 11, but the long match covers 16
+    let res = best_non_overlapping(vec![m(0, 5, 0), m(4, 20, 0), m(19, 25, 0)]);
+    assert!(res == vec![m(4, 20, 0)]);
+
+    // merging by the lowest k keeps only the middle match, but the two outer matches cover more
+    let matches = vec![m(9, 16, 1), m(0, 6, 1), m(5, 10, 0)];
+    let mut merged = matches.clone();
+    merge_overlapping(&mut merged, MergePolicy::LowestK);
+    assert!(merged == vec![m(5, 10, 0)]);
+    assert!(best_non_overlapping(matches) == vec![m(0, 6, 1), m(9, 16, 1)]);
+
+    // touching matches do not overlap, and ties in length prefer the lower k
+    let res = best_non_overlapping(vec![m(0, 4, 2), m(4, 8, 1), m(0, 4, 1), m(2, 6, 0)]);
+    assert!(res == vec![m(0, 4, 1), m(4, 8, 1)]);
+
+    assert!(best_non_overlapping(vec![]).is_empty());
+    assert!(best_non_overlapping(vec![m(3, 3, 0)]).is_empty());
+
+    // matches from a search are reduced to a disjoint set that covers as much as possible
+    let matches: Vec<Match> = levenshtein_search_simd_with_opts(b"abcd", b"xxabcdbcdxxabd", 2, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+    let res = best_non_overlapping(matches.clone());
+    assert!(res.windows(2).all(|w| w[0].end <= w[1].start));
+    assert!(res.iter().all(|r| matches.contains(r)));
+}
+
+#[test]
+fn test_basic_match_char_range() {
+    // multibyte characters before and inside the match
+    let haystack = "日本 naïve café";
+    let matches: Vec<Match> = levenshtein_search("naïve".as_bytes(), haystack.as_bytes()).collect();
+    assert!(matches == vec![Match{start: 7, end: 13, k: 0}]);
+    assert!(matches[0].char_range(haystack) == Some(3..8));
+    let chars: Vec<char> = haystack.chars().collect();
+    assert!(chars[3..8].iter().collect::<String>() == "naïve");
+
+    let m = Match{start: 14, end: 19, k: 0};
+    assert!(m.char_range(haystack) == Some(9..13));
+    assert!(Match{start: 0, end: 0, k: 0}.char_range(haystack) == Some(0..0));
+    assert!(Match{start: 0, end: haystack.len(), k: 0}.char_range(haystack) == Some(0..13));
+
+    // inside a multibyte character
+    assert!(Match{start: 1, end: 6, k: 0}.char_range(haystack) == None);
+    assert!(Match{start: 7, end: 10, k: 0}.char_range(haystack) == None);
+    // out of bounds
+    assert!(Match{start: 14, end: 100, k: 0}.char_range(haystack) == None);
+}
+
+#[test]
+fn test_basic_levenshtein_search_many() {
+    let needle = b"abcde";
+    let haystacks: [&[u8]; 6] = [b"  abxde  abcdd  abcd  xbcde  bcdea", b"", b"xxxxxxxxxxxx", b"abcde", b"edcba abcdeabcde", b"ab"];
+
+    for k in 0..4 {
+        let res = levenshtein_search_many(needle, &haystacks, k);
+        assert!(res.len() == haystacks.len());
+
+        for (matches, haystack) in res.iter().zip(haystacks.iter()) {
+            let expected: Vec<Match> = levenshtein_search_simd_with_opts(needle, haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            assert!(*matches == expected);
+        }
+    }
+
+    assert!(levenshtein_search_many(needle, &[], 1).is_empty());
+}
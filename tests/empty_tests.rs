@@ -0,0 +1,274 @@
+use triple_accel::*;
+use triple_accel::hamming::*;
+use triple_accel::levenshtein::*;
+
+// every public function is called with empty strings, to make sure that none of the SIMD
+// routines index into an empty vector and that the results agree with the definitions
+
+#[repr(align(32))]
+struct Aligned([u8; 256]);
+
+// short strings are handled in a single vector, while long strings need multiple vectors
+const NON_EMPTY: [&[u8]; 2] = [b"abc", b"abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyz"];
+
+#[test]
+fn test_empty_hamming() {
+    let e: &[u8] = b"";
+    assert!(hamming_naive(e, e) == 0);
+    assert!(hamming_simd_parallel(e, e) == 0);
+    assert!(hamming_simd_movemask(e, e) == 0);
+    assert!(hamming(e, e) == 0);
+    assert!(hamming_scalar(e, e) == 0);
+    assert!(hamming_checked(e, e) == Ok(0));
+    assert!(hamming_bits(e, e) == 0);
+    assert!(hamming_mismatches(e, e).is_empty());
+    assert!(hamming_dna(e, e) == 0);
+    assert!(hamming_revcomp(e, e) == 0);
+    assert!(hamming_ci(e, e) == 0);
+    assert!(hamming_normalized(e, e) == 0.0);
+    assert!(hamming_batch(e, &[e]) == vec![0]);
+    assert!(hamming_batch(e, &[]).is_empty());
+    assert!(byte_mismatches(e, e) == 0);
+    assert!(pack_dna(e).is_empty());
+    assert!(unpack_dna(&[], 0).is_empty());
+
+    let a = alloc_str(0);
+    let b = alloc_str(0);
+    assert!(hamming_words_64(&a, &b) == 0);
+    assert!(hamming_words_128(&a, &b) == 0);
+
+    let mut stream = HammingStream::new();
+    stream.update(e, e);
+    assert!(stream.finish() == 0);
+}
+
+#[test]
+fn test_empty_hamming_search() {
+    let e: &[u8] = b"";
+
+    for &s in [e, NON_EMPTY[0], NON_EMPTY[1]].iter() {
+        // an empty needle never matches
+        assert!(hamming_search_naive(e, s).next().is_none());
+        assert!(hamming_search_naive_with_opts(e, s, 0, SearchType::All).next().is_none());
+        assert!(hamming_search_simd(e, s).next().is_none());
+        assert!(hamming_search_simd_with_opts(e, s, 0, SearchType::All).next().is_none());
+        assert!(hamming_search(e, s).next().is_none());
+        assert!(hamming_search_scalar(e, s).next().is_none());
+        assert!(hamming_search_ci(e, s).next().is_none());
+        assert!(hamming_search_rev(e, s).next().is_none());
+        assert!(hamming_search_wildcard(e, s, 0, b'?').next().is_none());
+        assert!(hamming_search_multi(&[e], s, 0).is_empty());
+        assert!(hamming_search_count(e, s, 0) == 0);
+        assert!(hamming_search_profile(e, s) == vec![0; s.len() + 1]);
+
+        // an empty haystack is shorter than any non-empty needle
+        assert!(hamming_search(s, e).next().is_none());
+        assert!(hamming_search_naive_with_opts(s, e, 100, SearchType::All).next().is_none());
+        assert!(hamming_search_count(s, e, 100) == 0);
+        assert!(hamming_search_profile(s, e).len() == (s.is_empty() as usize));
+    }
+
+    assert!(hamming_search_str("", "abc").next().is_none());
+    assert!(hamming_search_multi(&[], b"abc", 0).is_empty());
+}
+
+#[test]
+fn test_empty_levenshtein() {
+    let e: &[u8] = b"";
+    let mut scratch = Scratch::new();
+    let mut buf = Aligned([0u8; 256]);
+
+    assert!(levenshtein_naive(e, e) == 0);
+    assert!(levenstein_naive_str("", "") == 0);
+    assert!(levenshtein_naive_k(e, e, 0) == Some(0));
+    assert!(levenshtein_simd_k(e, e, 0) == Some(0));
+    assert!(levenshtein_simd_k_str("", "", 0) == Some(0));
+    assert!(levenshtein_k(e, e, 0) == Some(0));
+    assert!(levenshtein(e, e) == 0);
+    assert!(levenshtein_str("", "") == 0);
+    assert!(levenshtein_report(e, e).distance == 0);
+    assert!(levenshtein_with_scratch(e, e, &mut scratch) == 0);
+    assert!(levenshtein_into(e, e, &mut buf.0) == 0);
+    assert!(levenshtein_similarity(e, e) == 1.0);
+    assert!(levenshtein_normalized(e, e) == 0.0);
+    assert!(nearest(e, &[e]) == Some((0, 0)));
+    assert!(nearest(e, &[]) == None);
+    assert!(top_k_nearest(e, &[], 3).is_empty());
+
+    for &s in NON_EMPTY.iter() {
+        let len = s.len() as u32;
+
+        for &(a, b) in [(e, s), (s, e)].iter() {
+            assert!(levenshtein_naive(a, b) == len);
+            assert!(levenshtein_naive_with_opts(a, b, false, RDAMERAU_COSTS).0 == len);
+            assert!(levenshtein_naive_k(a, b, len) == Some(len));
+            assert!(levenshtein_naive_k(a, b, len - 1) == None);
+            assert!(levenshtein_simd_k(a, b, len) == Some(len));
+            assert!(levenshtein_simd_k(a, b, len - 1) == None);
+            assert!(levenshtein_simd_k_with_opts(a, b, len, false, RDAMERAU_COSTS) == Some((len, None)));
+            assert!(levenshtein_k(a, b, len) == Some(len));
+            assert!(histogram_bound(a, b) == len);
+            assert!(levenshtein(a, b) == len);
+            assert!(levenshtein_with_escalation(a, b) == (len, false));
+            assert!(levenshtein_report(a, b).distance == len);
+            assert!(levenshtein_with_scratch(a, b, &mut scratch) == len);
+            assert!(levenshtein_into(a, b, &mut buf.0) == len);
+            assert!(levenshtein_skip_common(a, b) == len);
+            assert!(levenshtein_scalar(a, b) == len);
+            assert!(levenshtein_iter(a.iter().cloned(), b.iter().cloned()) == len);
+            assert!(levenshtein_ci(a, b) == len);
+            assert!(levenshtein_latin1_ci(a, b) == len);
+            assert!(rdamerau(a, b) == len);
+            assert!(levenshtein_exp(a, b) == len);
+            assert!(rdamerau_exp(a, b) == len);
+            assert!(levenshtein_normalized(a, b) == 1.0);
+            assert!(levenshtein_similarity(a, b) == 0.0);
+            assert!(levenshtein_batch(a, &[b, b]) == vec![len, len]);
+            assert!(nearest(a, &[b]) == Some((0, len)));
+            assert!(top_k_nearest(a, &[b], 1) == vec![(0, len)]);
+            assert!(lcs(a, b) == 0);
+            assert!(osa_distance(a, b) == len);
+            assert!(damerau_levenshtein(a, b) == len);
+            assert!(damerau_weighted(a, b, RDAMERAU_COSTS) == len);
+            assert!(levenshtein_by(a, b, |x, y| x == y) == len);
+            assert!(levenshtein_matrix(a, b, &[[1u8; 256]; 256], 1) == len);
+            assert!(levenshtein_matrix_naive(a, b, &[[1u8; 256]; 256], 1) == len);
+        }
+    }
+}
+
+#[test]
+fn test_empty_levenshtein_align() {
+    let e: &[u8] = b"";
+    assert!(levenshtein_naive_with_opts(e, e, true, LEVENSHTEIN_COSTS) == (0, Some(vec![])));
+    assert!(levenshtein_simd_k_with_opts(e, e, 0, true, LEVENSHTEIN_COSTS) == Some((0, Some(vec![]))));
+    assert!(levenshtein_exp_with_opts(e, e, true, LEVENSHTEIN_COSTS) == (0, Some(vec![])));
+    assert!(levenshtein_align(e, e) == (0, vec![]));
+    assert!(levenshtein_ops(e, e) == (0, vec![]));
+    assert!(lcs_align(e, e).is_empty());
+    assert!(edits_to_cigar(&[], false) == "");
+    assert!(edit_ops(&[]).is_empty());
+    assert!(render_alignment(e, e, &[], b'-') == (vec![], vec![]));
+
+    for &s in NON_EMPTY.iter() {
+        let len = s.len() as u32;
+        let a_gap = vec![Edit{edit: EditType::AGap, count: s.len()}];
+        let b_gap = vec![Edit{edit: EditType::BGap, count: s.len()}];
+        assert!(levenshtein_naive_with_opts(e, s, true, LEVENSHTEIN_COSTS) == (len, Some(a_gap.clone())));
+        assert!(levenshtein_naive_with_opts(s, e, true, LEVENSHTEIN_COSTS) == (len, Some(b_gap.clone())));
+        assert!(levenshtein_simd_k_with_opts(e, s, len, true, LEVENSHTEIN_COSTS) == Some((len, Some(a_gap.clone()))));
+        assert!(levenshtein_simd_k_with_opts(s, e, len, true, LEVENSHTEIN_COSTS) == Some((len, Some(b_gap.clone()))));
+        assert!(levenshtein_exp_with_opts(e, s, true, LEVENSHTEIN_COSTS) == (len, Some(a_gap.clone())));
+        assert!(levenshtein_align(e, s) == (len, a_gap.clone()));
+        assert!(levenshtein_align(s, e) == (len, b_gap.clone()));
+        assert!(levenshtein_ops(e, s).1.len() == s.len());
+        assert!(lcs_align(e, s) == a_gap);
+        assert!(render_alignment(e, s, &a_gap, b'-') == (vec![b'-'; s.len()], s.to_vec()));
+        assert!(render_alignment(s, e, &b_gap, b'-') == (s.to_vec(), vec![b'-'; s.len()]));
+    }
+}
+
+#[test]
+fn test_empty_levenshtein_search() {
+    let e: &[u8] = b"";
+
+    for &s in [e, NON_EMPTY[0], NON_EMPTY[1]].iter() {
+        // an empty needle never matches, unless the search is anchored
+        assert!(levenshtein_search_naive(e, s).next().is_none());
+        assert!(levenshtein_search_naive_with_opts(e, s, 1, SearchType::All, LEVENSHTEIN_COSTS, false).next().is_none());
+        assert!(levenshtein_search_simd(e, s).next().is_none());
+        assert!(levenshtein_search_simd_with_opts(e, s, 1, SearchType::All, LEVENSHTEIN_COSTS, false).next().is_none());
+        assert!(levenshtein_search(e, s).next().is_none());
+        assert!(levenshtein_search_scalar(e, s).next().is_none());
+        assert!(levenshtein_search_best(e, s, 1) == None);
+        assert!(damerau_search(e, s, 1).is_empty());
+        assert!(levenshtein_search_ranked(e, s, 1).is_empty());
+        assert!(levenshtein_search_ratio(e, s, 0.5).is_empty());
+        assert!(levenshtein_search_iter(e, s, 1).next().is_none());
+        assert!(levenshtein_search_count(e, s, 1) == 0);
+        assert!(levenshtein_search_ci(e, s).next().is_none());
+        assert!(levenshtein_search_with(e, s, &SearchConfig::new()).next().is_none());
+        assert!(CompiledNeedle::new(e).search(s, 1).is_empty());
+        assert!(!levenshtein_contains(e, s, 0));
+        assert!(levenshtein_search_profile(e, s) == vec![0; s.len()]);
+        assert!(levenshtein_prefix(e, s) == 0);
+        assert!(levenshtein_match_prefix(e, s, 0) == Some(Match{start: 0, end: 0, k: 0}));
+
+        let mut res: Vec<Match> = levenshtein_search_naive_with_opts(e, s, 1, SearchType::All, LEVENSHTEIN_COSTS, true).collect();
+        let expected = if s.is_empty() { vec![Match{start: 0, end: 0, k: 0}] } else { vec![Match{start: 0, end: 0, k: 0}, Match{start: 0, end: 1, k: 1}] };
+        assert!(res == expected);
+        res = levenshtein_search_simd_with_opts(e, s, 1, SearchType::All, LEVENSHTEIN_COSTS, true).collect();
+        assert!(res == expected);
+    }
+
+    for &s in NON_EMPTY.iter() {
+        // an empty haystack only matches a needle that can be deleted within k edits
+        let len = s.len() as u32;
+        let expected = vec![Match{start: 0, end: 0, k: len}];
+        let mut res: Vec<Match> = levenshtein_search_simd_with_opts(s, e, len, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == expected);
+        res = levenshtein_search_naive_with_opts(s, e, len, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+        assert!(res == expected);
+        assert!(levenshtein_search_simd_with_opts(s, e, len - 1, SearchType::All, LEVENSHTEIN_COSTS, false).next().is_none());
+        assert!(levenshtein_search_naive_with_opts(s, e, len - 1, SearchType::All, LEVENSHTEIN_COSTS, false).next().is_none());
+        assert!(levenshtein_search_count(s, e, len) == 1);
+        assert!(levenshtein_contains(s, e, len));
+        assert!(!levenshtein_contains(s, e, len - 1));
+        assert!(levenshtein_search_profile(s, e).is_empty());
+        assert!(levenshtein_prefix(s, e) == len);
+    }
+
+    assert!(fuzzy_split(e, b"abc", 1) == vec![e]);
+    assert!(fuzzy_split(b"abc", e, 1) == vec![&b"abc"[..]]);
+    assert!(fuzzy_split(e, e, 1) == vec![e]);
+}
+
+#[test]
+fn test_empty_levenshtein_structs() {
+    let e: &[u8] = b"";
+
+    let p = QgramProfile::new(e, 2);
+    assert!(p.distance(&QgramProfile::new(e, 2)) == 0);
+    assert!(p.lower_bound(&QgramProfile::new(b"abc", 2)) == 1);
+
+    let h = ByteHistogram::new(e);
+    assert!(h.distance(&ByteHistogram::new(e)) == 0);
+    assert!(h.lower_bound(&ByteHistogram::new(b"abc")) == 3);
+
+    let mut inc = IncrementalLevenshtein::new(e);
+    assert!(inc.distance() == 0);
+    assert!(inc.pop_char() == None);
+    assert!(inc.push_char(b'a') == 1);
+    inc = IncrementalLevenshtein::new(b"abc");
+    assert!(inc.distance() == 3);
+
+    let automaton = LevenshteinAutomaton::new(e, 1);
+    let state = automaton.start();
+    assert!(automaton.is_match(&state));
+    assert!(automaton.distance(&state) == Some(0));
+    let state = automaton.step(&state, b'a').unwrap();
+    assert!(automaton.distance(&state) == Some(1));
+    assert!(automaton.step(&state, b'a').is_none());
+
+    let index = DeletionIndex::new(&[], 1);
+    assert!(index.is_empty());
+    assert!(index.lookup(e, 1).is_empty());
+    let index = DeletionIndex::new(&[e], 1);
+    assert!(index.lookup(e, 0) == vec![(e, 0)]);
+    assert!(index.lookup(b"a", 1) == vec![(e, 1)]);
+}
+
+#[test]
+fn test_empty_utils() {
+    let mut v: Vec<u8> = vec![];
+    ascii_to_lower_simd(&mut v);
+    ascii_to_upper_simd(&mut v);
+    assert!(v.is_empty());
+
+    let mut a = alloc_str(0);
+    fill_str(&mut a, b"");
+
+    let mut matches: Vec<Match> = vec![];
+    merge_overlapping(&mut matches, MergePolicy::LowestK);
+    assert!(matches.is_empty());
+}
@@ -0,0 +1,113 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use proptest::prelude::*;
+use triple_accel::*;
+use triple_accel::hamming::*;
+use triple_accel::levenshtein::*;
+
+// differential tests that check the SIMD routines against the naive scalar routines on random
+// strings, with lengths that are biased toward the boundaries of the SIMD vectors
+
+// lengths near multiples of the 16 and 32 byte vectors, where padding bugs usually show up
+fn biased_len() -> impl Strategy<Value = usize> {
+    prop_oneof![
+        1 => Just(0usize),
+        2 => 1usize..8,
+        4 => (1usize..5, 0usize..5).prop_map(|(m, d)| (m * 32 + d).saturating_sub(2)),
+        2 => (1usize..9, 0usize..3).prop_map(|(m, d)| (m * 16 + d).saturating_sub(1)),
+        1 => 0usize..300,
+    ]
+}
+
+// mostly a small alphabet so there are many close matches, with some arbitrary non-null bytes
+fn byte() -> impl Strategy<Value = u8> {
+    prop_oneof![
+        4 => prop::sample::select(b"ACGT".to_vec()),
+        1 => 1u8..=255u8,
+    ]
+}
+
+fn string() -> impl Strategy<Value = Vec<u8>> {
+    biased_len().prop_flat_map(|len| prop::collection::vec(byte(), len))
+}
+
+fn equal_len_strings() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+    biased_len().prop_flat_map(|len| (prop::collection::vec(byte(), len), prop::collection::vec(byte(), len)))
+}
+
+// a needle that is usually taken from the haystack and then mutated, so matches are common
+fn needle_and_haystack() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+    (string(), 0usize..40, any::<prop::sample::Index>(), prop::collection::vec((any::<prop::sample::Index>(), byte()), 0..4)).prop_map(|(haystack, len, start, edits)| {
+        let mut needle = if haystack.is_empty() {
+            Vec::new()
+        } else {
+            let start = start.index(haystack.len());
+            haystack[start..std::cmp::min(start + len, haystack.len())].to_vec()
+        };
+
+        for (i, c) in edits {
+            if !needle.is_empty() {
+                let i = i.index(needle.len());
+                needle[i] = c;
+            }
+        }
+
+        (needle, haystack)
+    })
+}
+
+proptest! {
+    #[test]
+    fn prop_hamming((a, b) in equal_len_strings()) {
+        let expected = hamming_naive(&a, &b);
+        prop_assert_eq!(hamming(&a, &b), expected);
+        prop_assert_eq!(byte_mismatches(&a, &b), expected);
+    }
+
+    #[test]
+    fn prop_levenshtein(a in string(), b in string()) {
+        let expected = levenshtein_naive(&a, &b);
+        prop_assert_eq!(levenshtein(&a, &b), expected);
+        prop_assert_eq!(levenshtein_exp(&a, &b), expected);
+    }
+
+    #[test]
+    fn prop_levenshtein_k(a in string(), b in string(), k in 0u32..20) {
+        prop_assert_eq!(levenshtein_simd_k(&a, &b, k), levenshtein_naive_k(&a, &b, k));
+
+        let simd = levenshtein_simd_k_with_opts(&a, &b, k, true, LEVENSHTEIN_COSTS);
+        let naive = levenshtein_naive_k_with_opts(&a, &b, k, true, LEVENSHTEIN_COSTS);
+        prop_assert_eq!(simd.as_ref().map(|r| r.0), naive.as_ref().map(|r| r.0));
+
+        // the traceback may be a different alignment, but it must have the same cost
+        if let Some((dist, Some(edits))) = simd {
+            let cost: usize = edits.iter().filter(|e| e.edit != EditType::Match).map(|e| e.count).sum();
+            prop_assert_eq!(cost as u32, dist);
+        }
+    }
+
+    #[test]
+    fn prop_local_align(a in string(), b in string(), bonus in 0u8..4, mismatch in 0u8..4, gap in 0u8..4) {
+        prop_assert_eq!(local_align(&a, &b, bonus, mismatch, gap), local_align_naive(&a, &b, bonus, mismatch, gap));
+    }
+
+    #[test]
+    fn prop_hamming_search((needle, haystack) in needle_and_haystack(), k in 0u32..4) {
+        for &search_type in &[SearchType::All, SearchType::Best] {
+            let simd: Vec<Match> = hamming_search_simd_with_opts(&needle, &haystack, k, search_type).collect();
+            let naive: Vec<Match> = hamming_search_naive_with_opts(&needle, &haystack, k, search_type).collect();
+            prop_assert_eq!(simd, naive);
+        }
+    }
+
+    #[test]
+    fn prop_levenshtein_search((needle, haystack) in needle_and_haystack(), k in 0u32..6) {
+        for &search_type in &[SearchType::All, SearchType::Best] {
+            for &anchored in &[false, true] {
+                let simd: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+                let naive: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, search_type, LEVENSHTEIN_COSTS, anchored).collect();
+                prop_assert_eq!(simd, naive);
+            }
+        }
+    }
+}
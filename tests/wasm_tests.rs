@@ -0,0 +1,69 @@
+#![cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+
+use triple_accel::*;
+use triple_accel::levenshtein::*;
+use wasm_bindgen_test::*;
+use rand::prelude::*;
+
+fn rand_str(len: usize, rng: &mut StdRng) -> Vec<u8> {
+    (0..len).map(|_| b"ACGT"[rng.gen_range(0, 4)]).collect()
+}
+
+fn rand_mutate(a: &[u8], edits: usize, rng: &mut StdRng) -> Vec<u8> {
+    let mut res = a.to_vec();
+
+    for _ in 0..edits {
+        let idx = rng.gen_range(0, res.len() + 1);
+
+        match rng.gen_range(0, 3) {
+            0 if idx < res.len() => res[idx] = b'N',
+            1 if idx < res.len() => { res.remove(idx); },
+            _ => res.insert(idx, b'N')
+        }
+    }
+
+    res
+}
+
+#[wasm_bindgen_test]
+fn test_wasm_levenshtein_matches_naive() {
+    let mut rng = StdRng::seed_from_u64(1);
+
+    // lengths chosen to cross the 16 byte vector boundaries
+    for &len in &[0usize, 1, 15, 16, 17, 31, 33, 64, 100, 250] {
+        for &edits in &[0usize, 1, 3, 10] {
+            let a = rand_str(len, &mut rng);
+            let b = rand_mutate(&a, edits, &mut rng);
+
+            let naive = levenshtein_naive(&a, &b);
+            assert!(levenshtein_simd_k(&a, &b, u32::MAX) == Some(naive));
+            assert!(levenshtein_exp(&a, &b) == naive);
+
+            let (dist, trace) = levenshtein_simd_k_with_opts(&a, &b, u32::MAX, true, LEVENSHTEIN_COSTS).unwrap();
+            assert!(dist == naive);
+            assert!(trace.is_some());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_wasm_levenshtein_search_matches_naive() {
+    let mut rng = StdRng::seed_from_u64(2);
+
+    for &needle_len in &[8usize, 15, 16, 17, 40] {
+        for &k in &[0u32, 1, 2, 5] {
+            let needle = rand_str(needle_len, &mut rng);
+            let mut haystack = rand_str(50, &mut rng);
+            haystack.extend_from_slice(&rand_mutate(&needle, k as usize, &mut rng));
+            haystack.extend_from_slice(&rand_str(50, &mut rng));
+
+            let simd: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            let naive: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, SearchType::All, LEVENSHTEIN_COSTS, false).collect();
+            assert!(simd == naive);
+
+            let simd: Vec<Match> = levenshtein_search_simd_with_opts(&needle, &haystack, k, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+            let naive: Vec<Match> = levenshtein_search_naive_with_opts(&needle, &haystack, k, SearchType::Best, LEVENSHTEIN_COSTS, false).collect();
+            assert!(simd == naive);
+        }
+    }
+}